@@ -0,0 +1,49 @@
+import * as vscode from 'vscode';
+import { Decorations } from './decorations/decorations';
+import { reloadConfiguration, mathInputMode } from './utils';
+import getWASM from './wasmHelper';
+
+// Complete the abbreviation typed before the cursor with the symbols it names, like `\le` or `RR`
+const provider = (decorations: Decorations): vscode.CompletionItemProvider => ({
+    provideCompletionItems(document: vscode.TextDocument, position: vscode.Position) {
+        let line = document.lineAt(position.line).text;
+        let options = decorations.generateOptions();
+        let candidates = getWASM().input_candidates(line, position.character, options, 50);
+        options.free();
+        return new vscode.CompletionList(candidates.map((candidate, index) => {
+            let item = new vscode.CompletionItem({ label: candidate.symbol, description: candidate.name }, vscode.CompletionItemKind.Constant);
+            let range = new vscode.Range(position.line, candidate.start, position.line, candidate.end);
+            item.range = range;
+            item.insertText = candidate.name;
+            // Candidates are ranked by rust, VSCode must neither filter nor sort them again
+            item.filterText = document.getText(range);
+            item.sortText = index.toString().padStart(4, '0');
+            candidate.free();
+            return item;
+        }), true);
+    }
+});
+
+// Register the provider while the setting is enabled, typing `\` or a name then suggesting symbols
+export const abbreviationsProvider = (decorations: Decorations): vscode.Disposable => {
+    let registration: vscode.Disposable | undefined;
+    let update = () => {
+        if (mathInputMode() && !registration) {
+            registration = vscode.languages.registerCompletionItemProvider('typst', provider(decorations), '\\');
+        } else if (!mathInputMode() && registration) {
+            registration.dispose();
+            registration = undefined;
+        }
+    };
+    update();
+    let listener = vscode.workspace.onDidChangeConfiguration((event) => {
+        if (event.affectsConfiguration("typst-math.mathInputMode")) {
+            reloadConfiguration();
+            update();
+        }
+    });
+    return new vscode.Disposable(() => {
+        listener.dispose();
+        registration?.dispose();
+    });
+};
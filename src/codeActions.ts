@@ -0,0 +1,21 @@
+import * as vscode from 'vscode';
+import getWASM from './wasmHelper';
+
+// Fix common mistakes in math, like `e^-x` whose script is only the sign or LaTeX commands like `\frac{a}{b}`
+export const codeActionProvider: vscode.CodeActionProvider = {
+    provideCodeActions(document: vscode.TextDocument, range: vscode.Range | vscode.Selection) {
+        let actions: vscode.CodeAction[] = [];
+        for (let fix of getWASM().quick_fixes(document.getText())) {
+            let position = fix.edit.position;
+            let fixRange = new vscode.Range(position.start_line, position.start_column, position.end_line, position.end_column);
+            if (fixRange.intersection(range)) {
+                let action = new vscode.CodeAction(fix.title, vscode.CodeActionKind.QuickFix);
+                action.edit = new vscode.WorkspaceEdit();
+                action.edit.replace(document.uri, fixRange, fix.edit.text);
+                actions.push(action);
+            }
+            fix.free();
+        }
+        return actions;
+    }
+};
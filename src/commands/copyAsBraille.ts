@@ -0,0 +1,19 @@
+import * as vscode from 'vscode';
+import { Decorations } from '../decorations/decorations';
+import getWASM from '../wasmHelper';
+
+export const copyAsBrailleCommand = (decorations: Decorations) => {
+    return vscode.commands.registerCommand('typst-math.copy-as-braille', async () => {
+        let editor = vscode.window.activeTextEditor;
+        if (editor === undefined) { return; }
+        let document = editor.document;
+        let options = decorations.generateOptions();
+        let braille = getWASM().braille_equation(document.getText(), document.offsetAt(editor.selection.active), options);
+        options.free();
+        if (braille === undefined) {
+            vscode.window.showInformationMessage('No equation to copy');
+            return;
+        }
+        await vscode.env.clipboard.writeText(braille);
+    });
+};
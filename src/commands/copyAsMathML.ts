@@ -0,0 +1,20 @@
+import * as vscode from 'vscode';
+import { Decorations } from '../decorations/decorations';
+import getWASM from '../wasmHelper';
+
+export const copyAsMathMLCommand = (decorations: Decorations) => {
+    return vscode.commands.registerCommand('typst-math.copy-as-mathml', async () => {
+        let editor = vscode.window.activeTextEditor;
+        if (editor === undefined) { return; }
+        let document = editor.document;
+        let options = decorations.generateOptions();
+        // Without a selection, the equation under the cursor is copied
+        let mathml = getWASM().render_to_mathml(document.getText(), document.offsetAt(editor.selection.start), document.offsetAt(editor.selection.end), options);
+        options.free();
+        if (mathml.length === 0) {
+            vscode.window.showInformationMessage('No equation to copy');
+            return;
+        }
+        await vscode.env.clipboard.writeText(mathml);
+    });
+};
@@ -0,0 +1,15 @@
+import * as vscode from 'vscode';
+import { Decorations } from '../decorations/decorations';
+import getWASM from '../wasmHelper';
+
+export const copyAsUnicodeCommand = (decorations: Decorations) => {
+    return vscode.commands.registerCommand('typst-math.copy-as-unicode', async () => {
+        let editor = vscode.window.activeTextEditor;
+        if (editor === undefined || editor.selection.isEmpty) { return; }
+        let document = editor.document;
+        let options = decorations.generateOptions();
+        let text = getWASM().render_to_unicode(document.getText(), document.offsetAt(editor.selection.start), document.offsetAt(editor.selection.end), options);
+        options.free();
+        await vscode.env.clipboard.writeText(text);
+    });
+};
@@ -0,0 +1,20 @@
+import * as vscode from 'vscode';
+import { Decorations } from '../decorations/decorations';
+import getWASM from '../wasmHelper';
+
+export const exportMarkdownCommand = (decorations: Decorations) => {
+    return vscode.commands.registerCommand('typst-math.export-markdown', async () => {
+        let editor = vscode.window.activeTextEditor;
+        if (editor === undefined) { return; }
+        let options = decorations.generateOptions();
+        let markdown = getWASM().export_markdown(editor.document.getText(), options);
+        options.free();
+        if (markdown.length === 0) {
+            vscode.window.showInformationMessage('No equation to export');
+            return;
+        }
+        // The export is opened as an untitled document, to be saved wherever it is needed
+        let document = await vscode.workspace.openTextDocument({ language: 'markdown', content: markdown });
+        await vscode.window.showTextDocument(document, vscode.ViewColumn.Beside);
+    });
+};
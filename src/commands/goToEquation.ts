@@ -0,0 +1,32 @@
+import * as vscode from 'vscode';
+import { Decorations } from '../decorations/decorations';
+import getWASM from '../wasmHelper';
+
+interface EquationItem extends vscode.QuickPickItem {
+    range: vscode.Range;
+}
+
+export const goToEquationCommand = (decorations: Decorations) => {
+    return vscode.commands.registerCommand('typst-math.go-to-equation', async () => {
+        let editor = vscode.window.activeTextEditor;
+        if (editor === undefined) { return; }
+        let options = decorations.generateOptions();
+        let equations = getWASM().list_equations(editor.document.getText(), options);
+        options.free();
+        let items = equations.map<EquationItem>((equation) => {
+            let position = equation.position;
+            let item = {
+                label: equation.unicode,
+                description: `${equation.block ? "Block" : "Inline"}, line ${position.start_line + 1}`,
+                range: new vscode.Range(position.start_line, position.start_column, position.end_line, position.end_column)
+            };
+            equation.free();
+            return item;
+        });
+        let item = await vscode.window.showQuickPick(items, { placeHolder: "Go to an equation", matchOnDescription: true });
+        if (item) {
+            editor.selection = new vscode.Selection(item.range.start, item.range.end);
+            editor.revealRange(item.range, vscode.TextEditorRevealType.InCenterIfOutsideViewport);
+        }
+    });
+};
@@ -0,0 +1,37 @@
+import * as vscode from 'vscode';
+import { Decorations } from '../decorations/decorations';
+import getWASM from '../wasmHelper';
+
+interface SymbolItem extends vscode.QuickPickItem {
+    name: string;
+}
+
+export const insertSymbolCommand = (decorations: Decorations) => {
+    return vscode.commands.registerCommand('typst-math.insert-symbol', async () => {
+        let editor = vscode.window.activeTextEditor;
+        if (editor === undefined) { return; }
+        let quickPick = vscode.window.createQuickPick<SymbolItem>();
+        quickPick.placeholder = "Search a symbol by name, like `arow r`";
+        quickPick.matchOnDescription = true;
+        quickPick.onDidChangeValue((query) => {
+            // Symbols are ranked by rust, VSCode only filters the items it is given
+            let options = decorations.generateOptions();
+            let symbols = getWASM().search_symbols(query, options, 50);
+            options.free();
+            quickPick.items = symbols.map((symbol) => {
+                let item = { label: symbol.symbol, description: symbol.name, name: symbol.name, alwaysShow: true };
+                symbol.free();
+                return item;
+            });
+        });
+        quickPick.onDidAccept(() => {
+            let item = quickPick.selectedItems[0];
+            if (item) {
+                editor?.insertSnippet(new vscode.SnippetString(item.name), editor.selection);
+            }
+            quickPick.hide();
+        });
+        quickPick.onDidHide(() => quickPick.dispose());
+        quickPick.show();
+    });
+};
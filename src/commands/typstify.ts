@@ -0,0 +1,18 @@
+import * as vscode from 'vscode';
+import getWASM from '../wasmHelper';
+
+export const typstifyCommand = vscode.commands.registerCommand('typst-math.typstify', async () => {
+    let editor = vscode.window.activeTextEditor;
+    if (editor === undefined) { return; }
+    let document = editor.document;
+    // Convert the selection, or the entire document if nothing is selected
+    let range = editor.selection.isEmpty ? new vscode.Range(document.positionAt(0), document.positionAt(document.getText().length)) : editor.selection;
+    let edits = getWASM().typstify(document.getText(), document.offsetAt(range.start), document.offsetAt(range.end));
+    await editor.edit((builder) => {
+        for (let edit of edits) {
+            let position = edit.position;
+            builder.replace(new vscode.Range(position.start_line, position.start_column, position.end_line, position.end_column), edit.text);
+            edit.free();
+        }
+    });
+});
@@ -1,10 +1,10 @@
 import * as vscode from 'vscode';
 import { createDecorationType, strictIntersection } from './helpers';
 import { Logger } from '../logger';
-import { blacklistedSymbols, getColors, getRenderingMode, customSymbols, renderSpaces, renderSymbolsOutsideMath, revealOffset, hideUnnecessaryDelimiters, reloadConfiguration } from '../utils';
+import { blacklistedSymbols, whitelistedSymbols, minSymbolLength, maxDepth, hiddenCategories, getColors, getRenderingMode, getScope, customSymbols, renderSpaces, renderSymbolsOutsideMath, revealOffset, renderVisibleLinesOnly, showSyntaxErrors, showUnknownSymbols, showLints, hideUnnecessaryDelimiters, renderInvisibleCharacters, renderAlignmentPoints, renderLatex, plainUnicode, symbolTable, functionRules, userColors, features, attachmentStyle, fonts, reloadConfiguration } from '../utils';
 import getWASM from '../wasmHelper';
 import { updateStatusBarItem } from '../statusbar';
-import { CustomSymbol } from 'typst-math-rust';
+import { CustomSymbol, Decoration, Diagnostic, Options, Lint, Parsed, Session, Severity, Suggestion } from 'typst-math-rust';
 
 export class Decorations {
     allDecorations: {
@@ -28,14 +28,40 @@ export class Decorations {
         };
     offset = 0; // Line offset of the edition, used to translate symbols
     activeEditor = vscode.window.activeTextEditor;
+    session: Session | undefined = undefined; // Source of the active document, reparsed incrementally by typst
+    uuids: string[] = []; // Uuids of the decoration ids of the session, each one being sent only once
+    delta_version = -1; // Version of the last delta applied to allDecorations, -1 if they don't follow a delta
+    rendered_lines: { start: number, end: number } | undefined = undefined; // Lines rendered when only visible lines are rendered
+    visible_timeout: NodeJS.Timeout | undefined = undefined;
     rendering = true;
     // VSCode settings
     renderingMode = getRenderingMode();
+    scope = getScope();
+    features = features();
+    attachmentStyle = attachmentStyle();
+    fonts = fonts();
     renderOutsideMath = renderSymbolsOutsideMath();
     renderSpaces = renderSpaces();
     hideUnnecessaryDelimiters = hideUnnecessaryDelimiters();
+    renderInvisible = renderInvisibleCharacters();
+    renderAlignPoints = renderAlignmentPoints();
+    renderLatex = renderLatex();
+    plainUnicode = plainUnicode();
+    symbolTable = symbolTable();
+    functionRules = functionRules();
     blacklistedSymbols = blacklistedSymbols();
+    whitelistedSymbols = whitelistedSymbols();
+    minSymbolLength = minSymbolLength();
+    maxDepth = maxDepth();
+    hiddenCategories = hiddenCategories();
     reveal_offset = revealOffset();
+    visibleLinesOnly = renderVisibleLinesOnly();
+    showSyntaxErrors = showSyntaxErrors();
+    syntaxErrors = vscode.languages.createDiagnosticCollection("typst-math");
+    showUnknownSymbols = showUnknownSymbols();
+    unknownSymbols = vscode.languages.createDiagnosticCollection("typst-math-symbols");
+    showLints = showLints();
+    lints = vscode.languages.createDiagnosticCollection("typst-math-lints");
     customSymbols: CustomSymbol[] = [];
 
     // generate a list of custom symbols
@@ -46,6 +72,74 @@ export class Decorations {
             this.customSymbols.push(getWASM().generate_custom_symbol(value.name, value.symbol, value.category));
         }
     }
+    // Generate the rendering options from the settings
+    generateOptions(): Options {
+        let options = new (getWASM().Options)();
+        options.set_rendering_mode(this.renderingMode);
+        for (let [name, enabled] of Object.entries(this.features)) {
+            try {
+                options.set_feature(name, enabled);
+            } catch (error) {
+                console.error(`Invalid feature: ${error}`);
+            }
+        }
+        let style = options.attachment_style;
+        style.scale = this.attachmentStyle.scale ?? style.scale;
+        style.top_offset = this.attachmentStyle.topOffset ?? style.top_offset;
+        style.bottom_offset = this.attachmentStyle.bottomOffset ?? style.bottom_offset;
+        style.limits_scale = this.attachmentStyle.limitsScale ?? style.limits_scale;
+        style.limits_offset = this.attachmentStyle.limitsOffset ?? style.limits_offset;
+        options.attachment_style = style;
+        let fonts = options.fonts;
+        fonts.math = this.fonts.math || fonts.math;
+        fonts.mono = this.fonts.mono || fonts.mono;
+        fonts.symbols = this.fonts.symbols || fonts.symbols;
+        fonts.fallback = this.fonts.fallback ?? fonts.fallback;
+        options.fonts = fonts;
+        options.render_outside_math = this.renderOutsideMath;
+        options.scope = this.scope;
+        options.render_spaces = this.renderSpaces;
+        options.hide_unnecessary_delimiters = this.hideUnnecessaryDelimiters;
+        options.render_invisible = this.renderInvisible;
+        options.render_align_points = this.renderAlignPoints;
+        options.render_latex = this.renderLatex;
+        options.plain_unicode = this.plainUnicode;
+        options.blacklisted_symbols = this.blacklistedSymbols;
+        options.whitelisted_symbols = this.whitelistedSymbols;
+        options.min_symbol_length = this.minSymbolLength;
+        options.max_depth = this.maxDepth;
+        for (let category of this.hiddenCategories) {
+            try {
+                options.hide_category(category);
+            } catch (error) {
+                console.error(`Invalid category: ${error}`);
+            }
+        }
+        for (let symbol of this.customSymbols) {
+            options.add_custom_symbol(symbol);
+        }
+        try {
+            options.add_symbol_table(this.symbolTable);
+        } catch (error) {
+            console.error(`Invalid symbol table: ${error}`);
+        }
+        try {
+            options.add_function_rules(this.functionRules);
+        } catch (error) {
+            console.error(`Invalid function rules: ${error}`);
+        }
+        for (let [name, color] of Object.entries(userColors())) {
+            if (color === "") {
+                continue;
+            }
+            try {
+                options.set_color(name, color);
+            } catch (error) {
+                console.error(`Invalid color: ${error}`);
+            }
+        }
+        return options;
+    }
     // Render decorations, while revealing current line
     renderDecorations() {
         console.time("renderDecorations");
@@ -93,6 +187,7 @@ export class Decorations {
             this.allDecorations[key].decorationType.dispose();
         }
         this.allDecorations = {};
+        this.delta_version = -1;
         this.edition_state = { // Force complete rendering next time
             reload_type: -2,
             edited_range: undefined,
@@ -104,10 +199,37 @@ export class Decorations {
         if (event.affectsConfiguration("typst-math")) {
             reloadConfiguration();
             this.renderingMode = getRenderingMode();
+            this.scope = getScope();
+            this.features = features();
+            this.attachmentStyle = attachmentStyle();
+            this.fonts = fonts();
             this.renderOutsideMath = renderSymbolsOutsideMath();
             this.renderSpaces = renderSpaces();
             this.hideUnnecessaryDelimiters = hideUnnecessaryDelimiters();
+            this.renderInvisible = renderInvisibleCharacters();
+            this.renderAlignPoints = renderAlignmentPoints();
+            this.renderLatex = renderLatex();
+            this.plainUnicode = plainUnicode();
+            this.visibleLinesOnly = renderVisibleLinesOnly();
+            this.showSyntaxErrors = showSyntaxErrors();
+            if (!this.showSyntaxErrors) {
+                this.syntaxErrors.clear();
+            }
+            this.showUnknownSymbols = showUnknownSymbols();
+            if (!this.showUnknownSymbols) {
+                this.unknownSymbols.clear();
+            }
+            this.showLints = showLints();
+            if (!this.showLints) {
+                this.lints.clear();
+            }
+            this.symbolTable = symbolTable();
+            this.functionRules = functionRules();
             this.blacklistedSymbols = blacklistedSymbols();
+            this.whitelistedSymbols = whitelistedSymbols();
+            this.minSymbolLength = minSymbolLength();
+            this.maxDepth = maxDepth();
+            this.hiddenCategories = hiddenCategories();
             this.reveal_offset = revealOffset();
             this.clearDecorations();
         }
@@ -121,18 +243,97 @@ export class Decorations {
             // Get symbols list
             this.generateCustomSymbols();
 
-            let start = this.edition_state.edited_range?.start.line === undefined ? -1 : this.edition_state.edited_range?.start.line;
-            let end = this.edition_state.edited_range?.end.line === undefined ? -1 : this.edition_state.edited_range?.end.line;
-            let parsed = getWASM().parse_document(this.activeEditor.document.getText() as string, start, end, this.renderingMode, this.renderOutsideMath, this.renderSpaces, this.hideUnnecessaryDelimiters, this.blacklistedSymbols, this.customSymbols);
-
-            // If edited lines aren't defined, we clear all ranges
-            // If they are defined, remove symbols whiwh were rendered again, and trnaslate ones after the edition
-            if (this.edition_state.reload_type < 0) {
-                // Reset ranges
+            let options = this.generateOptions();
+            let fresh = !this.session;
+            if (!this.session) {
+                this.session = new (getWASM().Session)(this.activeEditor.document.getText());
+            }
+            // Complete renderings only update the decorations which changed since the last delta
+            // Incremental renderings only traverse the part of the document reparsed since the last rendering
+            let decorations: Decoration[];
+            let erroneous: boolean;
+            let truncated: boolean;
+            let diagnostics: Diagnostic[];
+            // Incremental renderings only check the reparsed nodes, previous suggestions and lints are kept
+            let suggestions: Suggestion[] | undefined;
+            let lints: Lint[] | undefined;
+            if (this.edition_state.reload_type < 0 && this.visibleLinesOnly) {
+                this.rendered_lines = this.visibleLines(editor);
+                let parsed = this.session.parse_range(this.rendered_lines.start, this.rendered_lines.end, options);
+                options.free();
+                this.storeUuids(parsed.uuids_start, parsed.uuids);
                 for (let t in this.allDecorations) {
                     this.allDecorations[t].ranges = [];
                 }
+                this.delta_version = -1; // Decorations no longer follow the last delta
+                decorations = parsed.decorations;
+                erroneous = parsed.erroneous;
+                truncated = parsed.truncated;
+                diagnostics = parsed.diagnostics;
+                suggestions = parsed.suggestions;
+                lints = parsed.lints;
+            } else if (this.edition_state.reload_type < 0 && fresh) {
+                // The first rendering of a document shows the top of the document as soon as it is parsed
+                for (let t in this.allDecorations) {
+                    this.allDecorations[t].ranges = [];
+                }
+                decorations = [];
+                erroneous = false;
+                truncated = false;
+                diagnostics = [];
+                let streamed: Suggestion[] = [];
+                let streamedLints: Lint[] = [];
+                suggestions = streamed;
+                lints = streamedLints;
+                let first = true;
+                this.session.parse_streaming(options, (parsed: Parsed) => {
+                    this.storeUuids(parsed.uuids_start, parsed.uuids);
+                    this.addDecorations(parsed.decorations);
+                    erroneous = parsed.erroneous;
+                    truncated = parsed.truncated;
+                    diagnostics.push(...parsed.diagnostics);
+                    streamed.push(...parsed.suggestions);
+                    streamedLints.push(...parsed.lints);
+                    if (first) {
+                        first = false;
+                        this.renderDecorations();
+                    }
+                });
+                options.free();
+                this.delta_version = -1; // Decorations no longer follow the last delta
+            } else if (this.edition_state.reload_type < 0) {
+                let delta = this.session.parse_delta(options, this.delta_version);
+                options.free();
+                if (delta.reset) {
+                    for (let t in this.allDecorations) {
+                        this.allDecorations[t].ranges = [];
+                    }
+                }
+                this.storeUuids(delta.uuids_start, delta.uuids);
+                for (let id of delta.removed) {
+                    let uuid = this.uuids[id];
+                    if (this.allDecorations.hasOwnProperty(uuid)) {
+                        this.allDecorations[uuid].ranges = [];
+                    }
+                }
+                for (let decoration of delta.added) {
+                    let uuid = this.uuids[decoration.id];
+                    if (this.allDecorations.hasOwnProperty(uuid)) {
+                        this.allDecorations[uuid].ranges = [];
+                    }
+                }
+                this.delta_version = delta.version;
+                decorations = delta.added;
+                erroneous = delta.erroneous;
+                truncated = delta.truncated;
+                diagnostics = delta.diagnostics;
+                suggestions = delta.suggestions;
+                lints = delta.lints;
             } else {
+                let parsed = this.session.parse_edits(options);
+                options.free();
+                this.storeUuids(parsed.uuids_start, parsed.uuids);
+                // Remove symbols which were rendered again, and translate ones after the edition
                 let reparsed_range = new vscode.Range(new vscode.Position(parsed.edit_start_line, parsed.edit_start_column), new vscode.Position(parsed.edit_end_line, parsed.edit_end_column));
                 let edited_range = this.edition_state.edited_range as vscode.Range;
                 let selection_end = this.edition_state.selection_end as vscode.Position;
@@ -159,31 +360,25 @@ export class Decorations {
                         return !strictIntersection(reparsed_range, range.range) && document.lineAt(document.lineCount - 1).range.end.isAfterOrEqual(range.range.end);
                     });
                 }
+                this.delta_version = -1; // Decorations no longer follow the last delta
+                decorations = parsed.decorations;
+                erroneous = parsed.erroneous;
+                truncated = parsed.truncated;
+                diagnostics = parsed.diagnostics;
             }
 
-            for (let decoration of parsed.decorations) {
-                if (!this.allDecorations.hasOwnProperty(decoration.uuid)) {
-                    this.allDecorations[decoration.uuid] = {
-                        decorationType: createDecorationType({
-                            contentText: decoration.symbol,
-                            color: getColors(decoration.color),
-                            textDecoration: decoration.text_decoration
-                        }),
-                        ranges: []
-                    };
-                }
-                // Generate ranges with rust data
-                let ranges = decoration.positions.map<vscode.DecorationOptions>((pos) => {
-                    return {
-                        range: new vscode.Range(editor.document.positionAt(pos.start), editor.document.positionAt(pos.end)),
-                    };
-                });
-                this.allDecorations[decoration.uuid].ranges.push(...ranges);
+            this.addDecorations(decorations);
+            this.setSyntaxErrors(editor.document, diagnostics);
+            if (suggestions) {
+                this.setUnknownSymbols(editor.document, suggestions);
+            }
+            if (lints) {
+                this.setLints(editor.document, lints);
             }
             // Reset edited line
             // If there is an error in AST, force complete rendering next time, otherwise some symbols are never renderer after errors like missing $
             this.edition_state = {
-                reload_type: parsed.erroneous ? -2 : -1,
+                reload_type: erroneous ? -2 : -1,
                 edited_range: undefined,
                 selection_end: undefined
             };
@@ -191,6 +386,41 @@ export class Decorations {
             this.renderDecorations();
             updateStatusBarItem(this);
             Logger.info(`Loaded ${Object.keys(this.allDecorations).length} decorations`);
+            if (truncated) {
+                Logger.warn(`Parts of the document nested deeper than ${this.maxDepth} levels weren't rendered`);
+            }
+        }
+    }
+
+    // Lines around the visible ranges of an editor, with a screen of margin above and below
+    visibleLines(editor: vscode.TextEditor): { start: number, end: number } {
+        let start = Math.min(...editor.visibleRanges.map(range => range.start.line));
+        let end = Math.max(...editor.visibleRanges.map(range => range.end.line));
+        if (!isFinite(start) || !isFinite(end)) {
+            return { start: 0, end: editor.document.lineCount - 1 };
+        }
+        let margin = end - start + 1;
+        return { start: Math.max(0, start - margin), end: Math.min(editor.document.lineCount - 1, end + margin) };
+    }
+
+    // When only visible lines are rendered, render the document again when scrolling outside the rendered lines
+    onVisibleRangesChange(event: vscode.TextEditorVisibleRangesChangeEvent) {
+        if (this.visibleLinesOnly && this.activeEditor && event.textEditor === this.activeEditor && this.activeEditor.document.languageId === "typst" && this.rendering && this.renderingMode > 0) {
+            let rendered = this.rendered_lines;
+            if (rendered && event.visibleRanges.every(range => range.start.line >= rendered.start && range.end.line <= rendered.end)) {
+                return;
+            }
+            if (this.visible_timeout) {
+                clearTimeout(this.visible_timeout);
+            }
+            this.visible_timeout = setTimeout(async () => {
+                this.edition_state = { // Force complete rendering of the new lines
+                    reload_type: -2,
+                    edited_range: undefined,
+                    selection_end: undefined
+                };
+                this.reloadDecorations();
+            }, 100);
         }
     }
 
@@ -229,6 +459,14 @@ export class Decorations {
             if (event.contentChanges.length === 0) { return; }
             this.editing = true;
 
+            // Keep the session in sync with the document, it is created again from the text after multiple changes
+            if (this.session && event.contentChanges.length === 1) {
+                let change = event.contentChanges[0];
+                this.session.edit(change.rangeOffset, change.rangeOffset + change.rangeLength, change.text);
+            } else {
+                this.resetSession();
+            }
+
             // This part compute the edited range and update it for incremental rendering
             // negative values mean that next rendering will be complete, but -2 force it while -1 let the next edition change the edition range
             if (event.contentChanges.length > 1) { // too many changes, next rendering will be complete
@@ -268,9 +506,145 @@ export class Decorations {
         }
     }
 
+    // Describe the symbol rendered under the mouse, with its typst name and category
+    provideHover(document: vscode.TextDocument, position: vscode.Position): vscode.Hover | undefined {
+        if (!this.rendering || this.renderingMode === 0) { return undefined; }
+        let options = this.generateOptions();
+        let offset = document.offsetAt(position);
+        let hover = this.session && document === this.activeEditor?.document
+            ? this.session.symbol_at(offset, options)
+            : getWASM().symbol_at(document.getText(), offset, options);
+        options.free();
+        if (!hover) { return undefined; }
+        let category = getWASM().Category[hover.category].toLowerCase();
+        let range = new vscode.Range(hover.position.start_line, hover.position.start_column, hover.position.end_line, hover.position.end_column);
+        let contents = new vscode.MarkdownString(`${hover.symbol} \`${hover.name}\` (${category})`);
+        hover.free();
+        return new vscode.Hover(contents, range);
+    }
+
+    // Go to the declaration of an identifier of math bound by the user, like `RR` in `#let RR = $bb(R)$`
+    provideDefinition(document: vscode.TextDocument, position: vscode.Position): vscode.Location | undefined {
+        let offset = document.offsetAt(position);
+        let declaration = this.session && document === this.activeEditor?.document
+            ? this.session.definition_at(offset)
+            : getWASM().definition_at(document.getText(), offset);
+        if (!declaration) { return undefined; }
+        let range = new vscode.Range(declaration.start_line, declaration.start_column, declaration.end_line, declaration.end_column);
+        declaration.free();
+        return new vscode.Location(document.uri, range);
+    }
+
+    // Get the ranges of the identifiers to rename with the one at a position, empty if it isn't bound by the user
+    private renameRanges(document: vscode.TextDocument, position: vscode.Position): vscode.Range[] {
+        let offset = document.offsetAt(position);
+        let locations = this.session && document === this.activeEditor?.document
+            ? this.session.rename_locations(offset)
+            : getWASM().rename_locations(document.getText(), offset);
+        return locations.map((location) => {
+            let range = new vscode.Range(location.start_line, location.start_column, location.end_line, location.end_column);
+            location.free();
+            return range;
+        });
+    }
+
+    // Only identifiers of math bound by the user can be renamed, like `RR` in `#let RR = $bb(R)$`
+    prepareRename(document: vscode.TextDocument, position: vscode.Position): vscode.Range {
+        let range = this.renameRanges(document, position).find((range) => range.contains(position));
+        if (!range) {
+            throw new Error("Only identifiers of math bound by a let binding, an import or a parameter can be renamed");
+        }
+        return range;
+    }
+
+    provideRenameEdits(document: vscode.TextDocument, position: vscode.Position, newName: string): vscode.WorkspaceEdit {
+        let edit = new vscode.WorkspaceEdit();
+        for (let range of this.renameRanges(document, position)) {
+            edit.replace(document.uri, range, newName);
+        }
+        return edit;
+    }
+
+    // Show the syntax errors reported by typst as squiggles, if enabled
+    setSyntaxErrors(document: vscode.TextDocument, diagnostics: Diagnostic[]) {
+        if (!this.showSyntaxErrors) { return; }
+        this.syntaxErrors.set(document.uri, diagnostics.map((diagnostic) => {
+            let range = new vscode.Range(diagnostic.position.start_line, diagnostic.position.start_column, diagnostic.position.end_line, diagnostic.position.end_column);
+            return new vscode.Diagnostic(range, diagnostic.message, vscode.DiagnosticSeverity.Error);
+        }));
+    }
+
+    // Show the unknown identifiers of math with the known names they may be a typo of, if enabled
+    setUnknownSymbols(document: vscode.TextDocument, suggestions: Suggestion[]) {
+        if (!this.showUnknownSymbols) { return; }
+        this.unknownSymbols.set(document.uri, suggestions.map((suggestion) => {
+            let range = new vscode.Range(suggestion.position.start_line, suggestion.position.start_column, suggestion.position.end_line, suggestion.position.end_column);
+            let candidates = suggestion.candidates.map((candidate) => `\`${candidate}\``).join(", ");
+            let message = `Unknown symbol \`${suggestion.name}\`, did you mean ${candidates}?`;
+            return new vscode.Diagnostic(range, message, vscode.DiagnosticSeverity.Information);
+        }));
+    }
+
+    // Show the probable mistakes found in math, if enabled
+    setLints(document: vscode.TextDocument, lints: Lint[]) {
+        if (!this.showLints) { return; }
+        let severities = {
+            [Severity.Error]: vscode.DiagnosticSeverity.Error,
+            [Severity.Warning]: vscode.DiagnosticSeverity.Warning,
+            [Severity.Information]: vscode.DiagnosticSeverity.Information,
+            [Severity.Hint]: vscode.DiagnosticSeverity.Hint,
+        };
+        this.lints.set(document.uri, lints.map((lint) => {
+            let range = new vscode.Range(lint.position.start_line, lint.position.start_column, lint.position.end_line, lint.position.end_column);
+            let diagnostic = new vscode.Diagnostic(range, lint.message, severities[lint.severity]);
+            diagnostic.code = lint.code;
+            diagnostic.source = "typst-math";
+            return diagnostic;
+        }));
+    }
+
+    // Drop the session, it will be created again from the document at the next rendering
+    resetSession() {
+        this.session?.free();
+        this.session = undefined;
+        this.uuids = [];
+    }
+
+    // Add the ranges of decorations generated by rust, creating their decoration type if needed
+    addDecorations(decorations: Decoration[]) {
+        for (let decoration of decorations) {
+            let uuid = this.uuids[decoration.id];
+            if (!this.allDecorations.hasOwnProperty(uuid)) {
+                this.allDecorations[uuid] = {
+                    decorationType: createDecorationType({
+                        contentText: decoration.symbol,
+                        color: decoration.resolved_color ?? getColors(decoration.color),
+                        textDecoration: decoration.text_decoration
+                    }),
+                    ranges: []
+                };
+            }
+            // Generate ranges with rust data
+            let ranges = decoration.positions.map<vscode.DecorationOptions>((pos) => {
+                return {
+                    range: new vscode.Range(pos.start_line, pos.start_column, pos.end_line, pos.end_column),
+                };
+            });
+            this.allDecorations[uuid].ranges.push(...ranges);
+        }
+    }
+
+    // Add the uuids met for the first time by the session to the table
+    storeUuids(start: number, uuids: string[]) {
+        uuids.forEach((uuid, index) => {
+            this.uuids[start + index] = uuid;
+        });
+    }
+
     // When the editor change, update activeEditor and reload decorations
     onActiveTextEditorChange(editor: vscode.TextEditor | undefined) {
         this.activeEditor = editor;
+        this.resetSession();
         if (this.activeEditor) {
             this.clearDecorations(); // Clear decorations on the previous editor
             this.reloadDecorations();
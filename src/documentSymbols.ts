@@ -0,0 +1,19 @@
+import * as vscode from 'vscode';
+import { Decorations } from './decorations/decorations';
+import getWASM from './wasmHelper';
+
+// Show the labeled equations in the outline and the breadcrumbs
+export const documentSymbolProvider = (decorations: Decorations): vscode.DocumentSymbolProvider => ({
+    provideDocumentSymbols(document: vscode.TextDocument) {
+        let options = decorations.generateOptions();
+        let symbols = getWASM().equation_symbols(document.getText(), options);
+        options.free();
+        return symbols.map((symbol) => {
+            let range = (position: { start_line: number, start_column: number, end_line: number, end_column: number }) =>
+                new vscode.Range(position.start_line, position.start_column, position.end_line, position.end_column);
+            let documentSymbol = new vscode.DocumentSymbol(symbol.name, symbol.preview, vscode.SymbolKind.Constant, range(symbol.position), range(symbol.selection));
+            symbol.free();
+            return documentSymbol;
+        });
+    }
+});
@@ -0,0 +1,40 @@
+import * as vscode from 'vscode';
+import { Decorations } from './decorations/decorations';
+import { equationLayout, reloadConfiguration } from './utils';
+import getWASM from './wasmHelper';
+
+// Show the equation under the mouse laid out on several lines
+const provider = (decorations: Decorations): vscode.HoverProvider => ({
+    provideHover(document: vscode.TextDocument, position: vscode.Position) {
+        let offset = document.offsetAt(position);
+        let options = decorations.generateOptions();
+        let layout = getWASM().render_to_layout(document.getText(), offset, offset, options);
+        options.free();
+        if (!layout) { return undefined; }
+        return new vscode.Hover(new vscode.MarkdownString().appendCodeblock(layout, 'text'));
+    }
+});
+
+// Register the provider while the setting is enabled
+export const equationLayoutProvider = (decorations: Decorations): vscode.Disposable => {
+    let registration: vscode.Disposable | undefined;
+    let update = () => {
+        if (equationLayout() && !registration) {
+            registration = vscode.languages.registerHoverProvider('typst', provider(decorations));
+        } else if (!equationLayout() && registration) {
+            registration.dispose();
+            registration = undefined;
+        }
+    };
+    update();
+    let listener = vscode.workspace.onDidChangeConfiguration((event) => {
+        if (event.affectsConfiguration("typst-math.equationLayout")) {
+            reloadConfiguration();
+            update();
+        }
+    });
+    return new vscode.Disposable(() => {
+        listener.dispose();
+        registration?.dispose();
+    });
+};
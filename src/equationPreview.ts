@@ -0,0 +1,44 @@
+import * as vscode from 'vscode';
+import { equationPreview, reloadConfiguration } from './utils';
+import getWASM from './wasmHelper';
+
+// Only exported by WASM modules built with the `preview` feature
+type PreviewModule = { equation_svg?: (content: string, offset: number, fill: string) => string | undefined };
+const equationSvg = () => (getWASM() as unknown as PreviewModule).equation_svg;
+
+// Render the equation under the mouse with the typst compiler, for equations too complex to be decorated
+const provider: vscode.HoverProvider = {
+    provideHover(document: vscode.TextDocument, position: vscode.Position) {
+        let kind = vscode.window.activeColorTheme.kind;
+        let dark = kind === vscode.ColorThemeKind.Dark || kind === vscode.ColorThemeKind.HighContrast;
+        let svg = equationSvg()?.(document.getText(), document.offsetAt(position), dark ? '#ffffff' : '#000000');
+        if (!svg) { return undefined; }
+        let contents = new vscode.MarkdownString(`![Equation preview](data:image/svg+xml;base64,${Buffer.from(svg).toString('base64')})`);
+        return new vscode.Hover(contents);
+    }
+};
+
+// Register the provider while the setting is enabled, if the WASM module is built with the typst compiler
+export const equationPreviewProvider = (): vscode.Disposable => {
+    let registration: vscode.Disposable | undefined;
+    let update = () => {
+        let available = equationSvg() !== undefined;
+        if (equationPreview() && available && !registration) {
+            registration = vscode.languages.registerHoverProvider('typst', provider);
+        } else if (!equationPreview() && registration) {
+            registration.dispose();
+            registration = undefined;
+        }
+    };
+    update();
+    let listener = vscode.workspace.onDidChangeConfiguration((event) => {
+        if (event.affectsConfiguration("typst-math.equationPreview")) {
+            reloadConfiguration();
+            update();
+        }
+    });
+    return new vscode.Disposable(() => {
+        listener.dispose();
+        registration?.dispose();
+    });
+};
@@ -3,7 +3,25 @@ import { askForFonts, installFontsCommandProvider } from './commands/installFont
 import { mathCommand } from './commands/math';
 import { matrix2Command, matrix3Command, matrixCommand, squareMatrixCommand } from './commands/matrix';
 import { toggleSymbolsCommand } from './commands/toggleSymbols';
+import { insertSymbolCommand } from './commands/insertSymbol';
+import { copyAsUnicodeCommand } from './commands/copyAsUnicode';
+import { copyAsMathMLCommand } from './commands/copyAsMathML';
+import { copyAsLaTeXCommand } from './commands/copyAsLaTeX';
+import { copyAsTextCommand } from './commands/copyAsText';
+import { copyAsBrailleCommand } from './commands/copyAsBraille';
+import { exportMarkdownCommand } from './commands/exportMarkdown';
+import { typstifyCommand } from './commands/typstify';
+import { goToEquationCommand } from './commands/goToEquation';
 import { Logger } from './logger';
+import { documentSymbolProvider } from './documentSymbols';
+import { foldingRangeProvider } from './folding';
+import { inlayHintsProvider } from './inlayHints';
+import { codeActionProvider } from './codeActions';
+import { semanticTokensProvider } from './semanticTokens';
+import { abbreviationsProvider } from './abbreviations';
+import { equationPreviewProvider } from './equationPreview';
+import { spokenMathProvider } from './spokenMath';
+import { equationLayoutProvider } from './equationLayout';
 import { Decorations } from './decorations/decorations';
 import { initWASM } from './wasmHelper';
 import { initStatusBar, updateStatusBarItem } from './statusbar';
@@ -41,9 +59,46 @@ export async function activate(context: vscode.ExtensionContext) {
     vscode.window.onDidChangeActiveTextEditor(decorations.onActiveTextEditorChange.bind(decorations));
     vscode.workspace.onDidChangeTextDocument(decorations.onTextDocumentChange.bind(decorations));
     vscode.window.onDidChangeTextEditorSelection(decorations.onSelectionChange.bind(decorations));
+    vscode.window.onDidChangeTextEditorVisibleRanges(decorations.onVisibleRangesChange.bind(decorations));
+    // Hover tooltips of the rendered symbols
+    context.subscriptions.push(vscode.languages.registerHoverProvider('typst', { provideHover: decorations.provideHover.bind(decorations) }));
+    // Declarations of the identifiers of math
+    context.subscriptions.push(vscode.languages.registerDefinitionProvider('typst', { provideDefinition: decorations.provideDefinition.bind(decorations) }));
+    // Renaming of the identifiers of math, respecting the scopes of the bindings
+    context.subscriptions.push(vscode.languages.registerRenameProvider('typst', {
+        prepareRename: decorations.prepareRename.bind(decorations),
+        provideRenameEdits: decorations.provideRenameEdits.bind(decorations)
+    }));
+    // Labeled equations in the outline
+    context.subscriptions.push(vscode.languages.registerDocumentSymbolProvider('typst', documentSymbolProvider(decorations), { label: "Typst math" }));
+    // Folding of heavy math blocks
+    context.subscriptions.push(vscode.languages.registerFoldingRangeProvider('typst', foldingRangeProvider));
+    // Descriptions of the named arguments of math functions
+    context.subscriptions.push(vscode.languages.registerInlayHintsProvider('typst', inlayHintsProvider));
+    // Fixes of common mistakes in math
+    context.subscriptions.push(vscode.languages.registerCodeActionsProvider('typst', codeActionProvider, { providedCodeActionKinds: [vscode.CodeActionKind.QuickFix] }));
+    // Semantic tokens of math, if enabled
+    context.subscriptions.push(semanticTokensProvider(decorations));
+    // Abbreviations of the math input mode, if enabled
+    context.subscriptions.push(abbreviationsProvider(decorations));
+    // Previews of equations compiled by typst, if enabled
+    context.subscriptions.push(equationPreviewProvider());
+    // Spoken readings of equations, if enabled
+    context.subscriptions.push(spokenMathProvider(decorations));
+    // Multi-line layouts of equations, if enabled
+    context.subscriptions.push(equationLayoutProvider(decorations));
 
     // Register commands
     context.subscriptions.push(toggleSymbolsCommand(decorations));
+    context.subscriptions.push(insertSymbolCommand(decorations));
+    context.subscriptions.push(copyAsUnicodeCommand(decorations));
+    context.subscriptions.push(copyAsMathMLCommand(decorations));
+    context.subscriptions.push(copyAsLaTeXCommand(decorations));
+    context.subscriptions.push(copyAsTextCommand(decorations));
+    context.subscriptions.push(copyAsBrailleCommand(decorations));
+    context.subscriptions.push(exportMarkdownCommand(decorations));
+    context.subscriptions.push(typstifyCommand);
+    context.subscriptions.push(goToEquationCommand(decorations));
     context.subscriptions.push(mathCommand);
     context.subscriptions.push(matrixCommand);
     context.subscriptions.push(squareMatrixCommand);
@@ -0,0 +1,13 @@
+import * as vscode from 'vscode';
+import getWASM from './wasmHelper';
+
+// Fold multi-line display equations and long `mat` or `cases` calls
+export const foldingRangeProvider: vscode.FoldingRangeProvider = {
+    provideFoldingRanges(document: vscode.TextDocument) {
+        return getWASM().folding_ranges(document.getText()).map((range) => {
+            let folding = new vscode.FoldingRange(range.start_line, range.end_line);
+            range.free();
+            return folding;
+        });
+    }
+};
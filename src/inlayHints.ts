@@ -0,0 +1,20 @@
+import * as vscode from 'vscode';
+import getWASM from './wasmHelper';
+
+// Describe the named arguments of math functions, like `[ … ]` after `delim: "["`
+export const inlayHintsProvider: vscode.InlayHintsProvider = {
+    provideInlayHints(document: vscode.TextDocument, range: vscode.Range) {
+        let hints: vscode.InlayHint[] = [];
+        for (let hint of getWASM().inlay_hints(document.getText())) {
+            let position = new vscode.Position(hint.position.start_line, hint.position.start_column);
+            if (range.contains(position)) {
+                let inlayHint = new vscode.InlayHint(position, hint.label);
+                inlayHint.tooltip = hint.tooltip;
+                inlayHint.paddingLeft = true;
+                hints.push(inlayHint);
+            }
+            hint.free();
+        }
+        return hints;
+    }
+};
@@ -0,0 +1,50 @@
+import * as vscode from 'vscode';
+import { Decorations } from './decorations/decorations';
+import { reloadConfiguration, semanticTokens } from './utils';
+import getWASM from './wasmHelper';
+
+// Token types in the order of the TokenKind enum of the WASM module
+export const semanticTokensLegend = new vscode.SemanticTokensLegend(['operator', 'relation', 'greek', 'number', 'function', 'delimiter']);
+
+// Classify the math source, so that themes highlight equations even when symbols aren't rendered
+const provider = (decorations: Decorations): vscode.DocumentSemanticTokensProvider => ({
+    provideDocumentSemanticTokens(document: vscode.TextDocument) {
+        let options = decorations.generateOptions();
+        let tokens = getWASM().semantic_tokens(document.getText(), options);
+        options.free();
+        let builder = new vscode.SemanticTokensBuilder(semanticTokensLegend);
+        for (let token of tokens) {
+            let position = token.position;
+            // Tokens can't span several lines
+            if (position.start_line === position.end_line) {
+                builder.push(position.start_line, position.start_column, position.end - position.start, token.kind);
+            }
+            token.free();
+        }
+        return builder.build();
+    }
+});
+
+// Register the provider while the setting is enabled, it would otherwise replace the tokens of other typst extensions
+export const semanticTokensProvider = (decorations: Decorations): vscode.Disposable => {
+    let registration: vscode.Disposable | undefined;
+    let update = () => {
+        if (semanticTokens() && !registration) {
+            registration = vscode.languages.registerDocumentSemanticTokensProvider('typst', provider(decorations), semanticTokensLegend);
+        } else if (!semanticTokens() && registration) {
+            registration.dispose();
+            registration = undefined;
+        }
+    };
+    update();
+    let listener = vscode.workspace.onDidChangeConfiguration((event) => {
+        if (event.affectsConfiguration("typst-math.semanticTokens")) {
+            reloadConfiguration();
+            update();
+        }
+    });
+    return new vscode.Disposable(() => {
+        listener.dispose();
+        registration?.dispose();
+    });
+};
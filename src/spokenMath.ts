@@ -0,0 +1,39 @@
+import * as vscode from 'vscode';
+import { Decorations } from './decorations/decorations';
+import { reloadConfiguration, spokenMath } from './utils';
+import getWASM from './wasmHelper';
+
+// Read the equation under the mouse in words, so that screen readers can speak it
+const provider = (decorations: Decorations): vscode.HoverProvider => ({
+    provideHover(document: vscode.TextDocument, position: vscode.Position) {
+        let options = decorations.generateOptions();
+        let spoken = getWASM().speak_equation(document.getText(), document.offsetAt(position), options);
+        options.free();
+        if (!spoken) { return undefined; }
+        return new vscode.Hover(new vscode.MarkdownString().appendText(spoken));
+    }
+});
+
+// Register the provider while the setting is enabled
+export const spokenMathProvider = (decorations: Decorations): vscode.Disposable => {
+    let registration: vscode.Disposable | undefined;
+    let update = () => {
+        if (spokenMath() && !registration) {
+            registration = vscode.languages.registerHoverProvider('typst', provider(decorations));
+        } else if (!spokenMath() && registration) {
+            registration.dispose();
+            registration = undefined;
+        }
+    };
+    update();
+    let listener = vscode.workspace.onDidChangeConfiguration((event) => {
+        if (event.affectsConfiguration("typst-math.spokenMath")) {
+            reloadConfiguration();
+            update();
+        }
+    });
+    return new vscode.Disposable(() => {
+        listener.dispose();
+        registration?.dispose();
+    });
+};
@@ -1,6 +1,7 @@
 
 import * as vscode from 'vscode';
 import { Decorations } from './decorations/decorations';
+import getWASM from './wasmHelper';
 
 let statusBarItem: vscode.StatusBarItem;
 
@@ -18,15 +19,51 @@ export function updateStatusBarItem(decorations: Decorations): void {
             const n = Object.values(decorations.allDecorations).length;
             if (n > 0) {
                 statusBarItem.text = `Decorations: ${n}`;
+                statusBarItem.tooltip = statisticsTooltip(decorations);
                 statusBarItem.show();
             } else {
                 statusBarItem.hide();
             }
         } else {
             statusBarItem.text = `Decorations: $(eye-closed)`;
+            statusBarItem.tooltip = 'Toggle decorations rendering';
             statusBarItem.show();
         }
     } else {
         statusBarItem.hide();
     }
-}
\ No newline at end of file
+}
+
+// Summarize the math of the active document, symbols written in several ways hinting at inconsistent notations
+function statisticsTooltip(decorations: Decorations): vscode.MarkdownString | string {
+    if (!decorations.session) {
+        return 'Toggle decorations rendering';
+    }
+    let options = decorations.generateOptions();
+    let statistics = decorations.session.statistics(options);
+    options.free();
+    let lines = [
+        `**${statistics.equations}** equations (${statistics.block_equations} display), **${statistics.symbols.length}** distinct symbols, nesting up to ${statistics.max_nesting}`,
+    ];
+    let categories = statistics.categories.map((count) => {
+        let text = `${getWASM().Category[count.category].toLowerCase()} ${count.count}`;
+        count.free();
+        return text;
+    });
+    if (categories.length > 0) {
+        lines.push(`By category: ${categories.join(', ')}`);
+    }
+    let inconsistent: string[] = [];
+    for (let symbol of statistics.symbols) {
+        if (symbol.spellings.length > 1) {
+            inconsistent.push(`${symbol.symbol} written ${symbol.spellings.map((spelling) => `\`${spelling}\``).join(', ')}`);
+        }
+        symbol.free();
+    }
+    if (inconsistent.length > 0) {
+        lines.push(`Several notations: ${inconsistent.join('; ')}`);
+    }
+    statistics.free();
+    lines.push('Click to toggle decorations rendering');
+    return new vscode.MarkdownString(lines.join('\n\n'));
+}
@@ -15,6 +15,7 @@ interface Colors {
     group: string,
     operator: string,
     number: string,
+    differential: string,
 }
 
 // Default color themes
@@ -25,7 +26,8 @@ const darkTheme = {
     comparison: "#AE81FF",
     letter: "#A6E22E",
     group: "#66D9EF",
-    operator: "#FD971F"
+    operator: "#FD971F",
+    differential: "#E6DB74"
 };
 // Light theme based on the Monokai theme
 const lightTheme = {
@@ -34,7 +36,8 @@ const lightTheme = {
     comparison: "#EE0000",
     letter: "#795E26",
     group: "#008000",
-    operator: "#0070C1"
+    operator: "#0070C1",
+    differential: "#AF00DB"
 };
 
 
@@ -51,26 +54,27 @@ function enumToColorName(colorEnum: Color): keyof Colors {
         case getWASM().Color.Letter: return "letter";
         case getWASM().Color.Set: return "group";
         case getWASM().Color.Number: return "number";
+        case getWASM().Color.Differential: return "differential";
     }
 }
-// Get colors from settings
+// Get the default color of the theme, colors set by the user are resolved by the parser
 export function getColors(colorType: Color) {
+    const color = enumToColorName(colorType);
+    // Get the theme kind (light or dark)
+    const themeKind = vscode.window.activeColorTheme.kind;
+    if (themeKind === vscode.ColorThemeKind.Dark) {
+        return darkTheme[color];
+    } else {
+        return lightTheme[color];
+    }
+}
+// Retreive the colors set by the user
+export function userColors() {
     const colors = config.get<Colors>('colors');
     if (!colors) {
         throw new Error("Invalid colors");
     }
-    const color = enumToColorName(colorType);
-    if (colors[color] === "") {
-        // Get the theme kind (light or dark)
-        const themeKind = vscode.window.activeColorTheme.kind;
-        if (themeKind === vscode.ColorThemeKind.Dark) {
-            return darkTheme[color];
-        } else {
-            return lightTheme[color];
-        }
-    } else {
-        return colors[color];
-    }
+    return colors;
 }
 
 // Retreive the settings for decorations outside math mode
@@ -85,6 +89,61 @@ export function renderSpaces() {
 export function hideUnnecessaryDelimiters() {
     return config.get<boolean>('hideUnnecessaryDelimiters') || false;
 }
+// Retreive the settings for invisible characters rendering
+export function renderInvisibleCharacters() {
+    return config.get<boolean>('renderInvisibleCharacters') || false;
+}
+// Retreive the settings for alignment points rendering
+export function renderAlignmentPoints() {
+    return config.get<boolean>('renderAlignmentPoints') || false;
+}
+// Retreive the settings for LaTeX rendering
+export function renderLatex() {
+    const value = config.get<boolean>('renderLatex');
+    return value === undefined ? true : value;
+}
+// Retreive the settings for the unicode approximation of attachments, accents and roots
+export function plainUnicode() {
+    return config.get<boolean>('plainUnicode') || false;
+}
+// Retreive the settings for rendering only the visible lines
+export function renderVisibleLinesOnly() {
+    return config.get<boolean>('renderVisibleLinesOnly') || false;
+}
+// Retreive the settings for showing typst syntax errors
+export function showSyntaxErrors() {
+    return config.get<boolean>('showSyntaxErrors') || false;
+}
+// Retreive the settings for showing the unknown symbols of math with suggestions
+export function showUnknownSymbols() {
+    const value = config.get<boolean>('showUnknownSymbols');
+    return value === undefined ? true : value;
+}
+// Retreive the settings for showing the lints of math
+export function showLints() {
+    const value = config.get<boolean>('showLints');
+    return value === undefined ? true : value;
+}
+// Retreive the settings for the semantic tokens of math
+export function semanticTokens() {
+    return config.get<boolean>('semanticTokens') || false;
+}
+// Retreive the settings for the abbreviations of the math input mode
+export function mathInputMode() {
+    return config.get<boolean>('mathInputMode') || false;
+}
+// Retreive the settings for the previews of equations compiled by typst
+export function equationPreview() {
+    return config.get<boolean>('equationPreview') || false;
+}
+// Retreive the settings for the spoken readings of equations
+export function spokenMath() {
+    return config.get<boolean>('spokenMath') || false;
+}
+// Retreive the settings for the multi-line layouts of equations
+export function equationLayout() {
+    return config.get<boolean>('equationLayout') || false;
+}
 // Retreive the settings for space rendering
 export function revealOffset() {
     return config.get<number>('revealOffset') || 0;
@@ -112,12 +171,69 @@ export function customSymbols(): {
     }
     return user;
 }
+// Retreive the features enabled or disabled by the user
+export function features() {
+    return config.get<{ [name: string]: boolean }>('features') || {};
+}
+// Retreive the size and position of the attachments set by the user
+export function attachmentStyle() {
+    return config.get<{
+        scale?: number,
+        topOffset?: number,
+        bottomOffset?: number,
+        limitsScale?: number,
+        limitsOffset?: number,
+    }>('attachmentStyle') || {};
+}
+// Retreive the fonts set by the user
+export function fonts() {
+    return config.get<{
+        math?: string,
+        mono?: string,
+        symbols?: string,
+        fallback?: string[],
+    }>('fonts') || {};
+}
+// Retreive the symbol table as JSON
+export function symbolTable() {
+    return JSON.stringify(config.get<object>('symbolTable') || {});
+}
+// Retreive the function rules as JSON
+export function functionRules() {
+    return JSON.stringify(config.get<object>('functionRules') || {});
+}
+// Retreive the minimum length of symbol names
+export function minSymbolLength() {
+    return config.get<number>('minSymbolLength') || 0;
+}
+// Retreive the maximum depth of the rendered syntax tree
+export function maxDepth() {
+    return config.get<number>('maxDepth') || 256;
+}
+// Retreive the categories of hidden symbols
+export function hiddenCategories() {
+    return config.get<string[]>('hiddenCategories') || [];
+}
 // Retreive blacklisted symbols
 export function blacklistedSymbols() {
     return config.get<string[]>('blacklist') || [];
 }
+// Retreive whitelisted symbols
+export function whitelistedSymbols() {
+    return config.get<string[]>('whitelist') || [];
+}
 
 // Get the rendering mode
+export function getScope() {
+    let scope = config.get<string>('scope');
+    if (scope === "block") {
+        return getWASM().Scope.Block;
+    } else if (scope === "inline") {
+        return getWASM().Scope.Inline;
+    } else {
+        return getWASM().Scope.All;
+    }
+}
 export function getRenderingMode() {
     let mode = config.get<string>('renderingMode');
     if (mode === "nothing") {
@@ -37,7 +37,7 @@ pub fn symbols(stream: TokenStream) -> Result<TokenStream> {
         quote! { #symbol }
     });
     // Ok(quote! { stringify! { #(#pairs),* }})
-    Ok(quote! { phf_map! { #(#pairs),* } })
+    Ok(quote! { phf_ordered_map! { #(#pairs),* } })
 }
 
 struct Symbol {
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents a symbol color, passed to the frontend for styling.
+ */
+export type Color = "Keyword" | "Comparison" | "Operator" | "Letter" | "Set" | "Number" | "Differential";
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Color } from "./Color";
+import type { Position } from "./Position";
+
+/**
+ * Represents a content which will be replaced in VSCode, with a specific style, position and color
+ * id is the interned uuid used to identify decorations :
+ * - rust side: in the decoraions hasmap
+ * - js side: in the decorations array, to avoid generating the same decoration multiple times (Expensive)
+ */
+export type Decoration = { id: number, symbol: string, color: Color, text_decoration: string, 
+/**
+ * Css color set by the user for the color of the decoration, None to use the theme color
+ */
+resolved_color: string | null, positions: Array<Position>, };
@@ -0,0 +1,54 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Decoration } from "./Decoration";
+import type { Diagnostic } from "./Diagnostic";
+import type { Lint } from "./Lint";
+import type { Suggestion } from "./Suggestion";
+
+/**
+ * Represents the changes of the decorations since the previous result of a session
+ */
+export type Delta = { 
+/**
+ * Version of this result, to pass to the next call
+ */
+version: number, 
+/**
+ * The delta doesn't follow the version given, all decorations are added and previous ones must be cleared
+ */
+reset: boolean, 
+/**
+ * New decorations, or decorations whose symbol, style or positions changed
+ */
+added: Array<Decoration>, 
+/**
+ * Ids of the decorations which disappeared
+ */
+removed: Array<number>, 
+/**
+ * Ids of the decorations identical to the previous result
+ */
+unchanged: Array<number>, 
+/**
+ * Uuids of the ids met for the first time, the first one having the id `uuids_start`
+ */
+uuids: Array<string>, uuids_start: number, erroneous: boolean, 
+/**
+ * Syntax errors of the document
+ */
+diagnostics: Array<Diagnostic>, 
+/**
+ * Unknown identifiers of the document, with the known names they may be a typo of
+ */
+suggestions: Array<Suggestion>, 
+/**
+ * Probable mistakes found in the math of the document
+ */
+lints: Array<Lint>, 
+/**
+ * Some nodes were deeper than the maximum depth and weren't rendered
+ */
+truncated: boolean, 
+/**
+ * The parse was cancelled before its end, the delta is empty and the previous result is still the reference
+ */
+cancelled: boolean, };
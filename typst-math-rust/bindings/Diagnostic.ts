@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Position } from "./Position";
+
+/**
+ * Represents a syntax error reported by typst
+ */
+export type Diagnostic = { message: string, 
+/**
+ * Span of the error in the document, in UTF-16 offsets
+ */
+position: Position, };
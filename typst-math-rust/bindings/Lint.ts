@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Position } from "./Position";
+import type { Severity } from "./Severity";
+
+/**
+ * Represents a probable mistake in math found by a lint, like an unclosed delimiter
+ */
+export type Lint = { 
+/**
+ * Name of the lint, like `unclosed-delimiter`
+ */
+code: string, message: string, severity: Severity, 
+/**
+ * Span of the mistake in the document, in UTF-16 offsets
+ */
+position: Position, };
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Decoration } from "./Decoration";
+import type { Diagnostic } from "./Diagnostic";
+import type { Lint } from "./Lint";
+import type { Suggestion } from "./Suggestion";
+
+/**
+ * Represents the result of the parsing function
+ */
+export type Parsed = { decorations: Array<Decoration>, 
+/**
+ * Uuids of the ids met for the first time, the first one having the id `uuids_start`
+ */
+uuids: Array<string>, uuids_start: number, edit_start_line: number, edit_end_line: number, edit_start_column: number, edit_end_column: number, erroneous: boolean, 
+/**
+ * Syntax errors of the document, or of the node of a streamed result
+ */
+diagnostics: Array<Diagnostic>, 
+/**
+ * Unknown identifiers of the parsed nodes, with the known names they may be a typo of
+ */
+suggestions: Array<Suggestion>, 
+/**
+ * Probable mistakes found in the math of the parsed nodes
+ */
+lints: Array<Lint>, 
+/**
+ * Some nodes were deeper than the maximum depth and weren't rendered
+ */
+truncated: boolean, 
+/**
+ * The parse was cancelled before its end, decorations are incomplete
+ */
+cancelled: boolean, };
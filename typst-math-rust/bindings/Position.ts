@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents a symbol position in the document
+ */
+export type Position = { 
+/**
+ * UTF-16 offsets in the document, like VSCode offsets
+ */
+start: number, end: number, 
+/**
+ * Lines and UTF-16 columns of the offsets, like VSCode positions
+ */
+start_line: number, start_column: number, end_line: number, end_column: number, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Position } from "./Position";
+
+/**
+ * Represents a math identifier which isn't a known symbol, with the closest known names
+ */
+export type Suggestion = { 
+/**
+ * The unknown name, like `alpa`
+ */
+name: string, 
+/**
+ * Known names close to it, the closest first, like `alpha`
+ */
+candidates: Array<string>, 
+/**
+ * Span of the identifier in the document, in UTF-16 offsets
+ */
+position: Position, };
@@ -0,0 +1,5 @@
+fn main() {
+    // Link flags of the native Node addon
+    #[cfg(feature = "napi")]
+    napi_build::setup();
+}
@@ -0,0 +1,72 @@
+/*
+ * C API of typst-math-rust, built with `cargo build --release --features ffi`.
+ *
+ * Strings are UTF-8 and null-terminated, offsets are UTF-16.
+ * Every object returned by the library must be released with the matching `free` function.
+ */
+#ifndef TYPST_MATH_H
+#define TYPST_MATH_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Rendering options, opaque */
+typedef struct TypstMathOptions TypstMathOptions;
+
+/* Colors of the decorations */
+enum TypstMathColor {
+    TYPST_MATH_COLOR_KEYWORD = 0,
+    TYPST_MATH_COLOR_COMPARISON = 1,
+    TYPST_MATH_COLOR_OPERATOR = 2,
+    TYPST_MATH_COLOR_LETTER = 3,
+    TYPST_MATH_COLOR_SET = 4,
+    TYPST_MATH_COLOR_NUMBER = 5,
+};
+
+/* A decoration at a single position of the document */
+typedef struct TypstMathDecoration {
+    size_t start;
+    size_t end;
+    size_t start_line;
+    size_t start_column;
+    size_t end_line;
+    size_t end_column;
+    /* Text displayed instead of the range, empty to hide it */
+    char *symbol;
+    /* Css style of the symbol */
+    char *text_decoration;
+    /* Identifier shared by identical decorations */
+    char *uuid;
+    /* A TypstMathColor */
+    uint8_t color;
+} TypstMathDecoration;
+
+/* The decorations of a document */
+typedef struct TypstMathResult {
+    TypstMathDecoration *decorations;
+    size_t len;
+    bool erroneous;
+} TypstMathResult;
+
+/* Create rendering options with the default settings */
+TypstMathOptions *typst_math_options_new(void);
+/* Set the rendering mode, from 0 (nothing) to 3 (everything) */
+void typst_math_options_set_rendering_mode(TypstMathOptions *options, uint8_t rendering_mode);
+/* Enable or disable a feature by its name (`attachments`, `sqrt`...), return false if it is unknown */
+bool typst_math_options_set_feature(TypstMathOptions *options, const char *name, bool enabled);
+void typst_math_options_free(TypstMathOptions *options);
+
+/* Parse a document, with the default options if `options` is NULL. Return NULL if the source isn't valid UTF-8 */
+TypstMathResult *typst_math_parse(const char *source, const TypstMathOptions *options);
+void typst_math_result_free(TypstMathResult *result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,66 @@
+//! Parsing of several documents in a single call, to reduce the overhead of the WASM boundary
+
+#[cfg(not(feature = "coverage"))]
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    decorate,
+    interface::{DocumentResult, Options},
+    utils::interner::Interner,
+};
+
+/// Document waiting to be parsed in a batch
+struct Document {
+    uri: String,
+    source: typst_syntax::Source,
+    options: Options,
+}
+
+/// Documents parsed together, like all open editors of a workspace
+#[derive(Default)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct Batch {
+    documents: Vec<Document>,
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl Batch {
+    /// Create an empty batch
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(constructor))]
+    pub fn new() -> Batch {
+        Batch::default()
+    }
+    /// Add a document identified by its uri, with its own options
+    pub fn add(&mut self, uri: String, content: String, options: &Options) {
+        self.documents.push(Document {
+            uri,
+            source: typst_syntax::Source::detached(content),
+            options: options.clone(),
+        });
+    }
+    /// Number of documents in the batch
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+    /// Check if no document was added
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+    /// Get the decorations of every document, in the order they were added \
+    /// Each result has its own uuid table, starting at 0
+    pub fn parse(&self) -> Vec<DocumentResult> {
+        self.documents
+            .iter()
+            .map(|document| DocumentResult {
+                uri: document.uri.clone(),
+                parsed: decorate(
+                    &document.source,
+                    None,
+                    &document.options,
+                    &mut Interner::default(),
+                    None,
+                ),
+            })
+            .collect()
+    }
+}
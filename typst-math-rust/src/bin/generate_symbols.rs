@@ -0,0 +1,372 @@
+//! Generate the symbols table of `src/utils/symbols.rs` from typst's symbol definitions.
+//!
+//! Usage: `cargo run --bin generate_symbols -- <path to typst's sym.rs> [path to symbols.rs]` \
+//! The emoji table is updated the same way, with typst's `emoji.rs` and `src/utils/emoji.rs`.
+//!
+//! Symbols are read from the `symbols!` macro of typst (`crates/typst/src/symbols/sym.rs`),
+//! categories of already known symbols are kept, and new ones are inferred from their unicode block.
+//! Symbols which are no longer defined by typst (like the math-only `dif`) are kept at the end of the table.
+
+use std::{collections::HashMap, env, fs, process};
+
+/// Start of a symbols table, in typst and in this project
+const TABLE_START: &str = "symbols! {";
+
+/// A parsed symbol, with all its variants
+struct Entry {
+    /// Comment lines placed before the entry, like `// Delimiters.`
+    comments: Vec<String>,
+    name: String,
+    /// Variants, as (modifiers, char literal) pairs. The default variant has no modifiers
+    variants: Vec<(String, String)>,
+    /// Whether the entry was written as a list of variants
+    multiple: bool,
+    category: Option<String>,
+}
+
+/// Minimal parser for the `symbols!` macro syntax, with optional categories (`name: 'c'; Category,`)
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    source: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+            source,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Skip whitespaces and comments, returning comments written on their own line
+    fn skip_trivia(&mut self) -> Vec<String> {
+        let mut comments = vec![];
+        let mut line_start = false;
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                line_start = true;
+                self.pos += 1;
+            } else if c.is_whitespace() {
+                self.pos += 1;
+            } else if c == '/' && self.chars.get(self.pos + 1) == Some(&'/') {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+                if line_start {
+                    comments.push(self.chars[start..self.pos].iter().collect());
+                }
+            } else {
+                break;
+            }
+        }
+        comments
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_trivia();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", expected)))
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count();
+        format!(
+            "{} at line {}: `{}`",
+            message,
+            line + 1,
+            self.source.lines().nth(line).unwrap_or("").trim()
+        )
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        self.skip_trivia();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected an identifier"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    /// Parse a char literal, keeping its source representation
+    fn char_literal(&mut self) -> Result<String, String> {
+        self.expect('\'')?;
+        let start = self.pos - 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => self.pos += 1,
+                '\'' => return Ok(self.chars[start..self.pos].iter().collect()),
+                _ => {}
+            }
+        }
+        Err(self.error("unterminated char literal"))
+    }
+
+    fn variant(&mut self) -> Result<(String, String), String> {
+        self.skip_trivia();
+        if self.peek() == Some('\'') {
+            return Ok((String::new(), self.char_literal()?));
+        }
+        let mut modifiers = self.ident()?;
+        while self.peek() == Some('.') {
+            self.pos += 1;
+            modifiers.push('.');
+            modifiers.push_str(&self.ident()?);
+        }
+        self.expect(':')?;
+        Ok((modifiers, self.char_literal()?))
+    }
+
+    fn entries(&mut self) -> Result<Vec<Entry>, String> {
+        let mut entries = vec![];
+        loop {
+            let comments = self.skip_trivia();
+            if self.peek().is_none() || self.peek() == Some('}') {
+                return Ok(entries);
+            }
+            let name = self.ident()?;
+            self.expect(':')?;
+            self.skip_trivia();
+            let (variants, multiple) = if self.peek() == Some('[') {
+                self.pos += 1;
+                let mut variants = vec![];
+                loop {
+                    self.skip_trivia();
+                    if self.peek() == Some(']') {
+                        self.pos += 1;
+                        break;
+                    }
+                    variants.push(self.variant()?);
+                    self.skip_trivia();
+                    if self.peek() == Some(',') {
+                        self.pos += 1;
+                    }
+                }
+                (variants, true)
+            } else {
+                (vec![(String::new(), self.char_literal()?)], false)
+            };
+            self.skip_trivia();
+            let category = if self.peek() == Some(';') {
+                self.pos += 1;
+                Some(self.ident()?)
+            } else {
+                None
+            };
+            self.skip_trivia();
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            }
+            entries.push(Entry {
+                comments,
+                name,
+                variants,
+                multiple,
+                category,
+            });
+        }
+    }
+}
+
+/// Parse the content of the `symbols!` macro following `start`
+fn parse_table(source: &str, start: &str) -> Result<Vec<Entry>, String> {
+    let index = source
+        .find(start)
+        .ok_or_else(|| format!("`{}` not found", start))?;
+    let table = &source[index + start.len()..];
+    Parser::new(table).entries()
+}
+
+/// Decode a char literal like `'\u{2060}'` or `'α'`
+fn decode_char(literal: &str) -> Option<char> {
+    let inner = literal.strip_prefix('\'')?.strip_suffix('\'')?;
+    if let Some(hex) = inner.strip_prefix("\\u{") {
+        return char::from_u32(u32::from_str_radix(hex.strip_suffix('}')?, 16).ok()?);
+    }
+    let mut chars = inner.chars();
+    match (chars.next()?, chars.next()) {
+        ('\\', Some(c)) => Some(c),
+        (c, None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Infer the category of a new symbol from its unicode block
+fn infer_category(c: char) -> &'static str {
+    match c as u32 {
+        0x0391..=0x03FF | 0x1D6A8..=0x1D7CB | 0x05D0..=0x05EA => "Letter",
+        0x0020 | 0x00A0 | 0x2000..=0x200F | 0x202F | 0x205F | 0x2060 => "Space",
+        0x2190..=0x21FF | 0x27F0..=0x27FF | 0x2900..=0x297F | 0x2B00..=0x2BFF => "Comparison",
+        0x2200..=0x22FF | 0x2A00..=0x2AFF => "Operator",
+        0x1D538..=0x1D56B | 0x2102 | 0x210D | 0x2115 | 0x2119 | 0x211A | 0x211D | 0x2124 => "Set",
+        _ => "Default",
+    }
+}
+
+/// Format an entry the way `symbols.rs` is written
+fn format_entry(entry: &Entry, category: &str) -> String {
+    let variant = |(modifiers, literal): &(String, String)| {
+        if modifiers.is_empty() {
+            literal.clone()
+        } else {
+            format!("{}: {}", modifiers, literal)
+        }
+    };
+    if !entry.multiple {
+        return format!(
+            "    {}: {}; {},\n",
+            entry.name, entry.variants[0].1, category
+        );
+    }
+    let inline = format!(
+        "    {}: [{}]; {},",
+        entry.name,
+        entry
+            .variants
+            .iter()
+            .map(variant)
+            .collect::<Vec<_>>()
+            .join(", "),
+        category
+    );
+    if inline.chars().count() <= 100 {
+        return inline + "\n";
+    }
+    let mut result = format!("    {}: [\n", entry.name);
+    for v in &entry.variants {
+        result.push_str(&format!("        {},\n", variant(v)));
+    }
+    result.push_str(&format!("    ]; {},\n", category));
+    result
+}
+
+fn generate(typst_source: &str, current_source: &str) -> Result<String, String> {
+    let mut typst = parse_table(typst_source, TABLE_START)?;
+    // Typst keeps the last definition of a name (`emoji.man` is defined twice)
+    let mut index = 0;
+    while index < typst.len() {
+        if typst[index + 1..]
+            .iter()
+            .any(|e| e.name == typst[index].name)
+        {
+            typst.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+    let mut current = parse_table(current_source, TABLE_START)?;
+    // Categories of known chars, used for new symbols sharing a char with an existing one
+    let mut known_chars = HashMap::new();
+    for entry in &current {
+        for (_, literal) in &entry.variants {
+            if let Some(category) = &entry.category {
+                known_chars
+                    .entry(literal.clone())
+                    .or_insert_with(|| category.clone());
+            }
+        }
+    }
+
+    let mut table = String::new();
+    for mut entry in typst {
+        let known = current
+            .iter()
+            .position(|e| e.name == entry.name)
+            .map(|i| current.remove(i));
+        // Drop duplicated definitions
+        current.retain(|e| e.name != entry.name);
+        // Keep variants removed from typst, they can still be used with older versions
+        if let Some(known) = &known {
+            for v in &known.variants {
+                if !entry
+                    .variants
+                    .iter()
+                    .any(|(modifiers, _)| *modifiers == v.0)
+                {
+                    entry.variants.push(v.clone());
+                    entry.multiple = true;
+                }
+            }
+        }
+        let category = match known.and_then(|known| known.category) {
+            Some(category) => category,
+            None => match known_chars.get(&entry.variants[0].1) {
+                Some(category) => category.clone(),
+                None => {
+                    let c = decode_char(&entry.variants[0].1)
+                        .ok_or_else(|| format!("invalid char for `{}`", entry.name))?;
+                    infer_category(c).to_string()
+                }
+            },
+        };
+        if !entry.comments.is_empty() && !table.is_empty() {
+            table.push('\n');
+        }
+        for comment in &entry.comments {
+            table.push_str(&format!("    {}\n", comment.trim()));
+        }
+        table.push_str(&format_entry(&entry, &category));
+    }
+    if !current.is_empty() {
+        table.push_str("\n    // Not defined in the typst sym module.\n");
+        for entry in &current {
+            let category = entry.category.as_deref().unwrap_or("Default");
+            table.push_str(&format_entry(entry, category));
+        }
+    }
+
+    // Replace the table content
+    let start = current_source.find(TABLE_START).unwrap() + TABLE_START.len();
+    let end = start
+        + current_source[start..]
+            .find("\n};")
+            .ok_or("end of the symbols table not found")?;
+    Ok(format!(
+        "{}\n{}{}",
+        &current_source[..start],
+        table.trim_end_matches('\n'),
+        &current_source[end..]
+    ))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: generate_symbols <path to typst's sym.rs> [path to symbols.rs]");
+        process::exit(1);
+    }
+    let output = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| "src/utils/symbols.rs".to_string());
+    let read = |path: &str| {
+        fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("Cannot read {}: {}", path, err);
+            process::exit(1);
+        })
+    };
+    match generate(&read(&args[1]), &read(&output)) {
+        Ok(result) => fs::write(&output, result).expect("Cannot write the symbols table"),
+        Err(err) => {
+            eprintln!("Cannot generate the symbols table: {}", err);
+            process::exit(1);
+        }
+    }
+}
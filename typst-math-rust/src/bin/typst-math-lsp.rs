@@ -0,0 +1,619 @@
+//! Language server serving the rendering of typst math to editors without the VSCode extension.
+//!
+//! Usage: `typst-math-lsp`, talking LSP over stdin and stdout.
+//!
+//! Decorations are sent after each change of a document with the `typst-math/decorations` notification,
+//! whose params are `{ uri, parsed }`, `parsed` being the JSON result typed by `bindings/Parsed.ts`. \
+//! Hovers describe the rendered symbol under the cursor and completions are the symbols matching the word before the cursor. \
+//! Folding ranges cover multi-line display equations and `mat` or `cases` calls, and inlay hints describe
+//! the named arguments of math functions. Document symbols list the labeled equations, and definitions
+//! point to the declaration of the identifiers of math bound by the user, which can be renamed. \
+//! Code actions fix common mistakes, like `e^-x` whose script is only the sign or LaTeX commands like `\frac{a}{b}`. \
+//! Semantic tokens classify the math source, with the `relation`, `greek` and `delimiter` types added to the standard ones.
+
+use std::{collections::HashMap, error::Error};
+
+use lsp_server::{Connection, ExtractError, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification,
+    },
+    request::{
+        CodeActionRequest, Completion, DocumentSymbolRequest, FoldingRangeRequest, GotoDefinition,
+        HoverRequest, InlayHintRequest, PrepareRenameRequest, Rename, Request as LspRequest,
+        SemanticTokensFullRequest,
+    },
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionProviderCapability, CompletionItem,
+    CompletionItemKind, CompletionList, CompletionOptions, CompletionResponse, CompletionTextEdit,
+    DocumentSymbol, DocumentSymbolResponse, FoldingRange, FoldingRangeProviderCapability,
+    GotoDefinitionResponse, Hover, HoverContents, HoverProviderCapability, InlayHint,
+    InlayHintLabel, InlayHintTooltip, Location, MarkupContent, MarkupKind, OneOf, Position,
+    PrepareRenameResponse, Range, RenameOptions, SemanticToken, SemanticTokenType, SemanticTokens,
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions,
+    SemanticTokensServerCapabilities, ServerCapabilities, SymbolKind, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+use typst_math_rust::{
+    equation_symbols, folding_ranges, inlay_hints, quick_fixes, search_symbols, semantic_tokens,
+    Options, Session, TokenKind,
+};
+use typst_syntax::split_newlines;
+
+/// Name of the notification carrying the decorations of a document
+const DECORATIONS: &str = "typst-math/decorations";
+
+/// Maximum number of completions sent at once, the list being incomplete so that clients ask again while typing
+const COMPLETIONS: usize = 100;
+
+/// Types of the semantic tokens, in the order of their index in the legend
+const TOKEN_TYPES: [TokenKind; 6] = [
+    TokenKind::Operator,
+    TokenKind::Relation,
+    TokenKind::Greek,
+    TokenKind::Number,
+    TokenKind::Function,
+    TokenKind::Delimiter,
+];
+
+/// Get the name of a semantic token type
+fn token_type(kind: TokenKind) -> SemanticTokenType {
+    match kind {
+        TokenKind::Operator => SemanticTokenType::OPERATOR,
+        TokenKind::Relation => SemanticTokenType::new("relation"),
+        TokenKind::Greek => SemanticTokenType::new("greek"),
+        TokenKind::Number => SemanticTokenType::NUMBER,
+        TokenKind::Function => SemanticTokenType::FUNCTION,
+        TokenKind::Delimiter => SemanticTokenType::new("delimiter"),
+    }
+}
+
+/// Convert a position of the library into an LSP range
+fn range(position: &typst_math_rust::Position) -> Range {
+    Range::new(
+        Position::new(position.start_line as u32, position.start_column as u32),
+        Position::new(position.end_line as u32, position.end_column as u32),
+    )
+}
+
+/// Open documents, each one being reparsed incrementally
+struct Server {
+    connection: Connection,
+    documents: HashMap<Url, Session>,
+    options: Options,
+}
+
+impl Server {
+    /// Send the decorations of an open document
+    fn publish_decorations(&mut self, uri: &Url) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let Some(session) = self.documents.get_mut(uri) else {
+            return Ok(());
+        };
+        let parsed = session.parse(&self.options);
+        let params = serde_json::json!({ "uri": uri, "parsed": parsed });
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                DECORATIONS.to_string(),
+                params,
+            )))?;
+        Ok(())
+    }
+
+    fn handle_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: <DidOpenTextDocument as LspNotification>::Params =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), Session::new(&params.text_document.text));
+                self.publish_decorations(&uri)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: <DidChangeTextDocument as LspNotification>::Params =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                let Some(session) = self.documents.get_mut(&uri) else {
+                    return Ok(());
+                };
+                for change in params.content_changes {
+                    match change.range {
+                        Some(range) => {
+                            let start = session.offset_at(
+                                range.start.line as usize,
+                                range.start.character as usize,
+                            );
+                            let end = session
+                                .offset_at(range.end.line as usize, range.end.character as usize);
+                            match (start, end) {
+                                (Some(start), Some(end)) => session.edit(start, end, &change.text),
+                                _ => *session = Session::new(&change.text),
+                            }
+                        }
+                        None => *session = Session::new(&change.text),
+                    }
+                }
+                self.publish_decorations(&uri)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: <DidCloseTextDocument as LspNotification>::Params =
+                    serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn hover(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) =
+            request.extract::<<HoverRequest as LspRequest>::Params>(HoverRequest::METHOD)?;
+        let position = params.text_document_position_params;
+        let hover = self
+            .documents
+            .get(&position.text_document.uri)
+            .and_then(|session| {
+                let offset = session.offset_at(
+                    position.position.line as usize,
+                    position.position.character as usize,
+                )?;
+                session.symbol_at(offset, &self.options)
+            })
+            .map(|hover| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!(
+                        "{} `{}` ({})",
+                        hover.symbol,
+                        hover.name,
+                        format!("{:?}", hover.category).to_lowercase()
+                    ),
+                }),
+                range: Some(range(&hover.position)),
+            });
+        Ok(Response::new_ok(id, hover))
+    }
+
+    fn completion(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) =
+            request.extract::<<Completion as LspRequest>::Params>(Completion::METHOD)?;
+        let position = params.text_document_position.position;
+        let Some(session) = self
+            .documents
+            .get(&params.text_document_position.text_document.uri)
+        else {
+            return Ok(Response::new_ok(id, None::<CompletionResponse>));
+        };
+        // Symbol names are made of letters and dots (`arrow.r`)
+        // Lines are split like the positions of the session, `\r\n` and `\r` ending a line like `\n`
+        let text = session.text();
+        let line = split_newlines(&text)
+            .get(position.line as usize)
+            .copied()
+            .unwrap_or_default();
+        let mut column = 0;
+        let before: String = line
+            .chars()
+            .take_while(|c| {
+                column += c.len_utf16();
+                column <= position.character as usize
+            })
+            .collect();
+        let word: String = before
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '.')
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        if word.is_empty() {
+            return Ok(Response::new_ok(id, None::<CompletionResponse>));
+        }
+        let start = Position::new(
+            position.line,
+            position.character - word.encode_utf16().count() as u32,
+        );
+        let items = search_symbols(&word, &self.options, COMPLETIONS)
+            .into_iter()
+            .map(|symbol| CompletionItem {
+                label: symbol.name.clone(),
+                detail: Some(symbol.symbol),
+                kind: Some(CompletionItemKind::CONSTANT),
+                filter_text: Some(word.clone()),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+                    Range::new(start, position),
+                    symbol.name,
+                ))),
+                ..Default::default()
+            })
+            .collect();
+        Ok(Response::new_ok(
+            id,
+            Some(CompletionResponse::List(CompletionList {
+                is_incomplete: true,
+                items,
+            })),
+        ))
+    }
+
+    fn definition(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) =
+            request.extract::<<GotoDefinition as LspRequest>::Params>(GotoDefinition::METHOD)?;
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        let definition = self.documents.get(&uri).and_then(|session| {
+            let offset = session.offset_at(position.line as usize, position.character as usize)?;
+            let declaration = session.definition_at(offset)?;
+            Some(GotoDefinitionResponse::Scalar(Location::new(
+                uri.clone(),
+                range(&declaration),
+            )))
+        });
+        Ok(Response::new_ok(id, definition))
+    }
+
+    fn prepare_rename(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request.extract::<<PrepareRenameRequest as LspRequest>::Params>(
+            PrepareRenameRequest::METHOD,
+        )?;
+        let position = params.position;
+        let range = self
+            .documents
+            .get(&params.text_document.uri)
+            .and_then(|session| {
+                let offset =
+                    session.offset_at(position.line as usize, position.character as usize)?;
+                session
+                    .rename_locations(offset)
+                    .into_iter()
+                    .find(|location| location.start <= offset && offset <= location.end)
+                    .map(|location| PrepareRenameResponse::Range(range(&location)))
+            });
+        Ok(Response::new_ok(id, range))
+    }
+
+    fn rename(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request.extract::<<Rename as LspRequest>::Params>(Rename::METHOD)?;
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        let new_name = params.new_name;
+        let edit = self.documents.get(&uri).and_then(|session| {
+            let offset = session.offset_at(position.line as usize, position.character as usize)?;
+            let edits: Vec<TextEdit> = session
+                .rename_locations(offset)
+                .iter()
+                .map(|location| TextEdit::new(range(location), new_name.clone()))
+                .collect();
+            (!edits.is_empty()).then(|| WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)])))
+        });
+        Ok(Response::new_ok(id, edit))
+    }
+
+    fn document_symbols(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request.extract::<<DocumentSymbolRequest as LspRequest>::Params>(
+            DocumentSymbolRequest::METHOD,
+        )?;
+        let symbols = self
+            .documents
+            .get(&params.text_document.uri)
+            .map(|session| {
+                #[allow(deprecated)]
+                let symbols = equation_symbols(&session.text(), &self.options)
+                    .into_iter()
+                    .map(|symbol| DocumentSymbol {
+                        name: symbol.name,
+                        detail: Some(symbol.preview),
+                        kind: SymbolKind::CONSTANT,
+                        tags: None,
+                        deprecated: None,
+                        range: range(&symbol.position),
+                        selection_range: range(&symbol.selection),
+                        children: None,
+                    })
+                    .collect();
+                DocumentSymbolResponse::Nested(symbols)
+            });
+        Ok(Response::new_ok(id, symbols))
+    }
+
+    fn folding_ranges(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request
+            .extract::<<FoldingRangeRequest as LspRequest>::Params>(FoldingRangeRequest::METHOD)?;
+        let ranges = self
+            .documents
+            .get(&params.text_document.uri)
+            .map(|session| {
+                folding_ranges(&session.text())
+                    .into_iter()
+                    .map(|range| FoldingRange {
+                        start_line: range.start_line as u32,
+                        end_line: range.end_line as u32,
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>()
+            });
+        Ok(Response::new_ok(id, ranges))
+    }
+
+    fn inlay_hints(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request
+            .extract::<<InlayHintRequest as LspRequest>::Params>(InlayHintRequest::METHOD)?;
+        let lines = params.range.start.line as usize..=params.range.end.line as usize;
+        let hints = self
+            .documents
+            .get(&params.text_document.uri)
+            .map(|session| {
+                inlay_hints(&session.text())
+                    .into_iter()
+                    .filter(|hint| lines.contains(&hint.position.start_line))
+                    .map(|hint| InlayHint {
+                        position: Position::new(
+                            hint.position.start_line as u32,
+                            hint.position.start_column as u32,
+                        ),
+                        label: InlayHintLabel::String(hint.label),
+                        kind: None,
+                        text_edits: None,
+                        tooltip: Some(InlayHintTooltip::String(hint.tooltip)),
+                        padding_left: Some(true),
+                        padding_right: None,
+                        data: None,
+                    })
+                    .collect::<Vec<_>>()
+            });
+        Ok(Response::new_ok(id, hints))
+    }
+
+    fn code_actions(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request
+            .extract::<<CodeActionRequest as LspRequest>::Params>(CodeActionRequest::METHOD)?;
+        let uri = params.text_document.uri;
+        let (start, end) = (params.range.start, params.range.end);
+        let actions = self.documents.get(&uri).map(|session| {
+            quick_fixes(&session.text())
+                .into_iter()
+                .filter(|fix| {
+                    let fix = range(&fix.edit.position);
+                    fix.start <= end && start <= fix.end
+                })
+                .map(|fix| {
+                    let edit = TextEdit::new(range(&fix.edit.position), fix.edit.text);
+                    CodeActionOrCommand::CodeAction(CodeAction {
+                        title: fix.title,
+                        kind: Some(CodeActionKind::QUICKFIX),
+                        edit: Some(WorkspaceEdit::new(HashMap::from([(
+                            uri.clone(),
+                            vec![edit],
+                        )]))),
+                        ..Default::default()
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+        Ok(Response::new_ok(id, actions))
+    }
+
+    fn semantic_tokens(&self, request: Request) -> Result<Response, ExtractError<Request>> {
+        let (id, params) = request.extract::<<SemanticTokensFullRequest as LspRequest>::Params>(
+            SemanticTokensFullRequest::METHOD,
+        )?;
+        let Some(session) = self.documents.get(&params.text_document.uri) else {
+            return Ok(Response::new_ok(id, None::<SemanticTokens>));
+        };
+        // Tokens are encoded relatively to the previous one, and can't span several lines
+        let mut data = vec![];
+        let (mut line, mut column) = (0, 0);
+        for token in semantic_tokens(&session.text(), &self.options) {
+            let position = &token.position;
+            if position.start_line != position.end_line {
+                continue;
+            }
+            let delta_line = (position.start_line - line) as u32;
+            let delta_start = if delta_line == 0 {
+                position.start_column - column
+            } else {
+                position.start_column
+            } as u32;
+            data.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length: (position.end - position.start) as u32,
+                token_type: TOKEN_TYPES
+                    .iter()
+                    .position(|kind| *kind == token.kind)
+                    .unwrap_or_default() as u32,
+                token_modifiers_bitset: 0,
+            });
+            (line, column) = (position.start_line, position.start_column);
+        }
+        Ok(Response::new_ok(
+            id,
+            SemanticTokens {
+                result_id: None,
+                data,
+            },
+        ))
+    }
+
+    fn run(&mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        for message in self.connection.receiver.clone() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let result = match request.method.as_str() {
+                        HoverRequest::METHOD => self.hover(request),
+                        Completion::METHOD => self.completion(request),
+                        GotoDefinition::METHOD => self.definition(request),
+                        DocumentSymbolRequest::METHOD => self.document_symbols(request),
+                        PrepareRenameRequest::METHOD => self.prepare_rename(request),
+                        Rename::METHOD => self.rename(request),
+                        FoldingRangeRequest::METHOD => self.folding_ranges(request),
+                        InlayHintRequest::METHOD => self.inlay_hints(request),
+                        CodeActionRequest::METHOD => self.code_actions(request),
+                        SemanticTokensFullRequest::METHOD => self.semantic_tokens(request),
+                        _ => Ok(Response::new_err(
+                            request.id,
+                            lsp_server::ErrorCode::MethodNotFound as i32,
+                            format!("Unknown method {}", request.method),
+                        )),
+                    };
+                    let response = result.map_err(|error| format!("{:?}", error))?;
+                    self.connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        definition_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        inlay_hint_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        })),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: SemanticTokensLegend {
+                    token_types: TOKEN_TYPES.iter().copied().map(token_type).collect(),
+                    token_modifiers: vec![],
+                },
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    })?;
+    connection.initialize(capabilities)?;
+    let mut server = Server {
+        connection,
+        documents: HashMap::new(),
+        options: Options::default(),
+    };
+    server.run()?;
+    drop(server);
+    io_threads.join()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::{
+        DidChangeTextDocumentParams, DidOpenTextDocumentParams, HoverParams,
+        TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+        TextDocumentPositionParams, VersionedTextDocumentIdentifier,
+    };
+
+    /// Send a notification to the server and read the decorations it publishes
+    fn decorations(
+        client: &Connection,
+        method: &str,
+        params: impl serde::Serialize,
+    ) -> serde_json::Value {
+        let notification = Notification::new(method.to_string(), params);
+        client
+            .sender
+            .send(Message::Notification(notification))
+            .unwrap();
+        match client.receiver.recv().unwrap() {
+            Message::Notification(notification) if notification.method == DECORATIONS => {
+                notification.params
+            }
+            message => panic!("Unexpected message {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_server() {
+        let (connection, client) = Connection::memory();
+        let server = std::thread::spawn(move || {
+            let mut server = Server {
+                connection,
+                documents: HashMap::new(),
+                options: Options::default(),
+            };
+            server.run().map_err(|error| error.to_string())
+        });
+        let uri = Url::parse("file:///main.typ").unwrap();
+
+        let params = decorations(
+            &client,
+            DidOpenTextDocument::METHOD,
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(
+                    uri.clone(),
+                    "typst".to_string(),
+                    0,
+                    "$alpha$".to_string(),
+                ),
+            },
+        );
+        assert_eq!(params["uri"], uri.as_str());
+        assert_eq!(params["parsed"]["decorations"][0]["symbol"], "α");
+
+        // Incremental changes are applied to the open document
+        let params = decorations(
+            &client,
+            DidChangeTextDocument::METHOD,
+            DidChangeTextDocumentParams {
+                text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 1),
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(0, 1), Position::new(0, 6))),
+                    range_length: None,
+                    text: "beta".to_string(),
+                }],
+            },
+        );
+        assert_eq!(params["parsed"]["decorations"][0]["symbol"], "β");
+
+        let hover = HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(uri),
+                Position::new(0, 2),
+            ),
+            work_done_progress_params: Default::default(),
+        };
+        let request = Request::new(1.into(), HoverRequest::METHOD.to_string(), hover);
+        client.sender.send(Message::Request(request)).unwrap();
+        let Message::Response(response) = client.receiver.recv().unwrap() else {
+            panic!("Expected a response");
+        };
+        assert_eq!(response.id, 1.into());
+        let hover: Hover = serde_json::from_value(response.result.unwrap()).unwrap();
+        let HoverContents::Markup(contents) = hover.contents else {
+            panic!("Expected markdown");
+        };
+        assert_eq!(contents.value, "β `beta` (letter)");
+
+        let request = Request::new(2.into(), "unknown".to_string(), serde_json::Value::Null);
+        client.sender.send(Message::Request(request)).unwrap();
+        let Message::Response(response) = client.receiver.recv().unwrap() else {
+            panic!("Expected a response");
+        };
+        assert!(response.error.is_some());
+
+        drop(client);
+        assert_eq!(server.join().unwrap(), Ok(()));
+    }
+}
@@ -0,0 +1,98 @@
+//! Render typst documents in the terminal, with math written with unicode characters.
+//!
+//! Usage: `typst-math <command> <file.typ>`, reading the standard input if the file is `-`.
+//!
+//! - `render` prints the document with symbols replaced by their character, attachments written as superscripts
+//!   and subscripts when possible, and equation delimiters removed, ready for diffs and pagers.
+//! - `layout` prints each equation of the document on several lines, with fraction bars, stacked limits and
+//!   matrix grids, the equations being separated by empty lines.
+//! - `markdown` prints a Markdown document listing the equations with their label, typst source and unicode rendering.
+//! - `extmarks` prints the JSON list of the Neovim extmarks concealing the symbols, for editor plugins.
+//! - `overlays` prints the JSON list of the Emacs overlays displaying the symbols, for editor plugins.
+//!
+//! `typst-math serve` instead answers newline-delimited JSON requests (`parse`, `query`, `convert`) read on the
+//! standard input, one response line per request, so that sandboxed hosts can drive the engine without FFI.
+
+use std::{
+    env, fs,
+    io::{self, BufRead, Read, Write},
+    process,
+};
+
+use typst_math_rust::{
+    export_markdown, handle_request, parse_document_extmarks, parse_document_overlays,
+    render_to_layout, render_to_unicode, Options,
+};
+
+/// Answer the requests of the standard input until it is closed
+fn serve() -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(stdout, "{}", handle_request(&line))?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() == 2 && args[1] == "serve" {
+        if let Err(err) = serve() {
+            eprintln!("Cannot serve requests: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+    if args.len() != 3
+        || !matches!(
+            args[1].as_str(),
+            "render" | "layout" | "markdown" | "extmarks" | "overlays"
+        )
+    {
+        eprintln!(
+            "Usage: typst-math <render | layout | markdown | extmarks | overlays> <file.typ | ->"
+        );
+        eprintln!("       typst-math serve");
+        process::exit(1);
+    }
+    let path = &args[2];
+    let content = if path == "-" {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .unwrap_or_else(|err| {
+                eprintln!("Cannot read the standard input: {}", err);
+                process::exit(1);
+            });
+        content
+    } else {
+        fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("Cannot read {}: {}", path, err);
+            process::exit(1);
+        })
+    };
+    let options = Options::default();
+    match args[1].as_str() {
+        "extmarks" => {
+            let extmarks = parse_document_extmarks(&content, &options);
+            println!("{}", serde_json::to_string(&extmarks).unwrap());
+        }
+        "layout" => {
+            let len = content.encode_utf16().count();
+            println!("{}", render_to_layout(&content, 0, len, &options));
+        }
+        "markdown" => print!("{}", export_markdown(&content, &options)),
+        "overlays" => {
+            let overlays = parse_document_overlays(&content, &options);
+            println!("{}", serde_json::to_string(&overlays).unwrap());
+        }
+        _ => {
+            let len = content.encode_utf16().count();
+            println!("{}", render_to_unicode(&content, 0, len, &options));
+        }
+    }
+}
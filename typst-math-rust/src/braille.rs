@@ -0,0 +1,468 @@
+//! Nemeth braille of equations, written with the unicode braille patterns for braille displays
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    convert::{
+        attachment, call_arguments, fraction_parts, function_name, has_limits, matrix_rows,
+        parenthesized,
+    },
+    interface::Options,
+    parser::utils::find_symbol,
+    unicode::equation_at,
+    utils::{depth::DepthLimit, symbols::OPERATORS},
+};
+
+const SPACE: char = '⠀';
+const NUMERIC: char = '⠼';
+const CAPITAL: char = '⠠';
+const GREEK: char = '⠨';
+const BOLD: char = '⠸';
+const SUPERSCRIPT: char = '⠘';
+const SUBSCRIPT: char = '⠰';
+const BASELINE: char = '⠐';
+/// Multipurpose indicator, starting an expression modified by scripts above or below it
+const MODIFIED: char = '⠐';
+const UNDERSCRIPT: char = '⠩';
+const OVERSCRIPT: char = '⠣';
+const TERMINATION: char = '⠻';
+const RADICAL: char = '⠜';
+const INDEX: char = '⠣';
+
+/// Get the cell of a latin letter
+fn letter(c: char) -> Option<char> {
+    const LETTERS: &str = "⠁⠃⠉⠙⠑⠋⠛⠓⠊⠚⠅⠇⠍⠝⠕⠏⠟⠗⠎⠞⠥⠧⠺⠭⠽⠵";
+    let index = (c.to_ascii_lowercase() as usize).checked_sub('a' as usize)?;
+    c.is_ascii_alphabetic()
+        .then(|| LETTERS.chars().nth(index))
+        .flatten()
+}
+
+/// Get the cell of a digit, written in the lower part of the cell
+fn digit(c: char) -> Option<char> {
+    const DIGITS: &str = "⠴⠂⠆⠒⠲⠢⠖⠶⠦⠔";
+    DIGITS.chars().nth(c.to_digit(10)? as usize)
+}
+
+/// Get the cell of a greek letter, written after the greek indicator
+fn greek(c: char) -> Option<char> {
+    const GREEK_LETTERS: &str = "⠁⠃⠛⠙⠑⠵⠱⠹⠊⠅⠇⠍⠝⠭⠕⠏⠗⠎⠎⠞⠥⠋⠯⠽⠺";
+    let lower = c.to_lowercase().next()?;
+    let index = (lower as usize).checked_sub('α' as usize)?;
+    GREEK_LETTERS.chars().nth(index)
+}
+
+/// Get the cells of a symbol, and if it is a comparison spaced from its operands
+fn symbol(c: char) -> Option<(&'static str, bool)> {
+    Some(match c {
+        '+' => ("⠬", false),
+        '-' | '−' => ("⠤", false),
+        '±' => ("⠬⠤", false),
+        '×' => ("⠈⠡", false),
+        '⋅' | '·' | '*' | '∗' => ("⠡", false),
+        '÷' => ("⠨⠌", false),
+        '/' => ("⠸⠌", false),
+        '=' => ("⠨⠅", true),
+        '≠' => ("⠌⠨⠅", true),
+        '<' => ("⠐⠅", true),
+        '>' => ("⠨⠂", true),
+        '≤' => ("⠐⠅⠱", true),
+        '≥' => ("⠨⠂⠱", true),
+        '≈' => ("⠈⠱⠈⠱", true),
+        '≡' => ("⠸⠇", true),
+        '∈' => ("⠈⠑", true),
+        '⊂' => ("⠸⠐⠅", true),
+        '→' => ("⠫⠕", true),
+        '⇒' => ("⠫⠶⠕", true),
+        '∪' => ("⠨⠬", false),
+        '∩' => ("⠨⠩", false),
+        '∞' => ("⠠⠿", false),
+        '∂' => ("⠈⠙", false),
+        '∇' => ("⠨⠫", false),
+        '∑' => ("⠠⠨⠎", false),
+        '∏' => ("⠠⠨⠏", false),
+        '∫' => ("⠮", false),
+        '′' => ("⠄", false),
+        '!' => ("⠯", false),
+        ',' => ("⠠", false),
+        '…' | '⋯' => ("⠄⠄⠄", false),
+        '(' => ("⠷", false),
+        ')' => ("⠾", false),
+        '[' => ("⠈⠷", false),
+        ']' => ("⠈⠾", false),
+        '{' => ("⠨⠷", false),
+        '}' => ("⠨⠾", false),
+        '|' => ("⠳", false),
+        '‖' => ("⠳⠳", false),
+        _ => return None,
+    })
+}
+
+/// Writer of the Nemeth braille of an equation
+struct Writer<'a> {
+    options: &'a Options,
+    braille: String,
+    /// Indicators of the scripts the writer is in, from the outermost one
+    levels: Vec<char>,
+    /// Number of fractions written, to know if a fraction is nested in another one
+    fractions: usize,
+    /// Span of the last level indicator, replaced by the next one if nothing is written between them
+    last_level: (usize, usize),
+    /// Depth of the node being written, the nodes deeper than the maximum depth being skipped
+    limit: DepthLimit,
+}
+
+impl Writer<'_> {
+    /// Write a character, with the indicators it needs
+    fn char(&mut self, c: char) {
+        if let Some(cell) = digit(c) {
+            // Numbers at the start of the expression or after a space are written after the numeric indicator
+            if self.braille.is_empty() || self.braille.ends_with([SPACE, '\n']) {
+                self.braille.push(NUMERIC);
+            }
+            self.braille.push(cell);
+        } else if c == '.' && self.braille.ends_with(|last| "⠴⠂⠆⠒⠲⠢⠖⠶⠦⠔".contains(last))
+        {
+            self.braille.push('⠨');
+        } else if let Some(cell) = letter(c) {
+            if c.is_ascii_uppercase() {
+                self.braille.push(CAPITAL);
+            }
+            self.braille.push(cell);
+        } else if let Some(cell) = greek(c) {
+            if c.is_uppercase() {
+                self.braille.push(CAPITAL);
+            }
+            self.braille.push(GREEK);
+            self.braille.push(cell);
+        } else if let Some((cells, comparison)) = symbol(c) {
+            if comparison {
+                self.space();
+                self.braille.push_str(cells);
+                self.space();
+            } else {
+                self.braille.push_str(cells);
+            }
+        } else if c.is_whitespace() {
+            self.space();
+        } else {
+            self.braille.push(c);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.char(c);
+        }
+    }
+
+    fn space(&mut self) {
+        if !self.braille.is_empty() && !self.braille.ends_with([SPACE, '\n']) {
+            self.braille.push(SPACE);
+        }
+    }
+
+    /// Write the indicator of the current level of scripts, the baseline one if it isn't in a script
+    fn level(&mut self) {
+        if self.braille.len() == self.last_level.1 {
+            self.braille.truncate(self.last_level.0);
+        }
+        let start = self.braille.len();
+        if self.levels.is_empty() {
+            self.braille.push(BASELINE);
+        } else {
+            self.braille.extend(self.levels.clone());
+        }
+        self.last_level = (start, self.braille.len());
+    }
+
+    /// Write the children of a node, skipping the code embedded with `#`
+    fn sequence(&mut self, children: &[LinkedNode]) {
+        let mut embedded = false;
+        for child in children {
+            if std::mem::take(&mut embedded) {
+                continue;
+            }
+            match child.kind() {
+                SyntaxKind::Hash => embedded = true,
+                SyntaxKind::Linebreak => self.braille.push('\n'),
+                _ => self.write(child),
+            }
+        }
+    }
+
+    /// Write a node, nothing being written for spaces, code or nodes deeper than the maximum depth
+    fn write(&mut self, node: &LinkedNode) {
+        if !self.limit.enter() {
+            return;
+        }
+        self.write_node(node);
+        self.limit.leave();
+    }
+
+    fn write_node(&mut self, node: &LinkedNode) {
+        match node.kind() {
+            SyntaxKind::Math | SyntaxKind::MathDelimited => {
+                self.sequence(&node.children().collect::<Vec<_>>())
+            }
+            SyntaxKind::Text => self.text(node.text()),
+            SyntaxKind::Str => {
+                if let Some(text) = node.cast::<ast::Str>() {
+                    self.text(&text.get());
+                }
+            }
+            SyntaxKind::Escape => {
+                if let Some(escape) = node.cast::<ast::Escape>() {
+                    self.char(escape.get());
+                }
+            }
+            SyntaxKind::Shorthand => {
+                if let Some(shorthand) = node.cast::<ast::Shorthand>() {
+                    self.char(shorthand.get());
+                }
+            }
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                self.name(&node.get().clone().into_text())
+            }
+            SyntaxKind::MathAttach => self.attach(node),
+            SyntaxKind::MathPrimes => {
+                let count = node
+                    .cast::<ast::MathPrimes>()
+                    .map_or(1, |primes| primes.count());
+                self.text(&"′".repeat(count));
+            }
+            SyntaxKind::MathFrac => {
+                if let Some((num, denom)) = fraction_parts(node) {
+                    self.fraction(&num, &denom);
+                }
+            }
+            SyntaxKind::MathRoot => {
+                let index = node.cast::<ast::MathRoot>().and_then(|root| root.index());
+                if let Some(radicand) = node.children().last() {
+                    self.root(index.map(|index| index.to_string()), &radicand);
+                }
+            }
+            SyntaxKind::FuncCall => self.call(node),
+            SyntaxKind::Equation => {
+                if let Some(math) = node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::Math)
+                {
+                    self.write(&math);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Write an identifier or a field access, like `alpha`, `arrow.r` or `sin`
+    fn name(&mut self, name: &str) {
+        if OPERATORS.contains_key(name) {
+            // Abbreviated function names are followed by a space
+            self.text(name);
+            self.space();
+            return;
+        }
+        match find_symbol(name.to_string(), self.options) {
+            Some((_, symbol)) => self.text(&symbol),
+            None => self.text(name),
+        }
+    }
+
+    /// Write a script or an argument, the parentheses grouping it being removed like typst does (`x^(a + b)`)
+    fn unparen(&mut self, node: &LinkedNode) {
+        match parenthesized(node) {
+            Some(children) => self.sequence(&children),
+            None => self.write(node),
+        }
+    }
+
+    /// Write a fraction, with the indicators of complex fractions if it contains another fraction
+    fn fraction(&mut self, num: &LinkedNode, denom: &LinkedNode) {
+        let fractions = self.fractions;
+        let open = self.braille.len();
+        self.braille.push('⠹');
+        self.unparen(num);
+        let line = self.braille.len();
+        self.braille.push('⠌');
+        self.unparen(denom);
+        let close = self.braille.len();
+        self.braille.push('⠼');
+        if self.fractions > fractions {
+            for index in [close, line, open] {
+                self.braille.insert(index, CAPITAL);
+            }
+        }
+        self.fractions += 1;
+    }
+
+    /// Write a square root, or a root with an index
+    fn root(&mut self, index: Option<String>, radicand: &LinkedNode) {
+        if let Some(index) = index.filter(|index| index != "2") {
+            self.braille.push(INDEX);
+            let levels = std::mem::take(&mut self.levels);
+            self.text(&index);
+            self.levels = levels;
+        }
+        self.braille.push(RADICAL);
+        self.unparen(radicand);
+        self.braille.push(TERMINATION);
+    }
+
+    /// Write a script at a new level, then the indicator of the level it returns to
+    fn script(&mut self, indicator: char, script: &LinkedNode) {
+        self.levels.push(indicator);
+        self.level();
+        self.unparen(script);
+        self.levels.pop();
+    }
+
+    /// Write an attachment, with level indicators for scripts and as a modified expression for limits
+    fn attach(&mut self, node: &LinkedNode) {
+        let Some(attachment) = attachment(node) else {
+            return;
+        };
+        if has_limits(&attachment.base, self.options) {
+            self.braille.push(MODIFIED);
+            self.write(&attachment.base);
+            for (indicator, script) in [
+                (UNDERSCRIPT, &attachment.bottom),
+                (OVERSCRIPT, &attachment.top),
+            ] {
+                if let Some(script) = script {
+                    self.braille.push(indicator);
+                    self.unparen(script);
+                }
+            }
+            self.braille.push(TERMINATION);
+            return;
+        }
+        self.write(&attachment.base);
+        if let Some(primes) = &attachment.primes {
+            self.write(primes);
+        }
+        // Numeric subscripts of letters are written right after them, like `x_1`
+        let numeric = attachment.bottom.as_ref().is_some_and(|bottom| {
+            bottom.kind() == SyntaxKind::Text
+                && bottom.text().chars().all(|c| c.is_ascii_digit())
+                && attachment.base.kind() == SyntaxKind::Text
+                && self.levels.is_empty()
+        });
+        if let Some(bottom) = &attachment.bottom {
+            if numeric {
+                for c in bottom.text().chars() {
+                    self.braille.extend(digit(c));
+                }
+            } else {
+                self.script(SUBSCRIPT, bottom);
+            }
+        }
+        if let Some(top) = &attachment.top {
+            self.script(SUPERSCRIPT, top);
+        }
+        if attachment.top.is_some() || (attachment.bottom.is_some() && !numeric) {
+            self.level();
+        }
+    }
+
+    /// Write a function call, like `frac(a, b)`, `sqrt(x)` or `abs(x)`
+    fn call(&mut self, node: &LinkedNode) {
+        let name = function_name(node).unwrap_or_default();
+        let args = call_arguments(node);
+        let Some(first) = args.first() else {
+            self.name(&name);
+            return;
+        };
+        match name.as_str() {
+            "frac" if args.len() == 2 => self.fraction(first, &args[1]),
+            "sqrt" => self.root(None, first),
+            "root" if args.len() == 2 => {
+                let index = first.get().clone().into_text().to_string();
+                self.root(Some(index), &args[1]);
+            }
+            "abs" | "norm" => {
+                let bar = if name == "abs" { "⠳" } else { "⠳⠳" };
+                self.braille.push_str(bar);
+                self.unparen(first);
+                self.braille.push_str(bar);
+            }
+            // Accents are written above their argument, like a modified expression
+            "overline" | "macron" | "tilde" | "arrow" | "dot" => {
+                self.braille.push(MODIFIED);
+                self.unparen(first);
+                self.braille.push(OVERSCRIPT);
+                self.braille.push_str(match name.as_str() {
+                    "tilde" => "⠈⠱",
+                    "arrow" => "⠫⠕",
+                    "dot" => "⠡",
+                    _ => "⠱",
+                });
+                self.braille.push(TERMINATION);
+            }
+            "bold" => {
+                self.braille.push(BOLD);
+                self.unparen(first);
+            }
+            "mat" | "vec" => {
+                let rows = if name == "mat" {
+                    matrix_rows(&args)
+                } else {
+                    args.iter().map(|arg| vec![arg.clone()]).collect()
+                };
+                self.braille.push_str("⠈⠷");
+                for (index, cells) in rows.iter().enumerate() {
+                    if index > 0 {
+                        self.braille.push_str("⠸⠆");
+                        self.space();
+                    }
+                    for (index, cell) in cells.iter().enumerate() {
+                        if index > 0 {
+                            self.char(',');
+                            self.space();
+                        }
+                        self.unparen(cell);
+                    }
+                }
+                self.braille.push_str("⠈⠾");
+            }
+            "op" | "lr" | "display" | "inline" | "script" | "cramped" | "limits" | "scripts"
+            | "upright" | "italic" | "bb" | "cal" | "frak" | "sans" | "mono" | "hat" | "cancel" => {
+                self.unparen(first)
+            }
+            // Other functions are written with their arguments, like `sin ⠷x⠾`
+            _ => {
+                self.name(&name);
+                self.char('(');
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        self.char(',');
+                    }
+                    self.unparen(arg);
+                }
+                self.char(')');
+            }
+        }
+    }
+}
+
+/// Write the equation around a UTF-16 offset of a source in Nemeth braille, None if there is no equation there
+pub fn nemeth(source: &Source, offset: usize, options: &Options) -> Option<String> {
+    let offset = source.utf16_to_byte(offset)?;
+    let equation = equation_at(source, offset)?;
+    let mut writer = Writer {
+        options,
+        braille: String::new(),
+        levels: vec![],
+        fractions: 0,
+        last_level: (0, 0),
+        limit: DepthLimit::new(options.max_depth),
+    };
+    writer.write(&equation);
+    // The expression ends on the baseline
+    Some(
+        writer
+            .braille
+            .trim_end_matches([SPACE, BASELINE])
+            .to_string(),
+    )
+}
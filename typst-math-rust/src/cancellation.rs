@@ -0,0 +1,37 @@
+//! Cancellation of a parse in progress, when its result is no longer needed
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[cfg(not(feature = "coverage"))]
+use wasm_bindgen::prelude::*;
+
+/// Flag shared between the parser and its caller, checked before each node of the traversal
+#[derive(Clone, Default, Debug)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl CancellationToken {
+    /// Create a token which isn't cancelled
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(constructor))]
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+    /// Stop the parses using this token as soon as possible
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    /// Check if the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+    /// Allow the token to be used for a new parse
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,179 @@
+//! Parts of math nodes shared by the converters of equations into other notations (MathML, LaTeX, speech, braille)
+
+use typst_syntax::{LinkedNode, Source, SyntaxKind};
+
+use crate::{interface::Options, parser::utils::find_symbol, utils::symbols::OPERATORS};
+
+/// Large operators whose attachments are written above and below them in display style
+pub const LARGE_OPERATORS: &str = "∑∏∐⋀⋁⋂⋃⨀⨁⨂⨄⨆";
+
+/// Get the name of a called function, without the math module (`math.frac` -> `frac`)
+pub fn function_name(call: &LinkedNode) -> Option<String> {
+    let callee = call.children().next()?;
+    if !matches!(
+        callee.kind(),
+        SyntaxKind::MathIdent | SyntaxKind::FieldAccess
+    ) {
+        return None;
+    }
+    let name = callee.get().clone().into_text();
+    Some(name.strip_prefix("math.").unwrap_or(&name).to_string())
+}
+
+/// Get the positional arguments of a function call, the rows of `mat(1, 2; 3, 4)` being arrays
+pub fn call_arguments<'a>(call: &LinkedNode<'a>) -> Vec<LinkedNode<'a>> {
+    call.children()
+        .find(|child| child.kind() == SyntaxKind::Args)
+        .map(|args| {
+            args.children()
+                .filter(|arg| {
+                    !arg.kind().is_trivia()
+                        && !matches!(
+                            arg.kind(),
+                            SyntaxKind::LeftParen
+                                | SyntaxKind::RightParen
+                                | SyntaxKind::Comma
+                                | SyntaxKind::Semicolon
+                                | SyntaxKind::Named
+                        )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get the cells of the rows of a matrix from its arguments, a matrix without `;` being a single row
+pub fn matrix_rows<'a>(args: &[LinkedNode<'a>]) -> Vec<Vec<LinkedNode<'a>>> {
+    if !args.iter().any(|arg| arg.kind() == SyntaxKind::Array) {
+        return vec![args.to_vec()];
+    }
+    args.iter()
+        .map(|row| match row.kind() {
+            SyntaxKind::Array => row
+                .children()
+                .filter(|cell| !cell.kind().is_trivia() && cell.kind() != SyntaxKind::Comma)
+                .collect(),
+            _ => vec![row.clone()],
+        })
+        .collect()
+}
+
+/// Get the nodes between the parentheses grouping a script or an argument, removed like typst does (`x^(a + b)`) \
+/// None if the node isn't parenthesized
+pub fn parenthesized<'a>(node: &LinkedNode<'a>) -> Option<Vec<LinkedNode<'a>>> {
+    let mut children: Vec<LinkedNode> = node
+        .children()
+        .filter(|child| !child.kind().is_trivia())
+        .collect();
+    if children.len() < 2 {
+        return None;
+    }
+    let (first, last) = (&children[0], &children[children.len() - 1]);
+    let grouped = match node.kind() {
+        SyntaxKind::Math => {
+            first.kind() == SyntaxKind::LeftParen && last.kind() == SyntaxKind::RightParen
+        }
+        SyntaxKind::MathDelimited => first.text() == "(" && last.text() == ")",
+        _ => false,
+    };
+    if !grouped {
+        return None;
+    }
+    children.pop();
+    children.remove(0);
+    Some(children)
+}
+
+/// Get the delimiters written around the argument of a function, like `|` for `abs`
+pub fn delimiters(name: &str) -> Option<(char, char)> {
+    Some(match name {
+        "abs" => ('|', '|'),
+        "norm" => ('‖', '‖'),
+        "floor" => ('⌊', '⌋'),
+        "ceil" => ('⌈', '⌉'),
+        "round" => ('⌊', '⌉'),
+        _ => return None,
+    })
+}
+
+/// Get the numerator and the denominator of a fraction written with `/`
+pub fn fraction_parts<'a>(node: &LinkedNode<'a>) -> Option<(LinkedNode<'a>, LinkedNode<'a>)> {
+    let mut parts = node
+        .children()
+        .filter(|child| !child.kind().is_trivia() && child.kind() != SyntaxKind::Slash);
+    Some((parts.next()?, parts.next()?))
+}
+
+/// Check if the attachments of a base are written above and below it in display style, like the bounds of `sum` or `lim`
+pub fn has_limits(base: &LinkedNode, options: &Options) -> bool {
+    let symbol = match base.kind() {
+        SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+            let name = base.get().clone().into_text();
+            if let Some(limits) = OPERATORS.get(name.as_str()) {
+                return *limits;
+            }
+            find_symbol(name.to_string(), options).map(|(_, symbol)| symbol)
+        }
+        SyntaxKind::Text => Some(base.text().to_string()),
+        _ => None,
+    };
+    symbol.is_some_and(|symbol| LARGE_OPERATORS.contains(symbol.as_str()))
+}
+
+/// Parts of an attachment, like `x'_i^2`
+pub struct Attachment<'a> {
+    pub base: LinkedNode<'a>,
+    pub bottom: Option<LinkedNode<'a>>,
+    pub top: Option<LinkedNode<'a>>,
+    pub primes: Option<LinkedNode<'a>>,
+}
+
+/// Split an attachment into its base, its scripts and its primes
+pub fn attachment<'a>(node: &LinkedNode<'a>) -> Option<Attachment<'a>> {
+    let mut children = node.children().filter(|child| !child.kind().is_trivia());
+    let mut attachment = Attachment {
+        base: children.next()?,
+        bottom: None,
+        top: None,
+        primes: None,
+    };
+    while let Some(child) = children.next() {
+        match child.kind() {
+            SyntaxKind::Underscore => attachment.bottom = children.next(),
+            SyntaxKind::Hat => attachment.top = children.next(),
+            SyntaxKind::MathPrimes => attachment.primes = Some(child),
+            _ => {}
+        }
+    }
+    Some(attachment)
+}
+
+/// Get the equations crossing a range of UTF-16 offsets of a source, without the ones nested in another equation \
+/// An empty range gets the equation around it
+pub fn equations_in_range(source: &Source, start: usize, end: usize) -> Vec<LinkedNode<'_>> {
+    let len = source.len_bytes();
+    let start = source.utf16_to_byte(start).unwrap_or(len);
+    let end = source.utf16_to_byte(end).unwrap_or(len).max(start);
+    let mut equations = vec![];
+    find_outer_equations(&LinkedNode::new(source.root()), &mut equations);
+    equations.retain(|equation| {
+        let range = equation.range();
+        if start == end {
+            range.start <= start && start <= range.end
+        } else {
+            range.start < end && start < range.end
+        }
+    });
+    equations
+}
+
+/// Collect the equations of a node, without the ones nested in another equation
+fn find_outer_equations<'a>(node: &LinkedNode<'a>, equations: &mut Vec<LinkedNode<'a>>) {
+    for child in node.children() {
+        if child.kind() == SyntaxKind::Equation {
+            equations.push(child);
+        } else {
+            find_outer_equations(&child, equations);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Output formats for editors other than VSCode, without css styling
+
+use serde::Serialize;
+use typst_syntax::Source;
+#[cfg(not(feature = "coverage"))]
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    interface::{Parsed, Position},
+    unicode::plain_symbol,
+    utils::symbols::Color,
+};
+
+/// Represents a concealed range for Neovim's extmark API (`nvim_buf_set_extmark`), \
+/// with a 0-based line and byte columns like Neovim
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct Extmark {
+    pub line: usize,
+    pub start_col: usize,
+    pub end_col: usize,
+    /// Text displayed instead of the range, empty to hide it
+    pub conceal: String,
+    /// Highlight group of the symbol, like `TypstMathLetter`
+    pub hl_group: String,
+}
+
+/// Get the name of the highlight group of a color
+fn highlight_group(color: Color) -> String {
+    format!("TypstMath{:?}", color)
+}
+
+/// Get the line and the byte columns of a single-line position, None if it spans several lines
+fn line_columns(source: &Source, position: &Position) -> Option<(usize, usize, usize)> {
+    let start = source.utf16_to_byte(position.start)?;
+    let end = source.utf16_to_byte(position.end)?;
+    let line = source.byte_to_line(start)?;
+    if source.byte_to_line(end)? != line {
+        return None;
+    }
+    let line_start = source.line_to_byte(line)?;
+    Some((line, start - line_start, end - line_start))
+}
+
+/// Convert decorations into Neovim extmarks, sorted by position \
+/// Ranges spanning several lines can't be concealed and are skipped
+pub fn extmarks(source: &Source, parsed: &Parsed) -> Vec<Extmark> {
+    let mut extmarks = vec![];
+    for decoration in &parsed.decorations {
+        let uuid = parsed.uuid(decoration.id).unwrap_or_default();
+        let conceal = plain_symbol(&uuid, &decoration.symbol);
+        for position in &decoration.positions {
+            let Some((line, start_col, end_col)) = line_columns(source, position) else {
+                continue;
+            };
+            extmarks.push(Extmark {
+                line,
+                start_col,
+                end_col,
+                conceal: conceal.clone(),
+                hl_group: highlight_group(decoration.color),
+            });
+        }
+    }
+    extmarks.sort_by_key(|extmark| (extmark.line, extmark.start_col));
+    extmarks
+}
+
+/// Represents an overlay for Emacs (`make-overlay`), with 1-based character positions like Emacs points
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct EmacsOverlay {
+    pub start: usize,
+    pub end: usize,
+    /// `display` property of the overlay, empty to hide the range
+    pub display: String,
+    /// `face` property of the overlay, like `typst-math-letter`
+    pub face: String,
+}
+
+/// Convert decorations into Emacs overlays, sorted by position
+pub fn overlays(source: &Source, parsed: &Parsed) -> Vec<EmacsOverlay> {
+    // Byte ranges first, converted to characters in a single pass over the text
+    let mut ranges = vec![];
+    for decoration in &parsed.decorations {
+        let uuid = parsed.uuid(decoration.id).unwrap_or_default();
+        let display = plain_symbol(&uuid, &decoration.symbol);
+        let face = format!(
+            "typst-math-{}",
+            format!("{:?}", decoration.color).to_lowercase()
+        );
+        for position in &decoration.positions {
+            let (Some(start), Some(end)) = (
+                source.utf16_to_byte(position.start),
+                source.utf16_to_byte(position.end),
+            ) else {
+                continue;
+            };
+            ranges.push((start, end, display.clone(), face.clone()));
+        }
+    }
+    ranges.sort_by_key(|(start, end, _, _)| (*start, *end));
+    let text = source.text();
+    let mut offsets: Vec<usize> = ranges
+        .iter()
+        .flat_map(|(start, end, _, _)| [*start, *end])
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    let mut points = std::collections::HashMap::new();
+    let (mut byte, mut point) = (0, 1);
+    for offset in offsets {
+        point += text[byte..offset].chars().count();
+        byte = offset;
+        points.insert(offset, point);
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end, display, face)| EmacsOverlay {
+            start: points[&start],
+            end: points[&end],
+            display,
+            face,
+        })
+        .collect()
+}
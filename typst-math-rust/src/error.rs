@@ -0,0 +1,33 @@
+//! Errors of the public functions, returned to the host instead of panicking and killing the WASM instance
+
+use std::fmt;
+
+#[cfg(not(feature = "coverage"))]
+use wasm_bindgen::prelude::*;
+
+/// Error of a parse request which can't be fulfilled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The first edited line is after the last one
+    InvalidLines { start: usize, end: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLines { start, end } => {
+                write!(f, "Invalid edited lines {} to {}", start, end)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Thrown as a JS `Error` with the message of the error
+#[cfg(not(feature = "coverage"))]
+impl From<Error> for JsValue {
+    fn from(error: Error) -> Self {
+        JsError::new(&error.to_string()).into()
+    }
+}
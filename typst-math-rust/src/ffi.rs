@@ -0,0 +1,253 @@
+//! C API of the library, enabled with the `ffi` feature, for editors and tools which can't load the WASM module.
+//!
+//! Strings are UTF-8 and null-terminated, offsets are UTF-16 like in the WASM API. \
+//! Every object returned by the library must be released with the matching `free` function.
+//! The declarations are in `include/typst_math.h`.
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use crate::{decorate, interface::Options, utils::interner::Interner};
+
+/// A decoration at a single position of the document
+#[repr(C)]
+pub struct TypstMathDecoration {
+    pub start: usize,
+    pub end: usize,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    /// Text displayed instead of the range, empty to hide it
+    pub symbol: *mut c_char,
+    /// Css style of the symbol
+    pub text_decoration: *mut c_char,
+    /// Identifier shared by identical decorations
+    pub uuid: *mut c_char,
+    /// Index of the color: keyword, comparison, operator, letter, set, number
+    pub color: u8,
+}
+
+/// The decorations of a document
+#[repr(C)]
+pub struct TypstMathResult {
+    pub decorations: *mut TypstMathDecoration,
+    pub len: usize,
+    pub erroneous: bool,
+}
+
+/// Copy a string into a C string, dropping the null characters it may contain
+fn c_string(string: &str) -> *mut c_char {
+    CString::new(string.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Read a C string, None if it is null or not valid UTF-8
+///
+/// # Safety
+/// `string` must be null or point to a null-terminated string
+unsafe fn read_string<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Create rendering options with the default settings
+#[no_mangle]
+pub extern "C" fn typst_math_options_new() -> *mut Options {
+    Box::into_raw(Box::default())
+}
+
+/// Set the rendering mode of options, from 0 (nothing) to 3 (everything)
+///
+/// # Safety
+/// `options` must come from `typst_math_options_new`
+#[no_mangle]
+pub unsafe extern "C" fn typst_math_options_set_rendering_mode(
+    options: *mut Options,
+    rendering_mode: u8,
+) {
+    if let Some(options) = options.as_mut() {
+        options.set_rendering_mode(rendering_mode);
+    }
+}
+
+/// Enable or disable a feature of options by its name, return false if the feature is unknown
+///
+/// # Safety
+/// `options` must come from `typst_math_options_new` and `name` must be a null-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn typst_math_options_set_feature(
+    options: *mut Options,
+    name: *const c_char,
+    enabled: bool,
+) -> bool {
+    match (options.as_mut(), read_string(name)) {
+        (Some(options), Some(name)) => options.set_feature(name, enabled).is_ok(),
+        _ => false,
+    }
+}
+
+/// Release options
+///
+/// # Safety
+/// `options` must be null or come from `typst_math_options_new`, and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn typst_math_options_free(options: *mut Options) {
+    if !options.is_null() {
+        drop(Box::from_raw(options));
+    }
+}
+
+/// Parse a document and get its decorations, with the default options if `options` is null \
+/// Return null if the source isn't valid UTF-8
+///
+/// # Safety
+/// `source` must be a null-terminated string and `options` must be null or come from `typst_math_options_new`
+#[no_mangle]
+pub unsafe extern "C" fn typst_math_parse(
+    source: *const c_char,
+    options: *const Options,
+) -> *mut TypstMathResult {
+    let Some(content) = read_string(source) else {
+        return ptr::null_mut();
+    };
+    let default = Options::default();
+    let options = options.as_ref().unwrap_or(&default);
+    let source = typst_syntax::Source::detached(content.to_string());
+    let parsed = decorate(&source, None, options, &mut Interner::default(), None);
+    let mut decorations = vec![];
+    for decoration in &parsed.decorations {
+        let uuid = parsed.uuid(decoration.id).unwrap_or_default();
+        for position in &decoration.positions {
+            decorations.push(TypstMathDecoration {
+                start: position.start,
+                end: position.end,
+                start_line: position.start_line,
+                start_column: position.start_column,
+                end_line: position.end_line,
+                end_column: position.end_column,
+                symbol: c_string(&decoration.symbol),
+                text_decoration: c_string(&decoration.text_decoration),
+                uuid: c_string(&uuid),
+                color: decoration.color as u8,
+            });
+        }
+    }
+    let decorations = decorations.into_boxed_slice();
+    let len = decorations.len();
+    Box::into_raw(Box::new(TypstMathResult {
+        decorations: Box::into_raw(decorations) as *mut TypstMathDecoration,
+        len,
+        erroneous: parsed.erroneous,
+    }))
+}
+
+/// Release a result and its decorations
+///
+/// # Safety
+/// `result` must be null or come from `typst_math_parse`, and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn typst_math_result_free(result: *mut TypstMathResult) {
+    if result.is_null() {
+        return;
+    }
+    let result = Box::from_raw(result);
+    let decorations = Box::from_raw(ptr::slice_from_raw_parts_mut(
+        result.decorations,
+        result.len,
+    ));
+    for decoration in decorations.iter() {
+        drop(CString::from_raw(decoration.symbol));
+        drop(CString::from_raw(decoration.text_decoration));
+        drop(CString::from_raw(decoration.uuid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let string = |text: &str| CString::new(text).unwrap();
+        unsafe {
+            let options = typst_math_options_new();
+            assert!(typst_math_options_set_feature(
+                options,
+                string("attachments").as_ptr(),
+                true
+            ));
+            assert!(!typst_math_options_set_feature(
+                options,
+                string("unknown").as_ptr(),
+                true
+            ));
+            let result = typst_math_parse(string("$alpha$").as_ptr(), options);
+            let decorations = std::slice::from_raw_parts((*result).decorations, (*result).len);
+            assert_eq!(decorations.len(), 1);
+            assert_eq!(CStr::from_ptr(decorations[0].symbol).to_str(), Ok("α"));
+            assert_eq!(CStr::from_ptr(decorations[0].uuid).to_str(), Ok("alpha"));
+            assert_eq!((decorations[0].start, decorations[0].end), (1, 6));
+            typst_math_result_free(result);
+            typst_math_options_free(options);
+        }
+    }
+
+    #[test]
+    fn test_parse_default_options() {
+        let string = |text: &str| CString::new(text).unwrap();
+        unsafe {
+            // Every decoration position has its own strings, released with the result
+            let result = typst_math_parse(string("$x^2 + alpha alpha$").as_ptr(), ptr::null());
+            let decorations = std::slice::from_raw_parts((*result).decorations, (*result).len);
+            let read = |text: *mut c_char| CStr::from_ptr(text).to_str().unwrap().to_string();
+            let mut symbols: Vec<(usize, String, String)> = decorations
+                .iter()
+                .map(|decoration| {
+                    (
+                        decoration.start,
+                        read(decoration.symbol),
+                        read(decoration.uuid),
+                    )
+                })
+                .collect();
+            symbols.sort();
+            assert_eq!(
+                symbols,
+                vec![
+                    (2, "2".to_string(), "top--text-2".to_string()),
+                    (5, "+".to_string(), "-+".to_string()),
+                    (7, "α".to_string(), "alpha".to_string()),
+                    (13, "α".to_string(), "alpha".to_string()),
+                ]
+            );
+            assert!(!(*result).erroneous);
+            typst_math_result_free(result);
+
+            // Documents without decorations still return a result to release
+            let options = typst_math_options_new();
+            typst_math_options_set_rendering_mode(options, 0);
+            let result = typst_math_parse(string("$x^2$").as_ptr(), options);
+            assert_eq!((*result).len, 0);
+            typst_math_result_free(result);
+            typst_math_options_free(options);
+
+            // Invalid sources return null, and null pointers are ignored
+            let invalid = [0xff, 0];
+            assert!(typst_math_parse(invalid.as_ptr() as *const c_char, ptr::null()).is_null());
+            assert!(typst_math_parse(ptr::null(), ptr::null()).is_null());
+            assert!(!typst_math_options_set_feature(
+                ptr::null_mut(),
+                string("sqrt").as_ptr(),
+                true
+            ));
+            typst_math_result_free(ptr::null_mut());
+            typst_math_options_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Quick fixes of common mistakes in math, returned as text edits for code actions: scripts cut from their operand (`e^-x`)
+//! and LaTeX commands written in typst math (`\frac{a}{b}`)
+
+use typst_syntax::{LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    interface::{QuickFix, TextEdit},
+    parser::utils::{byte_range_to_utf16, utf16_position},
+    utils::symbols::{LATEX_COMMANDS, LATEX_LETTER_STYLES, OPERATORS, SYMBOLS},
+};
+
+/// Check if a node can be part of the operand following a script, like `x` in `e^-x` or `pi` in `e^2pi`
+fn is_operand(node: &LinkedNode) -> bool {
+    match node.kind() {
+        SyntaxKind::Text => node.text().starts_with(char::is_alphanumeric),
+        SyntaxKind::MathIdent
+        | SyntaxKind::FieldAccess
+        | SyntaxKind::FuncCall
+        | SyntaxKind::MathAttach => true,
+        _ => false,
+    }
+}
+
+/// Check if a script only attaches a sign, like `-` in `e^-x`
+fn is_sign(script: &LinkedNode) -> bool {
+    matches!(script.kind(), SyntaxKind::Text | SyntaxKind::Shorthand)
+        && matches!(script.text().as_str(), "-" | "+" | "−")
+}
+
+/// Check if a script is a number, like `2` in `e^2x`
+fn is_number(script: &LinkedNode) -> bool {
+    script.kind() == SyntaxKind::Text && script.text().starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Get the fix of an attachment whose last script is cut from the operand written right after it, like `e^-x`
+fn attachment_fix(source: &Source, attach: &LinkedNode) -> Option<QuickFix> {
+    let script = attach.children().last()?;
+    // Numbers in subscripts are usually indices, like `x_1y`
+    let superscript = script.prev_sibling_kind() == Some(SyntaxKind::Hat);
+    if !(is_sign(&script) || superscript && is_number(&script)) {
+        return None;
+    }
+    // Siblings glued to the attachment, without any space
+    let mut end = attach.range().end;
+    let mut next = attach.next_sibling();
+    while let Some(sibling) = next.filter(|sibling| sibling.offset() == end && is_operand(sibling))
+    {
+        end = sibling.range().end;
+        next = sibling.next_sibling();
+    }
+    if end == attach.range().end {
+        return None;
+    }
+    let start = script.offset();
+    let intended = source.get(start..end)?;
+    let range = byte_range_to_utf16(source, &(start..end))?;
+    Some(QuickFix {
+        title: format!("Wrap `{}` in parentheses", intended),
+        edit: TextEdit {
+            position: utf16_position(source, range),
+            text: format!("({})", intended),
+        },
+    })
+}
+
+/// LaTeX accents with the same name in typst
+const LATEX_ACCENTS: [&str; 8] = [
+    "hat", "tilde", "dot", "ddot", "check", "breve", "acute", "grave",
+];
+
+/// Get the typst function of a LaTeX command taking arguments between braces, with its number of arguments
+fn latex_function(name: &str) -> Option<(&'static str, usize)> {
+    if let Some(style) = LATEX_LETTER_STYLES.get(name) {
+        return Some((style, 1));
+    }
+    if let Some(accent) = LATEX_ACCENTS.iter().find(|accent| **accent == name) {
+        return Some((accent, 1));
+    }
+    Some(match name {
+        "frac" | "dfrac" | "tfrac" => ("frac", 2),
+        "binom" => ("binom", 2),
+        "vec" => ("arrow", 1),
+        "bar" | "overline" => ("overline", 1),
+        "underline" => ("underline", 1),
+        "overbrace" => ("overbrace", 1),
+        "underbrace" => ("underbrace", 1),
+        _ => return None,
+    })
+}
+
+/// Read the argument between braces starting at byte `start` of a text, spaces before it skipped \
+/// Return its content and the byte after the closing brace
+fn latex_group(text: &str, start: usize) -> Option<(&str, usize)> {
+    let open = start + text[start..].len() - text[start..].trim_start().len();
+    if !text[open..].starts_with('{') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut escaped = false;
+    for (index, c) in text[open..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&text[open + 1..open + index], open + index + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Convert the LaTeX command starting at byte `start` of a text into typst, None if it isn't known \
+/// Return the byte after the command and its arguments, and the typst replacing them
+fn latex_command(text: &str, start: usize) -> Option<(usize, String)> {
+    let name_start = start + 1;
+    let name_end = text[name_start..]
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .map_or(text.len(), |end| name_start + end);
+    let name = &text[name_start..name_end];
+    match name {
+        "" => None,
+        // Typst delimiters scale to their content by default, `\left.` being an invisible one
+        "left" | "right" => Some((
+            name_end + usize::from(text[name_end..].starts_with('.')),
+            String::new(),
+        )),
+        "text" => {
+            let (content, end) = latex_group(text, name_end)?;
+            Some((end, format!("\"{}\"", content)))
+        }
+        "operatorname" => {
+            let (content, end) = latex_group(text, name_end)?;
+            Some((end, format!("op(\"{}\")", content)))
+        }
+        "sqrt" => {
+            // Optional index between brackets, like `\sqrt[3]{x}`
+            let index = text[name_end..]
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+                .map(|(index, _)| index);
+            let group_start = index.map_or(name_end, |index| name_end + index.len() + 2);
+            let (content, end) = latex_group(text, group_start)?;
+            Some((
+                end,
+                match index {
+                    Some(index) => format!(
+                        "root({}, {})",
+                        latex_to_typst(index),
+                        latex_to_typst(content)
+                    ),
+                    None => format!("sqrt({})", latex_to_typst(content)),
+                },
+            ))
+        }
+        _ => {
+            if let Some((function, count)) = latex_function(name) {
+                let mut end = name_end;
+                let mut arguments = vec![];
+                for _ in 0..count {
+                    let (content, next) = latex_group(text, end)?;
+                    arguments.push(latex_to_typst(content));
+                    end = next;
+                }
+                return Some((end, format!("{}({})", function, arguments.join(", "))));
+            }
+            let symbol = LATEX_COMMANDS.get(name).copied().or_else(|| {
+                (SYMBOLS.contains_key(name) || OPERATORS.contains_key(name)).then_some(name)
+            })?;
+            // Keep the symbol apart from a following name, like `\alpha\beta`
+            let glued = text[name_end..].starts_with(|c: char| c.is_alphanumeric() || c == '\\');
+            Some((
+                name_end,
+                format!("{}{}", symbol, if glued { " " } else { "" }),
+            ))
+        }
+    }
+}
+
+/// Convert the LaTeX of an argument into typst, braces grouping like parentheses and unknown commands being kept
+fn latex_to_typst(text: &str) -> String {
+    let mut typst = String::new();
+    let mut index = 0;
+    while let Some(c) = text[index..].chars().next() {
+        if c == '\\' {
+            if let Some((end, replacement)) = latex_command(text, index) {
+                typst.push_str(&replacement);
+                index = end;
+                continue;
+            }
+            // Escaped character or unknown command, kept as is
+            let next = text[index + 1..].chars().next().map_or(0, char::len_utf8);
+            typst.push_str(&text[index..index + 1 + next]);
+            index += 1 + next;
+            continue;
+        }
+        typst.push(match c {
+            '{' => '(',
+            '}' => ')',
+            c => c,
+        });
+        index += c.len_utf8();
+    }
+    typst
+}
+
+/// Get the fix of a LaTeX command written in math, like `\alpha` which is `alpha` in typst
+fn latex_fix(source: &Source, escape: &LinkedNode) -> Option<QuickFix> {
+    if !escape.text()[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    // Arguments can't go past the end of the equation
+    let mut equation = escape.parent()?;
+    while equation.kind() != SyntaxKind::Equation {
+        equation = equation.parent()?;
+    }
+    let start = escape.offset();
+    let text = &source.text()[..equation.range().end];
+    let (end, replacement) = latex_command(text, start)?;
+    let latex = &text[start..end];
+    let range = byte_range_to_utf16(source, &(start..end))?;
+    Some(QuickFix {
+        title: if replacement.is_empty() {
+            format!("Remove `{}`", latex)
+        } else {
+            format!("Replace `{}` with `{}`", latex, replacement.trim_end())
+        },
+        edit: TextEdit {
+            position: utf16_position(source, range),
+            text: replacement,
+        },
+    })
+}
+
+/// Collect the fixes of a node and of its children, skipping the ones overlapping a previous fix \
+/// The tree is walked in the order of the document with an explicit stack, so that deeply nested documents don't overflow the stack
+fn collect(source: &Source, node: &LinkedNode, fixes: &mut Vec<QuickFix>) {
+    let mut stack: Vec<LinkedNode> = node.children().rev().collect();
+    while let Some(child) = stack.pop() {
+        let fix = match child.kind() {
+            SyntaxKind::MathAttach => attachment_fix(source, &child),
+            SyntaxKind::Escape => latex_fix(source, &child),
+            _ => None,
+        };
+        if let Some(fix) = fix {
+            let free = fixes
+                .last()
+                .is_none_or(|last| last.edit.position.end <= fix.edit.position.start);
+            if free {
+                fixes.push(fix);
+            }
+        }
+        stack.extend(child.children().rev());
+    }
+}
+
+/// List the quick fixes of the math of a source, sorted by position
+pub fn quick_fixes(source: &Source) -> Vec<QuickFix> {
+    let mut fixes = vec![];
+    collect(source, &LinkedNode::new(source.root()), &mut fixes);
+    fixes
+}
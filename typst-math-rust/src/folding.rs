@@ -0,0 +1,59 @@
+//! Folding ranges of heavy math: multi-line display equations and long `mat` or `cases` calls
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::interface::FoldingRange;
+
+/// Functions whose call is folded when it spans several lines
+const FOLDED_FUNCTIONS: [&str; 2] = ["mat", "cases"];
+
+/// Get the lines folded for a node, keeping its closing delimiter visible if it starts its line
+fn folding_range(source: &Source, node: &LinkedNode) -> Option<FoldingRange> {
+    let range = node.range();
+    let start_line = source.byte_to_line(range.start)?;
+    let mut end_line = source.byte_to_line(range.end)?;
+    let closing = node.rightmost_leaf()?.offset();
+    let line_start = source.line_to_byte(end_line)?;
+    if source.get(line_start..closing)?.trim().is_empty() {
+        end_line = end_line.saturating_sub(1);
+    }
+    (end_line > start_line).then_some(FoldingRange {
+        start_line,
+        end_line,
+    })
+}
+
+/// Check if a node is a call of a folded function, like `mat(...)` or `math.cases(...)`
+fn is_folded_call(node: &LinkedNode) -> bool {
+    let Some(call) = node.cast::<ast::FuncCall>() else {
+        return false;
+    };
+    let name = match call.callee() {
+        ast::Expr::MathIdent(ident) => ident.get().clone(),
+        ast::Expr::FieldAccess(access) => access.field().get().clone(),
+        _ => return false,
+    };
+    FOLDED_FUNCTIONS.contains(&name.as_str())
+}
+
+/// Collect the folding ranges of a node and of its children
+fn collect(source: &Source, node: &LinkedNode, ranges: &mut Vec<FoldingRange>) {
+    for child in node.children() {
+        let folded = match child.kind() {
+            SyntaxKind::Equation => child.cast::<ast::Equation>().is_some_and(|eq| eq.block()),
+            SyntaxKind::FuncCall => is_folded_call(&child),
+            _ => false,
+        };
+        if folded {
+            ranges.extend(folding_range(source, &child));
+        }
+        collect(source, &child, ranges);
+    }
+}
+
+/// List the folding ranges of the math of a source, sorted by their first line
+pub fn folding_ranges(source: &Source) -> Vec<FoldingRange> {
+    let mut ranges = vec![];
+    collect(source, &LinkedNode::new(source.root()), &mut ranges);
+    ranges
+}
@@ -0,0 +1,118 @@
+//! Inlay hints of the named arguments of math functions, describing their effect like `[ … ]` after `delim: "["`
+
+use typst_syntax::{
+    ast::{self, AstNode},
+    LinkedNode, Source, SyntaxKind,
+};
+
+use crate::{
+    interface::InlayHint,
+    parser::utils::{byte_range_to_utf16, utf16_position},
+};
+
+/// Get the closing delimiter of an opening one
+fn closing(delimiter: &str) -> Option<&'static str> {
+    Some(match delimiter {
+        "(" => ")",
+        "[" => "]",
+        "{" => "}",
+        "|" => "|",
+        "||" | "‖" => "‖",
+        "⟨" | "<" => "⟩",
+        "⌈" => "⌉",
+        "⌊" => "⌋",
+        "⟦" => "⟧",
+        _ => return None,
+    })
+}
+
+/// Get the label and the tooltip of a named argument of a math function, None if it isn't described
+fn describe(function: &str, name: &str, value: ast::Expr) -> Option<(String, &'static str)> {
+    match (function, name, value) {
+        ("mat" | "vec" | "cases", "delim", ast::Expr::Str(delimiter)) => {
+            let opening = delimiter.get();
+            let label = format!("{} … {}", opening, closing(&opening)?);
+            Some((label, "Delimiters around the content"))
+        }
+        ("mat" | "vec" | "cases", "delim", ast::Expr::None(_)) => {
+            Some(("no delimiters".to_string(), "Delimiters around the content"))
+        }
+        ("op", "limits", ast::Expr::Bool(limits)) => {
+            let label = if limits.get() {
+                "attachments as limits"
+            } else {
+                "attachments as scripts"
+            };
+            Some((
+                label.to_string(),
+                "Whether the attachments are displayed under and over the operator in display mode",
+            ))
+        }
+        ("lr", "size", ast::Expr::Numeric(size)) => Some((
+            format!("{} of the content", size.to_untyped().text()),
+            "Size of the delimiters, relative to the height of the content",
+        )),
+        ("accent", "size", ast::Expr::Numeric(size)) => Some((
+            format!("{} of the base", size.to_untyped().text()),
+            "Width of the accent, relative to the width of the base",
+        )),
+        ("cases", "reverse", ast::Expr::Bool(reverse)) => Some((
+            if reverse.get() {
+                "brace on the right"
+            } else {
+                "brace on the left"
+            }
+            .to_string(),
+            "Side of the brace",
+        )),
+        ("mat", "augment", ast::Expr::Int(column)) if column.get() > 0 => Some((
+            format!("line after column {}", column.get()),
+            "Vertical line separating the columns",
+        )),
+        _ => None,
+    }
+}
+
+/// Get the name of the function called by a math call, like `mat` for `math.mat(...)`
+fn function_name(call: &LinkedNode) -> Option<String> {
+    match call.cast::<ast::FuncCall>()?.callee() {
+        ast::Expr::MathIdent(ident) => Some(ident.get().to_string()),
+        ast::Expr::FieldAccess(access) => Some(access.field().get().to_string()),
+        _ => None,
+    }
+}
+
+/// Collect the hints of the named arguments of the math calls of a node and of its children, in the order of the document \
+/// The tree is walked with an explicit stack, so that deeply nested documents don't overflow the stack
+fn collect(source: &Source, node: &LinkedNode, hints: &mut Vec<InlayHint>) {
+    let mut stack: Vec<LinkedNode> = node.children().rev().collect();
+    while let Some(child) = stack.pop() {
+        if child.kind() == SyntaxKind::Named && child.parent_kind() == Some(SyntaxKind::Args) {
+            let function = child
+                .parent()
+                .and_then(|args| args.parent())
+                .and_then(function_name);
+            let named = child.cast::<ast::Named>();
+            let end = byte_range_to_utf16(source, &child.range()).map(|range| range.end);
+            if let (Some(function), Some(named), Some(end)) = (function, named, end) {
+                if let Some((label, tooltip)) =
+                    describe(&function, named.name().get(), named.expr())
+                {
+                    hints.push(InlayHint {
+                        position: utf16_position(source, end..end),
+                        label,
+                        tooltip: tooltip.to_string(),
+                    });
+                }
+            }
+        }
+        stack.extend(child.children().rev());
+    }
+}
+
+/// List the inlay hints of the named arguments of the math calls of a source, sorted by position
+pub fn inlay_hints(source: &Source) -> Vec<InlayHint> {
+    let mut hints = vec![];
+    collect(source, &LinkedNode::new(source.root()), &mut hints);
+    hints
+}
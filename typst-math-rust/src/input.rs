@@ -0,0 +1,116 @@
+//! Abbreviations of a math input mode: the name typed before the cursor (`\le`, `int`, `RR`) is matched against the symbol tables
+
+use std::collections::HashSet;
+
+use crate::{
+    interface::{InputCandidate, Options},
+    parser::utils::{find_symbol, list_symbols},
+    utils::symbols::{LATEX_COMMANDS, SYMBOLS},
+};
+
+/// Abbreviation typed before the cursor
+struct Abbreviation {
+    /// UTF-16 columns of the abbreviation in its line, backslash included
+    start: usize,
+    end: usize,
+    /// The abbreviation is a LaTeX command, like `\le`
+    latex: bool,
+    name: String,
+}
+
+/// Find the abbreviation ending at a UTF-16 column of a line, made of letters, digits and dots
+fn abbreviation(line: &str, column: usize) -> Option<Abbreviation> {
+    let mut end = 0;
+    let mut before = vec![];
+    for c in line.chars() {
+        if end + c.len_utf16() > column {
+            break;
+        }
+        end += c.len_utf16();
+        before.push(c);
+    }
+    let length = before
+        .iter()
+        .rev()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '.')
+        .count();
+    let name: String = before[before.len() - length..].iter().collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let latex = before.len() > length && before[before.len() - length - 1] == '\\';
+    Some(Abbreviation {
+        start: end - length - usize::from(latex),
+        end,
+        latex,
+        name,
+    })
+}
+
+/// Find the symbols named by a LaTeX command starting with a name, as (command, typst name) pairs
+fn latex_matches(name: &str) -> Vec<(&'static str, &'static str)> {
+    let commands = LATEX_COMMANDS
+        .entries()
+        .map(|(command, symbol)| (*command, *symbol));
+    // Commands with the same name in typst aren't listed in the table
+    let same_names = SYMBOLS
+        .keys()
+        .filter(|symbol| !symbol.contains('.') && !LATEX_COMMANDS.contains_key(symbol))
+        .map(|symbol| (*symbol, *symbol));
+    let mut matches: Vec<(&str, &str)> = commands
+        .chain(same_names)
+        .filter(|(command, _)| command.starts_with(name))
+        .collect();
+    matches.sort_by_key(|(command, _)| (*command != name, command.len(), *command));
+    matches
+}
+
+/// List the symbols matching the abbreviation typed before a UTF-16 column of a line, the exact match first \
+/// LaTeX commands like `\le` are matched against their typst equivalent, and other abbreviations against the names of the symbols
+pub fn input_candidates(
+    line: &str,
+    column: usize,
+    options: &Options,
+    limit: usize,
+) -> Vec<InputCandidate> {
+    let Some(abbreviation) = abbreviation(line, column) else {
+        return vec![];
+    };
+    let candidate = |name: String, symbol: String, category| InputCandidate {
+        name,
+        symbol,
+        category,
+        start: abbreviation.start,
+        end: abbreviation.end,
+    };
+    let mut candidates: Vec<InputCandidate> = if abbreviation.latex {
+        latex_matches(&abbreviation.name)
+            .into_iter()
+            .filter_map(|(_, name)| {
+                let (category, symbol) = find_symbol(name.to_string(), options)?;
+                Some(candidate(name.to_string(), symbol, category))
+            })
+            .collect()
+    } else {
+        let mut symbols: Vec<_> = list_symbols(options)
+            .into_iter()
+            .filter(|symbol| symbol.name.starts_with(&abbreviation.name))
+            .collect();
+        symbols.sort_by_key(|symbol| {
+            (
+                symbol.name != abbreviation.name,
+                symbol.name.split('.').count(),
+                symbol.name.len(),
+            )
+        });
+        symbols
+            .into_iter()
+            .map(|symbol| candidate(symbol.name, symbol.symbol, symbol.category))
+            .collect()
+    };
+    // Several commands can name the same symbol, like `\le` and `\leq`
+    let mut seen = HashSet::new();
+    candidates.retain(|candidate| seen.insert(candidate.name.clone()));
+    candidates.truncate(limit);
+    candidates
+}
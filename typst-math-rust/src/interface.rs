@@ -1,56 +1,350 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
-use crate::utils::symbols::Color;
+use crate::cancellation::CancellationToken;
+use crate::parser::overlaps::Priority;
+use crate::utils::{
+    styles::is_css_color,
+    symbols::{get_category_by_name, get_color_by_name, Category, Color},
+};
 
 /// Represents a content which will be replaced in VSCode, with a specific style, position and color
-/// uuid is used to identify decorations :
+/// id is the interned uuid used to identify decorations :
 /// - rust side: in the decoraions hasmap
 /// - js side: in the decorations array, to avoid generating the same decoration multiple times (Expensive)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
 pub struct Decoration {
-    pub uuid: String,
+    pub id: u32,
     pub symbol: String,
     pub color: Color,
     pub text_decoration: String,
+    /// Css color set by the user for the color of the decoration, None to use the theme color
+    pub resolved_color: Option<String>,
     pub positions: Vec<Position>,
+    /// Priority over the decorations overlapping it, used to resolve overlaps before returning them
+    #[serde(skip)]
+    #[cfg_attr(test, ts(skip))]
+    pub(crate) priority: Priority,
 }
 
 /// Represents a symbol position in the document
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
 pub struct Position {
+    /// UTF-16 offsets in the document, like VSCode offsets
     pub start: usize,
     pub end: usize,
+    /// Lines and UTF-16 columns of the offsets, like VSCode positions
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
 }
 
 /// Represents the options for the rendering, set in the user settings
+#[derive(Clone)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
 pub struct Options {
-    pub rendering_mode: u8,
+    pub features: Features,
+    /// Size and position of the attachments
+    pub attachment_style: AttachmentStyle,
+    /// Fonts used to render the symbols
+    pub fonts: Fonts,
     pub render_outside_math: bool,
+    /// Equations in which symbols are rendered
+    pub scope: Scope,
     pub render_spaces: bool,
     pub hide_unnecessary_delimiters: bool,
+    /// Show a faint marker instead of invisible characters (`zws`, `wj`, soft hyphen...)
+    pub render_invisible: bool,
+    /// Show a faint column marker instead of the alignment points (`&`) of equations
+    pub render_align_points: bool,
+    /// Render the LaTeX math of the mitex package
+    pub render_latex: bool,
+    /// Approximate attachments, accents and roots with unicode characters (superscripts, combining accents)
+    /// instead of moving them with css, for hosts which can't apply css transforms
+    pub plain_unicode: bool,
+    /// Symbols which aren't rendered, by name or source text, `*` matching any characters (`arrow.*`)
     pub blacklisted_symbols: Vec<String>,
+    /// If not empty, only the named symbols matching one of these patterns are rendered
+    pub whitelisted_symbols: Vec<String>,
+    /// Symbols with a shorter name aren't rendered, to keep short names like `pi` as source
+    pub min_symbol_length: usize,
+    /// Nodes nested deeper in the syntax tree aren't rendered, to keep the traversal within the WASM stack
+    pub max_depth: usize,
+    /// Categories of the symbols which aren't rendered, like greek letters
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(skip))]
+    pub hidden_categories: Vec<Category>,
+    /// Symbols defined by the user, checked first so that they replace the built-in symbols of the same name
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(skip))]
     pub custom_symbols: HashMap<String, CustomSymbol>,
+    /// Symbols added to the built-in ones, checked last: unlike the custom symbols, a table loaded from a file
+    /// (like the symbols of a package) only fills the names which aren't a built-in symbol, emoji or variant,
+    /// so it can't change how the typst symbols are rendered
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(skip))]
+    pub symbol_table: HashMap<String, CustomSymbol>,
+    /// Rendering rules of functions, by name or field path, checked before the built-in ones
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(skip))]
+    pub function_rules: HashMap<String, FunctionRule>,
+    /// Css colors set by the user, replacing the theme colors
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(skip))]
+    pub colors: HashMap<Color, String>,
+    /// Token stopping the parses using these options
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(skip))]
+    pub cancellation: Option<CancellationToken>,
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl Options {
+    /// Create options with the default settings
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(constructor))]
+    pub fn new() -> Options {
+        Options::default()
+    }
+    /// Set the features of a rendering mode of the settings, for compatibility
+    pub fn set_rendering_mode(&mut self, rendering_mode: u8) {
+        self.features = Features::from_rendering_mode(rendering_mode);
+    }
+    /// Enable or disable a feature by its name in the settings (`letterStyles`, `absNorm`...)
+    pub fn set_feature(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        let feature = match name {
+            "attachments" => &mut self.features.attachments,
+            "letterStyles" => &mut self.features.letter_styles,
+            "operators" => &mut self.features.operators,
+            "aliases" => &mut self.features.aliases,
+            "macros" => &mut self.features.macros,
+            "packages" => &mut self.features.packages,
+            "accents" => &mut self.features.accents,
+            "absNorm" => &mut self.features.abs_norm,
+            "sqrt" => &mut self.features.sqrt,
+            "functionRules" => &mut self.features.function_rules,
+            "functionNames" => &mut self.features.function_names,
+            _ => return Err(format!("Unknown feature {}", name)),
+        };
+        *feature = enabled;
+        Ok(())
+    }
+    /// Add a user defined symbol
+    pub fn add_custom_symbol(&mut self, symbol: CustomSymbol) {
+        self.custom_symbols.insert(symbol.name.clone(), symbol);
+    }
+    /// Stop the parses using these options when the token is cancelled
+    pub fn set_cancellation_token(&mut self, token: &CancellationToken) {
+        self.cancellation = Some(token.clone());
+    }
+    /// Check if the token of these options was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+    /// Hide the symbols of a category (`letter`, `bigletter`, `operator`...)
+    pub fn hide_category(&mut self, name: &str) -> Result<(), String> {
+        let category = get_category_by_name(name);
+        if category == Category::Default && !name.eq_ignore_ascii_case("default") {
+            return Err(format!("Unknown category {}", name));
+        }
+        self.hidden_categories.push(category);
+        Ok(())
+    }
+    /// Add symbols from a JSON table (`{"name": {"char": "…", "category": "operator"}}`), used when no built-in symbol matches
+    pub fn add_symbol_table(&mut self, table: &str) -> Result<(), String> {
+        let table: HashMap<String, SymbolTableEntry> =
+            serde_json::from_str(table).map_err(|e| e.to_string())?;
+        for (name, entry) in table {
+            self.symbol_table.insert(
+                name.clone(),
+                CustomSymbol {
+                    name,
+                    symbol: entry.symbol,
+                    category: entry.category,
+                },
+            );
+        }
+        Ok(())
+    }
+    /// Set the css color of a color category (`keyword`, `number`, `comparison`, `letter`, `group`, `operator`, `differential`)
+    pub fn set_color(&mut self, name: &str, color: &str) -> Result<(), String> {
+        let category = get_color_by_name(name).ok_or(format!("Unknown color {}", name))?;
+        if !is_css_color(color) {
+            return Err(format!("Invalid color {} for {}", color, name));
+        }
+        self.colors.insert(category, color.to_string());
+        Ok(())
+    }
+    /// Add function rules from a JSON table (`{"vecb": {"symbol": "→", "style": "…", "args": "simple"}}`)
+    pub fn add_function_rules(&mut self, rules: &str) -> Result<(), String> {
+        let rules: HashMap<String, FunctionRule> =
+            serde_json::from_str(rules).map_err(|e| e.to_string())?;
+        self.function_rules.extend(rules);
+        Ok(())
+    }
+}
+
+/// Rendering features, which can be enabled independently
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct Features {
+    /// Attachments rendered as exponents and indices (`x^2`, `attach(x, t: 2)`)
+    pub attachments: bool,
+    /// Letter styles (`bb(R)`, `cal(A)`)
+    pub letter_styles: bool,
+    /// Operators rendered upright (`sin`, `op("tr")`)
+    pub operators: bool,
+    /// Math aliases defined by the document (`#let RR = $bb(R)$`)
+    pub aliases: bool,
+    /// Math functions defined by the document (`#let avg(x) = $angle.l x angle.r$`)
+    pub macros: bool,
+    /// Functions of packages like physica, unify and mitex
+    pub packages: bool,
+    /// Accents (`arrow(x)`, `hat(x)`)
+    pub accents: bool,
+    /// Delimiters of `abs` and `norm`
+    pub abs_norm: bool,
+    /// Square roots (`sqrt(x)`)
+    pub sqrt: bool,
+    /// Function rules set by the user
+    pub function_rules: bool,
+    /// Names of other functions rendered like symbols (`alpha(x)`)
+    pub function_names: bool,
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl Features {
+    /// Features of a rendering mode of the settings: 1 for basic, 2 for medium and 3 for complex
+    pub fn from_rendering_mode(rendering_mode: u8) -> Features {
+        let medium = rendering_mode > 1;
+        let complex = rendering_mode > 2;
+        Features {
+            attachments: medium,
+            letter_styles: medium,
+            operators: medium,
+            aliases: medium,
+            macros: complex,
+            packages: complex,
+            accents: complex,
+            abs_norm: complex,
+            sqrt: complex,
+            function_rules: complex,
+            function_names: complex,
+        }
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features::from_rendering_mode(3)
+    }
+}
+
+/// Kinds of equations in which symbols are rendered
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub enum Scope {
+    All,
+    /// Only display equations (`$ x $`)
+    Block,
+    /// Only inline equations (`$x$`)
+    Inline,
+}
+
+/// Size and vertical position of the attachments, to tune them for the font of the editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct AttachmentStyle {
+    /// Font size of the attachments, in em
+    pub scale: f32,
+    /// Vertical translation of the top attachments, in percent of their height
+    pub top_offset: f32,
+    /// Vertical translation of the bottom attachments, in percent of their height
+    pub bottom_offset: f32,
+    /// Font size of the limits under operators (`lim_(x -> 0)`), in em
+    pub limits_scale: f32,
+    /// Vertical translation of the limits under operators, in percent of their height
+    pub limits_offset: f32,
+}
+
+impl Default for AttachmentStyle {
+    fn default() -> Self {
+        AttachmentStyle {
+            scale: 0.8,
+            top_offset: -30.0,
+            bottom_offset: 20.0,
+            limits_scale: 0.7,
+            limits_offset: 60.0,
+        }
+    }
+}
+
+/// Fonts used to render the symbols, referenced in the styles as `@math`, `@mono` and `@symbols`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct Fonts {
+    /// Font of most symbols and of letter styles
+    pub math: String,
+    /// Font of greek letters, accents and roots
+    pub mono: String,
+    /// Font of operators and sets
+    pub symbols: String,
+    /// Fonts used after each of them, for the characters they don't have
+    pub fallback: Vec<String>,
+}
+
+impl Default for Fonts {
+    fn default() -> Self {
+        Fonts {
+            math: "NewComputerModernMath".to_string(),
+            mono: "JuliaMono".to_string(),
+            symbols: "Fira Math".to_string(),
+            fallback: vec![],
+        }
+    }
+}
+
+/// Entry of a JSON symbol table
+#[derive(Deserialize)]
+struct SymbolTableEntry {
+    #[serde(alias = "char")]
+    symbol: String,
+    #[serde(default)]
+    category: String,
 }
 
 impl Default for Options {
     fn default() -> Self {
         Options {
-            rendering_mode: 3,
+            features: Features::default(),
+            attachment_style: AttachmentStyle::default(),
+            fonts: Fonts::default(),
             render_outside_math: true,
+            scope: Scope::All,
             render_spaces: false,
             hide_unnecessary_delimiters: false,
+            render_invisible: false,
+            render_align_points: false,
+            render_latex: true,
+            plain_unicode: false,
             blacklisted_symbols: vec![],
+            whitelisted_symbols: vec![],
+            min_symbol_length: 0,
+            max_depth: 256,
+            hidden_categories: vec![],
             custom_symbols: HashMap::new(),
+            symbol_table: HashMap::new(),
+            function_rules: HashMap::new(),
+            colors: HashMap::new(),
+            cancellation: None,
         }
     }
 }
 
 /// Represents a user defined symbol that can be used trough WASM
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
 pub struct CustomSymbol {
     pub name: String,
@@ -58,13 +352,332 @@ pub struct CustomSymbol {
     pub category: String,
 }
 
+/// Rendering rule of a user function, the callee and the opening paren are replaced by the symbol
+/// styled with the css, and the closing paren is hidden
+#[derive(Debug, Clone, Deserialize)]
+pub struct FunctionRule {
+    #[serde(alias = "char")]
+    pub symbol: String,
+    #[serde(default)]
+    pub style: String,
+    #[serde(default)]
+    pub args: ArgumentPolicy,
+}
+
+/// Arguments accepted by a function rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentPolicy {
+    /// A single identifier or simple attachment, like accents (`vec(x)`, `vec(x_1)`)
+    #[default]
+    Simple,
+    /// A single positional argument of any kind
+    Single,
+    /// Any arguments
+    Any,
+}
+
 /// Represents the result of the parsing function
+#[derive(Clone, Serialize)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
 pub struct Parsed {
     pub decorations: Vec<Decoration>,
+    /// Uuids of the ids met for the first time, the first one having the id `uuids_start`
+    pub uuids: Vec<String>,
+    pub uuids_start: u32,
     pub edit_start_line: usize,
     pub edit_end_line: usize,
     pub edit_start_column: usize,
     pub edit_end_column: usize,
     pub erroneous: bool,
+    /// Syntax errors of the document, or of the node of a streamed result
+    pub diagnostics: Vec<Diagnostic>,
+    /// Unknown identifiers of the parsed nodes, with the known names they may be a typo of
+    pub suggestions: Vec<Suggestion>,
+    /// Probable mistakes found in the math of the parsed nodes
+    pub lints: Vec<Lint>,
+    /// Some nodes were deeper than the maximum depth and weren't rendered
+    pub truncated: bool,
+    /// The parse was cancelled before its end, decorations are incomplete
+    pub cancelled: bool,
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl Parsed {
+    /// Get the uuid of an id from the table of this result
+    pub fn uuid(&self, id: u32) -> Option<String> {
+        id.checked_sub(self.uuids_start)
+            .and_then(|index| self.uuids.get(index as usize).cloned())
+    }
+    /// Serialize this result into a single JSON payload, typed by the generated `Parsed.ts`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Represents a syntax error reported by typst
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
+pub struct Diagnostic {
+    pub message: String,
+    /// Span of the error in the document, in UTF-16 offsets
+    pub position: Position,
+}
+
+/// Severity of a lint, like the ones of editor diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+/// Represents a probable mistake in math found by a lint, like an unclosed delimiter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
+pub struct Lint {
+    /// Name of the lint, like `unclosed-delimiter`
+    pub code: String,
+    pub message: String,
+    pub severity: Severity,
+    /// Span of the mistake in the document, in UTF-16 offsets
+    pub position: Position,
+}
+
+/// Represents a math identifier which isn't a known symbol, with the closest known names
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
+pub struct Suggestion {
+    /// The unknown name, like `alpa`
+    pub name: String,
+    /// Known names close to it, the closest first, like `alpha`
+    pub candidates: Vec<String>,
+    /// Span of the identifier in the document, in UTF-16 offsets
+    pub position: Position,
+}
+
+/// Represents an equation of the document, used for outlines and navigation
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct EquationInfo {
+    pub position: Position,
+    /// The equation is displayed as a block (`$ x $`)
+    pub block: bool,
+    /// Typst source of the equation, delimiters included
+    pub source: String,
+    /// Unicode rendering of the equation on a single line
+    pub unicode: String,
+}
+
+/// Represents an equation with a label, used for the outline and the breadcrumbs
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct EquationSymbol {
+    /// Name of the label, like `eq:euler` for `<eq:euler>`
+    pub name: String,
+    /// Span of the equation and of its label
+    pub position: Position,
+    /// Span of the label
+    pub selection: Position,
+    /// Unicode rendering of the equation on a single line
+    pub preview: String,
+    pub block: bool,
+}
+
+/// Represents the replacement of a range of the document by a text
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct TextEdit {
+    pub position: Position,
+    pub text: String,
+}
+
+/// Represents a fix of a mistake, applied by a code action
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct QuickFix {
+    /// Title of the code action, like ``Wrap `-x` in parentheses``
+    pub title: String,
+    pub edit: TextEdit,
+}
+
+/// Origin of a symbol of the catalog
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub enum SymbolSource {
+    /// Symbols of typst's `sym` module
+    Builtin,
+    /// Emojis of typst's `emoji` module, named `emoji.name`
+    Emoji,
+    /// Symbols defined in the settings or added with a symbol table
+    Custom,
+}
+
+/// Represents a symbol of the catalog, used for completions and symbol pickers
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct SymbolInfo {
+    pub name: String,
+    pub symbol: String,
+    pub category: Category,
+    pub source: SymbolSource,
+}
+
+/// Represents a symbol matching the abbreviation typed before the cursor, for a math input mode
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct InputCandidate {
+    /// Typst name of the symbol, like `lt.eq` for `\le`
+    pub name: String,
+    pub symbol: String,
+    pub category: Category,
+    /// UTF-16 columns of the abbreviation replaced by the candidate in its line, backslash included
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Represents how often a symbol is used in a document, with the different ways it is written
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct SymbolCount {
+    pub symbol: String,
+    pub category: Category,
+    /// Names and characters used for the symbol, like `phi.alt` and `ϕ`, the most used first
+    pub spellings: Vec<String>,
+    pub count: usize,
+}
+
+/// Represents how many symbols of a category are used in a document
+#[derive(Debug, Clone, Copy, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct CategoryCount {
+    pub category: Category,
+    pub count: usize,
+}
+
+/// Represents a summary of the math of a document
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct DocumentStatistics {
+    /// Number of equations, display ones included
+    pub equations: usize,
+    pub block_equations: usize,
+    /// Deepest nesting of attachments, fractions, roots, delimiters and calls in an equation
+    pub max_nesting: usize,
+    /// Symbols used, the most frequent first
+    pub symbols: Vec<SymbolCount>,
+    /// Number of uses of the symbols of each category, the most frequent first
+    pub categories: Vec<CategoryCount>,
+    /// Some nodes were deeper than the maximum depth and weren't counted
+    pub truncated: bool,
+}
+
+/// Represents lines of a document which can be folded, 0-based and inclusive, the first one staying visible
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct FoldingRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Represents an inlay hint displayed after a named argument of a math function, like `[ … ]` after `delim: "["`
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct InlayHint {
+    /// Empty span at the end of the argument, in UTF-16 offsets
+    pub position: Position,
+    pub label: String,
+    /// Description of the argument
+    pub tooltip: String,
+}
+
+/// Class of a semantic token of math, for themes highlighting the math source
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub enum TokenKind {
+    /// Binary and large operators, like `+`, `times` or `sum`
+    Operator,
+    /// Comparisons, arrows and set relations, like `=`, `<=` or `in`
+    Relation,
+    /// Greek letters, like `alpha` or `Omega`
+    Greek,
+    Number,
+    /// Called functions and named operators, like `vec` in `vec(x)` or `sin`
+    Function,
+    /// Parentheses, brackets, braces and bars
+    Delimiter,
+}
+
+/// Represents a semantic token of an equation, independently of the rendering of its symbols
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct SemanticToken {
+    pub kind: TokenKind,
+    /// Span of the token in the document, in UTF-16 offsets
+    pub position: Position,
+}
+
+/// Represents the symbol rendered at a position, used for hover tooltips
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct SymbolHover {
+    /// Typst source of the symbol, like `arrow.r`
+    pub name: String,
+    pub symbol: String,
+    pub category: Category,
+    /// Span of the symbol in the document, in UTF-16 offsets
+    pub position: Position,
+}
+
+/// Represents the result of a document parsed in a batch
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+pub struct DocumentResult {
+    pub uri: String,
+    pub parsed: Parsed,
+}
+
+/// Represents the changes of the decorations since the previous result of a session
+#[derive(Serialize)]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen(getter_with_clone))]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
+pub struct Delta {
+    /// Version of this result, to pass to the next call
+    pub version: u32,
+    /// The delta doesn't follow the version given, all decorations are added and previous ones must be cleared
+    pub reset: bool,
+    /// New decorations, or decorations whose symbol, style or positions changed
+    pub added: Vec<Decoration>,
+    /// Ids of the decorations which disappeared
+    pub removed: Vec<u32>,
+    /// Ids of the decorations identical to the previous result
+    pub unchanged: Vec<u32>,
+    /// Uuids of the ids met for the first time, the first one having the id `uuids_start`
+    pub uuids: Vec<String>,
+    pub uuids_start: u32,
+    pub erroneous: bool,
+    /// Syntax errors of the document
+    pub diagnostics: Vec<Diagnostic>,
+    /// Unknown identifiers of the document, with the known names they may be a typo of
+    pub suggestions: Vec<Suggestion>,
+    /// Probable mistakes found in the math of the document
+    pub lints: Vec<Lint>,
+    /// Some nodes were deeper than the maximum depth and weren't rendered
+    pub truncated: bool,
+    /// The parse was cancelled before its end, the delta is empty and the previous result is still the reference
+    pub cancelled: bool,
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl Delta {
+    /// Serialize this delta into a single JSON payload, typed by the generated `Delta.ts`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
 }
@@ -0,0 +1,492 @@
+//! Conversion of typst math into LaTeX, to paste equations into journals and tools requiring LaTeX
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    convert::{
+        attachment, call_arguments, delimiters, equations_in_range, fraction_parts, function_name,
+        matrix_rows, parenthesized,
+    },
+    interface::Options,
+    parser::utils::{find_symbol, get_letter_style, names_for_char},
+    utils::{
+        depth::DepthLimit,
+        symbols::{LATEX_COMMANDS, LATEX_LETTER_STYLES, OPERATORS},
+    },
+};
+
+/// Typst symbols written with a LaTeX command of the same name
+const SHARED_NAMES: [&str; 52] = [
+    "alpha", "beta", "gamma", "delta", "zeta", "eta", "theta", "iota", "kappa", "lambda", "mu",
+    "nu", "xi", "pi", "rho", "sigma", "tau", "upsilon", "chi", "psi", "omega", "Gamma", "Delta",
+    "Theta", "Lambda", "Xi", "Pi", "Sigma", "Upsilon", "Phi", "Psi", "Omega", "sum", "in",
+    "subset", "supset", "forall", "exists", "nabla", "partial", "emptyset", "times", "div",
+    "approx", "equiv", "prec", "succ", "parallel", "angle", "top", "bot", "ell",
+];
+
+/// Operators defined by LaTeX, the other typst operators are written with `\operatorname`
+const LATEX_OPERATORS: [&str; 32] = [
+    "arccos", "arcsin", "arctan", "arg", "cos", "cosh", "cot", "coth", "csc", "deg", "det", "dim",
+    "exp", "gcd", "hom", "inf", "ker", "lg", "lim", "liminf", "limsup", "ln", "log", "max", "min",
+    "Pr", "sec", "sin", "sinh", "sup", "tan", "tanh",
+];
+
+/// Get the LaTeX command of a typst symbol name, the shortest one if there are several (`\le` for `lt.eq`)
+fn command(name: &str) -> Option<String> {
+    if SHARED_NAMES.contains(&name) {
+        return Some(format!("\\{}", name));
+    }
+    LATEX_COMMANDS
+        .entries()
+        .filter(|(_, symbol)| **symbol == name)
+        .map(|(command, _)| *command)
+        .min_by_key(|command| (command.len(), *command))
+        .map(|command| format!("\\{}", command))
+}
+
+/// Write a character in math, with its command if it has one and escaped if it is special in LaTeX
+fn character(c: char) -> String {
+    match c {
+        '#' | '$' | '%' | '&' | '_' | '{' | '}' => format!("\\{}", c),
+        '\\' => "\\backslash".to_string(),
+        '^' => "\\hat{}".to_string(),
+        '~' => "\\sim".to_string(),
+        '\u{A0}' => "~".to_string(),
+        c if c.is_ascii() => c.to_string(),
+        c => names_for_char(c)
+            .iter()
+            .find_map(|name| command(name))
+            .or_else(|| styled_letter(c))
+            .unwrap_or_else(|| c.to_string()),
+    }
+}
+
+/// Write a styled letter with its letter style command, like `\mathbb{R}` for `ℝ`
+fn styled_letter(c: char) -> Option<String> {
+    LATEX_LETTER_STYLES.entries().find_map(|(command, style)| {
+        let (map, _) = get_letter_style(style)?;
+        map.entries()
+            .find(|(letter, styled)| **styled == c && letter.is_ascii_alphanumeric())
+            .map(|(letter, _)| format!("\\{}{{{}}}", command, letter))
+    })
+}
+
+/// Escape the characters of a text which are special in LaTeX, for `\text`
+fn escape_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => format!("\\{}", c),
+            '\\' => "\\textbackslash{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            '~' => "\\textasciitilde{}".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Write an operator like `sin` or `ctg`
+fn operator(name: &str) -> String {
+    if LATEX_OPERATORS.contains(&name) {
+        format!("\\{}", name)
+    } else {
+        format!("\\operatorname{{{}}}", name)
+    }
+}
+
+/// Get the LaTeX spacing of a typst one, like `\quad` for `quad`
+fn spacing(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "thin" => "\\,",
+        "med" => "\\:",
+        "thick" => "\\;",
+        "quad" => "\\quad",
+        "wide" => "\\qquad",
+        _ => return None,
+    })
+}
+
+/// Get the LaTeX accent of an accent function, like `\hat` for `hat`
+fn accent(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "hat" => "\\hat",
+        "tilde" => "\\tilde",
+        "dot" => "\\dot",
+        "dot.double" | "diaer" => "\\ddot",
+        "dot.triple" => "\\dddot",
+        "dot.quad" => "\\ddddot",
+        "arrow" => "\\vec",
+        "arrow.l" => "\\overleftarrow",
+        "arrow.l.r" => "\\overleftrightarrow",
+        "macron" => "\\bar",
+        "overline" => "\\overline",
+        "underline" => "\\underline",
+        "acute" => "\\acute",
+        "grave" => "\\grave",
+        "breve" => "\\breve",
+        "caron" | "check" => "\\check",
+        "circle" => "\\mathring",
+        "overbrace" => "\\overbrace",
+        "underbrace" => "\\underbrace",
+        _ => return None,
+    })
+}
+
+/// Write a delimiter after `\left` or `\right`, like `\lfloor` for `⌊`
+fn delimiter(c: char) -> String {
+    match c {
+        '‖' => "\\|".to_string(),
+        '⌊' => "\\lfloor".to_string(),
+        '⌋' => "\\rfloor".to_string(),
+        '⌈' => "\\lceil".to_string(),
+        '⌉' => "\\rceil".to_string(),
+        c => c.to_string(),
+    }
+}
+
+/// Get the environment of a matrix from its `delim` argument, like `bmatrix` for `mat(delim: "[", ..)`
+fn matrix_environment(call: &LinkedNode) -> &'static str {
+    let delim = call
+        .children()
+        .find(|child| child.kind() == SyntaxKind::Args)
+        .and_then(|args| {
+            args.children()
+                .filter_map(|arg| arg.get().cast::<ast::Named>())
+                .find(|named| named.name().as_str() == "delim")
+        });
+    match delim.map(|named| named.expr()) {
+        None => "pmatrix",
+        Some(ast::Expr::None(_)) => "matrix",
+        Some(ast::Expr::Str(delim)) => match delim.get().as_str() {
+            "[" => "bmatrix",
+            "{" => "Bmatrix",
+            "|" => "vmatrix",
+            "||" | "‖" => "Vmatrix",
+            _ => "pmatrix",
+        },
+        Some(_) => "pmatrix",
+    }
+}
+
+/// Group a script or an argument in braces, unless it is a single character
+fn group(latex: String) -> String {
+    if latex.chars().count() == 1 {
+        latex
+    } else {
+        format!("{{{}}}", latex)
+    }
+}
+
+/// Append an item, after a space if the source has one or if it would merge with a command (`\alpha x`)
+fn push(latex: &mut String, item: &str, spaced: bool) {
+    if item.is_empty() {
+        return;
+    }
+    let command = latex.rfind('\\').is_some_and(|index| {
+        let name = &latex[index + 1..];
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic())
+    });
+    if !latex.is_empty()
+        && (spaced || (command && item.starts_with(|c: char| c.is_ascii_alphabetic())))
+    {
+        latex.push(' ');
+    }
+    latex.push_str(item);
+}
+
+/// Converter of the math of an equation
+struct Converter<'a> {
+    options: &'a Options,
+    /// Depth of the node being converted, the nodes deeper than the maximum depth being skipped
+    limit: DepthLimit,
+}
+
+impl Converter<'_> {
+    /// Convert a sequence of nodes, with `\\` at line breaks and `&` at alignment points
+    fn join(&self, nodes: &[LinkedNode]) -> String {
+        let mut latex = String::new();
+        let mut spaced = false;
+        let mut embedded = false;
+        for node in nodes {
+            if std::mem::take(&mut embedded) {
+                continue;
+            }
+            let item = match node.kind() {
+                // Code embedded with `#` can't be converted
+                SyntaxKind::Hash => {
+                    embedded = true;
+                    continue;
+                }
+                SyntaxKind::Space => {
+                    spaced = true;
+                    continue;
+                }
+                SyntaxKind::Linebreak => Some("\\\\".to_string()),
+                SyntaxKind::MathAlignPoint => Some("&".to_string()),
+                _ => self.convert(node),
+            };
+            if let Some(item) = item {
+                push(&mut latex, &item, std::mem::take(&mut spaced));
+            }
+        }
+        latex
+    }
+
+    fn sequence(&self, node: &LinkedNode) -> String {
+        self.join(&node.children().collect::<Vec<_>>())
+    }
+
+    /// Convert the first and the last children of a node, which are delimiters, and the ones between them
+    fn delimited(&self, node: &LinkedNode) -> Option<(String, String, String)> {
+        let children: Vec<LinkedNode> = node.children().collect();
+        let first = children
+            .iter()
+            .position(|child| !child.kind().is_trivia())?;
+        let last = children
+            .iter()
+            .rposition(|child| !child.kind().is_trivia())?;
+        if first >= last {
+            return None;
+        }
+        Some((
+            self.convert(&children[first])?,
+            self.join(&children[first + 1..last]),
+            self.convert(&children[last])?,
+        ))
+    }
+
+    /// Convert a node, None if it isn't rendered like spaces or if it is deeper than the maximum depth
+    fn convert(&self, node: &LinkedNode) -> Option<String> {
+        if !self.limit.enter() {
+            return None;
+        }
+        let converted = self.convert_node(node);
+        self.limit.leave();
+        converted
+    }
+
+    fn convert_node(&self, node: &LinkedNode) -> Option<String> {
+        Some(match node.kind() {
+            SyntaxKind::Math | SyntaxKind::MathDelimited => self.sequence(node),
+            SyntaxKind::Text => node.text().chars().map(character).collect(),
+            SyntaxKind::LeftParen | SyntaxKind::RightParen => node.text().to_string(),
+            SyntaxKind::Str => format!(
+                "\\text{{{}}}",
+                escape_text(node.cast::<ast::Str>()?.get().as_str())
+            ),
+            SyntaxKind::Escape => character(node.cast::<ast::Escape>()?.get()),
+            SyntaxKind::Shorthand => character(node.cast::<ast::Shorthand>()?.get()),
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                self.name(&node.get().clone().into_text())
+            }
+            SyntaxKind::MathAttach => self.attach(node)?,
+            SyntaxKind::MathPrimes => "'".repeat(node.cast::<ast::MathPrimes>()?.count()),
+            SyntaxKind::MathFrac => {
+                let (num, denom) = fraction_parts(node)?;
+                format!(
+                    "\\frac{{{}}}{{{}}}",
+                    self.unparen(&num),
+                    self.unparen(&denom)
+                )
+            }
+            SyntaxKind::MathRoot => {
+                let radicand = self.unparen(&node.children().last()?);
+                match node.cast::<ast::MathRoot>()?.index() {
+                    Some(index) => format!("\\sqrt[{}]{{{}}}", index, radicand),
+                    None => format!("\\sqrt{{{}}}", radicand),
+                }
+            }
+            SyntaxKind::FuncCall => self.call(node),
+            SyntaxKind::Equation => {
+                let math = node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::Math)?;
+                let latex = self.sequence(&math);
+                // Equations with several lines or alignment points are aligned like in typst
+                if math.children().any(|child| {
+                    matches!(
+                        child.kind(),
+                        SyntaxKind::Linebreak | SyntaxKind::MathAlignPoint
+                    )
+                }) {
+                    format!("\\begin{{aligned}} {} \\end{{aligned}}", latex)
+                } else {
+                    latex
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Convert an identifier or a field access, like `alpha`, `arrow.r` or `sin`
+    fn name(&self, name: &str) -> String {
+        if OPERATORS.contains_key(name) {
+            return operator(name);
+        }
+        if let Some(space) = spacing(name) {
+            return space.to_string();
+        }
+        if let Some(command) = command(name) {
+            return command;
+        }
+        // Other symbols are written with the command of their character, or the character itself
+        match find_symbol(name.to_string(), self.options) {
+            Some((_, symbol)) => symbol.chars().map(character).collect(),
+            None => name.chars().map(character).collect(),
+        }
+    }
+
+    /// Convert a script or an argument, the parentheses grouping it being removed like typst does (`x^(a + b)`)
+    fn unparen(&self, node: &LinkedNode) -> String {
+        if parenthesized(node).is_some() {
+            if let Some((_, inner, _)) = self.delimited(node) {
+                return inner;
+            }
+        }
+        self.convert(node).unwrap_or_default()
+    }
+
+    /// Convert an attachment, the scripts being grouped in braces
+    fn attach(&self, node: &LinkedNode) -> Option<String> {
+        let attachment = attachment(node)?;
+        let mut latex = self
+            .convert(&attachment.base)
+            .filter(|base| !base.is_empty())
+            .unwrap_or_else(|| "{}".to_string());
+        if let Some(primes) = attachment.primes.and_then(|primes| self.convert(&primes)) {
+            latex.push_str(&primes);
+        }
+        if let Some(bottom) = attachment.bottom {
+            latex.push('_');
+            latex.push_str(&group(self.unparen(&bottom)));
+        }
+        if let Some(top) = attachment.top {
+            latex.push('^');
+            latex.push_str(&group(self.unparen(&top)));
+        }
+        Some(latex)
+    }
+
+    /// Convert the rows of cells of a table, with `&` between cells and `\\` between rows
+    fn table(&self, rows: Vec<Vec<LinkedNode>>) -> String {
+        rows.iter()
+            .map(|cells| {
+                cells
+                    .iter()
+                    .map(|cell| self.unparen(cell))
+                    .collect::<Vec<_>>()
+                    .join(" & ")
+            })
+            .collect::<Vec<_>>()
+            .join(" \\\\ ")
+    }
+
+    /// Convert a function call, like `frac(a, b)`, `hat(x)` or `mat(1, 2; 3, 4)`
+    fn call(&self, node: &LinkedNode) -> String {
+        let name = function_name(node).unwrap_or_default();
+        let args = call_arguments(node);
+        let arg = |index: usize| {
+            args.get(index)
+                .map(|arg| self.unparen(arg))
+                .unwrap_or_default()
+        };
+        match name.as_str() {
+            "frac" => format!("\\frac{{{}}}{{{}}}", arg(0), arg(1)),
+            "binom" => format!("\\binom{{{}}}{{{}}}", arg(0), arg(1)),
+            "sqrt" => format!("\\sqrt{{{}}}", arg(0)),
+            "root" => format!("\\sqrt[{}]{{{}}}", arg(0), arg(1)),
+            "cancel" => format!("\\cancel{{{}}}", arg(0)),
+            "lr" => match args.first().and_then(|arg| self.delimited(arg)) {
+                Some((open, inner, close)) => format!("\\left{} {} \\right{}", open, inner, close),
+                None => arg(0),
+            },
+            "mid" => format!("\\middle{}", arg(0)),
+            "display" => format!("{{\\displaystyle {}}}", arg(0)),
+            "inline" => format!("{{\\textstyle {}}}", arg(0)),
+            "script" => format!("{{\\scriptstyle {}}}", arg(0)),
+            "sscript" => format!("{{\\scriptscriptstyle {}}}", arg(0)),
+            "cramped" | "limits" | "scripts" => arg(0),
+            "op" => match args.first() {
+                Some(text) if text.kind() == SyntaxKind::Str => format!(
+                    "\\operatorname{{{}}}",
+                    escape_text(
+                        text.cast::<ast::Str>()
+                            .map(|text| text.get())
+                            .unwrap_or_default()
+                            .as_str()
+                    )
+                ),
+                _ => arg(0),
+            },
+            "mat" | "vec" => {
+                let rows = if name == "mat" {
+                    self.table(matrix_rows(&args))
+                } else {
+                    self.table(args.iter().map(|arg| vec![arg.clone()]).collect())
+                };
+                let environment = matrix_environment(node);
+                format!(
+                    "\\begin{{{}}} {} \\end{{{}}}",
+                    environment, rows, environment
+                )
+            }
+            "cases" => format!(
+                "\\begin{{cases}} {} \\end{{cases}}",
+                self.table(args.iter().map(|arg| vec![arg.clone()]).collect())
+            ),
+            _ => {
+                if let Some((open, close)) = delimiters(&name) {
+                    return format!(
+                        "\\left{} {} \\right{}",
+                        delimiter(open),
+                        arg(0),
+                        delimiter(close)
+                    );
+                }
+                if let Some(accent) = accent(&name) {
+                    let accented = format!("{}{{{}}}", accent, arg(0));
+                    // Annotation of a brace, like `overbrace(x + y, 2)`
+                    return match (args.get(1), name.as_str()) {
+                        (Some(_), "underbrace") => format!("{}_{}", accented, group(arg(1))),
+                        (Some(_), _) => format!("{}^{}", accented, group(arg(1))),
+                        (None, _) => accented,
+                    };
+                }
+                if get_letter_style(&name).is_some() {
+                    if let Some((command, _)) = LATEX_LETTER_STYLES
+                        .entries()
+                        .find(|(_, style)| **style == name)
+                    {
+                        return format!("\\{}{{{}}}", command, arg(0));
+                    }
+                }
+                // Unknown functions keep the syntax of a call, like `f(x, y)`
+                let args: Vec<String> = args.iter().map(|arg| self.unparen(arg)).collect();
+                format!("{}({})", self.name(&name), args.join(", "))
+            }
+        }
+    }
+}
+
+/// Convert the equations between two UTF-16 offsets of a source into LaTeX, one equation per line \
+/// An empty range converts the equation around it
+pub fn to_latex(source: &Source, start: usize, end: usize, options: &Options) -> String {
+    let converter = Converter {
+        options,
+        limit: DepthLimit::new(options.max_depth),
+    };
+    equations_in_range(source, start, end)
+        .into_iter()
+        .map(|equation| {
+            let latex = converter.convert(&equation).unwrap_or_default();
+            if equation
+                .cast::<ast::Equation>()
+                .is_some_and(|equation| equation.block())
+            {
+                format!("\\[ {} \\]", latex)
+            } else {
+                format!("${}$", latex)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
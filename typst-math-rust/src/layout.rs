@@ -0,0 +1,686 @@
+//! Layout of equations on several lines of unicode text, with fraction bars, stacked limits and matrix grids, \
+//! for hovers and terminals where the decorations of a single line aren't enough
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    convert::{
+        attachment, call_arguments, delimiters, equations_in_range, fraction_parts, function_name,
+        has_limits, matrix_rows, parenthesized,
+    },
+    interface::Options,
+    parser::{
+        functions::letter_style_content,
+        utils::{apply_letter_style, find_symbol, get_letter_style, subscript, superscript},
+    },
+    utils::{
+        depth::DepthLimit,
+        symbols::{OPERATORS, SPACINGS},
+    },
+};
+
+/// The character takes two cells in a monospace font, like CJK characters and emojis
+fn is_wide(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F680..=0x1F6FF
+            | 0x1F900..=0x1F9FF
+            | 0x1FA70..=0x1FAFF
+            | 0x20000..=0x3FFFD
+    )
+}
+
+/// Width of a text in a monospace font, counting the cells of its grapheme clusters so that combining
+/// characters take no space and wide characters two cells
+fn width(text: &str) -> usize {
+    text.graphemes(true)
+        .map(|grapheme| match grapheme.chars().next() {
+            Some(c) if matches!(c as u32, 0x300..=0x36F | 0x20D0..=0x20FF) => 0,
+            Some(c) if is_wide(c) => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Pad a text with spaces on its right up to a width
+fn pad(text: &str, size: usize) -> String {
+    format!("{}{}", text, " ".repeat(size.saturating_sub(width(text))))
+}
+
+/// Center a text in a width, the extra space being on the right
+fn center(text: &str, size: usize) -> String {
+    let left = size.saturating_sub(width(text)) / 2;
+    pad(&format!("{}{}", " ".repeat(left), text), size)
+}
+
+/// Rectangle of text, laid out with the blocks around it
+#[derive(Debug, Clone)]
+struct Block {
+    lines: Vec<String>,
+    /// Line aligned with the baselines of the blocks beside it
+    baseline: usize,
+}
+
+impl Block {
+    fn text(text: &str) -> Block {
+        Block {
+            lines: vec![text.to_string()],
+            baseline: 0,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.lines.iter().map(|line| width(line)).max().unwrap_or(0)
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Get the text of a block on a single line, None if it is higher
+    fn single_line(&self) -> Option<&str> {
+        match self.lines.as_slice() {
+            [line] => Some(line),
+            _ => None,
+        }
+    }
+
+    /// Put blocks side by side, aligned on their baselines
+    fn row(blocks: Vec<Block>) -> Block {
+        let above = blocks.iter().map(|block| block.baseline).max().unwrap_or(0);
+        let below = blocks
+            .iter()
+            .map(|block| block.height().saturating_sub(block.baseline + 1))
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![String::new(); above + below + 1];
+        for block in &blocks {
+            let size = block.width();
+            let top = above - block.baseline;
+            for (index, line) in lines.iter_mut().enumerate() {
+                let text = index
+                    .checked_sub(top)
+                    .and_then(|index| block.lines.get(index))
+                    .map_or("", String::as_str);
+                line.push_str(&pad(text, size));
+            }
+        }
+        Block {
+            lines,
+            baseline: above,
+        }
+    }
+
+    /// Stack blocks centered on each other, the baseline being the one of the block at index `base`
+    fn column(blocks: Vec<Block>, base: usize) -> Block {
+        let size = blocks.iter().map(Block::width).max().unwrap_or(0);
+        let mut lines = vec![];
+        let mut baseline = 0;
+        for (index, block) in blocks.into_iter().enumerate() {
+            if index == base {
+                baseline = lines.len() + block.baseline;
+            }
+            lines.extend(block.lines.iter().map(|line| center(line, size)));
+        }
+        Block { lines, baseline }
+    }
+
+    /// Pad the lines of a block up to a width, on their left if it is aligned on the right
+    fn align(self, size: usize, right: bool) -> Block {
+        let offset = size.saturating_sub(self.width());
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                let line = if right {
+                    format!("{}{}", " ".repeat(offset), line)
+                } else {
+                    line.clone()
+                };
+                pad(&line, size)
+            })
+            .collect();
+        Block {
+            lines,
+            baseline: self.baseline,
+        }
+    }
+
+    /// Surround a block with delimiters stretched to its height
+    fn delimited(self, open: &str, close: &str) -> Block {
+        let (height, baseline) = (self.height(), self.baseline);
+        Block::row(vec![
+            delimiter(open, height, baseline),
+            self,
+            delimiter(close, height, baseline),
+        ])
+    }
+}
+
+/// Stretch a delimiter to a height with the pieces of the delimiters of several lines (`⎛`, `⎜`, `⎝`)
+fn delimiter(symbol: &str, height: usize, baseline: usize) -> Block {
+    if height <= 1 || symbol.is_empty() {
+        return Block {
+            lines: vec![symbol.to_string(); height.max(1)],
+            baseline,
+        };
+    }
+    let (top, middle, bottom, center) = match symbol {
+        "(" => ('⎛', '⎜', '⎝', '⎜'),
+        ")" => ('⎞', '⎟', '⎠', '⎟'),
+        "[" => ('⎡', '⎢', '⎣', '⎢'),
+        "]" => ('⎤', '⎥', '⎦', '⎥'),
+        "{" => ('⎧', '⎪', '⎩', '⎨'),
+        "}" => ('⎫', '⎪', '⎭', '⎬'),
+        "⌊" => ('⎢', '⎢', '⎣', '⎢'),
+        "⌋" => ('⎥', '⎥', '⎦', '⎥'),
+        "⌈" => ('⎡', '⎢', '⎢', '⎢'),
+        "⌉" => ('⎤', '⎥', '⎥', '⎥'),
+        "|" => ('│', '│', '│', '│'),
+        "‖" => ('‖', '‖', '‖', '‖'),
+        // Other delimiters aren't stretched, like angles
+        _ => {
+            let mut lines = vec![" ".repeat(width(symbol)); height];
+            lines[baseline] = symbol.to_string();
+            return Block { lines, baseline };
+        }
+    };
+    let lines = (0..height)
+        .map(|index| {
+            match index {
+                0 => top,
+                _ if index == height - 1 => bottom,
+                // The middle of braces is on the baseline, if it isn't at an end
+                _ if index == baseline => center,
+                _ => middle,
+            }
+            .to_string()
+        })
+        .collect();
+    Block { lines, baseline }
+}
+
+/// Get the combining character of an accent function, and the character written over a wider argument
+fn accent(name: &str) -> Option<(char, char)> {
+    Some(match name {
+        "hat" => ('\u{302}', '^'),
+        "tilde" => ('\u{303}', '~'),
+        "dot" => ('\u{307}', '˙'),
+        "dot.double" | "diaer" => ('\u{308}', '¨'),
+        "dot.triple" => ('\u{20DB}', '…'),
+        "arrow" => ('\u{20D7}', '→'),
+        "arrow.l" => ('\u{20D6}', '←'),
+        "macron" | "overline" => ('\u{305}', '‾'),
+        "acute" => ('\u{301}', '´'),
+        "grave" => ('\u{300}', '`'),
+        "breve" => ('\u{306}', '˘'),
+        "caron" | "check" => ('\u{30C}', 'ˇ'),
+        "circle" => ('\u{30A}', '˚'),
+        _ => return None,
+    })
+}
+
+/// Write a script with unicode superscripts or subscripts, None if a character has none
+fn small(block: &Block, convert: fn(char) -> Option<char>) -> Option<String> {
+    block.single_line()?.chars().map(convert).collect()
+}
+
+/// Lay out a grid of cells, with the cells of each column centered or aligned on the left
+fn grid(rows: Vec<Vec<Block>>, left: bool) -> Block {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column).map(Block::width))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let lines: Vec<Block> = rows
+        .into_iter()
+        .map(|row| {
+            let mut cells = vec![];
+            for (column, cell) in row.into_iter().enumerate() {
+                if column > 0 {
+                    cells.push(Block::text("  "));
+                }
+                let size = widths[column];
+                cells.push(if left {
+                    cell.align(size, false)
+                } else {
+                    Block {
+                        lines: cell.lines.iter().map(|line| center(line, size)).collect(),
+                        baseline: cell.baseline,
+                    }
+                });
+            }
+            Block::row(cells)
+        })
+        .collect();
+    stack(lines)
+}
+
+/// Stack lines of blocks aligned on the left, the baseline being in the middle
+fn stack(blocks: Vec<Block>) -> Block {
+    let size = blocks.iter().map(Block::width).max().unwrap_or(0);
+    let lines: Vec<String> = blocks
+        .iter()
+        .flat_map(|block| block.lines.iter().map(|line| pad(line, size)))
+        .collect();
+    let baseline = lines.len().saturating_sub(1) / 2;
+    Block { lines, baseline }
+}
+
+/// Layout of the math of an equation
+struct Layout<'a> {
+    options: &'a Options,
+    /// Depth of the node being laid out, the nodes deeper than the maximum depth being skipped
+    limit: DepthLimit,
+}
+
+impl Layout<'_> {
+    /// Lay out a sequence of nodes, lines being stacked and their alignment points aligned
+    fn join(&self, nodes: &[LinkedNode]) -> Block {
+        let mut lines = vec![vec![vec![]]];
+        let mut spaced = false;
+        let mut embedded = false;
+        for node in nodes {
+            if std::mem::take(&mut embedded) {
+                continue;
+            }
+            match node.kind() {
+                // Code embedded with `#` can't be laid out
+                SyntaxKind::Hash => embedded = true,
+                SyntaxKind::Space => spaced = true,
+                SyntaxKind::Linebreak => {
+                    lines.push(vec![vec![]]);
+                    spaced = false;
+                }
+                SyntaxKind::MathAlignPoint => lines.last_mut().unwrap().push(vec![]),
+                _ => {
+                    if let Some(block) = self.layout(node) {
+                        let cells = lines.last_mut().unwrap();
+                        // Spaces before alignment points are kept after them, like in `x &= 1`
+                        let aligned = cells.len() > 1;
+                        let items = cells.last_mut().unwrap();
+                        if std::mem::take(&mut spaced) && (aligned || !items.is_empty()) {
+                            items.push(Block::text(" "));
+                        }
+                        items.push(block);
+                    }
+                }
+            }
+        }
+        if lines.len() == 1 && lines[0].len() == 1 {
+            return Block::row(lines.remove(0).remove(0));
+        }
+        // Cells are aligned on the right and on the left in turn, like in typst
+        let lines: Vec<Vec<Block>> = lines
+            .into_iter()
+            .map(|cells| cells.into_iter().map(Block::row).collect())
+            .collect();
+        let columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                lines
+                    .iter()
+                    .filter_map(|cells| cells.get(column).map(Block::width))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let aligned = columns > 1;
+        stack(
+            lines
+                .into_iter()
+                .map(|cells| {
+                    Block::row(
+                        cells
+                            .into_iter()
+                            .enumerate()
+                            .map(|(column, cell)| {
+                                cell.align(widths[column], aligned && column % 2 == 0)
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn sequence(&self, node: &LinkedNode) -> Block {
+        self.join(&node.children().collect::<Vec<_>>())
+    }
+
+    /// Lay out a script or an argument, the parentheses grouping it being removed like typst does (`x^(a + b)`)
+    fn unparen(&self, node: &LinkedNode) -> Block {
+        if parenthesized(node).is_some() {
+            let children: Vec<LinkedNode> = node.children().collect();
+            let first = children.iter().position(|child| !child.kind().is_trivia());
+            let last = children.iter().rposition(|child| !child.kind().is_trivia());
+            if let (Some(first), Some(last)) = (first, last) {
+                return self.join(&children[first + 1..last]);
+            }
+        }
+        self.layout(node).unwrap_or_else(|| Block::text(""))
+    }
+
+    /// Lay out a node, None if it isn't rendered like spaces or if it is deeper than the maximum depth
+    fn layout(&self, node: &LinkedNode) -> Option<Block> {
+        if !self.limit.enter() {
+            return None;
+        }
+        let block = self.layout_node(node);
+        self.limit.leave();
+        block
+    }
+
+    fn layout_node(&self, node: &LinkedNode) -> Option<Block> {
+        Some(match node.kind() {
+            SyntaxKind::Math => self.sequence(node),
+            SyntaxKind::Text | SyntaxKind::LeftParen | SyntaxKind::RightParen => {
+                Block::text(node.text())
+            }
+            SyntaxKind::Str => Block::text(node.cast::<ast::Str>()?.get().as_str()),
+            SyntaxKind::Escape => Block::text(&node.cast::<ast::Escape>()?.get().to_string()),
+            SyntaxKind::Shorthand => Block::text(&node.cast::<ast::Shorthand>()?.get().to_string()),
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                Block::text(&self.name(&node.get().clone().into_text()))
+            }
+            SyntaxKind::MathDelimited => {
+                let children: Vec<LinkedNode> = node.children().collect();
+                let (open, close) = (children.first()?, children.last()?);
+                let inner = self.join(&children[1..children.len() - 1]);
+                let (open, close) = (self.layout(open)?, self.layout(close)?);
+                inner.delimited(open.single_line()?, close.single_line()?)
+            }
+            SyntaxKind::MathAttach => self.attach(node)?,
+            SyntaxKind::MathPrimes => {
+                Block::text(&"′".repeat(node.cast::<ast::MathPrimes>()?.count()))
+            }
+            SyntaxKind::MathFrac => {
+                let (num, denom) = fraction_parts(node)?;
+                fraction(self.unparen(&num), self.unparen(&denom))
+            }
+            SyntaxKind::MathRoot => {
+                let radicand = self.unparen(&node.children().last()?);
+                let index = node.cast::<ast::MathRoot>()?.index();
+                root(index.map(|index| Block::text(&index.to_string())), radicand)
+            }
+            SyntaxKind::FuncCall => self.call(node),
+            SyntaxKind::Equation => self.sequence(
+                &node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::Math)?,
+            ),
+            _ => return None,
+        })
+    }
+
+    /// Get the text of an identifier or a field access, like `alpha`, `arrow.r` or `sin`
+    fn name(&self, name: &str) -> String {
+        if OPERATORS.contains_key(name) {
+            return name.to_string();
+        }
+        if let Some(space) = SPACINGS.get(name) {
+            return space.to_string();
+        }
+        find_symbol(name.to_string(), self.options)
+            .map_or_else(|| name.to_string(), |(_, symbol)| symbol)
+    }
+
+    /// Lay out an attachment, scripts being written with unicode superscripts and subscripts when possible, \
+    /// and stacked beside the base or above and below it otherwise
+    fn attach(&self, node: &LinkedNode) -> Option<Block> {
+        let attachment = attachment(node)?;
+        let limits = has_limits(&attachment.base, self.options);
+        let mut base = self
+            .layout(&attachment.base)
+            .unwrap_or_else(|| Block::text(""));
+        if let Some(primes) = attachment.primes.and_then(|primes| self.layout(&primes)) {
+            base = Block::row(vec![base, primes]);
+        }
+        let top = attachment.top.map(|top| self.unparen(&top));
+        let bottom = attachment.bottom.map(|bottom| self.unparen(&bottom));
+        if limits {
+            let mut blocks = vec![];
+            let mut index = 0;
+            if let Some(top) = top {
+                blocks.push(top);
+                index = 1;
+            }
+            blocks.push(base);
+            blocks.extend(bottom);
+            return Some(Block::column(blocks, index));
+        }
+        let top_small = top.as_ref().map(|top| small(top, superscript));
+        let bottom_small = bottom.as_ref().map(|bottom| small(bottom, subscript));
+        if !matches!(top_small, Some(None)) && !matches!(bottom_small, Some(None)) {
+            let scripts = bottom_small.flatten().unwrap_or_default()
+                + &top_small.flatten().unwrap_or_default();
+            return Some(Block::row(vec![base, Block::text(&scripts)]));
+        }
+        // The superscript ends above the baseline and the subscript starts below it
+        let top = top.map_or(vec![], |top| top.lines);
+        let bottom = bottom.map_or(vec![], |bottom| bottom.lines);
+        let size = top
+            .iter()
+            .chain(&bottom)
+            .map(|line| width(line))
+            .max()
+            .unwrap_or(0);
+        let mut lines: Vec<String> = top.iter().map(|line| pad(line, size)).collect();
+        let baseline = lines.len();
+        lines.push(" ".repeat(size));
+        lines.extend(bottom.iter().map(|line| pad(line, size)));
+        Some(Block::row(vec![base, Block { lines, baseline }]))
+    }
+
+    /// Lay out the rows of cells of a table
+    fn table(&self, rows: Vec<Vec<LinkedNode>>, left: bool) -> Block {
+        grid(
+            rows.iter()
+                .map(|cells| cells.iter().map(|cell| self.unparen(cell)).collect())
+                .collect(),
+            left,
+        )
+    }
+
+    /// Lay out the cells of a case split at its alignment points, like `x & "if" x > 0`
+    fn cells(&self, node: &LinkedNode) -> Vec<Block> {
+        if node.kind() != SyntaxKind::Math {
+            return vec![self.unparen(node)];
+        }
+        let children: Vec<LinkedNode> = node.children().collect();
+        children
+            .split(|child| child.kind() == SyntaxKind::MathAlignPoint)
+            .map(|cell| self.join(cell))
+            .collect()
+    }
+
+    /// Lay out a function call, like `frac(a, b)`, `hat(x)` or `mat(1, 2; 3, 4)`
+    fn call(&self, node: &LinkedNode) -> Block {
+        let name = function_name(node).unwrap_or_default();
+        let args = call_arguments(node);
+        let arg = |index: usize| {
+            args.get(index)
+                .map_or_else(|| Block::text(""), |arg| self.unparen(arg))
+        };
+        match name.as_str() {
+            "frac" => fraction(arg(0), arg(1)),
+            "binom" => Block::column(vec![arg(0), arg(1)], 0).delimited("(", ")"),
+            "sqrt" => root(None, arg(0)),
+            "root" => root(Some(arg(0)), arg(1)),
+            "lr" | "display" | "inline" | "script" | "sscript" | "cramped" | "limits"
+            | "scripts" | "mid" | "cancel" => match args.first() {
+                // The delimiters of `lr` are its first and last children
+                Some(arg) if name == "lr" && parenthesized(arg).is_none() => {
+                    let children: Vec<LinkedNode> = arg
+                        .children()
+                        .filter(|child| !child.kind().is_trivia())
+                        .collect();
+                    match (children.first(), children.last()) {
+                        (Some(open), Some(close)) if children.len() > 1 => {
+                            let inner: Vec<LinkedNode> = arg
+                                .children()
+                                .filter(|child| {
+                                    child.offset() > open.offset()
+                                        && child.offset() < close.offset()
+                                })
+                                .collect();
+                            let (open, close) = (self.unparen(open), self.unparen(close));
+                            match (open.single_line(), close.single_line()) {
+                                (Some(open), Some(close)) => {
+                                    self.join(&inner).delimited(open, close)
+                                }
+                                _ => self.unparen(arg),
+                            }
+                        }
+                        _ => self.unparen(arg),
+                    }
+                }
+                _ => arg(0),
+            },
+            "op" => match args.first() {
+                Some(text) if text.kind() == SyntaxKind::Str => Block::text(
+                    text.cast::<ast::Str>()
+                        .map(|text| text.get())
+                        .unwrap_or_default()
+                        .as_str(),
+                ),
+                _ => arg(0),
+            },
+            "mat" => self.table(matrix_rows(&args), false).delimited("(", ")"),
+            "vec" => self
+                .table(args.iter().map(|arg| vec![arg.clone()]).collect(), false)
+                .delimited("(", ")"),
+            "cases" => {
+                grid(args.iter().map(|arg| self.cells(arg)).collect(), true).delimited("{", "")
+            }
+            _ => {
+                if let Some((open, close)) = delimiters(&name) {
+                    return arg(0).delimited(&open.to_string(), &close.to_string());
+                }
+                if let Some((combining, over)) = accent(&name) {
+                    let content = arg(0);
+                    return match content.single_line() {
+                        Some(text) if width(text) == 1 => {
+                            Block::text(&format!("{}{}", text, combining))
+                        }
+                        _ => {
+                            let size = content.width();
+                            let over = match over {
+                                '‾' => "‾".repeat(size),
+                                '→' => format!("{}→", "─".repeat(size.saturating_sub(1))),
+                                '←' => format!("←{}", "─".repeat(size.saturating_sub(1))),
+                                over => over.to_string(),
+                            };
+                            Block::column(vec![Block::text(&over), content], 1)
+                        }
+                    };
+                }
+                if let Some((map, _)) = get_letter_style(&name) {
+                    if let Some(content) = args
+                        .first()
+                        .and_then(|arg| letter_style_content(arg, self.options))
+                    {
+                        return Block::text(&apply_letter_style(map, &content));
+                    }
+                    return arg(0);
+                }
+                // Any other call is laid out on a row, like `f(x, y)`
+                let mut items = vec![];
+                for (index, argument) in args.iter().enumerate() {
+                    if index > 0 {
+                        items.push(Block::text(", "));
+                    }
+                    items.push(self.unparen(argument));
+                }
+                Block::row(vec![
+                    Block::text(&self.name(&name)),
+                    Block::row(items).delimited("(", ")"),
+                ])
+            }
+        }
+    }
+}
+
+/// Stack a numerator over a denominator, with a bar wider than both of them
+fn fraction(num: Block, denom: Block) -> Block {
+    let bar = Block::text(&"─".repeat(num.width().max(denom.width()) + 2));
+    Block::column(vec![num, bar, denom], 1)
+}
+
+/// Write a radical over a radicand, with its index before it
+fn root(index: Option<Block>, radicand: Block) -> Block {
+    let size = radicand.width();
+    let height = radicand.height();
+    let mut lines = vec![format!(" {}", "_".repeat(size))];
+    for (row, line) in radicand.lines.iter().enumerate() {
+        let sign = if row == height - 1 { '√' } else { '│' };
+        lines.push(format!("{}{}", sign, pad(line, size)));
+    }
+    let mut block = Block {
+        lines,
+        baseline: radicand.baseline + 1,
+    };
+    // The index is written with superscripts before the sign
+    if let Some(index) = index {
+        let index = small(&index, superscript)
+            .or_else(|| index.single_line().map(str::to_string))
+            .unwrap_or_default();
+        let size = width(&index);
+        for (row, line) in block.lines.iter_mut().enumerate() {
+            let prefix = if row == height {
+                index.clone()
+            } else {
+                " ".repeat(size)
+            };
+            *line = format!("{}{}", prefix, line);
+        }
+    }
+    block
+}
+
+/// Lay out the equations between two UTF-16 offsets of a source on several lines of unicode text, \
+/// separated by empty lines, the truncated equations ending with `…` \
+/// An empty range lays out the equation around it
+pub fn to_layout(source: &Source, start: usize, end: usize, options: &Options) -> String {
+    let layout = Layout {
+        options,
+        limit: DepthLimit::new(options.max_depth),
+    };
+    equations_in_range(source, start, end)
+        .into_iter()
+        .filter_map(|equation| {
+            let block = layout.layout(&equation)?;
+            Some(if layout.limit.take_truncated() {
+                Block::row(vec![block, Block::text(" …")])
+            } else {
+                block
+            })
+        })
+        .map(|block| {
+            block
+                .lines
+                .iter()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
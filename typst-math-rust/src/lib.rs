@@ -1,14 +1,59 @@
+mod batch;
+mod braille;
+mod cancellation;
+mod convert;
+mod editors;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fixes;
+mod folding;
+mod hints;
+mod input;
 mod interface;
+mod latex;
+mod layout;
+mod mathml;
+#[cfg(feature = "napi")]
+pub mod node;
 mod parser;
+#[cfg(feature = "preview")]
+mod preview;
+mod protocol;
+mod semantic;
+mod session;
+mod speech;
+mod statistics;
+mod unicode;
 mod utils;
 
-use std::{collections::HashMap, ops::Range};
+use std::{borrow::Cow, collections::HashMap, ops::Range};
 
 use crate::parser::parser::State;
-use interface::{CustomSymbol, Decoration, Options, Parsed};
-use parser::{parser::ast_dfs, utils::InnerParser};
-use typst_syntax::LinkedNode;
-use utils::hook::set_panic_hook;
+pub use batch::Batch;
+pub use cancellation::CancellationToken;
+pub use editors::{EmacsOverlay, Extmark};
+pub use error::Error;
+pub use interface::{
+    AttachmentStyle, CategoryCount, CustomSymbol, Decoration, Delta, Diagnostic, DocumentResult,
+    DocumentStatistics, EquationInfo, EquationSymbol, Features, FoldingRange, Fonts, InlayHint,
+    InputCandidate, Lint, Options, Parsed, Position, QuickFix, Scope, SemanticToken, Severity,
+    Suggestion, SymbolCount, SymbolHover, SymbolInfo, SymbolSource, TextEdit, TokenKind,
+};
+use parser::{
+    bindings::Bindings,
+    overlaps::resolve_overlaps,
+    parser::{ast_dfs, is_in_scope, DocumentContext},
+    utils::{
+        byte_line_column, byte_range_to_utf16, get_category_by_char, get_symbol, leaf_at,
+        list_symbols as list_known_symbols, names_for_char as find_names, syntax_diagnostics,
+        utf16_position, InnerParser,
+    },
+};
+pub use protocol::handle_request;
+pub use session::Session;
+use typst_syntax::{LinkedNode, Source, SyntaxKind};
+use utils::{hook::set_panic_hook, interner::Interner, search::search, symbols::Category};
 #[cfg(not(feature = "coverage"))]
 use wasm_bindgen::prelude::*;
 
@@ -18,22 +63,54 @@ pub fn init_lib() {
     set_panic_hook();
 }
 
-/// Retrieve all nodes in a given range
+/// Retrieve all nodes in a given range, in the order of the document \
+/// The tree is walked with an explicit stack, so that deeply nested documents don't overflow it
 pub fn find_node<'a>(
     range: Range<usize>,
     current: LinkedNode<'a>,
     nodes: &mut Vec<LinkedNode<'a>>,
 ) {
-    if current.range().start >= range.start && current.range().end <= range.end {
-        nodes.push(current.clone())
-    } else {
-        for child in current.children() {
-            find_node(range.clone(), child, nodes);
+    let mut stack = vec![current];
+    while let Some(node) = stack.pop() {
+        if node.range().start >= range.start && node.range().end <= range.end {
+            nodes.push(node);
+        } else {
+            let children: Vec<LinkedNode> = node.children().collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+}
+
+/// Retrieve the nodes crossing a given range, without splitting equations so that they are parsed completely \
+/// The tree is walked with an explicit stack, like `find_node`
+pub fn find_visible_nodes<'a>(
+    range: Range<usize>,
+    current: LinkedNode<'a>,
+    nodes: &mut Vec<LinkedNode<'a>>,
+) {
+    let mut stack = vec![current];
+    while let Some(node) = stack.pop() {
+        let node_range = node.range();
+        if node_range.start >= node_range.end
+            || node_range.end <= range.start
+            || node_range.start >= range.end
+        {
+            continue;
+        }
+        if (node_range.start >= range.start && node_range.end <= range.end)
+            || node.kind() == SyntaxKind::Equation
+            || node.children().len() == 0
+        {
+            nodes.push(node);
+        } else {
+            let children: Vec<LinkedNode> = node.children().collect();
+            stack.extend(children.into_iter().rev());
         }
     }
 }
 
 /// Parse a document and return the decorations to apply
+#[allow(clippy::too_many_arguments)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
 pub fn parse_document(
     content: &str,
@@ -45,119 +122,698 @@ pub fn parse_document(
     hide_unnecessary_delimiters: bool,
     blacklisted_symbols: Vec<String>,
     custom_symbols: Vec<CustomSymbol>,
-) -> Parsed {
+) -> Result<Parsed, Error> {
+    let mut options = Options {
+        features: Features::from_rendering_mode(rendering_mode),
+        render_outside_math,
+        render_spaces,
+        hide_unnecessary_delimiters,
+        blacklisted_symbols,
+        ..Default::default()
+    };
+    for symbol in custom_symbols {
+        options.add_custom_symbol(symbol);
+    }
+    parse_document_with_options(content, edited_line_start, edited_line_end, &options)
+}
+
+/// Parse an entire document and return its decorations as Neovim extmarks, concealing symbols with plain text
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn parse_document_extmarks(content: &str, options: &Options) -> Vec<Extmark> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    let parsed = decorate(&source, None, options, &mut Interner::default(), None);
+    editors::extmarks(&source, &parsed)
+}
+
+/// Parse an entire document and return its decorations as Emacs overlays, displaying symbols with plain text
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn parse_document_overlays(content: &str, options: &Options) -> Vec<EmacsOverlay> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    let parsed = decorate(&source, None, options, &mut Interner::default(), None);
+    editors::overlays(&source, &parsed)
+}
+
+/// Parse an entire document and return its decorations as a single JSON payload, typed by the generated `Parsed.ts`, \
+/// for hosts which don't use the wasm-bindgen classes of the results
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn parse_document_json(content: &str, options: &Options) -> String {
+    let source = typst_syntax::Source::detached(content.to_string());
+    decorate(&source, None, options, &mut Interner::default(), None).to_json()
+}
+
+/// Parse a document and return the decorations to apply, with options built from JS \
+/// Fail if the first edited line is after the last one, lines outside the document being clamped to it
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn parse_document_with_options(
+    content: &str,
+    edited_line_start: i32,
+    edited_line_end: i32,
+    options: &Options,
+) -> Result<Parsed, Error> {
     // Generate a fake source
     let mut source = typst_syntax::Source::detached(content.to_string());
-    println!("{:#?}", source.root());
 
-    // These variable contains the range of the document that was parsed incrementally and will be returned to the extension
-    let mut edit_start_line = 0;
-    let mut edit_end_line = 0;
-    let mut edit_start_column = 0;
-    let mut edit_end_column = 0;
+    // if edited_line_start is -1, we render the complete text
+    if edited_line_start < 0 {
+        return Ok(decorate(
+            &source,
+            None,
+            options,
+            &mut Interner::default(),
+            None,
+        ));
+    }
+    let last_line = source.len_lines().saturating_sub(1);
+    let start_line = edited_line_start as usize;
+    let end_line = edited_line_end as usize;
+    if start_line > end_line {
+        return Err(Error::InvalidLines {
+            start: start_line,
+            end: end_line,
+        });
+    }
+    let edited_range = source
+        .line_to_range(start_line)
+        .or_else(|| source.line_to_range(0))
+        .map_or(0, |range| range.start)
+        ..source
+            .line_to_range(end_line.min(last_line))
+            .map_or(source.len_bytes(), |range| range.end);
+    let Some(txt) = source.get(edited_range.clone()).map(str::to_string) else {
+        return Ok(decorate(
+            &source,
+            None,
+            options,
+            &mut Interner::default(),
+            None,
+        ));
+    };
+
+    // Create a "fake" edit of the document (We don't change the content) to get the part which was reparsed
+    let range = source.edit(edited_range, txt.as_str());
+    Ok(decorate(
+        &source,
+        Some(range),
+        options,
+        &mut Interner::default(),
+        None,
+    ))
+}
+
+/// Parse only the lines from `start_line` to `end_line` (included) of a document, like the visible part of an editor \
+/// Equations crossing these lines are parsed completely, and bindings are still collected in the entire document
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn parse_range(content: &str, start_line: usize, end_line: usize, options: &Options) -> Parsed {
+    let source = typst_syntax::Source::detached(content.to_string());
+    decorate_lines(
+        &source,
+        start_line,
+        end_line,
+        options,
+        &mut Interner::default(),
+        None,
+    )
+}
+
+/// Get the symbol rendered at a UTF-16 offset of a document, None if there is no decoration there
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn symbol_at(content: &str, offset: usize, options: &Options) -> Option<SymbolHover> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    find_symbol_at(&source, offset, options, None)
+}
+
+/// Get where the identifier of math at a UTF-16 offset of a document is declared, None if it isn't bound by the user
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn definition_at(content: &str, offset: usize) -> Option<Position> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    find_definition(&source, offset)
+}
+
+/// Get the spans of the identifiers to rename with the identifier of math at a UTF-16 offset of a document, in the order of the document \
+/// They refer to the same declaration, respecting the scopes of the bindings, and the declaration is included. \
+/// Empty if the identifier isn't bound by the user
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn rename_locations(content: &str, offset: usize) -> Vec<Position> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    find_references(&source, offset)
+}
+
+/// Parse a document and give its decorations to `emit` one top-level node at a time, from the top of the document
+pub fn stream_document(content: &str, options: &Options, mut emit: impl FnMut(Parsed)) {
+    let source = typst_syntax::Source::detached(content.to_string());
+    decorate_streaming(&source, options, &mut Interner::default(), None, &mut emit);
+}
+
+/// Parse a document and call `callback` with the decorations of each top-level node, from the top of the document
+#[cfg(not(feature = "coverage"))]
+#[wasm_bindgen]
+pub fn parse_document_streaming(content: &str, options: &Options, callback: &js_sys::Function) {
+    stream_document(content, options, |parsed| {
+        let _ = callback.call1(&JsValue::NULL, &JsValue::from(parsed));
+    });
+}
+
+/// Generate the decorations of the nodes in the reparsed range of a source, or of the entire source if it is None
+pub(crate) fn decorate(
+    source: &Source,
+    reparsed: Option<Range<usize>>,
+    options: &Options,
+    interner: &mut Interner,
+    context: Option<&DocumentContext>,
+) -> Parsed {
     // List of nodes to parse again
     let mut nodes = vec![];
-    if edited_line_start >= 0 {
-        // if edited_line_start is -1, we render the complete text
-        let edited_range = source
-            .line_to_range(edited_line_start as usize)
-            .unwrap_or(
-                source
-                    .line_to_range(0)
-                    .expect("No lines in the current source"),
-            )
-            .start
-            ..source
-                .line_to_range(edited_line_end as usize)
-                .unwrap_or(
-                    source
-                        .line_to_range(source.len_lines() - 1)
-                        .expect("Unreachable"),
-                )
-                .end;
-
-        // Create a "fake" edit of the document (We don't change the content) to get the part which was reparsed
-        let txt = source
-            .get(edited_range.clone())
-            .expect("Edited range outside source")
-            .to_string();
-        let range = source.edit(edited_range, txt.as_str());
-
-        let root = source.find(source.root().span()).unwrap();
+    let root = LinkedNode::new(source.root());
+    if let Some(range) = reparsed {
         // Find all nodes in this range
-        find_node(range.clone(), root.clone(), &mut nodes);
+        find_node(range, root.clone(), &mut nodes);
+    }
+    if nodes.is_empty() {
+        // Parse the entire document, the edited lines stay at 0
+        let parsed = decorate_nodes(
+            source,
+            &root,
+            vec![root.clone()],
+            options,
+            interner,
+            context,
+        );
+        return Parsed {
+            edit_start_line: 0,
+            edit_end_line: 0,
+            edit_start_column: 0,
+            edit_end_column: 0,
+            ..parsed
+        };
+    }
+    decorate_nodes(source, &root, nodes, options, interner, context)
+}
 
-        // Get the range of part which will be reparsed
-        let first = source.find(nodes.first().unwrap().span()).unwrap().range();
-        let last = source.find(nodes.last().unwrap().span()).unwrap().range();
-        edit_start_line = source.byte_to_line(first.start).unwrap();
-        edit_end_line = source.byte_to_line(last.end).unwrap();
-        edit_start_column = source.byte_to_column(first.start).unwrap();
-        edit_end_column = source.byte_to_column(last.end).unwrap();
-    } else {
-        // Parse the entire document
-        let root = source.find(source.root().span()).unwrap();
-        nodes.push(root);
-    }
-
-    // Generate custom symbols hashmap
-    let custom_symbols = custom_symbols
+/// Generate the decorations of the nodes crossing some lines of a source
+pub(crate) fn decorate_lines(
+    source: &Source,
+    start_line: usize,
+    end_line: usize,
+    options: &Options,
+    interner: &mut Interner,
+    context: Option<&DocumentContext>,
+) -> Parsed {
+    let len = source.len_bytes();
+    let start = source.line_to_byte(start_line).unwrap_or(len);
+    let end = source
+        .line_to_range(end_line)
+        .map_or(len, |range| range.end);
+    let mut nodes = vec![];
+    let root = LinkedNode::new(source.root());
+    find_visible_nodes(start..end.max(start), root.clone(), &mut nodes);
+    decorate_nodes(source, &root, nodes, options, interner, context)
+}
+
+/// Find the narrowest decoration covering a UTF-16 offset, parsing only its line
+pub(crate) fn find_symbol_at(
+    source: &Source,
+    offset: usize,
+    options: &Options,
+    context: Option<&DocumentContext>,
+) -> Option<SymbolHover> {
+    let line = source.byte_to_line(source.utf16_to_byte(offset)?)?;
+    let parsed = decorate_lines(
+        source,
+        line,
+        line,
+        options,
+        &mut Interner::default(),
+        context,
+    );
+    let (decoration, position) = parsed
+        .decorations
         .iter()
-        .map(|pair| {
-            return (
-                pair.name.clone(),
-                CustomSymbol {
-                    name: pair.name.clone(),
-                    symbol: pair.symbol.clone(),
-                    category: pair.category.clone(),
-                },
-            );
+        .flat_map(|decoration| {
+            decoration
+                .positions
+                .iter()
+                .map(move |position| (decoration, position))
         })
-        .collect();
+        .filter(|(_, position)| position.start <= offset && offset < position.end)
+        .min_by_key(|(_, position)| position.end - position.start)?;
+    let start = source.utf16_to_byte(position.start)?;
+    let end = source.utf16_to_byte(position.end)?;
+    let name = source.get(start..end)?.to_string();
+    let category = get_symbol(name.clone(), options)
+        .map(|(category, _)| category)
+        .or_else(|| {
+            decoration
+                .symbol
+                .chars()
+                .next()
+                .and_then(get_category_by_char)
+        })
+        .unwrap_or(Category::Default);
+    Some(SymbolHover {
+        name,
+        symbol: decoration.symbol.clone(),
+        category,
+        position: position.clone(),
+    })
+}
 
-    let mut result: HashMap<String, Decoration> = HashMap::new();
-    let options = Options {
-        rendering_mode,
-        render_outside_math,
-        render_spaces,
-        hide_unnecessary_delimiters,
-        blacklisted_symbols,
-        custom_symbols,
+/// Get the identifier of math at a UTF-16 offset, the offset being inside it or just after it
+pub(crate) fn math_identifier_at<'a>(
+    root: &LinkedNode<'a>,
+    source: &Source,
+    offset: usize,
+) -> Option<LinkedNode<'a>> {
+    let byte = source.utf16_to_byte(offset)?;
+    // Code identifiers are only considered in equations, like `f` in `$#f(x)$`
+    let is_identifier = |leaf: &LinkedNode| match leaf.kind() {
+        SyntaxKind::MathIdent => true,
+        SyntaxKind::Ident => {
+            std::iter::successors(leaf.parent().cloned(), |node| node.parent().cloned())
+                .any(|node| node.kind() == SyntaxKind::Equation)
+        }
+        _ => false,
     };
-    let mut state = State {
-        is_base: false,
-        is_attachment: false,
+    leaf_at(root, byte + 1)
+        .filter(is_identifier)
+        .or_else(|| leaf_at(root, byte).filter(is_identifier))
+}
+
+/// Find where the identifier of math at a UTF-16 offset is declared by a let binding, an import or a parameter
+pub(crate) fn find_definition(source: &Source, offset: usize) -> Option<Position> {
+    let root = LinkedNode::new(source.root());
+    let identifier = math_identifier_at(&root, source, offset)?;
+    let declaration = Bindings::at(&root, &identifier).declaration(identifier.text())?;
+    let range = byte_range_to_utf16(source, &source.range(declaration)?)?;
+    Some(utf16_position(source, range))
+}
+
+/// Find the identifiers referring to the same declaration as the identifier of math at a UTF-16 offset,
+/// the declaration and the identifier included, empty if it isn't bound by the user
+pub(crate) fn find_references(source: &Source, offset: usize) -> Vec<Position> {
+    let root = LinkedNode::new(source.root());
+    let Some(identifier) = math_identifier_at(&root, source, offset) else {
+        return vec![];
     };
-    // Parse the AST produced by typst over nodes
-    for node in nodes {
-        let mut parser = InnerParser::new(&source, &node, &mut result, &mut state, &options);
-        ast_dfs(&mut parser, &node, "", "", (0, 0));
+    let Some(declaration) = Bindings::at(&root, &identifier).declaration(identifier.text()) else {
+        return vec![];
+    };
+    Bindings::references(&root, identifier.text(), declaration)
+        .into_iter()
+        .filter_map(|range| Some(utf16_position(source, byte_range_to_utf16(source, &range)?)))
+        .collect()
+}
+
+/// Generate the decorations of a list of nodes, the edited lines being the ones they cover
+fn decorate_nodes(
+    source: &Source,
+    root: &LinkedNode,
+    nodes: Vec<LinkedNode>,
+    options: &Options,
+    interner: &mut Interner,
+    context: Option<&DocumentContext>,
+) -> Parsed {
+    // These variable contains the range of the document that was parsed and will be returned to the extension
+    let mut edit_start_line = 0;
+    let mut edit_end_line = 0;
+    let mut edit_start_column = 0;
+    let mut edit_end_column = 0;
+    if let (Some(first), Some(last)) = (nodes.first(), nodes.last()) {
+        // Get the range of part which will be reparsed
+        let first = first.range();
+        let last = last.range();
+        (edit_start_line, edit_start_column) =
+            byte_line_column(source, first.start).unwrap_or_default();
+        (edit_end_line, edit_end_column) = byte_line_column(source, last.end).unwrap_or_default();
     }
 
+    let uuids_start = interner.next_id();
+    // Package rules only apply if the package is imported, and macros and other bindings can be defined anywhere in the document
+    let context = context.map_or_else(|| Cow::Owned(DocumentContext::collect(root)), Cow::Borrowed);
+    let mut state = context.state(root, options, std::mem::take(interner));
+    // Parse the AST produced by typst over nodes, skipping the ones in equations outside the scope
+    let nodes: Vec<LinkedNode> = nodes
+        .into_iter()
+        .filter(|node| is_in_scope(node, options))
+        .collect();
+    let mut result = traverse(source, root, nodes, &mut state, options);
+    resolve_overlaps(source, &mut result);
+    *interner = state.interner;
+
     // Convert the hasmap into an array
     Parsed {
         decorations: result.into_values().collect(),
+        uuids: interner.uuids_since(uuids_start),
+        uuids_start,
         edit_start_line,
         edit_end_line,
         edit_start_column,
         edit_end_column,
         erroneous: source.root().erroneous(),
+        diagnostics: syntax_diagnostics(source, source.root()),
+        suggestions: state.suggestions,
+        lints: state.lints,
+        truncated: state.truncated,
+        cancelled: state.cancelled,
+    }
+}
+
+/// Generate the decorations of the entire source one top-level node at a time, in the order of the document \
+/// Each batch only contains the uuids met for the first time, and a decoration can be in several batches with different positions
+pub(crate) fn decorate_streaming(
+    source: &Source,
+    options: &Options,
+    interner: &mut Interner,
+    context: Option<&DocumentContext>,
+    emit: &mut dyn FnMut(Parsed),
+) {
+    let root = LinkedNode::new(source.root());
+    let context = context.map_or_else(
+        || Cow::Owned(DocumentContext::collect(&root)),
+        Cow::Borrowed,
+    );
+    let mut state = State {
+        // Top-level nodes are one level below the root
+        depth: 1,
+        ..context.state(&root, options, std::mem::take(interner))
+    };
+    for node in root.children().filter(|node| is_in_scope(node, options)) {
+        if state.cancelled {
+            break;
+        }
+        let uuids_start = state.interner.next_id();
+        let mut result = traverse_nodes(source, std::slice::from_ref(&node), &mut state, options);
+        resolve_overlaps(source, &mut result);
+        if result.is_empty() {
+            continue;
+        }
+        let range = node.range();
+        let (edit_start_line, edit_start_column) =
+            byte_line_column(source, range.start).unwrap_or_default();
+        let (edit_end_line, edit_end_column) =
+            byte_line_column(source, range.end).unwrap_or_default();
+        emit(Parsed {
+            decorations: result.into_values().collect(),
+            uuids: state.interner.uuids_since(uuids_start),
+            uuids_start,
+            edit_start_line,
+            edit_end_line,
+            edit_start_column,
+            edit_end_column,
+            erroneous: source.root().erroneous(),
+            diagnostics: syntax_diagnostics(source, node.get()),
+            suggestions: std::mem::take(&mut state.suggestions),
+            lints: std::mem::take(&mut state.lints),
+            truncated: state.truncated,
+            cancelled: state.cancelled,
+        });
+    }
+    *interner = state.interner;
+}
+
+/// Traverse a list of nodes, one after the other
+fn traverse_nodes(
+    source: &Source,
+    nodes: &[LinkedNode],
+    state: &mut State,
+    options: &Options,
+) -> HashMap<u32, Decoration> {
+    let mut result = HashMap::new();
+    for node in nodes {
+        // Nodes reparsed inside an equation keep the style of the equation
+        state.math_style = state.styles.style(node);
+        let mut parser = InnerParser::new(source, node, &mut result, state, options);
+        ast_dfs(&mut parser, node, "", "", (0, 0));
+    }
+    result
+}
+
+/// Traverse the nodes to parse, in a single thread in WASM
+#[cfg(target_arch = "wasm32")]
+fn traverse(
+    source: &Source,
+    _root: &LinkedNode,
+    nodes: Vec<LinkedNode>,
+    state: &mut State,
+    options: &Options,
+) -> HashMap<u32, Decoration> {
+    traverse_nodes(source, &nodes, state, options)
+}
+
+/// Minimum number of top-level nodes for the document to be traversed in parallel, smaller ones being faster in a single thread
+#[cfg(not(target_arch = "wasm32"))]
+const MIN_PARALLEL_NODES: usize = 256;
+
+/// Traverse the nodes to parse, the top-level nodes of the entire document being traversed in parallel in native builds \
+/// They are split into one contiguous chunk per thread, each chunk having its own state, and the results are merged in the document order
+#[cfg(not(target_arch = "wasm32"))]
+fn traverse(
+    source: &Source,
+    root: &LinkedNode,
+    nodes: Vec<LinkedNode>,
+    state: &mut State,
+    options: &Options,
+) -> HashMap<u32, Decoration> {
+    use rayon::prelude::*;
+
+    let entire = nodes.len() == 1 && nodes[0].span() == root.span();
+    let threads = rayon::current_num_threads();
+    if !entire
+        || root.kind() != SyntaxKind::Markup
+        || threads < 2
+        || root.children().len() < MIN_PARALLEL_NODES
+    {
+        return traverse_nodes(source, &nodes, state, options);
+    }
+    let count = root.children().len();
+    let chunk_size = count.div_ceil(threads);
+    // Top-level nodes are one level below the root, each chunk interns its own uuids
+    let interner = std::mem::take(&mut state.interner);
+    let base = State {
+        depth: 1,
+        ..state.clone()
+    };
+    state.interner = interner;
+    // Linked nodes can't be shared between threads, each chunk takes its nodes from its own root
+    let results: Vec<(HashMap<u32, Decoration>, State)> = (0..count)
+        .step_by(chunk_size)
+        .collect::<Vec<usize>>()
+        .into_par_iter()
+        .map(|start| {
+            let root = LinkedNode::new(source.root());
+            let chunk: Vec<LinkedNode> = root.children().skip(start).take(chunk_size).collect();
+            let mut state = base.clone();
+            let result = traverse_nodes(source, &chunk, &mut state, options);
+            (result, state)
+        })
+        .collect();
+
+    // Merge the decorations, interning their uuids in the order they were met
+    let mut merged: HashMap<u32, Decoration> = HashMap::new();
+    for (mut result, mut chunk) in results {
+        state.truncated |= chunk.truncated;
+        state.cancelled |= chunk.cancelled;
+        state.suggestions.append(&mut chunk.suggestions);
+        state.lints.append(&mut chunk.lints);
+        for id in 0..chunk.interner.next_id() {
+            let (Some(mut decoration), Some(uuid)) = (result.remove(&id), chunk.interner.uuid(id))
+            else {
+                continue;
+            };
+            let id = state.interner.intern([uuid]);
+            if let Some(existing) = merged.get_mut(&id) {
+                existing.positions.append(&mut decoration.positions);
+            } else {
+                decoration.id = id;
+                merged.insert(id, decoration);
+            }
+        }
+    }
+    merged
+}
+
+/// List every symbol known with the options, their character, category and origin, for completions and symbol pickers
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn list_symbols(options: &Options) -> Vec<SymbolInfo> {
+    list_known_symbols(options)
+}
+
+/// Convert the typst math between two UTF-16 offsets of a document into plain unicode text, like `x²+aₙ≤∑`
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn render_to_unicode(content: &str, start: usize, end: usize, options: &Options) -> String {
+    let source = typst_syntax::Source::detached(content.to_string());
+    unicode::to_unicode(&source, start, end, options)
+}
+
+/// Convert the equations between two UTF-16 offsets of a document into presentation MathML, one `math` element per line,
+/// to paste them into word processors or web pages \
+/// An empty range converts the equation around the offset
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn render_to_mathml(content: &str, start: usize, end: usize, options: &Options) -> String {
+    let source = typst_syntax::Source::detached(content.to_string());
+    mathml::to_mathml(&source, start, end, options)
+}
+
+/// Convert the equations between two UTF-16 offsets of a document into LaTeX, one equation per line,
+/// to paste them into journals or tools requiring LaTeX \
+/// An empty range converts the equation around the offset
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn render_to_latex(content: &str, start: usize, end: usize, options: &Options) -> String {
+    let source = typst_syntax::Source::detached(content.to_string());
+    latex::to_latex(&source, start, end, options)
+}
+
+/// Lay out the equations between two UTF-16 offsets of a document on several lines of unicode text, with fraction bars,
+/// stacked limits and matrix grids, the equations being separated by empty lines \
+/// An empty range lays out the equation around the offset
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn render_to_layout(content: &str, start: usize, end: usize, options: &Options) -> String {
+    let source = typst_syntax::Source::detached(content.to_string());
+    layout::to_layout(&source, start, end, options)
+}
+
+/// Render the equation around a UTF-16 offset of a document into SVG with the typst compiler, with a css hex text color,
+/// for previews of equations too complex to be decorated \
+/// None if there is no equation there or if it doesn't compile
+#[cfg(feature = "preview")]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn equation_svg(content: &str, offset: usize, fill: &str) -> Option<String> {
+    preview::equation_svg(content, offset, fill)
+}
+
+/// Render the equation around a UTF-16 offset of a document into PNG with the typst compiler, with `scale` pixels per point
+#[cfg(feature = "preview")]
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn equation_png(content: &str, offset: usize, fill: &str, scale: f32) -> Option<Vec<u8>> {
+    preview::equation_png(content, offset, fill, scale)
+}
+
+/// Read the equation around a UTF-16 offset of a document aloud, like `x squared plus y sub i`, for screen readers \
+/// None if there is no equation there
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn speak_equation(content: &str, offset: usize, options: &Options) -> Option<String> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    speech::speak(&source, offset, options)
+}
+
+/// Write the equation around a UTF-16 offset of a document in Nemeth braille, with unicode braille patterns \
+/// None if there is no equation there
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn braille_equation(content: &str, offset: usize, options: &Options) -> Option<String> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    braille::nemeth(&source, offset, options)
+}
+
+/// List the equations of a document in their order, with their span, display mode, source and unicode rendering
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn list_equations(content: &str, options: &Options) -> Vec<EquationInfo> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    unicode::equations(&source, options)
+}
+
+/// List the equations of a document which have a label, with their label name, span and unicode preview, for outlines
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn equation_symbols(content: &str, options: &Options) -> Vec<EquationSymbol> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    unicode::equation_symbols(&source, options)
+}
+
+/// Export the equations of a document into a Markdown document, each equation under a heading with its label,
+/// its typst source and its unicode rendering, to share them outside of typst \
+/// Empty if the document has no equation
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn export_markdown(content: &str, options: &Options) -> String {
+    let source = typst_syntax::Source::detached(content.to_string());
+    unicode::to_markdown(&source, options)
+}
+
+/// Get the edits replacing the literal unicode math characters between two UTF-16 offsets of a document by their typst names, like `α` -> `alpha`
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn typstify(content: &str, start: usize, end: usize) -> Vec<TextEdit> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    unicode::typstify(&source, start, end)
+}
+
+/// List the lines of a document which can be folded: display equations and `mat` or `cases` calls spanning several lines
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn folding_ranges(content: &str) -> Vec<FoldingRange> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    folding::folding_ranges(&source)
+}
+
+/// List the inlay hints describing the named arguments of math functions, like `delim`, `limits` or `size`
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn inlay_hints(content: &str) -> Vec<InlayHint> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    hints::inlay_hints(&source)
+}
+
+/// List the fixes of common mistakes in math, like `e^-x` whose script is only the sign and is fixed into `e^(-x)`,
+/// or LaTeX commands like `\frac{a}{b}` converted into typst
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn quick_fixes(content: &str) -> Vec<QuickFix> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    fixes::quick_fixes(&source)
+}
+
+/// List the semantic tokens of the math of a document (operators, relations, greek letters, numbers, functions and delimiters),
+/// to highlight the source of equations even when symbols aren't rendered
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn semantic_tokens(content: &str, options: &Options) -> Vec<SemanticToken> {
+    let source = typst_syntax::Source::detached(content.to_string());
+    semantic::semantic_tokens(&source, options)
+}
+
+/// Get the statistics of the math of a document: number of equations, symbols used by frequency with the ways
+/// they are written, symbols by category and deepest nesting
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn document_statistics(content: &str, options: &Options) -> DocumentStatistics {
+    let source = typst_syntax::Source::detached(content.to_string());
+    statistics::document_statistics(&source, options)
+}
+
+/// Find the symbols whose name matches a fuzzy query like `arow r`, the best `limit` matches first
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn search_symbols(query: &str, options: &Options, limit: usize) -> Vec<SymbolInfo> {
+    search(list_known_symbols(options), query, limit)
+}
+
+/// Find the symbols matching the abbreviation typed before a UTF-16 column of a line, for a math input mode \
+/// LaTeX commands like `\le` give their typst equivalent, and names like `int` or `RR` the symbols they start, the best `limit` first
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn input_candidates(
+    line: &str,
+    column: usize,
+    options: &Options,
+    limit: usize,
+) -> Vec<InputCandidate> {
+    input::input_candidates(line, column, options, limit)
+}
+
+/// Get the typst names of a unicode character, from the shortest one, empty if it isn't a single known character
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub fn names_for_char(character: &str) -> Vec<String> {
+    let mut chars = character.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => find_names(c).to_vec(),
+        _ => vec![],
     }
 }
 
 /// Generate a custom symbol struct easily from JS
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
 pub fn generate_custom_symbol(name: String, symbol: String, category: String) -> CustomSymbol {
-    return CustomSymbol {
+    CustomSymbol {
         name,
         symbol,
         category,
-    };
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{generate_custom_symbol, init_lib, parse_document};
+    use typst_syntax::{LinkedNode, Source};
+
+    use crate::{find_node, find_visible_nodes, generate_custom_symbol, init_lib, parse_document};
 
     #[test]
     fn test_initialization() {
@@ -180,7 +836,32 @@ mod tests {
                 "symbol".to_string(),
                 "operator".to_string(),
             )],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 2);
     }
+
+    #[test]
+    fn test_find_nodes_deep() {
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let source = Source::detached(format!("#{}alpha{}", "f(".repeat(1000), ")".repeat(1000)));
+        let start = source.text().find("alpha").unwrap();
+        let (source, ranges) = std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(move || {
+                let root = LinkedNode::new(source.root());
+                let mut nodes = vec![];
+                find_node(start..start + 5, root.clone(), &mut nodes);
+                find_visible_nodes(start + 1..start + 2, root, &mut nodes);
+                let ranges: Vec<_> = nodes.iter().map(LinkedNode::range).collect();
+                // Dropping the found nodes drops their ancestors recursively, which isn't what is tested here
+                std::mem::forget(nodes);
+                (source, ranges)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(ranges, vec![start..start + 5, start..start + 5]);
+        drop(source);
+    }
 }
@@ -0,0 +1,425 @@
+//! Conversion of typst math into presentation MathML, to paste equations into word processors and web pages
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    convert::{
+        attachment, call_arguments, delimiters, equations_in_range, fraction_parts, function_name,
+        has_limits, matrix_rows, parenthesized, LARGE_OPERATORS,
+    },
+    interface::Options,
+    parser::{
+        functions::letter_style_content,
+        utils::{apply_letter_style, find_symbol, get_category_by_char, get_letter_style},
+    },
+    utils::{
+        depth::DepthLimit,
+        symbols::{Category, OPERATORS, SPACINGS},
+    },
+};
+
+const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Integrals, large operators whose attachments stay on their side
+const INTEGRALS: &str = "∫∬∭⨌∮∯∰∱∲∳";
+
+/// Escape the characters of a text which are special in XML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn element(tag: &str, content: &str) -> String {
+    format!("<{}>{}</{}>", tag, content, tag)
+}
+
+/// Group elements in a row, unless there is a single one
+fn row(mut items: Vec<String>) -> String {
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        element("mrow", &items.concat())
+    }
+}
+
+/// Error written in place of the nodes deeper than the maximum depth, to show that the output is truncated
+fn truncated() -> String {
+    element("merror", &element("mtext", "…"))
+}
+
+/// Get the token of a text from its shape and its category: number, identifier or operator
+fn token(text: &str, category: Option<Category>) -> String {
+    let tag = if text.starts_with(|c: char| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_ascii_digit() || c == '.')
+    {
+        "mn"
+    } else if text.chars().count() == 1
+        && (LARGE_OPERATORS.contains(text) || INTEGRALS.contains(text))
+    {
+        "mo"
+    } else {
+        match category {
+            Some(Category::Operator | Category::Comparison | Category::Keyword) => "mo",
+            Some(Category::Number) => "mn",
+            Some(Category::Space) => "mtext",
+            Some(_) => "mi",
+            None if text.chars().all(char::is_alphanumeric) => "mi",
+            None => "mo",
+        }
+    };
+    element(tag, &escape(text))
+}
+
+/// Get the token of a text leaf, a single character having the category of the symbol it is
+fn text_token(text: &str) -> String {
+    let mut chars = text.chars();
+    let category = match (chars.next(), chars.next()) {
+        (Some(c), None) => get_category_by_char(c),
+        _ => None,
+    };
+    token(text, category)
+}
+
+/// Get the accent written above or below the argument of an accent function, and if it is below
+fn accent(name: &str) -> Option<(char, bool)> {
+    Some(match name {
+        "hat" => ('^', false),
+        "tilde" => ('~', false),
+        "dot" => ('˙', false),
+        "dot.double" | "diaer" => ('¨', false),
+        "dot.triple" => ('\u{20DB}', false),
+        "dot.quad" => ('\u{20DC}', false),
+        "arrow" => ('→', false),
+        "arrow.l" => ('←', false),
+        "arrow.l.r" => ('↔', false),
+        "harpoon" => ('⇀', false),
+        "macron" | "overline" => ('‾', false),
+        "acute" => ('´', false),
+        "acute.double" => ('˝', false),
+        "grave" => ('`', false),
+        "breve" => ('˘', false),
+        "caron" | "check" => ('ˇ', false),
+        "circle" => ('∘', false),
+        "overbrace" => ('⏞', false),
+        "overbracket" => ('⎴', false),
+        "overparen" => ('⏜', false),
+        "underline" => ('_', true),
+        "underbrace" => ('⏟', true),
+        "underbracket" => ('⎵', true),
+        "underparen" => ('⏝', true),
+        _ => return None,
+    })
+}
+
+/// Converter of the math of an equation
+struct Converter<'a> {
+    options: &'a Options,
+    /// Attachments of large operators are written above and below them in display equations
+    block: bool,
+    /// Depth of the node being converted, the nodes deeper than the maximum depth being replaced by an error
+    limit: DepthLimit,
+}
+
+impl Converter<'_> {
+    /// Convert the children of a node, split into the rows of a table at line breaks and into cells at alignment points
+    fn sequence(&self, node: &LinkedNode) -> String {
+        if self.limit.is_reached() {
+            return truncated();
+        }
+        let mut lines = vec![vec![vec![]]];
+        let mut embedded = false;
+        for child in node.children() {
+            if std::mem::take(&mut embedded) {
+                continue;
+            }
+            match child.kind() {
+                // Code embedded with `#` can't be converted
+                SyntaxKind::Hash => embedded = true,
+                SyntaxKind::Linebreak => lines.push(vec![vec![]]),
+                SyntaxKind::MathAlignPoint => lines.last_mut().unwrap().push(vec![]),
+                _ => {
+                    if let Some(item) = self.convert(&child) {
+                        lines.last_mut().unwrap().last_mut().unwrap().push(item);
+                    }
+                }
+            }
+        }
+        if lines.len() == 1 && lines[0].len() == 1 {
+            return row(lines.remove(0).remove(0));
+        }
+        let rows: String = lines
+            .into_iter()
+            .map(|cells| {
+                let aligned = cells.len() > 1;
+                let cells: String = cells
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, items)| {
+                        let content = row(items);
+                        match (aligned, index % 2) {
+                            (false, _) => element("mtd", &content),
+                            (true, 0) => format!("<mtd columnalign=\"right\">{}</mtd>", content),
+                            (true, _) => format!("<mtd columnalign=\"left\">{}</mtd>", content),
+                        }
+                    })
+                    .collect();
+                element("mtr", &cells)
+            })
+            .collect();
+        element("mtable", &rows)
+    }
+
+    /// Convert a node, None if it isn't rendered like spaces \
+    /// A node deeper than the maximum depth is replaced by an error
+    fn convert(&self, node: &LinkedNode) -> Option<String> {
+        if !self.limit.enter() {
+            return (!node.kind().is_trivia()).then(truncated);
+        }
+        let converted = self.convert_node(node);
+        self.limit.leave();
+        converted
+    }
+
+    fn convert_node(&self, node: &LinkedNode) -> Option<String> {
+        Some(match node.kind() {
+            SyntaxKind::Math => self.sequence(node),
+            SyntaxKind::Text => text_token(node.text()),
+            SyntaxKind::Str => element("mtext", &escape(node.cast::<ast::Str>()?.get().as_str())),
+            SyntaxKind::Escape => text_token(&node.cast::<ast::Escape>()?.get().to_string()),
+            SyntaxKind::Shorthand => element(
+                "mo",
+                &escape(&node.cast::<ast::Shorthand>()?.get().to_string()),
+            ),
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                self.name(&node.get().clone().into_text())
+            }
+            SyntaxKind::MathDelimited => element("mrow", &self.sequence(node)),
+            SyntaxKind::MathAttach => self.attach(node),
+            SyntaxKind::MathPrimes => {
+                element("mo", &"′".repeat(node.cast::<ast::MathPrimes>()?.count()))
+            }
+            SyntaxKind::MathFrac => {
+                let (num, denom) = fraction_parts(node)?;
+                element("mfrac", &(self.unparen(&num) + &self.unparen(&denom)))
+            }
+            SyntaxKind::MathRoot => {
+                let radicand = self.unparen(&node.children().last()?);
+                match node.cast::<ast::MathRoot>()?.index() {
+                    Some(index) => element("mroot", &format!("{}<mn>{}</mn>", radicand, index)),
+                    None => element("msqrt", &radicand),
+                }
+            }
+            SyntaxKind::FuncCall => self.call(node),
+            SyntaxKind::Equation => self.sequence(
+                &node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::Math)?,
+            ),
+            _ => return None,
+        })
+    }
+
+    /// Convert an identifier or a field access, like `alpha`, `arrow.r` or `sin`
+    fn name(&self, name: &str) -> String {
+        if OPERATORS.contains_key(name) {
+            return element("mi", name);
+        }
+        if let Some(space) = SPACINGS.get(name) {
+            return element("mtext", space);
+        }
+        match find_symbol(name.to_string(), self.options) {
+            Some((category, symbol)) => token(&symbol, Some(category)),
+            None => element("mi", &escape(name)),
+        }
+    }
+
+    /// Convert a script or an argument, the parentheses grouping it being removed like typst does (`x^(a + b)`)
+    fn unparen(&self, node: &LinkedNode) -> String {
+        if let Some(children) = parenthesized(node) {
+            let inner: Vec<String> = children
+                .iter()
+                .filter_map(|child| self.convert(child))
+                .collect();
+            return if inner.is_empty() {
+                element("mrow", "")
+            } else {
+                row(inner)
+            };
+        }
+        self.convert(node).unwrap_or_else(|| element("mrow", ""))
+    }
+
+    /// Convert an attachment, with `msubsup` or with `munderover` for limits
+    fn attach(&self, node: &LinkedNode) -> String {
+        let Some(attachment) = attachment(node) else {
+            return element("mrow", "");
+        };
+        let bottom = attachment.bottom.map(|script| self.unparen(&script));
+        let top = attachment.top.map(|script| self.unparen(&script));
+        let primes = attachment.primes.and_then(|primes| self.convert(&primes));
+        // Primes are written before the superscript, like `f'^2`
+        let top = match (primes, top) {
+            (Some(primes), Some(top)) => Some(row(vec![primes, top])),
+            (primes, top) => primes.or(top),
+        };
+        let limits = self.block && has_limits(&attachment.base, self.options);
+        let base = self
+            .convert(&attachment.base)
+            .unwrap_or_else(|| element("mrow", ""));
+        let (tag, content) = match (bottom, top) {
+            (Some(bottom), Some(top)) => (
+                if limits { "munderover" } else { "msubsup" },
+                format!("{}{}{}", base, bottom, top),
+            ),
+            (Some(bottom), None) => (if limits { "munder" } else { "msub" }, base + &bottom),
+            (None, Some(top)) => (if limits { "mover" } else { "msup" }, base + &top),
+            (None, None) => return base,
+        };
+        element(tag, &content)
+    }
+
+    /// Convert the rows of cells of a table
+    fn table(&self, rows: Vec<Vec<LinkedNode>>) -> String {
+        rows.iter()
+            .map(|cells| {
+                let cells: String = cells
+                    .iter()
+                    .map(|cell| element("mtd", &self.unparen(cell)))
+                    .collect();
+                element("mtr", &cells)
+            })
+            .collect()
+    }
+
+    /// Convert a function call, like `frac(a, b)`, `hat(x)` or `mat(1, 2; 3, 4)`
+    fn call(&self, node: &LinkedNode) -> String {
+        let name = function_name(node).unwrap_or_default();
+        let args = call_arguments(node);
+        let arg = |index: usize| {
+            args.get(index)
+                .map_or_else(|| element("mrow", ""), |arg| self.unparen(arg))
+        };
+        let mo = |c: char| element("mo", &escape(&c.to_string()));
+        match name.as_str() {
+            "frac" => element("mfrac", &(arg(0) + &arg(1))),
+            "binom" => row(vec![
+                mo('('),
+                format!("<mfrac linethickness=\"0\">{}{}</mfrac>", arg(0), arg(1)),
+                mo(')'),
+            ]),
+            "sqrt" => element("msqrt", &arg(0)),
+            "root" => element("mroot", &(arg(1) + &arg(0))),
+            "cancel" => format!(
+                "<menclose notation=\"updiagonalstrike\">{}</menclose>",
+                arg(0)
+            ),
+            "lr" | "display" | "inline" | "script" | "cramped" | "limits" | "scripts" | "mid" => {
+                arg(0)
+            }
+            "op" => match args.first() {
+                Some(text) if text.kind() == SyntaxKind::Str => element(
+                    "mi",
+                    &escape(
+                        text.cast::<ast::Str>()
+                            .map(|text| text.get())
+                            .unwrap_or_default()
+                            .as_str(),
+                    ),
+                ),
+                _ => arg(0),
+            },
+            "mat" | "vec" | "cases" => {
+                let rows = if name == "mat" {
+                    self.table(matrix_rows(&args))
+                } else {
+                    self.table(args.iter().map(|arg| vec![arg.clone()]).collect())
+                };
+                let (open, close) = match name.as_str() {
+                    "cases" => ("{", ""),
+                    _ => ("(", ")"),
+                };
+                let table = if name == "cases" {
+                    format!("<mtable columnalign=\"left\">{}</mtable>", rows)
+                } else {
+                    element("mtable", &rows)
+                };
+                let mut items = vec![element("mo", open), table];
+                if !close.is_empty() {
+                    items.push(element("mo", close));
+                }
+                row(items)
+            }
+            _ => {
+                if let Some((open, close)) = delimiters(&name) {
+                    return row(vec![mo(open), arg(0), mo(close)]);
+                }
+                if let Some((accent, below)) = accent(&name) {
+                    let (tag, attribute) = if below {
+                        ("munder", "accentunder")
+                    } else {
+                        ("mover", "accent")
+                    };
+                    let accented = format!(
+                        "<{} {}=\"true\">{}{}</{}>",
+                        tag,
+                        attribute,
+                        arg(0),
+                        mo(accent),
+                        tag
+                    );
+                    // Annotation of a brace, like `overbrace(x + y, 2)`
+                    return match args.get(1) {
+                        Some(_) => element(tag, &(accented + &arg(1))),
+                        None => accented,
+                    };
+                }
+                if let Some((map, _)) = get_letter_style(&name) {
+                    if let Some(content) = args
+                        .first()
+                        .and_then(|arg| letter_style_content(arg, self.options))
+                    {
+                        return element("mi", &escape(&apply_letter_style(map, &content)));
+                    }
+                    return arg(0);
+                }
+                // Remaining calls are the name of the function followed by its arguments in parentheses
+                let mut items = vec![self.name(&name), mo('(')];
+                for (index, argument) in args.iter().enumerate() {
+                    if index > 0 {
+                        items.push(mo(','));
+                    }
+                    items.push(self.unparen(argument));
+                }
+                items.push(mo(')'));
+                row(items)
+            }
+        }
+    }
+}
+
+/// Convert the equations between two UTF-16 offsets of a source into MathML, one `math` element per line \
+/// An empty range converts the equation around it
+pub fn to_mathml(source: &Source, start: usize, end: usize, options: &Options) -> String {
+    equations_in_range(source, start, end)
+        .into_iter()
+        .map(|equation| {
+            let block = equation
+                .cast::<ast::Equation>()
+                .is_some_and(|equation| equation.block());
+            let converter = Converter {
+                options,
+                block,
+                limit: DepthLimit::new(options.max_depth),
+            };
+            format!(
+                "<math xmlns=\"{}\" display=\"{}\">{}</math>",
+                NAMESPACE,
+                if block { "block" } else { "inline" },
+                converter.convert(&equation).unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,291 @@
+//! Native Node addon, enabled with the `napi` feature, for desktop hosts which can load native modules.
+//!
+//! Functions and classes keep the names of the WASM module, so the extension can use either of them. \
+//! Results are plain JS objects built from their JSON serialization instead of wasm-bindgen classes,
+//! which also avoids freeing them.
+
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::interface::{CustomSymbol, Options, Scope};
+
+/// Convert a result into a plain JS object
+fn to_value(value: impl Serialize) -> Result<Value> {
+    serde_json::to_value(value).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Convert an error of the options into a JS error
+fn to_error(error: String) -> Error {
+    Error::from_reason(error)
+}
+
+/// Rendering options, with the methods and the setters of the WASM `Options` class
+#[napi(js_name = "Options")]
+pub struct NodeOptions {
+    inner: Options,
+}
+
+#[napi]
+impl NodeOptions {
+    #[napi(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        NodeOptions {
+            inner: Options::default(),
+        }
+    }
+    #[napi(js_name = "set_rendering_mode")]
+    pub fn set_rendering_mode(&mut self, rendering_mode: u32) {
+        self.inner.set_rendering_mode(rendering_mode as u8);
+    }
+    #[napi(js_name = "set_feature")]
+    pub fn set_feature(&mut self, name: String, enabled: bool) -> Result<()> {
+        self.inner.set_feature(&name, enabled).map_err(to_error)
+    }
+    #[napi(js_name = "add_custom_symbol")]
+    pub fn add_custom_symbol(&mut self, name: String, symbol: String, category: String) {
+        self.inner.add_custom_symbol(CustomSymbol {
+            name,
+            symbol,
+            category,
+        });
+    }
+    #[napi(js_name = "hide_category")]
+    pub fn hide_category(&mut self, name: String) -> Result<()> {
+        self.inner.hide_category(&name).map_err(to_error)
+    }
+    #[napi(js_name = "add_symbol_table")]
+    pub fn add_symbol_table(&mut self, table: String) -> Result<()> {
+        self.inner.add_symbol_table(&table).map_err(to_error)
+    }
+    #[napi(js_name = "set_color")]
+    pub fn set_color(&mut self, name: String, color: String) -> Result<()> {
+        self.inner.set_color(&name, &color).map_err(to_error)
+    }
+    #[napi(js_name = "add_function_rules")]
+    pub fn add_function_rules(&mut self, rules: String) -> Result<()> {
+        self.inner.add_function_rules(&rules).map_err(to_error)
+    }
+    #[napi(setter, js_name = "render_outside_math")]
+    pub fn set_render_outside_math(&mut self, value: bool) {
+        self.inner.render_outside_math = value;
+    }
+    /// Scope as the number of the WASM enum: 0 for all equations, 1 for blocks and 2 for inline equations
+    #[napi(setter, js_name = "scope")]
+    pub fn set_scope(&mut self, value: u32) {
+        self.inner.scope = match value {
+            1 => Scope::Block,
+            2 => Scope::Inline,
+            _ => Scope::All,
+        };
+    }
+    #[napi(setter, js_name = "render_spaces")]
+    pub fn set_render_spaces(&mut self, value: bool) {
+        self.inner.render_spaces = value;
+    }
+    #[napi(setter, js_name = "hide_unnecessary_delimiters")]
+    pub fn set_hide_unnecessary_delimiters(&mut self, value: bool) {
+        self.inner.hide_unnecessary_delimiters = value;
+    }
+    #[napi(setter, js_name = "render_invisible")]
+    pub fn set_render_invisible(&mut self, value: bool) {
+        self.inner.render_invisible = value;
+    }
+    #[napi(setter, js_name = "render_align_points")]
+    pub fn set_render_align_points(&mut self, value: bool) {
+        self.inner.render_align_points = value;
+    }
+    #[napi(setter, js_name = "render_latex")]
+    pub fn set_render_latex(&mut self, value: bool) {
+        self.inner.render_latex = value;
+    }
+    #[napi(setter, js_name = "plain_unicode")]
+    pub fn set_plain_unicode(&mut self, value: bool) {
+        self.inner.plain_unicode = value;
+    }
+    #[napi(setter, js_name = "blacklisted_symbols")]
+    pub fn set_blacklisted_symbols(&mut self, value: Vec<String>) {
+        self.inner.blacklisted_symbols = value;
+    }
+    #[napi(setter, js_name = "whitelisted_symbols")]
+    pub fn set_whitelisted_symbols(&mut self, value: Vec<String>) {
+        self.inner.whitelisted_symbols = value;
+    }
+    #[napi(setter, js_name = "min_symbol_length")]
+    pub fn set_min_symbol_length(&mut self, value: u32) {
+        self.inner.min_symbol_length = value as usize;
+    }
+    #[napi(setter, js_name = "max_depth")]
+    pub fn set_max_depth(&mut self, value: u32) {
+        self.inner.max_depth = value as usize;
+    }
+}
+
+/// Parsing session of a document, with the methods of the WASM `Session` class
+#[napi(js_name = "Session")]
+pub struct NodeSession {
+    inner: crate::Session,
+}
+
+#[napi]
+impl NodeSession {
+    #[napi(constructor)]
+    pub fn new(content: String) -> Self {
+        NodeSession {
+            inner: crate::Session::new(&content),
+        }
+    }
+    #[napi]
+    pub fn edit(&mut self, start: u32, end: u32, text: String) {
+        self.inner.edit(start as usize, end as usize, &text);
+    }
+    #[napi]
+    pub fn parse(&mut self, options: &NodeOptions) -> Result<Value> {
+        to_value(self.inner.parse(&options.inner))
+    }
+    #[napi(js_name = "parse_edits")]
+    pub fn parse_edits(&mut self, options: &NodeOptions) -> Result<Value> {
+        to_value(self.inner.parse_edits(&options.inner))
+    }
+    #[napi(js_name = "parse_range")]
+    pub fn parse_range(
+        &mut self,
+        start_line: u32,
+        end_line: u32,
+        options: &NodeOptions,
+    ) -> Result<Value> {
+        to_value(
+            self.inner
+                .parse_range(start_line as usize, end_line as usize, &options.inner),
+        )
+    }
+    #[napi(js_name = "parse_delta")]
+    pub fn parse_delta(&mut self, options: &NodeOptions, version: u32) -> Result<Value> {
+        to_value(self.inner.parse_delta(&options.inner, version))
+    }
+    #[napi(js_name = "symbol_at")]
+    pub fn symbol_at(&self, offset: u32, options: &NodeOptions) -> Result<Value> {
+        to_value(self.inner.symbol_at(offset as usize, &options.inner))
+    }
+    #[napi]
+    pub fn text(&self) -> String {
+        self.inner.text()
+    }
+}
+
+#[napi(js_name = "parse_document_with_options")]
+pub fn parse_document_with_options(
+    content: String,
+    edited_line_start: i32,
+    edited_line_end: i32,
+    options: &NodeOptions,
+) -> Result<Value> {
+    let parsed = crate::parse_document_with_options(
+        &content,
+        edited_line_start,
+        edited_line_end,
+        &options.inner,
+    )
+    .map_err(|error| to_error(error.to_string()))?;
+    to_value(parsed)
+}
+
+#[napi(js_name = "parse_range")]
+pub fn parse_range(
+    content: String,
+    start_line: u32,
+    end_line: u32,
+    options: &NodeOptions,
+) -> Result<Value> {
+    to_value(crate::parse_range(
+        &content,
+        start_line as usize,
+        end_line as usize,
+        &options.inner,
+    ))
+}
+
+#[napi(js_name = "symbol_at")]
+pub fn symbol_at(content: String, offset: u32, options: &NodeOptions) -> Result<Value> {
+    to_value(crate::symbol_at(&content, offset as usize, &options.inner))
+}
+
+#[napi(js_name = "list_symbols")]
+pub fn list_symbols(options: &NodeOptions) -> Result<Value> {
+    to_value(crate::list_symbols(&options.inner))
+}
+
+#[napi(js_name = "search_symbols")]
+pub fn search_symbols(query: String, options: &NodeOptions, limit: u32) -> Result<Value> {
+    to_value(crate::search_symbols(
+        &query,
+        &options.inner,
+        limit as usize,
+    ))
+}
+
+#[napi(js_name = "names_for_char")]
+pub fn names_for_char(character: String) -> Vec<String> {
+    crate::names_for_char(&character)
+}
+
+#[napi(js_name = "render_to_unicode")]
+pub fn render_to_unicode(content: String, start: u32, end: u32, options: &NodeOptions) -> String {
+    crate::render_to_unicode(&content, start as usize, end as usize, &options.inner)
+}
+
+#[napi]
+pub fn typstify(content: String, start: u32, end: u32) -> Result<Value> {
+    to_value(crate::typstify(&content, start as usize, end as usize))
+}
+
+#[napi(js_name = "list_equations")]
+pub fn list_equations(content: String, options: &NodeOptions) -> Result<Value> {
+    to_value(crate::list_equations(&content, &options.inner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options() {
+        let mut options = NodeOptions::new();
+        options.set_rendering_mode(0);
+        assert!(!options.inner.features.attachments);
+        options
+            .set_feature("attachments".to_string(), true)
+            .unwrap();
+        assert!(options.inner.features.attachments);
+        assert!(options.set_feature("unknown".to_string(), true).is_err());
+        options.set_scope(1);
+        assert_eq!(options.inner.scope, Scope::Block);
+        options.set_scope(7);
+        assert_eq!(options.inner.scope, Scope::All);
+        options.set_max_depth(12);
+        assert_eq!(options.inner.max_depth, 12);
+    }
+
+    #[test]
+    fn test_results() {
+        let options = NodeOptions::new();
+        let parsed = parse_document_with_options("$alpha$".to_string(), -1, -1, &options).unwrap();
+        assert_eq!(parsed["decorations"][0]["symbol"], "α");
+        assert_eq!(parsed["decorations"][0]["positions"][0]["start"], 1);
+        assert_eq!(parsed["erroneous"], false);
+
+        let mut session = NodeSession::new("$x$".to_string());
+        session.edit(1, 2, "beta".to_string());
+        assert_eq!(session.text(), "$beta$");
+        let parsed = session.parse(&options).unwrap();
+        assert_eq!(parsed["decorations"][0]["symbol"], "β");
+        let symbol = session.symbol_at(2, &options).unwrap();
+        assert_eq!(symbol["name"], "beta");
+        assert_eq!(session.symbol_at(0, &options).unwrap(), Value::Null);
+
+        assert_eq!(render_to_unicode("$x^2$".to_string(), 0, 5, &options), "x²");
+        assert_eq!(names_for_char("α".to_string()), vec!["alpha".to_string()]);
+    }
+}
@@ -0,0 +1,366 @@
+//! Blocks of the attachments (`x^2`, `attach(x, t: 2)`) and of the parentheses around them
+
+use super::parser::{schedule, AttachmentState, ScriptLevel};
+use super::rules::{Call, DecorationRule, Rendering};
+use super::utils::{
+    argument_items, cast_expr, find_child, get_symbol, is_comment, significant_children, subscript,
+    superscript, InnerParser,
+};
+use crate::interface::Options;
+use crate::utils::symbols::OPERATORS;
+use typst_syntax::ast::{Arg, AstNode, Escape, Expr, MathAttach, MathIdent, Named, Str};
+use typst_syntax::{LinkedNode, SyntaxKind};
+
+/// Check if an attachment base is an operator with limits (`lim`, `max`, `op("argmax", limits: #true)`), \
+/// for which the bottom attachment is placed under the operator
+fn has_limits(base: &LinkedNode) -> bool {
+    match base.cast::<Expr>() {
+        Some(Expr::MathIdent(ident)) => OPERATORS.get(ident.as_str()) == Some(&true),
+        Some(Expr::FuncCall(func)) => {
+            matches!(func.callee(), Expr::MathIdent(ident) if ident.as_str() == "op")
+                && func.args().items().any(|arg| match arg {
+                    Arg::Named(named) => {
+                        named.name().as_str() == "limits"
+                            && matches!(named.expr(), Expr::Bool(value) if value.get())
+                    }
+                    _ => false,
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Parse a math attach block (subscript, superscript) \
+/// Apply specific style and offset for each attachment, and compute specific style with rendering mode and current state
+pub fn math_attach_block(parser: &mut InnerParser) {
+    let Some(attachment) = cast_expr::<MathAttach>(parser.expr) else {
+        return;
+    };
+    // Keep the current state to restore it after the attachment
+    let state = AttachmentState::save(parser.state);
+    let Some(base) = find_child(parser.expr, attachment.base().span()) else {
+        return;
+    };
+    if parser.state.is_attachment {
+        // Nested in another attachment (`a^b^c`), the base is styled like the attachment containing it
+        parser.state.is_base = false;
+        schedule(
+            parser,
+            &base,
+            parser.uuid,
+            parser.added_text_decoration,
+            parser.offset,
+        );
+    } else if parser.expr.parent_kind() != Some(SyntaxKind::MathAttach) {
+        // Check if it is the 'main' base, and render it if true
+        parser.state.is_base = true;
+        parser.state.is_attachment = false;
+        schedule(
+            parser,
+            &base,
+            parser.uuid,
+            parser.added_text_decoration,
+            parser.offset,
+        );
+    } else {
+        parser.state.is_base = false;
+        parser.state.is_attachment = false;
+        schedule(parser, &base, "", "", (0, 0));
+    }
+    // Hide the attachment symbols if attachments are rendered
+    if parser.options.features.attachments {
+        parser.offset = (1, 0);
+    }
+    let ((top_decor, top_uuid, top_level), (bottom_decor, bottom_uuid, bottom_level)) =
+        attachment_styles(parser, &base);
+    // Set state for top and bottom attachment
+    parser.state.is_base = false;
+    parser.state.is_attachment = parser.options.features.attachments;
+    if let Some(top) = attachment
+        .top()
+        .and_then(|top| find_child(parser.expr, top.span()))
+    {
+        parser.state.script_level = top_level;
+        schedule(parser, &top, &top_uuid, &top_decor, parser.offset)
+    }
+    if let Some(bottom) = attachment
+        .bottom()
+        .and_then(|bottom| find_child(parser.expr, bottom.span()))
+    {
+        parser.state.script_level = bottom_level;
+        schedule(parser, &bottom, &bottom_uuid, &bottom_decor, parser.offset)
+    }
+    // Restore the state
+    state.restore(parser.state);
+}
+
+/// Css, uuid prefix and level of an attachment
+type ScriptStyle = (String, String, Option<ScriptLevel>);
+
+/// Style and uuid prefix of the top and bottom attachments of a base, empty if attachments aren't rendered \
+/// Attachments nested in another one (`a^b^c`) are scaled from it and shifted from its position
+fn attachment_styles(parser: &InnerParser, base: &LinkedNode) -> (ScriptStyle, ScriptStyle) {
+    if !parser.options.features.attachments {
+        return (
+            (String::new(), String::new(), None),
+            (String::new(), String::new(), None),
+        );
+    }
+    let style = &parser.options.attachment_style;
+    let css = |scale: f32, offset: f32| {
+        format!(
+            "font-size: {}em; transform: translateY({}%); display: inline-block;",
+            scale, offset
+        )
+    };
+    let round = |value: f32| (value * 100.0).round() / 100.0;
+    let script = |scale: f32, offset: f32, uuid: &str| match parser.state.script_level {
+        None => (
+            css(scale, offset),
+            uuid.to_string(),
+            Some(ScriptLevel {
+                depth: 1,
+                scale,
+                shift: scale * offset / 100.0,
+            }),
+        ),
+        // The translation is in percent of the size of the nested attachment,
+        // and the depth is in the uuid as the style differs from the first level
+        Some(parent) => {
+            let scale = parent.scale * scale;
+            let shift = parent.shift + scale * offset / 100.0;
+            let depth = parent.depth + 1;
+            (
+                css(round(scale), round(shift / scale * 100.0)),
+                format!("{}{}-", uuid, depth),
+                Some(ScriptLevel {
+                    depth,
+                    scale,
+                    shift,
+                }),
+            )
+        }
+    };
+    let top = script(style.scale, style.top_offset, "top-");
+    let bottom = if has_limits(base) {
+        script(style.limits_scale, style.limits_offset, "under-")
+    } else {
+        script(style.scale, style.bottom_offset, "bottom-")
+    };
+    (top, bottom)
+}
+
+/// Content of a function argument, without the delimiters of equations and content blocks (`$x$` -> `x`), \
+/// or the single expression of the content
+fn argument_content(node: LinkedNode) -> LinkedNode {
+    match node.kind() {
+        SyntaxKind::Equation | SyntaxKind::ContentBlock => {
+            let Some(content) = node
+                .children()
+                .find(|c| matches!(c.kind(), SyntaxKind::Math | SyntaxKind::Markup))
+            else {
+                return node;
+            };
+            let mut children = content
+                .children()
+                .filter(|c| c.kind() != SyntaxKind::Space && !is_comment(c));
+            match (children.next(), children.next()) {
+                (Some(child), None) => child,
+                _ => content,
+            }
+        }
+        _ => node,
+    }
+}
+
+/// Parse an attachment built with the attach function (`attach(x, t: 2)`), like a math attach block \
+/// The callee, the argument names and the delimiters around the base and the attachments are hidden, \
+/// return false if there are other arguments than the base and the attachments
+fn attach_call_block(
+    parser: &mut InnerParser,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+    hash_offset: usize,
+) -> bool {
+    let children: Vec<LinkedNode> = args.children().collect();
+    let Some(items) = argument_items(&children) else {
+        return false;
+    };
+    let Some((base, attachments)) = items.split_first() else {
+        return false;
+    };
+    if children[*base].kind() == SyntaxKind::Named {
+        return false;
+    }
+    let base = argument_content(children[*base].clone());
+    let ((top_decor, top_uuid, top_level), (bottom_decor, bottom_uuid, bottom_level)) =
+        attachment_styles(parser, &base);
+    let mut contents = vec![];
+    for attachment in attachments.iter().map(|i| &children[*i]) {
+        let Some(named) = attachment.cast::<Named>() else {
+            return false;
+        };
+        let style = match named.name().as_str() {
+            "t" | "tl" | "tr" => (top_uuid.as_str(), top_decor.as_str(), top_level),
+            "b" | "bl" | "br" => (bottom_uuid.as_str(), bottom_decor.as_str(), bottom_level),
+            _ => return false,
+        };
+        let Some(content) = attachment.find(named.expr().span()) else {
+            return false;
+        };
+        contents.push((argument_content(content), style));
+    }
+
+    // Keep the current state to restore it after the attachment
+    let state = AttachmentState::save(parser.state);
+    parser.insert_void_except_comments(
+        args,
+        callee.range().start..base.range().start,
+        (parser.offset.0 + hash_offset, 0),
+    );
+    parser.state.is_base = true;
+    parser.state.is_attachment = false;
+    schedule(
+        parser,
+        &base,
+        parser.uuid,
+        parser.added_text_decoration,
+        (0, 0),
+    );
+    parser.state.is_base = false;
+    parser.state.is_attachment = true;
+    let mut end = base.range().end;
+    for (content, (uuid, decoration, level)) in contents {
+        parser.insert_void_except_comments(args, end..content.range().start, (0, 0));
+        parser.state.script_level = level;
+        schedule(parser, &content, uuid, decoration, (0, 0));
+        end = content.range().end;
+    }
+    parser.insert_void_except_comments(args, end..args.range().end, (0, parser.offset.1));
+    // Restore the state
+    state.restore(parser.state);
+    true
+}
+
+/// Check if a node is a simple value in an attachment, a text, a known symbol,
+/// or an attachment made of these (`(b^c)`)
+fn is_simple_value(node: &LinkedNode, options: &Options) -> bool {
+    match node.kind() {
+        SyntaxKind::Text | SyntaxKind::Str | SyntaxKind::Escape => true,
+        SyntaxKind::MathIdent => node
+            .cast::<MathIdent>()
+            .is_some_and(|ident| get_symbol(ident.to_string(), options).is_some()),
+        SyntaxKind::MathAttach => node.children().all(|child| {
+            matches!(child.kind(), SyntaxKind::Hat | SyntaxKind::Underscore)
+                || is_simple_value(&child, options)
+        }),
+        _ => false,
+    }
+}
+
+/// Maximum number of tokens in parentheses around an attachment to style them like the attachment, like `x^(2n+1)`
+const MAX_SIMPLE_TOKENS: usize = 6;
+
+/// Check if the content of parentheses around an attachment is simple enough to be styled like the attachment, \
+/// a simple value or a few texts, symbols and shorthands without nested attachments (`(2n+1)`, `(i pi)`, `(-b)`)
+fn is_simple_group(children: &[LinkedNode], options: &Options) -> bool {
+    if let [value] = children {
+        return is_simple_value(value, options);
+    }
+    let mut tokens = 0;
+    for child in children {
+        match child.kind() {
+            SyntaxKind::Space => continue,
+            SyntaxKind::MathAttach => return false,
+            SyntaxKind::Shorthand => {}
+            _ if is_simple_value(child, options) => {}
+            _ => return false,
+        }
+        tokens += 1;
+    }
+    tokens <= MAX_SIMPLE_TOKENS
+}
+
+/// Check if the symbols of a group all have a unicode superscript or subscript, to approximate them without css
+fn has_scripts(children: &[LinkedNode], top: bool, options: &Options) -> bool {
+    let convert = if top { superscript } else { subscript };
+    children.iter().all(|child| {
+        let text = match child.kind() {
+            SyntaxKind::MathIdent => get_symbol(child.text().to_string(), options)
+                .map_or(child.text().to_string(), |(_, symbol)| symbol),
+            SyntaxKind::Str => child
+                .cast::<Str>()
+                .map_or(String::new(), |text| text.get().to_string()),
+            SyntaxKind::Escape => child
+                .cast::<Escape>()
+                .map_or(String::new(), |escape| escape.get().to_string()),
+            _ => child.text().to_string(),
+        };
+        text.chars()
+            .all(|c| c.is_whitespace() || convert(c).is_some())
+    })
+}
+
+/// Parse a math block, check if it is a simple block (paren around a few symbols) and propagate style if true \
+/// Otherwise, continue over children and reset style
+pub fn math_block(parser: &mut InnerParser) {
+    let children = significant_children(parser.expr);
+    // If we are in an attachment, check if the current math block is just paren around a symbol
+    if children.len() == 3
+        && children[0].kind() == SyntaxKind::LeftParen
+        && children[1].kind() == SyntaxKind::Math
+        && children[2].kind() == SyntaxKind::RightParen
+    {
+        // Check that the block inside paren is 'simple', wich means that we can propagate style (So top and bottom attachment)
+        let sub_children = significant_children(&children[1]);
+        let mut propagate_style = is_simple_group(&sub_children, parser.options);
+        // Without css, the symbols of a group would be approximated one by one, like `^i ^π`
+        if parser.options.plain_unicode && sub_children.len() > 1 {
+            let top = parser.uuid.starts_with("top-");
+            propagate_style &= has_scripts(&sub_children, top, parser.options);
+        }
+
+        // We can propagate, hide paren and then continue over children (With a for loop and a call to inner, to keep current style)
+        if propagate_style {
+            parser.insert_void(children[0].range(), (parser.offset.0, 0));
+            parser.insert_void(children[2].range(), (0, parser.offset.1));
+            for child in children[1].children() {
+                schedule(
+                    parser,
+                    &child,
+                    parser.uuid,
+                    parser.added_text_decoration,
+                    (0, 0),
+                );
+            }
+            return;
+        } else if parser.options.hide_unnecessary_delimiters {
+            // Simply hide the paren
+            parser.insert_void(children[0].range(), (0, 0));
+            parser.insert_void(children[2].range(), (0, 0));
+        }
+    }
+    // Style isn't propagated, reset state
+    parser.state.is_attachment = false;
+    parser.state.script_level = None;
+    for child in parser.expr.children() {
+        schedule(parser, &child, "", "", (0, 0)); // Propagate the function
+    }
+}
+
+/// Attachments built with a function (`attach(x, t: 2)`, `#math.attach($x$, t: $2$)`)
+pub struct Attach;
+
+impl DecorationRule for Attach {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&["attach"])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        match (call.math_function.as_deref(), call.hash_offset) {
+            (Some("attach"), Some(hash_offset)) if parser.options.features.attachments => {
+                attach_call_block(parser, &call.callee, &call.args, hash_offset).into()
+            }
+            _ => Rendering::Skipped,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Names bound by the user with let bindings, imports and parameters, which shadow symbols (`#let alpha = 5`)
+
+use std::{collections::HashSet, ops::Range};
+
+use super::{imports::get_package_by_path, utils::top_level_nodes};
+use typst_syntax::{
+    ast::{
+        AstNode, Closure, ForLoop, Ident, ImportItem, Imports as ImportedItems, LetBinding,
+        ModuleImport, Param, Str,
+    },
+    LinkedNode, Span, SyntaxKind,
+};
+
+/// Names bound in the current scopes with the span where they are declared, the innermost scope being at the end
+#[derive(Clone, Default, Debug)]
+pub struct Bindings {
+    names: Vec<(String, Span)>,
+    /// A wildcard import was met, the names it binds aren't known
+    wildcard: bool,
+}
+
+/// Check if a node is an identifier using a name, fields and the names of named arguments being other names
+fn is_reference(node: &LinkedNode, name: &str) -> bool {
+    if !matches!(node.kind(), SyntaxKind::MathIdent | SyntaxKind::Ident) || node.text() != name {
+        return false;
+    }
+    match node.parent_kind() {
+        Some(SyntaxKind::FieldAccess) => node.index() == 0,
+        Some(SyntaxKind::Named) => node.index() != 0,
+        _ => true,
+    }
+}
+
+/// Collect the spans of the names declared in the children of a node, with an explicit stack for deeply nested documents
+fn collect_declarations(node: &LinkedNode, declarations: &mut HashSet<Span>) {
+    let mut stack: Vec<LinkedNode> = node.children().collect();
+    while let Some(child) = stack.pop() {
+        let mut bindings = Bindings::default();
+        bindings.open(&child);
+        bindings.bind(&child);
+        declarations.extend(bindings.names.into_iter().map(|(_, span)| span));
+        stack.extend(child.children());
+    }
+}
+
+/// Step of a walk through the children of a node
+enum Step<'a> {
+    /// Visit a node, opening its scope before its children
+    Enter(LinkedNode<'a>),
+    /// Close the scope of a node after its children, then bind its names for its next siblings
+    Exit(LinkedNode<'a>, Option<usize>),
+}
+
+/// Push the children of a node so that they are entered in the order of the document
+fn push_children<'a>(stack: &mut Vec<Step<'a>>, node: &LinkedNode<'a>) {
+    let children: Vec<LinkedNode> = node.children().collect();
+    stack.extend(children.into_iter().rev().map(Step::Enter));
+}
+
+/// Get a bound name with its declaration
+fn declared(ident: Ident) -> (String, Span) {
+    (ident.to_string(), ident.span())
+}
+
+impl Bindings {
+    /// Collect the names bound at the top level of the document
+    pub fn collect(root: &LinkedNode) -> Bindings {
+        let mut bindings = Bindings::default();
+        for node in top_level_nodes(root) {
+            bindings.bind(&node);
+        }
+        bindings
+    }
+    /// Collect the names bound where a node is, replaying the scopes opened and the bindings met before it
+    pub fn at(root: &LinkedNode, target: &LinkedNode) -> Bindings {
+        let mut bindings = Bindings::collect(root);
+        bindings.walk_to(root, target);
+        bindings
+    }
+    /// Walk the children of a node until the target
+    fn walk_to(&mut self, node: &LinkedNode, target: &LinkedNode) {
+        let target_range = target.range();
+        let mut stack = vec![];
+        push_children(&mut stack, node);
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(child) => {
+                    let range = child.range();
+                    if range.start >= target_range.end {
+                        return;
+                    }
+                    let scope = self.open(&child);
+                    if range.start <= target_range.start && target_range.end <= range.end {
+                        // Ancestor of the target, its scope is still open there and its next siblings aren't walked
+                        if child.span() == target.span() {
+                            return;
+                        }
+                        stack.clear();
+                    } else {
+                        stack.push(Step::Exit(child.clone(), scope));
+                    }
+                    push_children(&mut stack, &child);
+                }
+                Step::Exit(child, scope) => {
+                    self.close(scope);
+                    self.bind(&child);
+                }
+            }
+        }
+    }
+    /// Collect the ranges of the identifiers of a document referring to a declaration, the declaration included
+    pub fn references(root: &LinkedNode, name: &str, declaration: Span) -> Vec<Range<usize>> {
+        let mut declarations = HashSet::new();
+        collect_declarations(root, &mut declarations);
+        let mut references = vec![];
+        let mut bindings = Bindings::collect(root);
+        bindings.find_references(root, name, declaration, &declarations, &mut references);
+        references
+    }
+    /// Walk the children of a node, collecting the identifiers referring to a declaration, other declarations excluded
+    fn find_references(
+        &mut self,
+        node: &LinkedNode,
+        name: &str,
+        declaration: Span,
+        declarations: &HashSet<Span>,
+        references: &mut Vec<Range<usize>>,
+    ) {
+        let mut stack = vec![];
+        push_children(&mut stack, node);
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(child) => {
+                    if child.span() == declaration
+                        || (is_reference(&child, name)
+                            && !declarations.contains(&child.span())
+                            && self.declaration(name) == Some(declaration))
+                    {
+                        references.push(child.range());
+                    }
+                    let scope = self.open(&child);
+                    stack.push(Step::Exit(child.clone(), scope));
+                    push_children(&mut stack, &child);
+                }
+                Step::Exit(child, scope) => {
+                    self.close(scope);
+                    self.bind(&child);
+                }
+            }
+        }
+    }
+    /// Check if a name is bound by the user
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|(bound, _)| bound == name)
+    }
+    /// Check if every name bound by the user is known, which isn't the case after a wildcard import
+    pub fn is_complete(&self) -> bool {
+        !self.wildcard
+    }
+    /// Get where a name bound by the user is declared, in the innermost scope binding it
+    pub fn declaration(&self, name: &str) -> Option<Span> {
+        self.names
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, span)| *span)
+    }
+    /// Bind the names of a let binding or of an import for the rest of the current scope \
+    /// Names imported from packages with specific rendering rules aren't bound, since these rules apply to them
+    pub fn bind(&mut self, node: &LinkedNode) {
+        if let Some(binding) = node.cast::<LetBinding>() {
+            self.names
+                .extend(binding.kind().bindings().into_iter().map(declared));
+        } else if let Some(import) = node.cast::<ModuleImport>() {
+            if let Some(ImportedItems::Wildcard) = import.imports() {
+                self.wildcard = true;
+            }
+            let package = import
+                .source()
+                .to_untyped()
+                .cast::<Str>()
+                .and_then(|path| get_package_by_path(&path.get()));
+            if package.is_some() {
+                return;
+            }
+            if let Some(name) = import.new_name() {
+                self.names.push(declared(name));
+            }
+            if let Some(ImportedItems::Items(items)) = import.imports() {
+                self.names.extend(items.iter().map(|item| match item {
+                    ImportItem::Simple(name) => declared(name),
+                    ImportItem::Renamed(renamed) => declared(renamed.new_name()),
+                }));
+            }
+        }
+    }
+    /// Open a new scope if the node is a block, a closure or a loop, binding its parameters \
+    /// Return the scope to close after the children of the node
+    pub fn open(&mut self, node: &LinkedNode) -> Option<usize> {
+        let scope = self.names.len();
+        if let Some(closure) = node.cast::<Closure>() {
+            for param in closure.params().children() {
+                match param {
+                    Param::Pos(pattern) => self
+                        .names
+                        .extend(pattern.bindings().into_iter().map(declared)),
+                    Param::Named(named) => self.names.push(declared(named.name())),
+                    Param::Spread(spread) => self.names.extend(spread.sink_ident().map(declared)),
+                }
+            }
+        } else if let Some(loop_) = node.cast::<ForLoop>() {
+            self.names
+                .extend(loop_.pattern().bindings().into_iter().map(declared));
+        } else if !matches!(
+            node.kind(),
+            SyntaxKind::CodeBlock | SyntaxKind::ContentBlock
+        ) {
+            return None;
+        }
+        Some(scope)
+    }
+    /// Close a scope opened by `open`, unbinding the names bound inside
+    pub fn close(&mut self, scope: Option<usize>) {
+        if let Some(scope) = scope {
+            self.names.truncate(scope);
+        }
+    }
+}
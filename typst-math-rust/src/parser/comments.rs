@@ -0,0 +1,175 @@
+//! Magic comments disabling the rendering of a region (`// typst-math: off` ... `// typst-math: on`),
+//! or of the equation before them (`$ x $ // typst-math: ignore`)
+
+use std::ops::Range;
+
+use typst_syntax::{is_newline, LinkedNode, SyntaxKind};
+
+/// Directive of a magic comment
+#[derive(Debug, PartialEq)]
+enum Directive {
+    Off,
+    On,
+    Ignore,
+}
+
+/// Get the directive of a line or block comment, None if it isn't a magic comment
+fn get_directive(comment: &str) -> Option<Directive> {
+    let content = comment.strip_prefix("//").or_else(|| {
+        comment
+            .strip_prefix("/*")
+            .and_then(|content| content.strip_suffix("*/"))
+    })?;
+    match content.trim().strip_prefix("typst-math:")?.trim() {
+        "off" => Some(Directive::Off),
+        "on" => Some(Directive::On),
+        "ignore" => Some(Directive::Ignore),
+        _ => None,
+    }
+}
+
+/// Get the equation just before a comment on the same line, whatever the line endings of the document
+fn previous_equation<'a>(comment: &LinkedNode<'a>) -> Option<LinkedNode<'a>> {
+    let parent = comment.parent()?;
+    let siblings: Vec<LinkedNode> = parent.children().take(comment.index()).collect();
+    siblings
+        .into_iter()
+        .rev()
+        .find(|sibling| sibling.kind() != SyntaxKind::Space || sibling.text().contains(is_newline))
+        .filter(|sibling| sibling.kind() == SyntaxKind::Equation)
+}
+
+/// Regions of the document disabled by magic comments, where nothing is rendered
+#[derive(Clone, Default, Debug)]
+pub struct DisabledRegions {
+    ranges: Vec<Range<usize>>,
+}
+
+impl DisabledRegions {
+    /// Collect the regions disabled in the whole document, a region without an `on` comment lasting until its end \
+    /// The comments are walked in the order of the document, with an explicit stack for deeply nested documents
+    pub fn collect(root: &LinkedNode) -> DisabledRegions {
+        let mut regions = DisabledRegions::default();
+        // Start of the current region if any
+        let mut start = None;
+        let mut stack = vec![root.clone()];
+        while let Some(node) = stack.pop() {
+            regions.visit(&node, &mut start);
+            let children: Vec<LinkedNode> = node.children().collect();
+            stack.extend(children.into_iter().rev());
+        }
+        if let Some(start) = start {
+            regions.ranges.push(start..root.range().end);
+        }
+        regions
+    }
+    /// Apply the magic comment of a node if it is one, `start` being the start of the current region if any
+    fn visit(&mut self, node: &LinkedNode, start: &mut Option<usize>) {
+        if !matches!(
+            node.kind(),
+            SyntaxKind::LineComment | SyntaxKind::BlockComment
+        ) {
+            return;
+        }
+        match get_directive(node.text()) {
+            Some(Directive::Off) => {
+                start.get_or_insert(node.range().end);
+            }
+            Some(Directive::On) => {
+                if let Some(start) = start.take() {
+                    self.ranges.push(start..node.range().start);
+                }
+            }
+            Some(Directive::Ignore) => {
+                if let Some(equation) = previous_equation(node) {
+                    self.ranges.push(equation.range());
+                }
+            }
+            None => {}
+        }
+    }
+    /// Check if a position of the document is disabled
+    pub fn contains(&self, position: usize) -> bool {
+        self.ranges.iter().any(|range| range.contains(&position))
+    }
+    /// Move the regions after an edit of the document, `shift` giving the new position of an old one
+    pub fn shift(&mut self, shift: impl Fn(usize) -> usize) {
+        for range in &mut self.ranges {
+            *range = shift(range.start)..shift(range.end);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_directive, Directive, DisabledRegions};
+
+    #[test]
+    fn test_get_directive() {
+        assert_eq!(get_directive("// typst-math: off"), Some(Directive::Off));
+        assert_eq!(get_directive("/*typst-math:on*/"), Some(Directive::On));
+        assert_eq!(
+            get_directive("//  typst-math: ignore "),
+            Some(Directive::Ignore)
+        );
+        assert_eq!(get_directive("// typst-math: maybe"), None);
+        assert_eq!(get_directive("// off"), None);
+    }
+
+    #[test]
+    fn test_collect() {
+        let source = typst_syntax::Source::detached(
+            "$a$ // typst-math: off\n$b$ // typst-math: on\n$ c $ // typst-math: ignore\n$d$\n// typst-math: ignore\n/* typst-math: off */ $e$",
+        );
+        let root = source.find(source.root().span()).unwrap();
+        let regions = DisabledRegions::collect(&root);
+        let text = source.text();
+        let disabled = |needle: &str| regions.contains(text.find(needle).unwrap());
+        assert!(!disabled("$a"));
+        assert!(disabled("$b"));
+        assert!(disabled("$ c"));
+        // The equation must be on the same line as the comment
+        assert!(!disabled("$d"));
+        assert!(disabled("$e"));
+    }
+
+    #[test]
+    fn test_collect_line_endings() {
+        for eol in ["\r\n", "\r"] {
+            let source = typst_syntax::Source::detached(
+                ["$a$", "// typst-math: ignore", "$b$ // typst-math: ignore"].join(eol),
+            );
+            let root = source.find(source.root().span()).unwrap();
+            let regions = DisabledRegions::collect(&root);
+            let text = source.text();
+            let disabled = |needle: &str| regions.contains(text.find(needle).unwrap());
+            assert!(!disabled("$a"));
+            assert!(disabled("$b"));
+        }
+    }
+
+    #[test]
+    fn test_collect_deep() {
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let source = typst_syntax::Source::detached(format!(
+            "// typst-math: off\n${}alpha{}$",
+            "(".repeat(1000),
+            ")".repeat(1000)
+        ));
+        let (source, disabled) = std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(move || {
+                let root = typst_syntax::LinkedNode::new(source.root());
+                let regions = DisabledRegions::collect(&root);
+                drop(root);
+                let disabled = regions.contains(source.text().find("alpha").unwrap());
+                // The tree is parsed and dropped on the stack of the test, only the walk runs on the small one
+                (source, disabled)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(disabled);
+        drop(source);
+    }
+}
@@ -0,0 +1,371 @@
+//! Set and show rules of the document changing how equations look (`#show math.equation: set text(font: "Fira Math")`),
+//! collected before the traversal so that parsing a part of the document still knows the rules before it
+
+use std::ops::Range;
+
+use typst_syntax::{
+    ast::{Arg, ArrayItem, Expr, SetRule, ShowRule, Unit},
+    LinkedNode, SyntaxKind,
+};
+
+/// Size of the text in typst when no rule sets it, in points
+const DEFAULT_TEXT_SIZE: f64 = 11.0;
+
+/// Size set by a rule, absolute or relative to the size of the text around
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Size {
+    Points(f64),
+    Em(f64),
+}
+
+impl Size {
+    /// Resolve the size from the size of the text around, in points
+    fn resolve(self, around: f64) -> f64 {
+        match self {
+            Size::Points(points) => points,
+            Size::Em(em) => em * around,
+        }
+    }
+    /// Combine with a size set after it, which scales it if it is relative
+    fn then(self, next: Size) -> Size {
+        match (self, next) {
+            (_, Size::Points(_)) => next,
+            (Size::Points(points), Size::Em(em)) => Size::Points(points * em),
+            (Size::Em(factor), Size::Em(em)) => Size::Em(factor * em),
+        }
+    }
+}
+
+/// Text settings of a `set text(...)` rule read by the parser
+#[derive(Clone, Debug, Default, PartialEq)]
+struct TextSettings {
+    fonts: Option<Vec<String>>,
+    size: Option<Size>,
+}
+
+/// Text settings applying after a rule, until the end of the block containing it
+#[derive(Clone, Debug)]
+struct Rule {
+    /// Range of the block containing the rule, or of the whole document
+    scope: Range<usize>,
+    /// End of the rule, from which it applies
+    start: usize,
+    /// The rule only applies to equations (`show math.equation: set text(...)`)
+    equation: bool,
+    settings: TextSettings,
+}
+
+/// Style of an equation set by show rules, applied to the css of its symbols
+#[derive(Clone, Debug, PartialEq)]
+pub struct MathStyle {
+    /// Fonts of the equation, preferred to the math font of the options
+    pub fonts: Vec<String>,
+    /// Size of the equation relative to the text around it
+    pub scale: f64,
+    /// Prefix of the uuids of the symbols with this style, computed once for the equation
+    prefix: String,
+}
+
+impl MathStyle {
+    /// Create the style of an equation from its fonts and its relative size
+    pub fn new(fonts: Vec<String>, scale: f64) -> MathStyle {
+        let prefix = format!("style({};{})-", fonts.join(","), scale);
+        MathStyle {
+            fonts,
+            scale,
+            prefix,
+        }
+    }
+    /// Prefix of the uuids of the symbols with this style, to avoid mixing styles
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    /// Apply the style to a css style, the fonts coming before the math font and the font sizes being scaled
+    pub fn apply(&self, css: &str) -> String {
+        let css = if self.fonts.is_empty() {
+            css.to_string()
+        } else {
+            let fonts: Vec<String> = self
+                .fonts
+                .iter()
+                .map(|font| format!("\"{}\"", font))
+                .collect();
+            css.replace("@math", &format!("{}, @math", fonts.join(", ")))
+        };
+        if self.scale == 1.0 {
+            css
+        } else {
+            scale_font_size(&css, self.scale)
+        }
+    }
+}
+
+/// Multiply the font sizes in em of a css style, or set one if it has none
+fn scale_font_size(css: &str, scale: f64) -> String {
+    let mut scaled = false;
+    let mut declarations: Vec<String> = css
+        .split(';')
+        .map(str::trim)
+        .filter(|declaration| !declaration.is_empty())
+        .map(|declaration| {
+            let size = declaration
+                .split_once(':')
+                .filter(|(property, _)| property.trim() == "font-size")
+                .and_then(|(_, value)| value.trim().strip_suffix("em")?.parse::<f64>().ok());
+            match size {
+                Some(size) => {
+                    scaled = true;
+                    format!("font-size: {}em", round(size * scale))
+                }
+                None => declaration.to_string(),
+            }
+        })
+        .collect();
+    if !scaled {
+        declarations.push(format!("font-size: {}em", round(scale)));
+    }
+    declarations
+        .iter()
+        .map(|declaration| format!("{};", declaration))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Round a scale to two decimals, like the sizes of the attachments
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Read a positive size expression (`12pt`, `1.2em`), None for other units or computed sizes
+fn size(expr: Expr) -> Option<Size> {
+    let Expr::Numeric(numeric) = expr else {
+        return None;
+    };
+    match numeric.get() {
+        (value, _) if value <= 0.0 => None,
+        (value, Unit::Pt) => Some(Size::Points(value)),
+        (value, Unit::Mm) => Some(Size::Points(value * 72.0 / 25.4)),
+        (value, Unit::Cm) => Some(Size::Points(value * 72.0 / 2.54)),
+        (value, Unit::In) => Some(Size::Points(value * 72.0)),
+        (value, Unit::Em) => Some(Size::Em(value)),
+        _ => None,
+    }
+}
+
+/// Read a font or a list of fonts (`"Fira Math"`, `("Fira Math", "Noto Sans")`), families given with a dictionary being skipped
+fn fonts(expr: Expr) -> Option<Vec<String>> {
+    let fonts: Vec<String> = match expr {
+        Expr::Str(font) => vec![font.get().to_string()],
+        Expr::Array(array) => array
+            .items()
+            .filter_map(|item| match item {
+                ArrayItem::Pos(Expr::Str(font)) => Some(font.get().to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+    (!fonts.is_empty()).then_some(fonts)
+}
+
+/// Read the settings of a `set text(...)` rule, None for other rules and rules with a condition
+fn text_settings(rule: SetRule) -> Option<TextSettings> {
+    if rule.condition().is_some() {
+        return None;
+    }
+    match rule.target() {
+        Expr::Ident(ident) if ident.as_str() == "text" => {}
+        _ => return None,
+    }
+    let mut settings = TextSettings::default();
+    for arg in rule.args().items() {
+        if let Arg::Named(named) = arg {
+            match named.name().as_str() {
+                "font" => settings.fonts = fonts(named.expr()),
+                "size" => settings.size = size(named.expr()),
+                _ => {}
+            }
+        }
+    }
+    (settings != TextSettings::default()).then_some(settings)
+}
+
+/// Check if the selector of a show rule is the equations (`math.equation`)
+fn is_equation_selector(selector: Option<Expr>) -> bool {
+    match selector {
+        Some(Expr::FieldAccess(access)) => {
+            matches!(access.target(), Expr::Ident(module) if module.as_str() == "math")
+                && access.field().as_str() == "equation"
+        }
+        _ => false,
+    }
+}
+
+/// Push the children of a node with the range of the block containing them, so that they are visited in the order of the document
+fn push_children<'a>(
+    stack: &mut Vec<(LinkedNode<'a>, Range<usize>)>,
+    node: &LinkedNode<'a>,
+    scope: Range<usize>,
+) {
+    let children: Vec<LinkedNode> = node.children().collect();
+    stack.extend(
+        children
+            .into_iter()
+            .rev()
+            .map(|child| (child, scope.clone())),
+    );
+}
+
+/// Text settings of the document and of its equations, in the order of the document
+#[derive(Clone, Default, Debug)]
+pub struct StyleContext {
+    rules: Vec<Rule>,
+}
+
+impl StyleContext {
+    /// Collect the `set text(...)` rules and the `show math.equation: set text(...)` rules of the whole document \
+    /// The rules are found in the order of the document, with an explicit stack for deeply nested documents
+    pub fn collect(root: &LinkedNode) -> StyleContext {
+        let mut context = StyleContext::default();
+        // Nodes to visit with the range of the block containing them
+        let mut stack = vec![];
+        push_children(&mut stack, root, root.range());
+        while let Some((node, scope)) = stack.pop() {
+            context.visit(&mut stack, node, scope);
+        }
+        context
+    }
+    /// Add the rule of a node if it is one, or push its children with the range of the block containing them
+    fn visit<'a>(
+        &mut self,
+        stack: &mut Vec<(LinkedNode<'a>, Range<usize>)>,
+        node: LinkedNode<'a>,
+        scope: Range<usize>,
+    ) {
+        match node.kind() {
+            SyntaxKind::ShowRule => {
+                let Some(show) = node.cast::<ShowRule>() else {
+                    return;
+                };
+                if let (true, Expr::Set(set)) =
+                    (is_equation_selector(show.selector()), show.transform())
+                {
+                    self.push(&node, &scope, true, set);
+                }
+            }
+            SyntaxKind::SetRule => {
+                if let Some(set) = node.cast::<SetRule>() {
+                    self.push(&node, &scope, false, set);
+                }
+            }
+            // Rules in equations aren't read
+            SyntaxKind::Equation => {}
+            SyntaxKind::CodeBlock | SyntaxKind::ContentBlock => {
+                push_children(stack, &node, node.range())
+            }
+            _ => push_children(stack, &node, scope),
+        }
+    }
+    /// Add a rule if it sets the text
+    fn push(&mut self, node: &LinkedNode, scope: &Range<usize>, equation: bool, set: SetRule) {
+        if let Some(settings) = text_settings(set) {
+            self.rules.push(Rule {
+                scope: scope.clone(),
+                start: node.range().end,
+                equation,
+                settings,
+            });
+        }
+    }
+    /// Move the rules after an edit of the document, `shift` giving the new position of an old one
+    pub fn shift(&mut self, shift: impl Fn(usize) -> usize) {
+        for rule in &mut self.rules {
+            rule.scope = shift(rule.scope.start)..shift(rule.scope.end);
+            rule.start = shift(rule.start);
+        }
+    }
+    /// Style of the equation containing a node, from the rules before it, None if there is none or if no rule changes it
+    pub fn style(&self, node: &LinkedNode) -> Option<MathStyle> {
+        let equation = std::iter::successors(Some(node.clone()), |node| node.parent().cloned())
+            .find(|node| node.kind() == SyntaxKind::Equation)?;
+        let position = equation.range().start;
+        let mut text_size = DEFAULT_TEXT_SIZE;
+        // Absolute sizes of the equations are kept when the text size changes after them
+        let mut equation_size = Size::Em(1.0);
+        let mut fonts = vec![];
+        let rules = self.rules.iter().filter(|rule| {
+            rule.start <= position && rule.scope.start <= position && position < rule.scope.end
+        });
+        for rule in rules {
+            match (rule.equation, rule.settings.size) {
+                (true, Some(size)) => equation_size = equation_size.then(size),
+                (false, Some(size)) => text_size = size.resolve(text_size),
+                _ => {}
+            }
+            if let (true, Some(rule_fonts)) = (rule.equation, &rule.settings.fonts) {
+                fonts = rule_fonts.clone();
+            }
+        }
+        let scale = round(equation_size.resolve(text_size) / text_size);
+        (!fonts.is_empty() || scale != 1.0).then(|| MathStyle::new(fonts, scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scale_font_size, MathStyle, StyleContext};
+    use typst_syntax::LinkedNode;
+
+    #[test]
+    fn test_scale_font_size() {
+        assert_eq!(
+            scale_font_size("font-size: 0.8em; transform: translateY(-30%);", 1.5),
+            "font-size: 1.2em; transform: translateY(-30%);"
+        );
+        assert_eq!(
+            scale_font_size("font-family: @math;", 2.0),
+            "font-family: @math; font-size: 2em;"
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let style = MathStyle::new(vec!["Fira Math".to_string()], 1.0);
+        assert_eq!(style.prefix(), "style(Fira Math;1)-");
+        assert_eq!(
+            style.apply("font-family: @math; font-weight: bold;"),
+            "font-family: \"Fira Math\", @math; font-weight: bold;"
+        );
+        assert_eq!(style.apply("font-family: @mono;"), "font-family: @mono;");
+    }
+
+    #[test]
+    fn test_collect_deep() {
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let source = typst_syntax::Source::detached(format!(
+            "#show math.equation: set text(font: \"Fira Math\")\n{}$alpha${}",
+            "#[".repeat(1000),
+            "]".repeat(1000)
+        ));
+        let (source, context) = std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(move || {
+                let root = LinkedNode::new(source.root());
+                let context = StyleContext::collect(&root);
+                drop(root);
+                // The tree is parsed and dropped on the stack of the test, only the walk runs on the small one
+                (source, context)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        let root = LinkedNode::new(source.root());
+        let alpha = root
+            .leaf_at(source.text().find("alpha").unwrap() + 1)
+            .unwrap();
+        assert_eq!(
+            context.style(&alpha).map(|style| style.fonts),
+            Some(vec!["Fira Math".to_string()])
+        );
+    }
+}
@@ -0,0 +1,818 @@
+//! Blocks of the function calls, dispatched to the decoration rules, and the built-in rules styling them
+
+use std::ops::Range;
+
+use super::macros::macro_call_block;
+use super::parser::schedule;
+use super::rules::{rules, Call, DecorationRule, Rendering};
+use super::symbols::{field_access_recursive, insert_operator};
+use super::utils::{
+    apply_letter_style, argument_items, arguments, get_category_by_char, get_letter_style,
+    get_symbol, is_comment, significant_children, InnerParser,
+};
+use crate::interface::{ArgumentPolicy, Options};
+use crate::utils::styles::NAMED_COLORS;
+use crate::utils::symbols::{Category, Color};
+use typst_syntax::ast::{Arg, AstNode, Escape, Expr, FuncCall, MathIdent, Str, Text};
+use typst_syntax::{LinkedNode, SyntaxKind};
+
+/// Step of the walk getting the content of a letter style function argument
+enum ContentStep<'a> {
+    /// Get the content of a node
+    Enter(LinkedNode<'a>),
+    /// Join the contents of the given number of children
+    Join(usize),
+    /// Apply a letter style to the content of the argument of a call, \
+    /// the call being kept so that its deeply nested argument isn't dropped with all its ancestors at once
+    Style {
+        map: &'static phf::Map<char, char>,
+        _call: LinkedNode<'a>,
+    },
+}
+
+/// Get the raw content of a letter style function argument (`bb(1 k)` -> `'1 k'`) \
+/// Symbols like `alpha` are replaced by their unicode character and other identifiers are kept as is, \
+/// return None if the content isn't only made of text \
+/// The nested styles are walked with an explicit stack, so that deeply nested arguments don't overflow it
+pub fn letter_style_content(node: &LinkedNode, options: &Options) -> Option<String> {
+    let mut stack = vec![ContentStep::Enter(node.clone())];
+    // Contents of the nodes walked, in the order of the document
+    let mut contents: Vec<String> = vec![];
+    while let Some(step) = stack.pop() {
+        match step {
+            ContentStep::Enter(node) => match node.kind() {
+                SyntaxKind::Text => contents.push(node.cast::<Text>()?.get().to_string()),
+                SyntaxKind::Str => contents.push(node.cast::<Str>()?.get().to_string()),
+                SyntaxKind::Escape => contents.push(node.cast::<Escape>()?.get().to_string()),
+                SyntaxKind::Space => contents.push(" ".to_string()),
+                // Content of an equation given from code (`#math.bb($R$)`), or of math
+                SyntaxKind::Equation | SyntaxKind::Math => {
+                    let children: Vec<LinkedNode> = significant_children(&node)
+                        .into_iter()
+                        .filter(|child| child.kind() != SyntaxKind::Dollar)
+                        .collect();
+                    stack.push(ContentStep::Join(children.len()));
+                    stack.extend(children.into_iter().rev().map(ContentStep::Enter));
+                }
+                SyntaxKind::MathIdent => {
+                    let ident = node.cast::<MathIdent>()?.to_string();
+                    // Unknown identifiers like `HH` are styled letter by letter
+                    let content = get_symbol(ident.clone(), options)
+                        .map(|(_, symbol)| symbol)
+                        .or_else(|| ident.chars().all(char::is_alphanumeric).then_some(ident))?;
+                    contents.push(content);
+                }
+                // Nested style functions like `bold(cal(A))`, the inner style is applied first
+                SyntaxKind::FuncCall => {
+                    let func = node.cast::<FuncCall>()?;
+                    let Expr::MathIdent(ident) = func.callee() else {
+                        return None;
+                    };
+                    let (map, _) = get_letter_style(ident.as_str())?;
+                    let args = significant_children(&node.find(func.args().span())?);
+                    match args.as_slice() {
+                        [left, content, right]
+                            if left.kind() == SyntaxKind::LeftParen
+                                && right.kind() == SyntaxKind::RightParen =>
+                        {
+                            let content = content.clone();
+                            stack.push(ContentStep::Style { map, _call: node });
+                            stack.push(ContentStep::Enter(content));
+                        }
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            },
+            ContentStep::Join(count) => {
+                let joined = contents.split_off(contents.len() - count).concat();
+                contents.push(joined);
+            }
+            ContentStep::Style { map, .. } => {
+                let content = contents.pop()?;
+                contents.push(apply_letter_style(map, &content));
+            }
+        }
+    }
+    contents.pop()
+}
+
+/// Get the css color of a `text` func call, given with the `fill` named argument or as a positional color
+fn text_fill(func: &FuncCall) -> Option<String> {
+    match func.callee() {
+        Expr::Ident(ident) if ident.as_str() == "text" => {}
+        Expr::MathIdent(ident) if ident.as_str() == "text" => {}
+        _ => return None,
+    }
+    func.args().items().find_map(|arg| match arg {
+        Arg::Named(named) if named.name().as_str() == "fill" => css_color(named.expr()),
+        Arg::Pos(expr) => css_color(expr),
+        _ => None,
+    })
+}
+
+/// Name of a math function called in math or from code with the math module (`bb(R)`, `#math.bb("R")`)
+pub fn math_function_name(func: &FuncCall) -> Option<String> {
+    match func.callee() {
+        Expr::MathIdent(ident) => Some(ident.to_string()),
+        Expr::FieldAccess(access) => match access.target() {
+            Expr::Ident(module) if module.as_str() == "math" => Some(access.field().to_string()),
+            Expr::MathIdent(module) if module.as_str() == "math" => {
+                Some(access.field().to_string())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the name or the field path of a called function, without the math module (`vec`, `math.arrow` -> `arrow`)
+pub fn function_path(func: &FuncCall) -> Option<String> {
+    match func.callee() {
+        Expr::MathIdent(ident) => Some(ident.to_string()),
+        Expr::Ident(ident) => Some(ident.to_string()),
+        Expr::FieldAccess(access) => field_access_recursive(access).map(|content| {
+            content
+                .strip_prefix("math.")
+                .unwrap_or(&content)
+                .to_string()
+        }),
+        _ => None,
+    }
+}
+
+/// Symbol and style of an accent function (`arrow(x)`, `hat(x)`)
+fn accent_style(name: &str) -> Option<(char, &'static str)> {
+    match name {
+        "arrow" => Some((
+            '→',
+            "font-family: @math; transform: translate(-0.1em, -0.9em); font-size: 0.8em; display: inline-block; position: absolute;",
+        )),
+        "dot" => Some((
+            '⋅',
+            "font-family: @symbols;
+                    transform: translate(0.15em, -0.55em);
+                    transform: translate(0.15em, -0.52em); display: inline-block; position: absolute;",
+        )),
+        "dot.double" | "diaer" => Some(('¨', "font-family: @mono; transform: translate(0, -0.25em); display: inline-block; position: absolute;")),
+        "dot.triple" => Some(('\u{20DB}', "font-family: @mono; font-size: 1.4em; transform: translate(-0.1em); display: inline-block;")),
+        "dot.quad" => Some(('\u{20DC}', "font-family: @mono; font-size: 1.4em; transform: translate(-0.1em); display: inline-block;")),
+        "hat" => Some((
+            '^',
+            "font-family: @symbols; transform: translate(0.03em, -0.3em); font-size: 0.9em; display: inline-block; position: absolute;",
+        )),
+        "tilde" => Some((
+            '~',
+            "font-family: @mono; transform: translate(0.05em, -0.7em); font-size: 0.9em; display: inline-block; position: absolute;",
+        )),
+        "overline" => Some(('\u{0305}', "font-family: @mono; transform: translate(0em, -0.2em); display: inline-block;")),
+        _ => None,
+    }
+}
+
+/// Combining character of an accent function, to approximate it without css (`hat(x)` -> `x̂`)
+fn combining_accent(name: &str) -> Option<char> {
+    match name {
+        "arrow" => Some('\u{20D7}'),
+        "dot" => Some('\u{0307}'),
+        "dot.double" | "diaer" => Some('\u{0308}'),
+        "dot.triple" => Some('\u{20DB}'),
+        "dot.quad" => Some('\u{20DC}'),
+        "hat" => Some('\u{0302}'),
+        "tilde" => Some('\u{0303}'),
+        "overline" => Some('\u{0305}'),
+        _ => None,
+    }
+}
+
+/// Render an accent with a single letter or symbol as argument as the argument followed by a combining character, \
+/// return false if the argument can't be combined
+fn plain_accent_block(
+    parser: &mut InnerParser,
+    name: &str,
+    args: &LinkedNode,
+    children: &[LinkedNode],
+) -> bool {
+    let Some(accent) = combining_accent(name) else {
+        return false;
+    };
+    let base = match children.get(1) {
+        Some(text) if text.kind() == SyntaxKind::Text => {
+            let mut chars = text.text().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some((
+                    get_category_by_char(c).unwrap_or(Category::Default),
+                    c.to_string(),
+                )),
+                _ => None,
+            }
+        }
+        Some(ident) if ident.kind() == SyntaxKind::MathIdent => {
+            get_symbol(ident.text().to_string(), parser.options)
+        }
+        _ => None,
+    };
+    let Some((category, base)) = base else {
+        return false;
+    };
+    let symbol = format!("{}{}", base, accent);
+    // Comments in the arguments stay visible around the accented symbol
+    if args.children().any(|child| is_comment(&child)) {
+        let range = children[1].range();
+        parser.insert_result_category(
+            range.clone(),
+            &[parser.uuid, "-func-", &symbol],
+            &symbol,
+            category,
+            parser.added_text_decoration,
+            (0, 0),
+        );
+        parser.insert_void_except_comments(
+            args,
+            parser.expr.range().start..range.start,
+            (parser.offset.0, 0),
+        );
+        parser.insert_void_except_comments(
+            args,
+            range.end..parser.expr.range().end,
+            (0, parser.offset.1),
+        );
+        return true;
+    }
+    parser.insert_result_category(
+        parser.expr.range(),
+        &[parser.uuid, "-func-", &symbol],
+        &symbol,
+        category,
+        parser.added_text_decoration,
+        parser.offset,
+    );
+    true
+}
+
+/// Check if the arguments are a single identifier, text or simple attachment between parens (`(x)`, `(x_1)`)
+fn is_simple_argument(children: &[LinkedNode]) -> bool {
+    children.len() == 3
+        && children[0].kind() == SyntaxKind::LeftParen
+        && (children[1].kind() == SyntaxKind::MathIdent
+            || children[1].kind() == SyntaxKind::Text
+            || (children[1].kind() == SyntaxKind::MathAttach && children[1].children().len() == 3))
+        && children[2].kind() == SyntaxKind::RightParen
+}
+
+/// Render a function with a rule set by the user, replacing the callee and the opening paren by the symbol
+/// and hiding the closing paren, return false if there is no rule or if the arguments don't match it
+fn function_rule_block(
+    parser: &mut InnerParser,
+    name: &str,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+    hash_offset: usize,
+) -> bool {
+    let Some(rule) = parser.options.function_rules.get(name).cloned() else {
+        return false;
+    };
+    let children = significant_children(args);
+    let matches = match rule.args {
+        ArgumentPolicy::Simple => is_simple_argument(&children),
+        ArgumentPolicy::Single => arguments(&children).is_some_and(|items| items.len() == 1),
+        ArgumentPolicy::Any => argument_items(&children).is_some(),
+    };
+    if !matches {
+        return false;
+    }
+    parser.insert_result(
+        callee.range(),
+        &[parser.uuid, "-func-rule-", name],
+        &rule.symbol,
+        Color::Number,
+        format!("{}{}", parser.added_text_decoration, rule.style),
+        (hash_offset + parser.offset.0, 1),
+    );
+    parser.insert_void(children[children.len() - 1].range(), (0, parser.offset.1));
+    schedule(parser, args, "", "", (0, 0));
+    true
+}
+
+/// Convert a typst color expression (`red`, `rgb("#ff0000")`, `rgb(255, 0, 0)`, `luma(50)`) into a css color
+fn css_color(expr: Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => NAMED_COLORS.get(ident.as_str()).map(|c| c.to_string()),
+        Expr::MathIdent(ident) => NAMED_COLORS.get(ident.as_str()).map(|c| c.to_string()),
+        Expr::FuncCall(call) => {
+            let name = match call.callee() {
+                Expr::Ident(ident) => ident.get().clone(),
+                Expr::MathIdent(ident) => ident.get().clone(),
+                _ => return None,
+            };
+            let args: Vec<Expr> = call
+                .args()
+                .items()
+                .filter_map(|arg| match arg {
+                    Arg::Pos(expr) => Some(expr),
+                    _ => None,
+                })
+                .collect();
+            // Components can be numbers or text in math mode
+            let component = |expr: &Expr| -> Option<u8> {
+                expr.to_untyped().clone().into_text().trim().parse().ok()
+            };
+            match (name.as_str(), args.as_slice()) {
+                ("rgb", [Expr::Str(hex)]) => {
+                    let hex = hex.get();
+                    let hex = hex.trim_start_matches('#');
+                    (hex.chars().all(|c| c.is_ascii_hexdigit())
+                        && matches!(hex.len(), 3 | 4 | 6 | 8))
+                    .then(|| format!("#{}", hex))
+                }
+                ("rgb", [r, g, b, ..]) => Some(format!(
+                    "rgb({}, {}, {})",
+                    component(r)?,
+                    component(g)?,
+                    component(b)?
+                )),
+                ("luma", [l]) => {
+                    let l = component(l)?;
+                    Some(format!("rgb({}, {}, {})", l, l, l))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the content of a content block argument if it is a single equation or text (`[$x + 1$]`, `[R]`), \
+/// the math of the equation being replaced by its only child if it has one
+fn content_argument<'a>(block: &LinkedNode<'a>) -> Option<LinkedNode<'a>> {
+    let markup = block
+        .children()
+        .find(|child| child.kind() == SyntaxKind::Markup)?;
+    let contents: Vec<LinkedNode> = significant_children(&markup)
+        .into_iter()
+        .filter(|child| child.kind() != SyntaxKind::Space)
+        .collect();
+    let [content] = contents.as_slice() else {
+        return None;
+    };
+    match content.kind() {
+        SyntaxKind::Text => Some(content.clone()),
+        SyntaxKind::Equation => {
+            let math = content
+                .children()
+                .find(|child| child.kind() == SyntaxKind::Math)?;
+            match significant_children(&math).as_slice() {
+                [child] => Some(child.clone()),
+                _ => Some(math),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Replace the content block arguments of a math function by their content (`bb(#[$x$])`, `#math.bb[R]`),
+/// with the ranges of the `#`, the brackets and the dollars around them
+pub fn content_arguments<'a>(
+    children: Vec<LinkedNode<'a>>,
+) -> (Vec<LinkedNode<'a>>, Vec<Range<usize>>) {
+    let mut arguments = vec![];
+    let mut delimiters = vec![];
+    let mut hash: Option<LinkedNode> = None;
+    for child in children {
+        if child.kind() == SyntaxKind::Hash {
+            arguments.extend(hash.replace(child));
+            continue;
+        }
+        if let (SyntaxKind::ContentBlock, Some(content)) = (child.kind(), content_argument(&child))
+        {
+            let start = hash.take().unwrap_or_else(|| child.clone()).range().start;
+            delimiters.push(start..content.range().start);
+            delimiters.push(content.range().end..child.range().end);
+            arguments.push(content);
+            continue;
+        }
+        arguments.extend(hash.take());
+        arguments.push(child);
+    }
+    arguments.extend(hash);
+    (arguments, delimiters)
+}
+
+/// Parse a func call block, rendered by the first rule of the registry rendering it, else continue over args
+pub fn func_call_block(parser: &mut InnerParser) {
+    let Some(call) = Call::new(parser) else {
+        return;
+    };
+    let propagate_style = match rules().render(parser, &call) {
+        Rendering::Rendered => return,
+        Rendering::Arguments { propagate_style } => propagate_style,
+        Rendering::Skipped => true,
+    };
+    schedule(
+        parser,
+        &call.args,
+        if propagate_style { parser.uuid } else { "" },
+        if propagate_style {
+            parser.added_text_decoration
+        } else {
+            ""
+        },
+        (0, 0),
+    );
+}
+
+/// Rules set by the user, which also apply to their own functions
+pub struct UserRules;
+
+impl DecorationRule for UserRules {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        match (&call.name, call.hash_offset) {
+            (Some(name), Some(hash_offset)) if parser.options.features.function_rules => {
+                function_rule_block(parser, name, &call.callee, &call.args, hash_offset).into()
+            }
+            _ => Rendering::Skipped,
+        }
+    }
+}
+
+/// Functions bound by the user aren't styled, except macros like `#let avg(x) = $angle.l x angle.r$`
+pub struct BoundFunctions;
+
+impl DecorationRule for BoundFunctions {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        let Some(name) = call
+            .ident
+            .as_deref()
+            .filter(|name| parser.state.bindings.contains(name))
+        else {
+            return Rendering::Skipped;
+        };
+        if !parser.options.features.macros
+            || !macro_call_block(parser, name, &call.callee, &call.args)
+        {
+            schedule(parser, &call.args, "", "", (0, 0));
+        }
+        Rendering::Rendered
+    }
+}
+
+/// Hide the delimiters of the content blocks given to math functions, before the rules reading their arguments
+pub struct ContentArguments;
+
+impl DecorationRule for ContentArguments {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        for range in &call.delimiters {
+            parser.insert_void(range.clone(), (0, 0));
+        }
+        Rendering::Skipped
+    }
+}
+
+/// Custom operator, rendered upright like built-in ones
+pub struct Operator;
+
+impl DecorationRule for Operator {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&["op"])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        if let (Some("op"), Some(first), Some(hash_offset)) = (
+            call.math_function.as_deref(),
+            call.func.args().items().next(),
+            call.hash_offset,
+        ) {
+            if parser.options.features.operators {
+                if let (Arg::Pos(Expr::Str(text)), Some(node)) = (first, call.children.get(1)) {
+                    let range = node.range();
+                    if call.commented {
+                        insert_operator(
+                            parser,
+                            range.start + 1..range.end - 1,
+                            text.get().to_string(),
+                            (1, 1),
+                        );
+                        parser.insert_void_except_comments(
+                            &call.args,
+                            parser.expr.range().start..range.start,
+                            (hash_offset + parser.offset.0, 0),
+                        );
+                        parser.insert_void_except_comments(
+                            &call.args,
+                            range.end..parser.expr.range().end,
+                            (0, parser.offset.1),
+                        );
+                        return Rendering::Rendered;
+                    }
+                    // Hide the callee, the paren and quotes, and other arguments like `limits`
+                    let offset = (
+                        range.start - parser.expr.range().start + 1 + hash_offset + parser.offset.0,
+                        parser.expr.range().end - range.end + 1 + parser.offset.1,
+                    );
+                    insert_operator(
+                        parser,
+                        range.start + 1..range.end - 1,
+                        text.get().to_string(),
+                        offset,
+                    );
+                    return Rendering::Rendered;
+                }
+            }
+        }
+        Rendering::Skipped
+    }
+}
+
+/// Text with a fill color, propagate the color to the content
+pub struct TextFill;
+
+impl DecorationRule for TextFill {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&["text"])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        if let Some(fill) = text_fill(&call.func) {
+            let previous = parser.state.fill.replace(fill);
+            schedule(
+                parser,
+                &call.args,
+                parser.uuid,
+                parser.added_text_decoration,
+                (0, 0),
+            );
+            parser.state.fill = previous;
+            return Rendering::Rendered;
+        }
+        Rendering::Skipped
+    }
+}
+
+/// If there is just text or identifiers, try to apply a text func like blackbold, caligraphy...
+pub struct LetterStyles;
+
+impl DecorationRule for LetterStyles {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&[
+            "cal", "frak", "bb", "bold", "italic", "sans", "mono", "upright",
+        ])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        let single_argument = match call.children.as_slice() {
+            [left, argument, right]
+                if left.kind() == SyntaxKind::LeftParen
+                    && right.kind() == SyntaxKind::RightParen =>
+            {
+                Some(argument)
+            }
+            // Trailing content block (`#math.bb[R]`)
+            [argument] => Some(argument),
+            _ => None,
+        };
+        if let (Some(text), true) = (single_argument, parser.options.features.letter_styles) {
+            let text_content = letter_style_content(text, parser.options);
+            if let (Some(name), Some(text_content), Some(hash_offset)) =
+                (&call.math_function, text_content, call.hash_offset)
+            {
+                if let Some((map, decoration)) = get_letter_style(name) {
+                    let symbol = apply_letter_style(map, &text_content);
+                    if call.commented {
+                        parser.insert_result(
+                            text.range(),
+                            &[parser.uuid, "-", &symbol],
+                            &symbol,
+                            Color::Number,
+                            format!("{}{}", parser.added_text_decoration, decoration),
+                            (0, 0),
+                        );
+                        parser.insert_void_except_comments(
+                            &call.args,
+                            parser.expr.range().start..text.range().start,
+                            (hash_offset + parser.offset.0, 0),
+                        );
+                        parser.insert_void_except_comments(
+                            &call.args,
+                            text.range().end..parser.expr.range().end,
+                            (0, parser.offset.1),
+                        );
+                        return Rendering::Rendered;
+                    }
+                    parser.insert_result(
+                        text.range(),
+                        &[parser.uuid, "-", &symbol],
+                        &symbol,
+                        Color::Number,
+                        format!("{}{}", parser.added_text_decoration, decoration),
+                        (
+                            text.range().start - parser.expr.range().start
+                                + hash_offset
+                                + parser.offset.0,
+                            parser.expr.range().end - text.range().end + parser.offset.1,
+                        ),
+                    );
+                    return Rendering::Rendered;
+                }
+            }
+        }
+        Rendering::Skipped
+    }
+}
+
+/// Name of a function called in math or with a field access, None for the functions called from code by an identifier
+fn styled_function_name(call: &Call) -> Option<String> {
+    match call.func.callee() {
+        Expr::Ident(_) => None,
+        _ => call.name.clone(),
+    }
+}
+
+/// Accents drawn above a simple argument (`hat(x)`, `arrow(v)`)
+pub struct Accents;
+
+impl DecorationRule for Accents {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&[
+            "arrow",
+            "dot",
+            "dot.double",
+            "diaer",
+            "dot.triple",
+            "dot.quad",
+            "hat",
+            "tilde",
+            "overline",
+        ])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        let Some(name) = styled_function_name(call) else {
+            return Rendering::Skipped;
+        };
+        let Some((symbol, decoration)) = accent_style(&name) else {
+            return Rendering::Skipped;
+        };
+        let mut buffer = [0; 4];
+        let symbol: &str = symbol.encode_utf8(&mut buffer);
+        let children = &call.children;
+        let mut propagate_style = true;
+        if !parser.options.features.accents {
+            propagate_style = false;
+        } else if parser.options.plain_unicode {
+            if is_simple_argument(children)
+                && plain_accent_block(parser, &name, &call.args, children)
+            {
+                return Rendering::Rendered;
+            }
+        } else if is_simple_argument(children) {
+            parser.insert_result(
+                call.callee.range(),
+                &[parser.uuid, "-func-", symbol],
+                symbol,
+                Color::Number,
+                decoration.to_string(),
+                (0, 1),
+            );
+            parser.insert_void(children[2].range(), (0, 0));
+            propagate_style = false;
+        }
+        Rendering::Arguments { propagate_style }
+    }
+}
+
+/// Absolute values and norms, the callee being hidden and the parentheses drawn as bars (`abs(x)` -> `|x|`)
+pub struct Delimiters;
+
+impl DecorationRule for Delimiters {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&["abs", "norm"])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        let symbol = match styled_function_name(call).as_deref() {
+            Some("abs") => "|",
+            Some("norm") => "‖",
+            _ => return Rendering::Skipped,
+        };
+        let (Some(first), Some(last)) = (call.children.first(), call.children.last()) else {
+            return Rendering::Arguments {
+                propagate_style: false,
+            };
+        };
+        // Content blocks given from code aren't delimited, like `#math.abs[x]`
+        if !parser.options.features.abs_norm || first.kind() != SyntaxKind::LeftParen {
+            return Rendering::Arguments {
+                propagate_style: false,
+            };
+        }
+        parser.insert_void(call.callee.range(), (parser.offset.0, 0));
+        parser.insert_result(
+            first.range(),
+            &[parser.uuid, "func-", symbol],
+            symbol,
+            Color::Operator,
+            parser.added_text_decoration.to_string(),
+            (0, 0),
+        );
+        parser.insert_result(
+            last.range(),
+            &[parser.uuid, "func-", symbol],
+            symbol,
+            Color::Operator,
+            parser.added_text_decoration.to_string(),
+            (0, parser.offset.1),
+        );
+        Rendering::Arguments {
+            propagate_style: true,
+        }
+    }
+}
+
+/// Square roots of a letter or of a simple attachment, the radicand being overlined (`sqrt(x)` -> `√x̅`)
+pub struct Roots;
+
+impl DecorationRule for Roots {
+    fn names(&self) -> Option<&'static [&'static str]> {
+        Some(&["sqrt"])
+    }
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        let children = &call.children;
+        if styled_function_name(call).as_deref() != Some("sqrt")
+            || children.len() != 3
+            || children[0].kind() != SyntaxKind::LeftParen
+            || children[2].kind() != SyntaxKind::RightParen
+        {
+            return Rendering::Skipped;
+        }
+        if !parser.options.features.sqrt {
+            return Rendering::Arguments {
+                propagate_style: false,
+            };
+        }
+        let root_size = if children[1].kind() == SyntaxKind::MathIdent
+            || children[1].kind() == SyntaxKind::Text
+        {
+            1.2
+        } else if children[1].kind() == SyntaxKind::MathAttach
+            && children[1].children().len() == 3
+            && (children[1].children().nth(2).unwrap().kind() == SyntaxKind::MathIdent
+                || children[1].children().nth(2).unwrap().kind() == SyntaxKind::Text)
+        {
+            1.8
+        } else {
+            return Rendering::Arguments {
+                propagate_style: true,
+            };
+        };
+        // The radicand isn't overlined without css
+        if parser.options.plain_unicode {
+            parser.insert_void(children[0].range(), (0, 0));
+        } else {
+            parser.insert_result(
+                children[0].range(),
+                &[parser.uuid, "func-\u{0305}-size-", &root_size.to_string()],
+                "\u{0305}",
+                Color::Operator,
+                format!(
+                    "font-family: @mono; transform: scaleX({:.1}) translate(-0.01em, -0.25em); display: inline-block;",
+                    root_size
+                ),
+                (0, 0),
+            );
+        }
+        parser.insert_result(
+            call.callee.range(),
+            &[parser.uuid, "func-√"],
+            "√",
+            Color::Operator,
+            "font-family: @mono; display: inline-block; transform: translate(0.1em, -0.1em);"
+                .to_string(),
+            (0, 0),
+        );
+        parser.insert_void(children[2].range(), (0, 0));
+        Rendering::Arguments {
+            propagate_style: false,
+        }
+    }
+}
+
+/// The names of the other functions are rendered like symbols (`arrow.r(x)`), their arguments are parsed without their style
+pub struct FunctionNames;
+
+impl DecorationRule for FunctionNames {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        let function_names = parser.options.features.function_names;
+        if styled_function_name(call).is_none() {
+            return Rendering::Arguments {
+                propagate_style: function_names,
+            };
+        }
+        if function_names {
+            schedule(
+                parser,
+                &call.callee,
+                parser.uuid,
+                parser.added_text_decoration,
+                parser.offset,
+            );
+        }
+        Rendering::Arguments {
+            propagate_style: false,
+        }
+    }
+}
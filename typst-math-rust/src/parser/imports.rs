@@ -0,0 +1,164 @@
+//! Detection of the packages imported by a document (`#import "@preview/physica:0.9.3": *`)
+
+use std::collections::HashMap;
+
+use typst_syntax::{
+    ast::{AstNode, ImportItem, Imports as ImportedItems, ModuleImport, Str},
+    LinkedNode,
+};
+
+use super::utils::top_level_nodes;
+
+/// Packages with specific rendering rules
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Package {
+    Physica,
+    Unify,
+    Metro,
+    Mitex,
+}
+
+/// Get a package from its name in an import path like `@preview/physica:0.9.3`
+pub fn get_package_by_path(path: &str) -> Option<Package> {
+    let name = path.strip_prefix('@')?.split_once('/')?.1;
+    match name.split(':').next()? {
+        "physica" => Some(Package::Physica),
+        "unify" => Some(Package::Unify),
+        "metro" => Some(Package::Metro),
+        "mitex" => Some(Package::Mitex),
+        _ => None,
+    }
+}
+
+/// Names imported by an import, as bound name -> original name, None for a wildcard import
+type Names = Option<HashMap<String, String>>;
+
+/// Names imported from each package, None for a wildcard import \
+/// Names are stored as bound name -> original name, to follow renamed imports (`qty as q`), with the end of the import
+/// binding them, since typst only binds imported names after the import
+#[derive(Clone, Default, Debug)]
+pub struct Imports {
+    packages: HashMap<Package, Vec<(usize, Names)>>,
+}
+
+impl Imports {
+    /// Collect the imports at the top level of the document
+    pub fn collect(root: &LinkedNode) -> Imports {
+        let mut imports = Imports::default();
+        for node in top_level_nodes(root) {
+            if let Some(import) = node.cast::<ModuleImport>() {
+                imports.insert(import, node.range().end);
+            }
+        }
+        imports
+    }
+    /// Record the names imported by an import expression ending at `end`
+    fn insert(&mut self, import: ModuleImport, end: usize) {
+        let Some(package) = import
+            .source()
+            .to_untyped()
+            .cast::<Str>()
+            .and_then(|path| get_package_by_path(&path.get()))
+        else {
+            return;
+        };
+        let names = match import.imports() {
+            Some(ImportedItems::Wildcard) => None,
+            Some(ImportedItems::Items(items)) => Some(
+                items
+                    .iter()
+                    .map(|item| match item {
+                        ImportItem::Simple(name) => (name.to_string(), name.to_string()),
+                        ImportItem::Renamed(renamed) => (
+                            renamed.new_name().to_string(),
+                            renamed.original_name().to_string(),
+                        ),
+                    })
+                    .collect(),
+            ),
+            // Only the module is imported, package functions aren't accessible by their name
+            None => Some(HashMap::new()),
+        };
+        self.packages.entry(package).or_default().push((end, names));
+    }
+    /// Names imported from a package before a position, the last import first
+    fn before(&self, package: &Package, position: usize) -> impl Iterator<Item = &Names> {
+        self.packages
+            .get(package)
+            .into_iter()
+            .flatten()
+            .rev()
+            .filter(move |(end, _)| *end <= position)
+            .map(|(_, names)| names)
+    }
+    /// Check if a name is imported from any package before a position
+    pub fn binds(&self, name: &str, position: usize) -> bool {
+        self.packages.keys().any(|package| {
+            self.before(package, position)
+                .any(|names| names.as_ref().is_none_or(|names| names.contains_key(name)))
+        })
+    }
+    /// Get the name of the package function bound to `name` at a position, None if it isn't imported before it
+    pub fn resolve(&self, package: Package, name: &str, position: usize) -> Option<String> {
+        self.before(&package, position)
+            .find_map(|names| match names {
+                None => Some(name.to_string()),
+                Some(names) => names.get(name).cloned(),
+            })
+    }
+    /// Move the imports after an edit of the document, `shift` giving the new position of an old one
+    pub fn shift(&mut self, shift: impl Fn(usize) -> usize) {
+        for (end, _) in self.packages.values_mut().flatten() {
+            *end = shift(*end);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_package_by_path, Imports, Package};
+
+    #[test]
+    fn test_get_package_by_path() {
+        assert_eq!(
+            get_package_by_path("@preview/physica:0.9.3"),
+            Some(Package::Physica)
+        );
+        assert_eq!(
+            get_package_by_path("@local/mitex:0.2.4"),
+            Some(Package::Mitex)
+        );
+        assert_eq!(get_package_by_path("physica.typ"), None);
+        assert_eq!(get_package_by_path("@preview/cetz:0.2.2"), None);
+    }
+
+    #[test]
+    fn test_collect() {
+        let source = typst_syntax::Source::detached(
+            "#import \"@preview/physica:0.9.3\": *\n#{ import \"@preview/unify:0.5.0\": qty as q, unit }\n#import \"@preview/mitex:0.2.4\"",
+        );
+        let root = source.find(source.root().span()).unwrap();
+        let imports = Imports::collect(&root);
+        let len = source.text().len();
+        assert_eq!(
+            imports.resolve(Package::Physica, "dv", len),
+            Some("dv".to_string())
+        );
+        assert_eq!(
+            imports.resolve(Package::Unify, "q", len),
+            Some("qty".to_string())
+        );
+        assert_eq!(
+            imports.resolve(Package::Unify, "unit", len),
+            Some("unit".to_string())
+        );
+        assert_eq!(imports.resolve(Package::Unify, "qty", len), None);
+        assert_eq!(imports.resolve(Package::Mitex, "mitex", len), None);
+        assert_eq!(imports.resolve(Package::Metro, "qty", len), None);
+        // Names are only bound after the import
+        assert_eq!(imports.resolve(Package::Physica, "dv", 0), None);
+        assert_eq!(imports.resolve(Package::Unify, "unit", 40), None);
+        assert!(imports.binds("unit", len));
+        assert!(!imports.binds("unit", 0));
+    }
+}
@@ -0,0 +1,264 @@
+//! Lints of math run during the traversal, reporting probable mistakes like unclosed delimiters, empty attachments,
+//! integrals without integrand or mixed multiplication signs
+
+use std::ops::Range;
+
+use typst_syntax::{
+    ast::{AstNode, FuncCall},
+    LinkedNode, SyntaxKind,
+};
+
+use super::{
+    functions::math_function_name,
+    utils::{byte_range_to_utf16, find_symbol, utf16_position, InnerParser},
+};
+use crate::interface::{Lint, Severity};
+
+/// Characters which can delimit the content of `lr`
+const DELIMITERS: &str = "()[]{}|‖⟨⟩⌈⌉⌊⌋⟦⟧⦃⦄⦇⦈⦉⦊";
+
+/// Add a lint covering a byte range, unless it was already reported by a node traversed twice
+fn report(
+    parser: &mut InnerParser,
+    range: Range<usize>,
+    code: &str,
+    message: String,
+    severity: Severity,
+) {
+    let Some(range) = byte_range_to_utf16(parser.source, &range) else {
+        return;
+    };
+    let lint = Lint {
+        code: code.to_string(),
+        message,
+        severity,
+        position: utf16_position(parser.source, range),
+    };
+    if !parser.state.lints.contains(&lint) {
+        parser.state.lints.push(lint);
+    }
+}
+
+/// Get the text of a leaf or the name of a field access, like `angle.l`
+fn name(node: &LinkedNode) -> String {
+    node.get().clone().into_text().to_string()
+}
+
+/// Check if a node is a delimiter, written with its character or its name (`angle.l`)
+fn is_delimiter(parser: &InnerParser, node: &LinkedNode) -> bool {
+    let text = match node.kind() {
+        SyntaxKind::Text | SyntaxKind::Shorthand => name(node),
+        SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+            match find_symbol(name(node), parser.options) {
+                Some((_, symbol)) => symbol,
+                None => return false,
+            }
+        }
+        _ => return false,
+    };
+    text == "||" || (text.chars().count() == 1 && DELIMITERS.contains(text.as_str()))
+}
+
+/// Check if a node can be multiplied or integrated, like `x`, `f(x)` or `(a + b)`
+fn is_operand(node: &LinkedNode) -> bool {
+    match node.kind() {
+        SyntaxKind::Text => node.text().starts_with(char::is_alphanumeric),
+        SyntaxKind::MathIdent
+        | SyntaxKind::FieldAccess
+        | SyntaxKind::FuncCall
+        | SyntaxKind::MathAttach
+        | SyntaxKind::MathDelimited
+        | SyntaxKind::MathFrac
+        | SyntaxKind::MathRoot
+        | SyntaxKind::Math => true,
+        _ => false,
+    }
+}
+
+/// Check if a node is an integral sign, maybe with its bounds (`integral_0^1`)
+fn is_integral(node: &LinkedNode) -> bool {
+    match node.kind() {
+        SyntaxKind::MathIdent | SyntaxKind::FieldAccess => name(node).starts_with("integral"),
+        SyntaxKind::Text => node.text().starts_with(['∫', '∬', '∭', '∮']),
+        SyntaxKind::MathAttach => node
+            .children()
+            .next()
+            .is_some_and(|base| is_integral(&base)),
+        _ => false,
+    }
+}
+
+/// Report the content of `lr` which doesn't start and end with delimiters, since nothing would be scaled
+fn lint_lr(parser: &mut InnerParser) {
+    let Some(call) = parser.expr.cast::<FuncCall>() else {
+        return;
+    };
+    if math_function_name(&call).as_deref() != Some("lr") {
+        return;
+    }
+    let Some(content) = parser
+        .expr
+        .find(call.args().span())
+        .and_then(|args| args.children().find(|arg| arg.kind() == SyntaxKind::Math))
+    else {
+        return;
+    };
+    let children: Vec<LinkedNode> = content
+        .children()
+        .filter(|child| !child.kind().is_trivia())
+        .collect();
+    let (Some(first), Some(last)) = (children.first(), children.last()) else {
+        return;
+    };
+    if !is_delimiter(parser, first) || !is_delimiter(parser, last) {
+        report(
+            parser,
+            content.range(),
+            "lr-delimiters",
+            "The content of `lr` should start and end with the delimiters it scales".to_string(),
+            Severity::Warning,
+        );
+    }
+}
+
+/// Report opening delimiters which are never closed, and closing ones which were never opened
+fn lint_delimiters(parser: &mut InnerParser) {
+    let expr = parser.expr;
+    if expr.kind() == SyntaxKind::Math && expr.parent_kind() == Some(SyntaxKind::Math) {
+        // Typst groups the rest of the math after an unclosed delimiter
+        if let Some(open) = expr.children().next().filter(|open| {
+            open.kind() == SyntaxKind::Text && matches!(open.text().as_str(), "(" | "[" | "{")
+        }) {
+            report(
+                parser,
+                open.range(),
+                "unclosed-delimiter",
+                format!("`{}` is never closed", open.text()),
+                Severity::Information,
+            );
+        }
+    } else if expr.kind() == SyntaxKind::Text
+        && expr.parent_kind() == Some(SyntaxKind::Math)
+        && matches!(expr.text().as_str(), ")" | "]" | "}")
+    {
+        report(
+            parser,
+            expr.range(),
+            "unopened-delimiter",
+            format!("`{}` closes no delimiter", expr.text()),
+            Severity::Information,
+        );
+    }
+}
+
+/// Report superscripts and subscripts without content, like `x^()` or `x_""`
+fn lint_attachment(parser: &mut InnerParser) {
+    let children: Vec<LinkedNode> = parser.expr.children().collect();
+    for pair in children.windows(2) {
+        let (marker, script) = (&pair[0], &pair[1]);
+        let kind = match marker.kind() {
+            SyntaxKind::Hat => "superscript",
+            SyntaxKind::Underscore => "subscript",
+            _ => continue,
+        };
+        let content = parser.source.get(script.range()).unwrap_or_default();
+        let inner = content
+            .strip_prefix('(')
+            .and_then(|content| content.strip_suffix(')'))
+            .or_else(|| {
+                content
+                    .strip_prefix('"')
+                    .and_then(|content| content.strip_suffix('"'))
+            });
+        if inner.is_some_and(|inner| inner.trim().is_empty()) {
+            report(
+                parser,
+                marker.offset()..script.range().end,
+                "empty-attachment",
+                format!("Empty {}", kind),
+                Severity::Warning,
+            );
+        }
+    }
+}
+
+/// Report differentials written right after an integral with nothing to integrate, like `integral dif x`
+fn lint_differential(parser: &mut InnerParser) {
+    let expr = parser.expr;
+    if expr.kind() != SyntaxKind::MathIdent
+        || expr.text() != "dif"
+        || !expr
+            .prev_sibling()
+            .is_some_and(|integral| is_integral(&integral))
+    {
+        return;
+    }
+    let Some(variable) = expr.next_sibling() else {
+        return;
+    };
+    // The integrand can also be written after the differential, like `integral dif x f(x)`
+    if variable
+        .next_sibling()
+        .is_some_and(|next| is_operand(&next))
+    {
+        return;
+    }
+    report(
+        parser,
+        expr.offset()..variable.range().end,
+        "missing-integrand",
+        "Integral without an integrand".to_string(),
+        Severity::Warning,
+    );
+}
+
+/// Collect the products of an equation written with `*`, and check if `dot` or `times` are used too
+fn collect_products(node: &LinkedNode, stars: &mut Vec<Range<usize>>, dots: &mut bool) {
+    for child in node.children() {
+        match child.kind() {
+            SyntaxKind::Shorthand if child.text() == "*" => stars.push(child.range()),
+            // Accents like `dot(x)` aren't products
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess
+                if child.parent_kind() != Some(SyntaxKind::FuncCall) =>
+            {
+                let name = name(&child);
+                *dots |= matches!(name.as_str(), "dot" | "dot.op" | "dot.c" | "times");
+            }
+            SyntaxKind::Text => *dots |= matches!(child.text().as_str(), "⋅" | "·" | "×"),
+            SyntaxKind::Equation => {}
+            _ => collect_products(&child, stars, dots),
+        }
+    }
+}
+
+/// Report the `*` of an equation also using `dot` or `times` for products, since `*` is rendered as an asterisk
+fn lint_products(parser: &mut InnerParser) {
+    let mut stars = vec![];
+    let mut dots = false;
+    collect_products(parser.expr, &mut stars, &mut dots);
+    if !dots {
+        return;
+    }
+    for star in stars {
+        report(
+            parser,
+            star,
+            "mixed-multiplication",
+            "`*` is rendered as an asterisk `∗`, while `dot` or `times` is used for other products of this equation"
+                .to_string(),
+            Severity::Hint,
+        );
+    }
+}
+
+/// Run the lints of the node visited by the parser
+pub fn lint(parser: &mut InnerParser) {
+    match parser.expr.kind() {
+        SyntaxKind::FuncCall => lint_lr(parser),
+        SyntaxKind::Math | SyntaxKind::Text => lint_delimiters(parser),
+        SyntaxKind::MathAttach => lint_attachment(parser),
+        SyntaxKind::MathIdent => lint_differential(parser),
+        SyntaxKind::Equation => lint_products(parser),
+        _ => {}
+    }
+}
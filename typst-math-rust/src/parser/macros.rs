@@ -0,0 +1,311 @@
+//! Expansion of the math macros defined by the user (`#let RR = $bb(R)$`, `#let avg(x) = $angle.l x angle.r$`)
+
+use std::{collections::HashMap, ops::Range};
+
+use super::{
+    functions::letter_style_content,
+    parser::schedule_argument,
+    utils::{
+        apply_letter_style, arguments, get_category_by_char, get_letter_style, get_symbol,
+        significant_children, top_level_nodes, InnerParser,
+    },
+};
+use crate::{interface::Options, utils::symbols::Color};
+use typst_syntax::{
+    ast::{
+        AstNode, Closure, Expr, FuncCall, LetBinding, LetBindingKind, Param, Pattern, Shorthand,
+    },
+    LinkedNode, SyntaxKind,
+};
+
+/// Part of the expanded body of a macro
+#[derive(Debug, PartialEq)]
+enum Part {
+    Text(String),
+    Parameter(usize),
+}
+
+/// Simple math macros defined at the top level of the document
+#[derive(Clone, Default, Debug)]
+pub struct Macros {
+    /// Name -> expanded content
+    aliases: HashMap<String, String>,
+    /// Name -> expanded content around the parameters, which are used once and in order
+    functions: HashMap<String, Vec<String>>,
+}
+
+impl Macros {
+    /// Collect the let bindings at the top level of the document which can be expanded
+    pub fn collect(root: &LinkedNode, options: &Options) -> Macros {
+        let mut macros = Macros::default();
+        for node in top_level_nodes(root) {
+            let Some(binding) = node.cast::<LetBinding>() else {
+                continue;
+            };
+            match (binding.kind(), binding.init()) {
+                (LetBindingKind::Normal(Pattern::Normal(Expr::Ident(name))), Some(init)) => {
+                    let Some(body) = expand_body(&node, init, &[], options) else {
+                        continue;
+                    };
+                    if let [text] = body.as_slice() {
+                        if !text.is_empty() {
+                            macros.aliases.insert(name.to_string(), text.clone());
+                        }
+                    }
+                }
+                (LetBindingKind::Closure(name), Some(Expr::Closure(closure))) => {
+                    let Some(parameters) = parameters(closure) else {
+                        continue;
+                    };
+                    if let Some(body) = expand_body(&node, closure.body(), &parameters, options) {
+                        macros.functions.insert(name.to_string(), body);
+                    }
+                }
+                _ => {}
+            }
+        }
+        macros
+    }
+}
+
+/// Names of the parameters of a closure, None if they aren't all simple positional parameters
+fn parameters(closure: Closure) -> Option<Vec<String>> {
+    closure
+        .params()
+        .children()
+        .map(|param| match param {
+            Param::Pos(Pattern::Normal(Expr::Ident(ident))) => Some(ident.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Expand the body of a macro into the texts around each parameter, \
+/// None if it can't be expanded or if the parameters aren't used once and in order
+fn expand_body(
+    binding: &LinkedNode,
+    body: Expr,
+    parameters: &[String],
+    options: &Options,
+) -> Option<Vec<String>> {
+    let node = binding.find(body.span())?;
+    let parts = expand(&node, parameters, options)?;
+    let mut texts = vec![String::new()];
+    for part in parts {
+        match part {
+            Part::Text(text) => texts.last_mut()?.push_str(&text),
+            Part::Parameter(index) if index == texts.len() - 1 => texts.push(String::new()),
+            Part::Parameter(_) => return None,
+        }
+    }
+    (texts.len() == parameters.len() + 1).then_some(texts)
+}
+
+/// Expand a node of a macro body into parts, None if the node can't be expanded \
+/// The node is walked with an explicit stack, so that deeply nested bodies don't overflow it
+fn expand(node: &LinkedNode, parameters: &[String], options: &Options) -> Option<Vec<Part>> {
+    let parameter = |name: &str| parameters.iter().position(|p| p == name);
+    let mut parts = vec![];
+    let mut stack = vec![node.clone()];
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            SyntaxKind::Equation | SyntaxKind::Math => {
+                let children: Vec<LinkedNode> = node.children().collect();
+                stack.extend(children.into_iter().rev());
+            }
+            SyntaxKind::Dollar | SyntaxKind::Space => {}
+            SyntaxKind::Text | SyntaxKind::MathIdent => match parameter(node.text()) {
+                Some(index) => parts.push(Part::Parameter(index)),
+                None if node.kind() == SyntaxKind::Text => {
+                    parts.push(Part::Text(node.text().to_string()))
+                }
+                None => parts.push(Part::Text(get_symbol(node.text().to_string(), options)?.1)),
+            },
+            SyntaxKind::FieldAccess => {
+                let content = node.get().clone().into_text().to_string();
+                parts.push(Part::Text(get_symbol(content, options)?.1));
+            }
+            SyntaxKind::Shorthand => {
+                parts.push(Part::Text(node.cast::<Shorthand>()?.get().to_string()))
+            }
+            SyntaxKind::Str => parts.push(Part::Text(letter_style_content(&node, options)?)),
+            SyntaxKind::FuncCall => {
+                // Letter styles can't contain parameters
+                if uses_parameters(&node, parameters) {
+                    return None;
+                }
+                let func = node.cast::<FuncCall>()?;
+                let content = match func.callee() {
+                    // Letter styles in math, which can be nested
+                    Expr::MathIdent(_) => letter_style_content(&node, options)?,
+                    // Letter styles in code (`math.bb("R")`)
+                    Expr::FieldAccess(access) if access.target().to_untyped().text() == "math" => {
+                        let (map, _) = get_letter_style(access.field().as_str())?;
+                        let args = significant_children(&node.find(func.args().span())?);
+                        let [_, content, _] = args.as_slice() else {
+                            return None;
+                        };
+                        apply_letter_style(map, &letter_style_content(content, options)?)
+                    }
+                    _ => return None,
+                };
+                parts.push(Part::Text(content));
+            }
+            _ => return None,
+        }
+    }
+    Some(parts)
+}
+
+/// Check if a parameter is used in a node, with an explicit stack for deeply nested nodes
+fn uses_parameters(node: &LinkedNode, parameters: &[String]) -> bool {
+    let mut stack = vec![node.clone()];
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            SyntaxKind::Text | SyntaxKind::MathIdent => {
+                if parameters.iter().any(|p| p == node.text()) {
+                    return true;
+                }
+            }
+            _ => {
+                let children: Vec<LinkedNode> = node.children().collect();
+                stack.extend(children.into_iter().rev());
+            }
+        }
+    }
+    false
+}
+
+/// Render the use of a macro alias (`RR` -> `ℝ`), return false if the identifier isn't a macro
+pub fn macro_ident_block(parser: &mut InnerParser, name: &str) -> bool {
+    let Some(content) = parser.state.macros.aliases.get(name).cloned() else {
+        return false;
+    };
+    insert_text(parser, parser.expr.range(), name, content, parser.offset);
+    true
+}
+
+/// Render the call of a macro function (`avg(a + b)` -> `⟨a + b⟩`), by replacing the callee and the opening paren,
+/// the commas and the closing paren with the content around the parameters, \
+/// return false if the function isn't a macro or if the number of arguments doesn't match
+pub fn macro_call_block(
+    parser: &mut InnerParser,
+    name: &str,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+) -> bool {
+    let Some(texts) = parser.state.macros.functions.get(name).cloned() else {
+        return false;
+    };
+    let children: Vec<LinkedNode> = args.children().collect();
+    let Some(items) = arguments(&children) else {
+        return false;
+    };
+    if items.len() + 1 != texts.len() {
+        return false;
+    }
+    let mut texts = texts.into_iter();
+    // Before the first argument
+    let (end, end_offset) = match items.first() {
+        Some(item) => (children[*item].range().start, 0),
+        None => (args.range().end, parser.offset.1),
+    };
+    insert_text(
+        parser,
+        callee.range().start..end,
+        name,
+        texts.next().unwrap_or_default(),
+        (parser.offset.0, end_offset),
+    );
+    for (index, item) in items.iter().enumerate() {
+        schedule_argument(parser, &children[*item]);
+        // Over the comma and the following spaces, or over the closing paren
+        let (range, offset) = match items.get(index + 1) {
+            Some(next) => (
+                children[*item].range().end..children[*next].range().start,
+                (0, 0),
+            ),
+            None => (
+                children[*item].range().end..args.range().end,
+                (0, parser.offset.1),
+            ),
+        };
+        insert_text(
+            parser,
+            range,
+            name,
+            texts.next().unwrap_or_default(),
+            offset,
+        );
+    }
+    true
+}
+
+/// Insert an expanded text with the style of its category if it is a single symbol, or hide the range if it is empty
+fn insert_text(
+    parser: &mut InnerParser,
+    range: Range<usize>,
+    name: &str,
+    text: String,
+    offset: (usize, usize),
+) {
+    if text.is_empty() {
+        parser.insert_void(range, offset);
+        return;
+    }
+    let uuid = &[parser.uuid, "let-", name, "-", &text];
+    let mut chars = text.chars();
+    match (chars.next().and_then(get_category_by_char), chars.next()) {
+        (Some(category), None) => parser.insert_result_category(
+            range,
+            uuid,
+            &text,
+            category,
+            parser.added_text_decoration,
+            offset,
+        ),
+        _ => parser.insert_result(
+            range,
+            uuid,
+            &text,
+            Color::Number,
+            parser.added_text_decoration.to_string(),
+            offset,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Macros;
+    use crate::interface::Options;
+    use typst_syntax::{LinkedNode, Source};
+
+    #[test]
+    fn test_collect_deep() {
+        // Deeply nested bodies are expanded without growing the stack, even on a small one
+        let source = Source::detached(format!(
+            "#let X = ${}A{}$\n#let f(x) = $x + g{}y{}$",
+            "bold(".repeat(1000),
+            ")".repeat(1000),
+            "(".repeat(1000),
+            ")".repeat(1000)
+        ));
+        let (source, macros) = std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(move || {
+                let root = LinkedNode::new(source.root());
+                let macros = Macros::collect(&root, &Options::default());
+                drop(root);
+                // The tree is parsed and dropped on the stack of the test, only the walk runs on the small one
+                (source, macros)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(macros.aliases.get("X").map(String::as_str), Some("𝐀"));
+        // Other functions than letter styles aren't expanded
+        assert!(!macros.functions.contains_key("f"));
+        drop(source);
+    }
+}
@@ -0,0 +1,138 @@
+//! Rendering of LaTeX math embedded with the mitex package (`#mitex(`\alpha + \beta`)`)
+
+use super::imports::Package;
+use super::rules::{Call, DecorationRule, Rendering};
+use super::utils::{apply_letter_style, get_letter_style, get_symbol, InnerParser};
+use crate::utils::symbols::{Color, LATEX_COMMANDS, LATEX_LETTER_STYLES};
+use typst_syntax::{LinkedNode, SyntaxKind};
+
+/// LaTeX accents and functions sharing their name with a typst symbol (`\dot{x}` isn't `⋅`)
+const IGNORED_COMMANDS: [&str; 11] = [
+    "dot", "ddot", "hat", "bar", "tilde", "vec", "check", "breve", "acute", "grave", "overline",
+];
+
+/// LaTeX, used in math and in markup
+pub struct Mitex;
+
+impl DecorationRule for Mitex {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        match &call.ident {
+            Some(name) if parser.options.features.packages => {
+                mitex_block(parser, name, &call.args).into()
+            }
+            _ => Rendering::Skipped,
+        }
+    }
+}
+
+/// Try to render the LaTeX argument of a mitex function call, return false if the function isn't handled or mitex isn't imported \
+/// Calls in markup are only rendered with the symbols outside math
+pub fn mitex_block(parser: &mut InnerParser, name: &str, args: &LinkedNode) -> bool {
+    if !parser.options.render_latex
+        || parser.hash_offset().is_none()
+        || !parser
+            .state
+            .imports
+            .resolve(Package::Mitex, name, parser.expr.range().start)
+            .is_some_and(|name| matches!(name.as_str(), "mitex" | "mi"))
+    {
+        return false;
+    }
+    let Some(latex) = args
+        .children()
+        .find(|c| matches!(c.kind(), SyntaxKind::Raw | SyntaxKind::Str))
+    else {
+        return false;
+    };
+    if latex.kind() == SyntaxKind::Raw {
+        for text in latex.children().filter(|c| c.kind() == SyntaxKind::Text) {
+            render_latex(parser, text.text(), text.range().start, "\\");
+        }
+    } else {
+        // Backslashes are escaped in strings
+        render_latex(parser, latex.text(), latex.range().start, "\\\\");
+    }
+    true
+}
+
+/// Decorate the LaTeX commands of a source text starting at byte `start`
+fn render_latex(parser: &mut InnerParser, text: &str, start: usize, backslash: &str) {
+    let mut index = 0;
+    while let Some(position) = text[index..].find(backslash) {
+        let command_start = index + position;
+        let name_start = command_start + backslash.len();
+        let name_end = text[name_start..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .map_or(text.len(), |end| name_start + end);
+        let name = &text[name_start..name_end];
+        if name.is_empty() {
+            // Escaped character like `\{`
+            index = name_start + text[name_start..].chars().next().map_or(0, char::len_utf8);
+            continue;
+        }
+        index = name_end;
+        if IGNORED_COMMANDS.contains(&name) {
+            continue;
+        }
+
+        // Letter styles, with only letters and spaces between braces
+        if let Some(style) = LATEX_LETTER_STYLES.get(name) {
+            let content = text[name_end..]
+                .strip_prefix('{')
+                .and_then(|rest| rest.split_once('}'))
+                .map(|(content, _)| content)
+                .filter(|content| content.chars().all(|c| c.is_alphanumeric() || c == ' '));
+            if let (Some(content), Some((map, decoration))) = (content, get_letter_style(style)) {
+                let end = name_end + content.len() + 2;
+                let symbol = apply_letter_style(map, content);
+                parser.insert_result(
+                    start + command_start..start + end,
+                    &[parser.uuid, "latex-", &symbol],
+                    &symbol,
+                    Color::Number,
+                    format!("{}{}", parser.added_text_decoration, decoration),
+                    (0, 0),
+                );
+                index = end;
+            }
+            continue;
+        }
+
+        let symbol = LATEX_COMMANDS.get(name).copied().unwrap_or(name);
+        if get_symbol(symbol.to_string(), parser.options).is_some() {
+            parser.insert_result_symbol(
+                start + command_start..start + name_end,
+                symbol.to_string(),
+                &[parser.uuid, symbol],
+                parser.added_text_decoration,
+                (0, 0),
+                ("", ""),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interface::Options,
+        parser::utils::{get_letter_style, get_symbol},
+        utils::symbols::{LATEX_COMMANDS, LATEX_LETTER_STYLES},
+    };
+
+    #[test]
+    fn test_latex_commands_exist() {
+        let options = Options::default();
+        for (command, symbol) in LATEX_COMMANDS.entries() {
+            assert!(
+                get_symbol(symbol.to_string(), &options).is_some(),
+                "\\{} -> {}",
+                command,
+                symbol
+            );
+        }
+        for style in LATEX_LETTER_STYLES.values() {
+            assert!(get_letter_style(style).is_some());
+        }
+    }
+}
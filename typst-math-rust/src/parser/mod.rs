@@ -1,2 +1,19 @@
+pub mod attachments;
+pub mod bindings;
+pub mod comments;
+pub mod context;
+pub mod functions;
+pub mod imports;
+pub mod lint;
+pub mod macros;
+pub mod mitex;
+pub mod overlaps;
+#[allow(clippy::module_inception)]
 pub mod parser;
+pub mod physica;
+pub mod rules;
+pub mod shorthands;
+pub mod symbols;
+pub mod text;
+pub mod units;
 pub mod utils;
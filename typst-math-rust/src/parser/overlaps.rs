@@ -0,0 +1,142 @@
+//! Resolution of the decorations overlapping each other, like the span hidden around an argument
+//! and the symbol of this argument, so that each character of the source is replaced at most once
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use typst_syntax::Source;
+
+use super::utils::utf16_position;
+use crate::interface::Decoration;
+
+/// Priority of a decoration over the ones overlapping it, the highest one keeping its range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Span hidden around a rendered construct (`^(`, `bb(`)
+    Hidden,
+    /// Rendered symbol
+    #[default]
+    Symbol,
+}
+
+impl Priority {
+    /// Default priority of a decoration from its symbol
+    pub fn of(symbol: &str) -> Self {
+        if symbol.is_empty() {
+            Priority::Hidden
+        } else {
+            Priority::Symbol
+        }
+    }
+}
+
+/// Parts of a range which aren't covered by the disjoint ranges already kept
+fn uncovered(range: Range<usize>, kept: &BTreeMap<usize, usize>) -> Vec<Range<usize>> {
+    let mut parts = vec![];
+    let mut end = range.end;
+    // Kept ranges are disjoint, so their ends are sorted like their starts
+    for (&start, &kept_end) in kept.range(..range.end).rev() {
+        if kept_end <= range.start {
+            break;
+        }
+        if kept_end < end {
+            parts.push(kept_end..end);
+        }
+        end = end.min(start);
+    }
+    if range.start < end {
+        parts.push(range.start..end);
+    }
+    parts.reverse();
+    parts
+}
+
+/// Trim the positions overlapping a position with a higher priority, or starting after one with the same priority \
+/// A symbol keeps the first part not covered by other decorations, hidden spans keep all of them,
+/// and decorations left without positions are removed
+pub fn resolve_overlaps(source: &Source, result: &mut HashMap<u32, Decoration>) {
+    let mut positions: Vec<(Priority, usize, usize, u32, usize)> = result
+        .values()
+        .flat_map(|decoration| {
+            decoration
+                .positions
+                .iter()
+                .enumerate()
+                .filter(|(_, position)| position.start < position.end)
+                .map(move |(index, position)| {
+                    (
+                        decoration.priority,
+                        position.start,
+                        position.end,
+                        decoration.id,
+                        index,
+                    )
+                })
+        })
+        .collect();
+    // From the highest priority, then in the order of the document and from the longest
+    positions.sort_by_key(|(priority, start, end, id, index)| {
+        (
+            std::cmp::Reverse(*priority),
+            *start,
+            std::cmp::Reverse(*end),
+            *id,
+            *index,
+        )
+    });
+
+    let mut kept = BTreeMap::new();
+    let mut trimmed: HashMap<(u32, usize), Vec<Range<usize>>> = HashMap::new();
+    for (priority, start, end, id, index) in positions {
+        let mut parts = uncovered(start..end, &kept);
+        if parts.len() == 1 && parts[0] == (start..end) {
+            kept.insert(start, end);
+            continue;
+        }
+        if priority != Priority::Hidden {
+            parts.truncate(1);
+        }
+        for part in &parts {
+            kept.insert(part.start, part.end);
+        }
+        trimmed.insert((id, index), parts);
+    }
+    if trimmed.is_empty() {
+        return;
+    }
+
+    for decoration in result.values_mut() {
+        let id = decoration.id;
+        let positions = std::mem::take(&mut decoration.positions);
+        for (index, position) in positions.into_iter().enumerate() {
+            match trimmed.get(&(id, index)) {
+                Some(parts) => decoration.positions.extend(
+                    parts
+                        .iter()
+                        .map(|part| utf16_position(source, part.clone())),
+                ),
+                None => decoration.positions.push(position),
+            }
+        }
+    }
+    result.retain(|_, decoration| !decoration.positions.is_empty());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::ops::Range;
+
+    #[test]
+    fn test_uncovered() {
+        let kept = BTreeMap::from([(2, 4), (6, 7), (10, 12)]);
+        assert_eq!(super::uncovered(0..2, &kept), vec![0..2]);
+        assert_eq!(super::uncovered(3..9, &kept), vec![4..6, 7..9]);
+        assert_eq!(
+            super::uncovered(1..13, &kept),
+            vec![1..2, 4..6, 7..10, 12..13]
+        );
+        assert_eq!(super::uncovered(10..12, &kept), Vec::<Range<usize>>::new());
+        assert_eq!(super::uncovered(11..15, &kept), vec![12..15]);
+    }
+}
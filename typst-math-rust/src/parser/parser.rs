@@ -1,543 +1,381 @@
 //! Parser module, traverse the AST to generate decorations
 
-use super::utils::{get_symbol, unchecked_cast_expr, InnerParser};
-use crate::utils::symbols::{Color, BLACKBOLD_LETTERS, CAL_LETTERS, FRAK_LETTERS};
-use typst_syntax::ast::{
-    AstNode, Expr, FieldAccess, FuncCall, MathAttach, MathIdent, Shorthand, Str, Text,
+use super::attachments::{math_attach_block, math_block};
+use super::bindings::Bindings;
+use super::comments::DisabledRegions;
+use super::context::{MathStyle, StyleContext};
+use super::functions::func_call_block;
+use super::imports::Imports;
+use super::lint::lint;
+use super::macros::Macros;
+use super::shorthands::shorthand_block;
+use super::symbols::{
+    align_point_block, embedded_code_block, field_access_block, linebreak_block, math_ident_block,
 };
-use typst_syntax::{LinkedNode, SyntaxKind};
+use super::text::{escape_block, str_block, text_block};
+use super::utils::InnerParser;
+use crate::interface::{Lint, Options, Scope, Suggestion};
+use crate::utils::interner::Interner;
+use typst_syntax::ast::Equation;
+use typst_syntax::{LinkedNode, Span, SyntaxKind};
 
 /// State of the parser, used to know if we are in a base, attachment, or other
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct State {
     pub is_base: bool,
     pub is_attachment: bool,
+    /// Level of the attachment being parsed, to place the attachments nested in it
+    pub script_level: Option<ScriptLevel>,
+    /// Css color set by a surrounding `text(fill: ...)`
+    pub fill: Option<String>,
+    /// Style of the equation being parsed, set by show rules
+    pub math_style: Option<MathStyle>,
+    /// Names bound by the user, which aren't rendered as symbols
+    pub bindings: Bindings,
+    /// Packages imported by the document
+    pub imports: Imports,
+    /// Math macros defined by the document
+    pub macros: Macros,
+    /// Regions disabled by magic comments
+    pub disabled: DisabledRegions,
+    /// Set and show rules changing the style of the equations
+    pub styles: StyleContext,
+    /// Ids of the decoration uuids
+    pub interner: Interner,
+    /// Depth of the current node in the syntax tree
+    pub depth: usize,
+    /// Some nodes were deeper than the maximum depth and were skipped
+    pub truncated: bool,
+    /// The cancellation token of the options was cancelled, the traversal stops
+    pub cancelled: bool,
+    /// Nodes scheduled by the block being parsed, visited once it returns
+    pub scheduled: Vec<Visit>,
+    /// Unknown identifiers met, with the known names close to them
+    pub suggestions: Vec<Suggestion>,
+    /// Probable mistakes found in the math met
+    pub lints: Vec<Lint>,
 }
 
-impl Default for State {
-    fn default() -> Self {
-        State {
-            is_base: false,
-            is_attachment: false,
-        }
-    }
+/// What the parse of any node reads from the whole document: the names bound at its top level, the packages it imports,
+/// the regions disabled by magic comments and the style rules \
+/// Sessions keep it between the edits which can't change it, the macros being collected by each parse since they depend on the options
+#[derive(Clone, Default)]
+pub struct DocumentContext {
+    pub bindings: Bindings,
+    pub imports: Imports,
+    pub disabled: DisabledRegions,
+    pub styles: StyleContext,
 }
 
-/// Use a recursive DFS to traverse the entire AST and apply style \
-/// Most complex part of the code, match the current expression and then,
-/// compute the appropriate style and/or if we need to continue over children
-pub fn ast_dfs(
-    parser: &mut InnerParser,
-    expr: &LinkedNode,
-    uuid: &str,
-    added_text_decoration: &str,
-    offset: (usize, usize),
-) {
-    // Create the new parser
-    let mut parser = InnerParser::from(parser, expr, uuid, added_text_decoration, offset);
-    // Math the current expression type
-    let result = if let Some(expr) = expr.cast::<Expr>() {
-        match expr {
-            // Math identifier, check if it is in the symbols list
-            Expr::MathIdent(_) => Some(math_ident_block(&mut parser)),
-            // Field Access, create a string containing all fields sparated with a dot (alpha.alt), and check if it is in symbols list
-            Expr::FieldAccess(_) => Some(field_access_block(&mut parser)),
-            // Replace linebreak with an arrow
-            Expr::Linebreak(_) => Some(linebreak_block(&mut parser)),
-            // Math attachment, power, subscript, superscript
-            Expr::MathAttach(_) => Some(math_attach_block(&mut parser)),
-            // Math block, continue over children and check current state to apply style
-            Expr::Math(_) => Some(math_block(&mut parser)),
-            // Typst shorthands
-            Expr::Shorthand(_) => Some(shorthand_block(&mut parser)),
-            // Typst text block, some symbols are here instead of shorthand
-            Expr::Text(_) => Some(text_block(&mut parser)),
-            // Typst string block (between quotes)
-            Expr::Str(_) => Some(str_block(&mut parser)),
-            // Typst func, if it's a common func, apply style, else continue over args and callee
-            Expr::FuncCall(_) => Some(func_call_block(&mut parser)),
-            _ => None,
-        }
-    } else {
-        None
-    };
-    if result.is_none() {
-        // Propagate the function
-        for child in expr.children() {
-            ast_dfs(&mut parser, &child, uuid, added_text_decoration, (0, 0));
+impl DocumentContext {
+    /// Collect the context of a document from its root
+    pub fn collect(root: &LinkedNode) -> DocumentContext {
+        DocumentContext {
+            bindings: Bindings::collect(root),
+            imports: Imports::collect(root),
+            disabled: DisabledRegions::collect(root),
+            styles: StyleContext::collect(root),
         }
     }
-}
-
-/// Recursive function to convert a field access into a string (`[alpha, ., alt]` -> `'alpha.alt'`)
-fn field_access_recursive(access: FieldAccess) -> Option<String> {
-    // Check if the target is a math identifier or another field access
-    match access.target() {
-        Expr::FieldAccess(subaccess) => {
-            if let Some(start) = field_access_recursive(subaccess) {
-                return Some(format!("{}.{}", start, access.field().to_string()));
-            }
-        }
-        Expr::MathIdent(ident) => {
-            return Some(format!(
-                "{}.{}",
-                ident.to_string(),
-                access.field().to_string()
-            ));
-        }
-        Expr::Ident(ident) => {
-            return Some(format!(
-                "{}.{}",
-                ident.to_string(),
-                access.field().to_string()
-            ));
+    /// Move the positions of the context after an edit which didn't change it, `shift` giving the new position of an old one
+    pub fn shift(&mut self, shift: impl Fn(usize) -> usize) {
+        self.disabled.shift(&shift);
+        self.imports.shift(&shift);
+        self.styles.shift(&shift);
+    }
+    /// State of a parse of the document, with the macros collected with the options
+    pub fn state(&self, root: &LinkedNode, options: &Options, interner: Interner) -> State {
+        State {
+            bindings: self.bindings.clone(),
+            imports: self.imports.clone(),
+            macros: Macros::collect(root, options),
+            disabled: self.disabled.clone(),
+            styles: self.styles.clone(),
+            interner,
+            ..Default::default()
         }
-        _ => {}
     }
-    None
 }
 
-// Next functions are the blocks of the parser, each one match a specific expression and apply style
+/// Nesting depth, font size and vertical shift in em of an attachment
+#[derive(Clone, Copy)]
+pub struct ScriptLevel {
+    pub depth: usize,
+    pub scale: f32,
+    pub shift: f32,
+}
 
-/// Parse a math ident block, symply add a symbol if it is in the symbols list
-fn math_ident_block(parser: &mut InnerParser) {
-    let ident = unchecked_cast_expr::<MathIdent>(parser.expr);
-    parser.insert_result_symbol(
-        parser.expr.range(),
-        ident.to_string(),
-        format!("{}{}", parser.uuid, ident.to_string()),
-        parser.added_text_decoration,
-        parser.offset,
-        ("", ""),
-    );
+/// Part of the state changed by the attachment blocks, kept to restore it once they scheduled the base and the attachments
+#[derive(Clone, Copy)]
+pub struct AttachmentState {
+    is_base: bool,
+    is_attachment: bool,
+    script_level: Option<ScriptLevel>,
 }
 
-/// Parse a field access block, create a string containing all fields sparated with a dot (alpha.alt), and check if it is in symbols list
-/// Also check if the symbol starts with `sym.` and remove it if needed
-fn field_access_block(parser: &mut InnerParser) {
-    let access = unchecked_cast_expr::<FieldAccess>(parser.expr);
-    if let Some(content) = field_access_recursive(access) {
-        // Add one to offset to remove the # with sym
-        if content.contains("sym") {
-            if parser.options.render_outside_math {
-                parser.offset.0 += 1;
-            } else {
-                return;
-            }
+impl AttachmentState {
+    /// Keep the attachment state of the parser
+    pub fn save(state: &State) -> AttachmentState {
+        AttachmentState {
+            is_base: state.is_base,
+            is_attachment: state.is_attachment,
+            script_level: state.script_level,
         }
-
-        let content = content.replace("sym.", "");
-        parser.insert_result_symbol(
-            parser.expr.range(),
-            content.clone(),
-            format!("{}{}", parser.uuid, content),
-            parser.added_text_decoration,
-            parser.offset,
-            ("", ""),
-        );
     }
-}
 
-/// Simply replace a linebreak with an arrow
-fn linebreak_block(parser: &mut InnerParser) {
-    parser.insert_result(
-        parser.expr.range(),
-        format!("{}linebreak", parser.uuid),
-        '⮰'.to_string(),
-        Color::Comparison,
-        format!(
-            "{}font-family: NewComputerModernMath; font-weight: bold;",
-            parser.added_text_decoration
-        ),
-        parser.offset,
-    );
-}
-
-/// Parse a math attach block (subscript, superscript) \
-/// Apply specific style and offset for each attachment, and compute specific style with rendering mode and current state
-fn math_attach_block(parser: &mut InnerParser) {
-    let attachment = unchecked_cast_expr::<MathAttach>(parser.expr);
-    // Keep the current state to restore it after the attachment
-    let state = State {
-        is_base: parser.state.is_base,
-        is_attachment: parser.state.is_attachment,
-    };
-    let base = parser.expr.find(attachment.base().span()).unwrap();
-    // Check if it is the 'main' base, and render it if true
-    if parser.expr.parent_kind() != Some(SyntaxKind::MathAttach) {
-        parser.state.is_base = true;
-        parser.state.is_attachment = false;
-        ast_dfs(
-            parser,
-            &base,
-            parser.uuid,
-            parser.added_text_decoration,
-            parser.offset,
-        );
-    } else {
-        parser.state.is_base = false;
-        parser.state.is_attachment = false;
-        ast_dfs(parser, &base, "", "", (0, 0));
-    }
-    // Compute specific offset and style with rendering mode
-    if parser.options.rendering_mode > 1 {
-        parser.offset = (1, 0);
+    /// Set the attachment state of the parser back to the one kept
+    pub fn restore(self, state: &mut State) {
+        state.is_base = self.is_base;
+        state.is_attachment = self.is_attachment;
+        state.script_level = self.script_level;
     }
-    let (top_decor, top_uuid) = if parser.options.rendering_mode > 1 {
-        (
-            "font-size: 0.8em; transform: translateY(-30%); display: inline-block;",
-            "top-",
-        )
-    } else {
-        ("", "")
-    };
-    let (bottom_decor, bottom_uuid) = if parser.options.rendering_mode > 1 {
-        (
-            "font-size: 0.8em; transform: translateY(20%); display: inline-block;",
-            "bottom-",
-        )
-    } else {
-        ("", "")
-    };
-    // Set state for top and bottom attachment
-    parser.state.is_base = false;
-    parser.state.is_attachment = parser.options.rendering_mode > 1;
-    if let Some(top) = attachment.top() {
-        let top = parser.expr.find(top.span()).unwrap();
-        ast_dfs(parser, &top, top_uuid, top_decor, parser.offset)
-    }
-    if let Some(bottom) = attachment.bottom() {
-        let bottom = parser.expr.find(bottom.span()).unwrap();
-        ast_dfs(parser, &bottom, bottom_uuid, bottom_decor, parser.offset)
-    }
-    // Restore the state
-    parser.state.is_base = state.is_base;
-    parser.state.is_attachment = state.is_attachment;
 }
 
-/// Parse a math block, check if it is a simple block (paren around a symbol) and propagate style if true \
-/// Otherwise, continue over children and reset style
-fn math_block(parser: &mut InnerParser) {
-    let children: Vec<LinkedNode> = parser.expr.children().collect();
-    // If we are in an attachment, check if the current math block is just paren around a symbol
-    if children.len() == 3
-        && children[0].kind() == SyntaxKind::LeftParen
-        && children[1].kind() == SyntaxKind::Math
-        && children[2].kind() == SyntaxKind::RightParen
-    {
-        // This serie of checks aims to verify that the block inside paren is 'simple', wich means that we can propagate style (So top and bottom attachment)
-        let mut propagate_style = false;
-        let sub_children: Vec<LinkedNode> = children[1].children().collect();
-
-        // Check if it's just a text
-        if sub_children.len() == 1
-            && (sub_children[0].kind() == SyntaxKind::Text
-                || sub_children[0].kind() == SyntaxKind::Str)
-        {
-            propagate_style = true;
-        }
-        // Check if it's just a symbol
-        else if sub_children.len() == 1 && sub_children[0].kind() == SyntaxKind::MathIdent {
-            if get_symbol(
-                sub_children[0].cast::<MathIdent>().unwrap().to_string(),
-                parser.options,
-            )
-            .is_some()
-            {
-                propagate_style = true;
-            }
-        }
-        // Check if it's a text with a sign
-        else if sub_children.len() == 2
-            && sub_children[0].kind() == SyntaxKind::Shorthand
-            && (sub_children[1].kind() == SyntaxKind::Text
-                || sub_children[1].kind() == SyntaxKind::Str)
-        {
-            propagate_style = true;
-        }
-        // Check if it's a symbol with a sign
-        else if sub_children.len() == 2
-            && sub_children[0].kind() == SyntaxKind::Shorthand
-            && sub_children[1].kind() == SyntaxKind::MathIdent
-        {
-            if get_symbol(
-                sub_children[1].cast::<MathIdent>().unwrap().to_string(),
-                parser.options,
-            )
-            .is_some()
-            {
-                propagate_style = true;
-            }
-        }
+/// Style of the nodes visited from a node scheduled by a block, restored when the traversal moves to one of them
+#[derive(Clone)]
+struct Frame {
+    uuid: String,
+    added_text_decoration: String,
+    is_base: bool,
+    is_attachment: bool,
+    script_level: Option<ScriptLevel>,
+    fill: Option<String>,
+    math_style: Option<MathStyle>,
+}
 
-        // We can propagate, hide paren and then continue over children (With a for loop and a call to inner, to keep current style)
-        if propagate_style {
-            parser.insert_void(children[0].range(), (parser.offset.0, 0));
-            parser.insert_void(children[2].range(), (0, parser.offset.1));
-            for child in children[1].children() {
-                ast_dfs(
-                    parser,
-                    &child,
-                    parser.uuid,
-                    parser.added_text_decoration,
-                    (0, 0),
-                );
-            }
-            return;
-        } else if parser.options.hide_unnecessary_delimiters {
-            // Simply hide the paren
-            parser.insert_void(children[0].range(), (0, 0));
-            parser.insert_void(children[2].range(), (0, 0));
+impl Frame {
+    /// Keep the style of the parser, with the uuid and the css applied to the nodes visited
+    fn capture(state: &State, uuid: &str, added_text_decoration: &str) -> Frame {
+        Frame {
+            uuid: uuid.to_string(),
+            added_text_decoration: added_text_decoration.to_string(),
+            is_base: state.is_base,
+            is_attachment: state.is_attachment,
+            script_level: state.script_level,
+            fill: state.fill.clone(),
+            math_style: state.math_style.clone(),
         }
     }
-    // Style isn't propagated, reset state
-    parser.state.is_attachment = false;
-    for child in parser.expr.children() {
-        ast_dfs(parser, &child, "", "", (0, 0)); // Propagate the function
+
+    /// Set the style of the parser back to the one kept
+    fn restore(&self, state: &mut State) {
+        state.is_base = self.is_base;
+        state.is_attachment = self.is_attachment;
+        state.script_level = self.script_level;
+        state.fill.clone_from(&self.fill);
+        state.math_style.clone_from(&self.math_style);
     }
 }
 
-/// Replace a shorthand with a specific style
-fn shorthand_block(parser: &mut InnerParser) {
-    let short = unchecked_cast_expr::<Shorthand>(parser.expr);
-    let (color, decoration, content) = match short.get() {
-        // Apply specific style for each shorthand
-        '\u{2212}' => (Color::Operator, "", '-'),
-        '∗' => (Color::Operator, "", '*'),
-        '⟦' | '⟧' => (Color::Set, "", short.get()),
-        c => (
-            Color::Comparison,
-            "font-family: \"NewComputerModernMath\"; font-weight: bold;",
-            c,
-        ),
-    };
-    parser.insert_result(
-        parser.expr.range(),
-        format!("{}-{}", parser.uuid, content.to_string()),
-        content.to_string(),
-        color,
-        format!("{}{}", parser.added_text_decoration, decoration),
-        parser.offset,
-    );
+/// Node scheduled by a block, visited once the block returns with the style the parser had when it was scheduled
+#[derive(Clone)]
+pub struct Visit {
+    span: Span,
+    offset: (usize, usize),
+    frame: Frame,
 }
 
-/// Replace a text block with a specific style \
-/// Some symbols are here instead of shorthand \
-/// Also, if we are in an attachment, apply a specific style
-fn text_block(parser: &mut InnerParser) {
-    let text = unchecked_cast_expr::<Text>(parser.expr);
-    if text.get().len() == 1 {
-        if let Some((color, decoration)) = match text.get().as_str() {
-            "+" => Some((Color::Operator, "")),
-            "=" | "<" | ">" => Some((Color::Comparison, "")),
-            "[" | "]" => Some((Color::Set, "")),
-            _ => None,
-        } {
-            parser.insert_result(
-                parser.expr.range(),
-                format!("{}-{}", parser.uuid, text.get().to_string()),
-                text.get().to_string(),
-                color,
-                format!("{}{}", parser.added_text_decoration, decoration),
-                parser.offset,
-            );
-            return;
-        }
-    }
-    if parser.state.is_attachment {
-        parser.insert_result(
-            parser.expr.range(),
-            format!("{}-text-{}", parser.uuid, text.get().to_string()),
-            text.get().to_string(),
-            Color::Number,
-            format!("{}", parser.added_text_decoration),
-            parser.offset,
-        );
-    }
+/// Step of the traversal waiting on its stack
+enum Task<'a> {
+    /// Visit a node at a given depth, with an offset applied to its ranges and the index of its frame
+    Visit(LinkedNode<'a>, usize, (usize, usize), usize),
+    /// Close the scope opened by a node once its children are visited
+    Leave(LinkedNode<'a>, Option<usize>),
 }
 
-/// Same as text block, but for a string block (between quotes) \
-/// Apply a specific style if we are in an attachment
-fn str_block(parser: &mut InnerParser) {
-    let text = unchecked_cast_expr::<Str>(parser.expr);
-    if parser.state.is_attachment {
-        parser.insert_result(
-            parser.expr.range(),
-            format!("{}-text-{}", parser.uuid, text.get().to_string()),
-            text.get().to_string(),
-            Color::Number,
-            format!("{}", parser.added_text_decoration),
-            parser.offset,
-        );
-    }
+/// Schedule a node nested in the expression of a block to be visited once the block returns, \
+/// with the uuid, css and offset given and the current style of the parser
+pub fn schedule(
+    parser: &mut InnerParser,
+    expr: &LinkedNode,
+    uuid: &str,
+    added_text_decoration: &str,
+    offset: (usize, usize),
+) {
+    let frame = Frame::capture(parser.state, uuid, added_text_decoration);
+    parser.state.scheduled.push(Visit {
+        span: expr.span(),
+        offset,
+        frame,
+    });
 }
 
-/// Parse a func call block, if it is a common func, apply style, else continue over args and callee
-fn func_call_block(parser: &mut InnerParser) {
-    let func = unchecked_cast_expr::<FuncCall>(parser.expr);
-    let callee = parser.expr.find(func.callee().span()).unwrap();
-    let args = parser.expr.find(func.args().span()).unwrap();
-    let children: Vec<LinkedNode> = args.children().collect();
-    let mut propagate_style = true;
+/// Schedule an argument of a call rendered by a package rule or a macro, styled like the call itself \
+/// (the arguments of a call in an attachment are styled like the attachment)
+pub fn schedule_argument(parser: &mut InnerParser, expr: &LinkedNode) {
+    schedule(
+        parser,
+        expr,
+        parser.uuid,
+        parser.added_text_decoration,
+        (0, 0),
+    );
+}
 
-    // If there is just a text, try to apply a text func like blackbold, caligraphy...
-    if args.children().len() == 3
-        && children[0].kind() == SyntaxKind::LeftParen
-        && (children[1].kind() == SyntaxKind::Text || children[1].kind() == SyntaxKind::Str)
-        && children[2].kind() == SyntaxKind::RightParen
-        && parser.options.rendering_mode > 1
-    {
-        let text = &children[1];
-        let text_content = match text.kind() {
-            SyntaxKind::Text => text.cast::<Text>().unwrap().get().to_string(),
-            SyntaxKind::Str => text.cast::<Str>().unwrap().get().to_string(),
-            _ => "".to_string(),
-        };
-        match func.callee() {
-            Expr::MathIdent(ident) => {
-                if let Some((map, decoration)) = match ident.as_str() {
-                    "cal" => Some((CAL_LETTERS, "font-family: \"NewComputerModernMath\";")),
-                    "frak" => Some((FRAK_LETTERS, "font-family: \"NewComputerModernMath\";")),
-                    "bb" => Some((BLACKBOLD_LETTERS, "")),
-                    _ => None,
-                } {
-                    let mut symbol = String::new();
-                    for letter in text_content.chars() {
-                        if let Some(c) = map.get(&letter) {
-                            symbol.push(*c);
-                        } else {
-                            symbol.push(letter);
-                        }
-                    }
-                    parser.insert_result(
-                        text.range(),
-                        format!("{}-{}", parser.uuid, symbol),
-                        symbol,
-                        Color::Number,
-                        format!("{}{}", parser.added_text_decoration, decoration),
-                        (
-                            ident.as_str().len() + 1 + parser.offset.0,
-                            1 + parser.offset.1,
-                        ),
-                    );
-                    return;
-                }
-            }
-            _ => {}
+/// Traverse the AST with a DFS and apply style, with an explicit stack so that deeply nested documents don't overflow it \
+/// Blocks don't visit the nodes they style themselves, they schedule them and these nodes are pushed on the same stack \
+/// once the block returns, one level deeper than it \
+/// Nodes deeper than the maximum depth of the options are skipped and the result marked as truncated, \
+/// the traversal stops once the cancellation token of the options is cancelled
+pub fn ast_dfs(
+    parser: &mut InnerParser,
+    expr: &LinkedNode,
+    uuid: &str,
+    added_text_decoration: &str,
+    offset: (usize, usize),
+) {
+    let depth = parser.state.depth;
+    let mut frames = vec![Frame::capture(parser.state, uuid, added_text_decoration)];
+    // Frame whose style the parser currently has, None once a block changed it
+    let mut current = Some(0);
+    let mut stack = vec![Task::Visit(expr.clone(), depth + 1, offset, 0)];
+    while let Some(task) = stack.pop() {
+        if parser.state.cancelled || parser.options.is_cancelled() {
+            parser.state.cancelled = true;
+            break;
         }
-    }
-    if parser.options.rendering_mode > 2 {
-        if let Some(content) = match func.callee() {
-            Expr::MathIdent(ident) => Some(ident.to_string()),
-            Expr::FieldAccess(access) => field_access_recursive(access),
-            _ => None,
-        } {
-            if let Some((symbol, decoration)) = match content.as_str() {
-                "arrow" => Some((
-                    '→',
-                    "font-family: \"NewComputerModernMath\"; transform: translate(-0.1em, -0.9em); font-size: 0.8em; display: inline-block; position: absolute;",
-                )),
-                "dot" => Some((
-                    '⋅',
-                    "font-family: \"Fira Math\";
-                    transform: translate(0.15em, -0.55em);
-                    transform: translate(0.15em, -0.52em); display: inline-block; position: absolute;",
-                )),
-                "dot.double" | "diaer" => Some(('¨', "font-family: JuliaMono; transform: translate(0, -0.25em); display: inline-block; position: absolute;")),
-                "dot.triple" => Some(('\u{20DB}', "font-family: JuliaMono; font-size: 1.4em; transform: translate(-0.1em); display: inline-block;")),
-                "dot.quad" => Some(('\u{20DC}', "font-family: JuliaMono; font-size: 1.4em; transform: translate(-0.1em); display: inline-block;")),
-                "hat" => Some((
-                    '^',
-                    "font-family: Fira math; transform: translate(0.03em, -0.3em); font-size: 0.9em; display: inline-block; position: absolute;",
-                )),
-                "tilde" => Some((
-                    '~',
-                    "font-family: JuliaMono; transform: translate(0.05em, -0.7em); font-size: 0.9em; display: inline-block; position: absolute;",
-                )),
-                "overline" => Some(('\u{0305}', "font-family: JuliaMono; transform: translate(0em, -0.2em); display: inline-block;")),
-                _ => None,
-            } {
-                if args.children().len() == 3
-                    && children[0].kind() == SyntaxKind::LeftParen
-                    && (children[1].kind() == SyntaxKind::MathIdent || children[1].kind() == SyntaxKind::Text || (children[1].kind() == SyntaxKind::MathAttach && children[1].children().len() == 3))
-                    && children[2].kind() == SyntaxKind::RightParen
-                {
-                    parser.insert_result(callee.range(), format!("{}-func-{}", parser.uuid, symbol), symbol.to_string(), Color::Number, format!("{}", decoration), (0, 1));
-                    parser.insert_void(children[2].range(), (0, 0));
-                    propagate_style = false;
+        match task {
+            Task::Visit(node, node_depth, offset, frame) => {
+                if node_depth > parser.options.max_depth {
+                    parser.state.truncated = true;
+                    continue;
                 }
-            } else if let Some(symbol) = match content.as_str() {
-                "abs" => Some('|'),
-                "norm" => Some('‖'),
-                _ => None,
-            } {
-                parser.insert_void(callee.range(), (parser.offset.0, 0));
-                parser.insert_result(
-                    children[0].range(),
-                    format!("{}func-{}", parser.uuid, symbol),
-                    symbol.to_string(),
-                    Color::Operator,
-                    format!("{}", parser.added_text_decoration),
-                    (0, 0),
-                );
-                parser.insert_result(
-                    children.last().unwrap().range(),
-                    format!("{}func-{}", parser.uuid, symbol),
-                    symbol.to_string(),
-                    Color::Operator,
-                    format!("{}", parser.added_text_decoration),
-                    (0, parser.offset.1),
-                );
-            } else if content.as_str() == "sqrt" && args.children().len() == 3 && children[0].kind() == SyntaxKind::LeftParen && children[2].kind() == SyntaxKind::RightParen {
-                let mut root_size = None;
-                if children[1].kind() == SyntaxKind::MathIdent || children[1].kind() == SyntaxKind::Text {
-                    root_size = Some(1.2);
-                } else if children[1].kind() == SyntaxKind::MathAttach
-                    && children[1].children().len() == 3
-                    && (children[1].children().nth(2).unwrap().kind() == SyntaxKind::MathIdent || children[1].children().nth(2).unwrap().kind() == SyntaxKind::Text)
-                {
-                    root_size = Some(1.8);
+                if current != Some(frame) {
+                    frames[frame].restore(parser.state);
+                    current = Some(frame);
                 }
-                if root_size.is_some() {
-                    parser.insert_result(
-                        children[0].range(),
-                        format!("{}func-{}-size-{}", parser.uuid, '\u{0305}', root_size.unwrap()),
-                        '\u{0305}'.to_string(),
-                        Color::Operator,
-                        format!(
-                            "font-family: JuliaMono; transform: scaleX({:.1}) translate(-0.01em, -0.25em); display: inline-block;",
-                            root_size.unwrap()
-                        ),
-                        (0, 0),
-                    );
-                    parser.insert_result(
-                        callee.range(),
-                        format!("{}func-{}", parser.uuid, '√'),
-                        '√'.to_string(),
-                        Color::Operator,
-                        format!("font-family: JuliaMono; display: inline-block; transform: translate(0.1em, -0.1em);"),
-                        (0, 0),
+                parser.state.depth = node_depth;
+                // Create the new parser
+                let Frame {
+                    uuid,
+                    added_text_decoration,
+                    ..
+                } = &frames[frame];
+                let mut inner =
+                    InnerParser::from(parser, &node, uuid, added_text_decoration, offset);
+                lint(&mut inner);
+                if visit(&mut inner) {
+                    // The block may have changed the style, and scheduled the nodes it styles, the first one being visited first
+                    current = None;
+                    let scheduled = std::mem::take(&mut parser.state.scheduled);
+                    for visit in scheduled.into_iter().rev() {
+                        let Some(child) = node.find(visit.span) else {
+                            continue;
+                        };
+                        frames.push(visit.frame);
+                        stack.push(Task::Visit(
+                            child,
+                            node_depth + 1,
+                            visit.offset,
+                            frames.len() - 1,
+                        ));
+                    }
+                } else {
+                    // Blocks, closures and loops open a scope for the names bound by the user
+                    let scope = inner.state.bindings.open(&node);
+                    stack.push(Task::Leave(node.clone(), scope));
+                    // Propagate the function, the first child being visited first
+                    let children: Vec<LinkedNode> = node.children().collect();
+                    stack.extend(
+                        children
+                            .into_iter()
+                            .rev()
+                            .map(|child| Task::Visit(child, node_depth + 1, (0, 0), frame)),
                     );
-                    parser.insert_void(children[2].range(), (0, 0));
-                    propagate_style = false;
                 }
-            } else {
-                ast_dfs(parser, &callee, parser.uuid, parser.added_text_decoration, parser.offset);
-                propagate_style = false;
+            }
+            Task::Leave(node, scope) => {
+                parser.state.bindings.close(scope);
+                // Let bindings and imports bind names for the rest of the scope
+                parser.state.bindings.bind(&node);
             }
         }
+    }
+    frames[0].restore(parser.state);
+    parser.state.depth = depth;
+}
+
+/// Block parsing a kind of expression and applying its style
+type Block = fn(&mut InnerParser);
+
+/// Blocks of the expressions with a style, the children of the other expressions are visited
+const BLOCKS: [(SyntaxKind, Block); 12] = [
+    // Math identifier, check if it is in the symbols list
+    (SyntaxKind::MathIdent, math_ident_block),
+    // Field Access, create a string containing all fields sparated with a dot (alpha.alt), and check if it is in symbols list
+    (SyntaxKind::FieldAccess, field_access_block),
+    // Replace linebreak with an arrow
+    (SyntaxKind::Linebreak, linebreak_block),
+    // Alignment point of a multi-line equation
+    (SyntaxKind::MathAlignPoint, align_point_block),
+    // Math attachment, power, subscript, superscript
+    (SyntaxKind::MathAttach, math_attach_block),
+    // Math block, continue over children and check current state to apply style
+    (SyntaxKind::Math, math_block),
+    // Typst shorthands
+    (SyntaxKind::Shorthand, shorthand_block),
+    // Typst text block, some symbols are here instead of shorthand
+    (SyntaxKind::Text, text_block),
+    // Escaped character (`\$`, `\u{1F600}`)
+    (SyntaxKind::Escape, escape_block),
+    // Typst string block (between quotes)
+    (SyntaxKind::Str, str_block),
+    // Typst func, if it's a common func, apply style, else continue over args and callee
+    (SyntaxKind::FuncCall, func_call_block),
+    // Equations outside the scope of the options are skipped, the others have the style of their show rules
+    (SyntaxKind::Equation, equation_block),
+];
+
+/// Match the current expression with its block in the dispatch table and apply its style, \
+/// returns false if we need to continue over children
+fn visit(parser: &mut InnerParser) -> bool {
+    let kind = parser.expr.kind();
+    if parser.expr.prev_sibling_kind() == Some(SyntaxKind::Hash)
+        && parser.expr.parent_kind() == Some(SyntaxKind::Math)
+    {
+        // Code expression embedded in math, like `#sym.alpha` or `#calc.pow(2, 3)`
+        embedded_code_block(parser);
+        true
+    } else if let Some((_, block)) = BLOCKS.iter().find(|(block_kind, _)| *block_kind == kind) {
+        block(parser);
+        true
     } else {
-        propagate_style = false;
+        false
     }
-    ast_dfs(
-        parser,
-        &args,
-        if propagate_style { parser.uuid } else { "" },
-        if propagate_style {
-            parser.added_text_decoration
-        } else {
-            ""
-        },
-        (0, 0),
-    );
+}
+
+/// Check if a node is rendered with the scope of the options, from the kind of the nearest equation containing it, \
+/// equations starting or ending with a space being blocks (`$ x $`)
+pub fn is_in_scope(node: &LinkedNode, options: &Options) -> bool {
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if let Some(equation) = node.cast::<Equation>() {
+            return match options.scope {
+                Scope::All => true,
+                Scope::Block => equation.block(),
+                Scope::Inline => !equation.block(),
+            };
+        }
+        current = node.parent();
+    }
+    true
+}
+
+/// Parse an equation in the scope of the options, with the style set by the show rules before it
+fn equation_block(parser: &mut InnerParser) {
+    if !is_in_scope(parser.expr, parser.options) {
+        return;
+    }
+    let style = parser.state.styles.style(parser.expr);
+    let previous = std::mem::replace(&mut parser.state.math_style, style);
+    for child in parser.expr.children() {
+        schedule(
+            parser,
+            &child,
+            parser.uuid,
+            parser.added_text_decoration,
+            (0, 0),
+        );
+    }
+    parser.state.math_style = previous;
 }
@@ -0,0 +1,233 @@
+//! Rendering of the functions of the physica package (`@preview/physica`)
+
+use super::{
+    imports::Package,
+    parser::schedule_argument,
+    rules::{Call, DecorationRule, Rendering},
+    utils::{arguments, superscript, InnerParser},
+};
+use crate::utils::symbols::{Category, Color, PHYSICA_OPERATORS};
+use typst_syntax::{ast::Expr, LinkedNode};
+
+/// Functions from packages like physica
+pub struct PhysicaFunctions;
+
+impl DecorationRule for PhysicaFunctions {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        match call.func.callee() {
+            Expr::MathIdent(ident) if parser.options.features.packages => {
+                physica_block(parser, ident.as_str(), &call.callee, &call.args).into()
+            }
+            _ => Rendering::Skipped,
+        }
+    }
+}
+
+/// Try to render a physica function call, return false if the function isn't handled or physica isn't imported
+pub fn physica_block(
+    parser: &mut InnerParser,
+    name: &str,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+) -> bool {
+    let Some(name) =
+        parser
+            .state
+            .imports
+            .resolve(Package::Physica, name, parser.expr.range().start)
+    else {
+        return false;
+    };
+    match name.as_str() {
+        "dv" => derivative_block(parser, callee, args, 'd'),
+        "pdv" => derivative_block(parser, callee, args, '∂'),
+        "bra" => delimited_block(parser, callee, args, ('⟨', '|', '|'), &[1]),
+        "ket" => delimited_block(parser, callee, args, ('|', '|', '⟩'), &[1]),
+        "braket" => delimited_block(parser, callee, args, ('⟨', '|', '⟩'), &[2, 3]),
+        "expval" => delimited_block(parser, callee, args, ('⟨', '|', '⟩'), &[1]),
+        name => match PHYSICA_OPERATORS.get(name) {
+            Some(operator) => operator_call_block(parser, callee, args, operator),
+            None => false,
+        },
+    }
+}
+
+/// Render a physica vector calculus operator used as an identifier (`grad f` -> `∇f`) \
+/// Once physica is imported, they replace standard symbols with the same name (`div`)
+pub fn physica_ident_block(parser: &mut InnerParser, name: &str) -> bool {
+    let Some(original) =
+        parser
+            .state
+            .imports
+            .resolve(Package::Physica, name, parser.expr.range().start)
+    else {
+        return false;
+    };
+    match PHYSICA_OPERATORS.get(original.as_str()) {
+        Some(operator) => {
+            parser.insert_result(
+                parser.expr.range(),
+                &[parser.uuid, name],
+                operator,
+                Color::Operator,
+                format!("font-family: @math; {}", parser.added_text_decoration),
+                parser.offset,
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Get the superscript version of a derivative order, like `2` or `n`
+fn order_superscript(order: &str) -> Option<String> {
+    if order.is_empty() {
+        return None;
+    }
+    order.chars().map(superscript).collect()
+}
+
+/// Render `dv(f, x)` and `pdv(f, x, n)` as a compact fraction `df/dx`, with the order as a superscript \
+/// The callee and the opening paren become the numerator differential, the first comma the fraction bar
+fn derivative_block(
+    parser: &mut InnerParser,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+    symbol: char,
+) -> bool {
+    let children: Vec<LinkedNode> = args.children().collect();
+    let Some(items) = arguments(&children) else {
+        return false;
+    };
+    let order = match items.as_slice() {
+        [_, _] => String::new(),
+        [_, _, order] => match order_superscript(children[*order].text()) {
+            Some(order) => order,
+            None => return false,
+        },
+        _ => return false,
+    };
+    let (function, variable) = (items[0], items[1]);
+
+    // Numerator differential, hiding the opening paren
+    let differential = format!("{}{}", symbol, order);
+    parser.insert_result_category(
+        callee.range(),
+        &[parser.uuid, "func-", &differential],
+        &differential,
+        Category::Differential,
+        parser.added_text_decoration,
+        (parser.offset.0, 1),
+    );
+    schedule_argument(parser, &children[function]);
+    // Fraction bar and denominator differential, over the comma and the following spaces
+    let bar = children[function].range().end..children[variable].range().start;
+    let denominator = format!("/{}", symbol);
+    parser.insert_result_category(
+        bar,
+        &[parser.uuid, "func-", &denominator],
+        &denominator,
+        Category::Differential,
+        parser.added_text_decoration,
+        (0, 0),
+    );
+    schedule_argument(parser, &children[variable]);
+    // The order is displayed after the variable, and the closing paren is hidden
+    let end = children[variable].range().end..args.range().end;
+    if order.is_empty() {
+        parser.insert_void(end, (0, parser.offset.1));
+    } else {
+        parser.insert_result_category(
+            end,
+            &[parser.uuid, "func-", &order],
+            &order,
+            Category::Differential,
+            parser.added_text_decoration,
+            (0, parser.offset.1),
+        );
+    }
+    true
+}
+
+/// Render bra-ket notations by replacing the callee and the opening paren, the commas and the closing paren with delimiters \
+/// (`braket(a, b)` -> `⟨a|b⟩`), only if the number of arguments is one of `counts`
+fn delimited_block(
+    parser: &mut InnerParser,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+    (open, separator, close): (char, char, char),
+    counts: &[usize],
+) -> bool {
+    let children: Vec<LinkedNode> = args.children().collect();
+    let Some(items) = arguments(&children) else {
+        return false;
+    };
+    if !counts.contains(&items.len()) {
+        return false;
+    }
+    let decoration = format!("font-family: @math; {}", parser.added_text_decoration);
+    let mut buffer = [0; 4];
+    let open: &str = open.encode_utf8(&mut buffer);
+    parser.insert_result(
+        callee.range(),
+        &[parser.uuid, "func-", open],
+        open,
+        Color::Operator,
+        decoration.clone(),
+        (parser.offset.0, 1),
+    );
+    for (index, item) in items.iter().enumerate() {
+        schedule_argument(parser, &children[*item]);
+        // Separator over the comma and the following spaces, or closing delimiter over the end
+        let (range, symbol, offset) = match items.get(index + 1) {
+            Some(next) => (
+                children[*item].range().end..children[*next].range().start,
+                separator,
+                (0, 0),
+            ),
+            None => (
+                children[*item].range().end..args.range().end,
+                close,
+                (0, parser.offset.1),
+            ),
+        };
+        let symbol: &str = symbol.encode_utf8(&mut buffer);
+        parser.insert_result(
+            range,
+            &[parser.uuid, "func-", symbol],
+            symbol,
+            Color::Operator,
+            decoration.clone(),
+            offset,
+        );
+    }
+    true
+}
+
+/// Render a vector calculus operator called like a function (`curl(F)` -> `∇×F`), hiding the parens
+fn operator_call_block(
+    parser: &mut InnerParser,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+    operator: &str,
+) -> bool {
+    let children: Vec<LinkedNode> = args.children().collect();
+    let item = match arguments(&children).as_deref() {
+        Some([item]) => *item,
+        _ => return false,
+    };
+    parser.insert_result(
+        callee.range(),
+        &[parser.uuid, "func-", operator],
+        operator,
+        Color::Operator,
+        format!("font-family: @math; {}", parser.added_text_decoration),
+        (parser.offset.0, 1),
+    );
+    schedule_argument(parser, &children[item]);
+    parser.insert_void(
+        children[item].range().end..args.range().end,
+        (0, parser.offset.1),
+    );
+    true
+}
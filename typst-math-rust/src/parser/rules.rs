@@ -0,0 +1,334 @@
+//! Registry of the rules decorating function calls, tried in their registration order until one of them renders the call
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use super::attachments::Attach;
+use super::functions::{
+    content_arguments, function_path, math_function_name, Accents, BoundFunctions,
+    ContentArguments, Delimiters, FunctionNames, LetterStyles, Operator, Roots, TextFill,
+    UserRules,
+};
+use super::mitex::Mitex;
+use super::physica::PhysicaFunctions;
+use super::units::Units;
+use super::utils::{cast_expr, find_child, is_comment, significant_children, InnerParser};
+use typst_syntax::ast::{AstNode, Expr, FuncCall};
+use typst_syntax::LinkedNode;
+
+/// A function call being decorated, with the parts of it read by the rules
+pub struct Call<'a> {
+    pub func: FuncCall<'a>,
+    pub callee: LinkedNode<'a>,
+    pub args: LinkedNode<'a>,
+    /// Arguments without comments, the content blocks given to math functions replaced by their content
+    pub children: Vec<LinkedNode<'a>>,
+    /// Ranges of the `#[$` and `$]` around the content blocks given to math functions
+    pub delimiters: Vec<Range<usize>>,
+    /// Comments in the arguments are skipped to recognize their shape, and stay visible
+    pub commented: bool,
+    /// Name or field path of the function, without the math module (`vec`, `math.arrow` -> `arrow`)
+    pub name: Option<String>,
+    /// Name of the function if the callee is an identifier
+    pub ident: Option<String>,
+    /// Math functions, also called from code with the math module (`#math.bb("R")`)
+    pub math_function: Option<String>,
+    /// Calls in markup aren't styled if symbols outside math aren't rendered
+    pub hash_offset: Option<usize>,
+}
+
+impl<'a> Call<'a> {
+    /// Read the function call parsed by the parser, if its callee and arguments can be found
+    pub fn new(parser: &InnerParser<'a>) -> Option<Call<'a>> {
+        let func = cast_expr::<FuncCall>(parser.expr)?;
+        let callee = find_child(parser.expr, func.callee().span())?;
+        let args = find_child(parser.expr, func.args().span())?;
+        let math_function = math_function_name(&func);
+        let hash_offset = parser.hash_offset();
+        let mut children = significant_children(&args);
+        let mut delimiters = vec![];
+        if math_function.is_some() && hash_offset.is_some() {
+            (children, delimiters) = content_arguments(children);
+        }
+        Some(Call {
+            commented: args.children().any(|child| is_comment(&child)),
+            name: function_path(&func),
+            ident: match func.callee() {
+                Expr::MathIdent(ident) => Some(ident.get().to_string()),
+                Expr::Ident(ident) => Some(ident.get().to_string()),
+                _ => None,
+            },
+            math_function,
+            hash_offset,
+            func,
+            callee,
+            args,
+            children,
+            delimiters,
+        })
+    }
+
+    /// Name used to find the rules registered for this call, the math function or else the name or field path
+    pub fn dispatch_name(&self) -> Option<&str> {
+        self.math_function.as_deref().or(self.name.as_deref())
+    }
+}
+
+/// Result of a rule applied to a function call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rendering {
+    /// The rule doesn't handle the call, the next rules are tried
+    Skipped,
+    /// The call is fully rendered
+    Rendered,
+    /// The callee is rendered, the arguments are still parsed, with the style of the call if `propagate_style`
+    Arguments { propagate_style: bool },
+}
+
+impl From<bool> for Rendering {
+    fn from(rendered: bool) -> Self {
+        if rendered {
+            Rendering::Rendered
+        } else {
+            Rendering::Skipped
+        }
+    }
+}
+
+/// A way to decorate function calls
+pub trait DecorationRule: Send + Sync {
+    /// Names of the functions handled by the rule, or `None` if it is tried on every call
+    fn names(&self) -> Option<&'static [&'static str]> {
+        None
+    }
+    /// Try to render a call, return `Rendering::Skipped` to let the next rules handle it
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering;
+}
+
+/// Rules tried in their registration order, the ones with names only on the calls of these functions
+#[derive(Default)]
+pub struct Registry {
+    rules: Vec<Box<dyn DecorationRule>>,
+    by_name: HashMap<&'static str, Vec<usize>>,
+    generic: Vec<usize>,
+}
+
+impl Registry {
+    /// Add a rule, tried after the ones already registered
+    pub fn register(&mut self, rule: impl DecorationRule + 'static) -> &mut Self {
+        let index = self.rules.len();
+        match rule.names() {
+            Some(names) => {
+                for name in names {
+                    self.by_name.entry(name).or_default().push(index);
+                }
+            }
+            None => self.generic.push(index),
+        }
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Indices of the rules to try on a call, in their registration order
+    fn candidates(&self, call: &Call) -> Vec<usize> {
+        let mut indices = self.generic.clone();
+        if let Some(named) = call.dispatch_name().and_then(|name| self.by_name.get(name)) {
+            indices.extend(named);
+            indices.sort_unstable();
+        }
+        indices
+    }
+
+    /// Apply the first rule rendering the call, `Rendering::Skipped` if none of them does
+    pub fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        for index in self.candidates(call) {
+            let rendering = self.rules[index].render(parser, call);
+            if rendering != Rendering::Skipped {
+                return rendering;
+            }
+        }
+        Rendering::Skipped
+    }
+}
+
+/// Rules applied to the function calls of documents
+pub fn rules() -> &'static Registry {
+    static RULES: OnceLock<Registry> = OnceLock::new();
+    RULES.get_or_init(|| {
+        let mut registry = Registry::default();
+        registry
+            .register(UserRules)
+            .register(BoundFunctions)
+            .register(ContentArguments)
+            .register(Operator)
+            .register(TextFill)
+            .register(LetterStyles)
+            .register(Attach)
+            .register(PhysicaFunctions)
+            .register(Units)
+            .register(Mitex)
+            .register(Accents)
+            .register(Delimiters)
+            .register(Roots)
+            .register(FunctionNames);
+        registry
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use typst_syntax::{LinkedNode, Source, SyntaxKind};
+
+    use super::{Call, DecorationRule, Registry, Rendering};
+    use crate::interface::Options;
+    use crate::parser::functions::Delimiters;
+    use crate::parser::parser::State;
+    use crate::parser::utils::InnerParser;
+
+    /// Rule recording that it was tried, with a fixed result
+    struct Probe {
+        label: &'static str,
+        names: Option<&'static [&'static str]>,
+        rendering: Rendering,
+        tried: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl DecorationRule for Probe {
+        fn names(&self) -> Option<&'static [&'static str]> {
+            self.names
+        }
+        fn render(&self, _: &mut InnerParser, _: &Call) -> Rendering {
+            self.tried.lock().unwrap().push(self.label);
+            self.rendering
+        }
+    }
+
+    fn find_call<'a>(node: &LinkedNode<'a>) -> Option<LinkedNode<'a>> {
+        if node.kind() == SyntaxKind::FuncCall {
+            return Some(node.clone());
+        }
+        node.children().find_map(|child| find_call(&child))
+    }
+
+    /// Render the first call of a document with a registry, returning the symbols decorated sorted by position
+    fn render_call(content: &str, registry: &Registry) -> (Rendering, Vec<String>) {
+        let source = Source::detached(content);
+        let root = LinkedNode::new(source.root());
+        let expr = find_call(&root).unwrap();
+        let (mut result, mut state, options) =
+            (HashMap::new(), State::default(), Options::default());
+        let mut parser = InnerParser::new(&source, &expr, &mut result, &mut state, &options);
+        let call = Call::new(&parser).unwrap();
+        let rendering = registry.render(&mut parser, &call);
+        let mut symbols: Vec<_> = result
+            .into_values()
+            .flat_map(|decoration| {
+                let symbol = decoration.symbol;
+                decoration
+                    .positions
+                    .into_iter()
+                    .map(move |position| (position.start, symbol.clone()))
+            })
+            .collect();
+        symbols.sort();
+        (
+            rendering,
+            symbols.into_iter().map(|(_, symbol)| symbol).collect(),
+        )
+    }
+
+    /// Render the first call of a document with rules `(label, names, rendering)`, returning the labels of the rules tried
+    fn render(
+        content: &str,
+        probes: &[(&'static str, Option<&'static [&'static str]>, Rendering)],
+    ) -> (Rendering, Vec<&'static str>) {
+        let tried = Arc::new(Mutex::new(vec![]));
+        let mut registry = Registry::default();
+        for &(label, names, rendering) in probes {
+            registry.register(Probe {
+                label,
+                names,
+                rendering,
+                tried: tried.clone(),
+            });
+        }
+        let (rendering, _) = render_call(content, &registry);
+        let tried = tried.lock().unwrap().clone();
+        (rendering, tried)
+    }
+
+    #[test]
+    fn test_registry() {
+        let probes: [(&str, Option<&'static [&'static str]>, Rendering); 3] = [
+            ("first", None, Rendering::Skipped),
+            ("abs", Some(&["abs", "norm"]), Rendering::Skipped),
+            ("last", None, Rendering::Skipped),
+        ];
+        // Rules are tried in their registration order, the named ones only on calls of their functions
+        assert_eq!(
+            render("$abs(x)$", &probes),
+            (Rendering::Skipped, vec!["first", "abs", "last"])
+        );
+        assert_eq!(
+            render("$foo(x)$", &probes),
+            (Rendering::Skipped, vec!["first", "last"])
+        );
+        // The first rule rendering the call stops the dispatch
+        let rendered = Rendering::Arguments {
+            propagate_style: true,
+        };
+        assert_eq!(
+            render(
+                "$norm(x)$",
+                &[
+                    ("first", None, Rendering::Skipped),
+                    ("norm", Some(&["norm"]), rendered),
+                    ("last", None, Rendering::Rendered),
+                ]
+            ),
+            (rendered, vec!["first", "norm"])
+        );
+        assert_eq!(
+            render(
+                "$foo(x)$",
+                &[
+                    ("norm", Some(&["norm"]), Rendering::Rendered),
+                    ("last", None, Rendering::Rendered),
+                ]
+            ),
+            (Rendering::Rendered, vec!["last"])
+        );
+    }
+
+    #[test]
+    fn test_delimiters() {
+        let mut registry = Registry::default();
+        registry.register(Delimiters);
+        let arguments = |propagate_style| Rendering::Arguments { propagate_style };
+        assert_eq!(
+            render_call("$abs(x)$", &registry),
+            (
+                arguments(true),
+                vec!["".to_string(), "|".to_string(), "|".to_string()]
+            )
+        );
+        // Calls from code by an identifier and other functions are left to the next rules
+        assert_eq!(
+            render_call("#abs(x)", &registry),
+            (Rendering::Skipped, vec![])
+        );
+        assert_eq!(
+            render_call("$sqrt(x)$", &registry),
+            (Rendering::Skipped, vec![])
+        );
+        // Content blocks given from code aren't delimited
+        assert_eq!(
+            render_call("#math.abs[$x$]", &registry),
+            (arguments(false), vec![])
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Block of the shorthands (`->`, `!=`, `...`)
+
+use super::utils::{cast_expr, InnerParser};
+use crate::utils::symbols::Color;
+use typst_syntax::ast::Shorthand;
+
+/// Replace a shorthand with a specific style
+pub fn shorthand_block(parser: &mut InnerParser) {
+    let Some(short) = cast_expr::<Shorthand>(parser.expr) else {
+        return;
+    };
+    let (color, decoration, content) = match short.get() {
+        // Apply specific style for each shorthand
+        '\u{2212}' => (Color::Operator, "", '-'),
+        '∗' => (Color::Operator, "", '*'),
+        '…' => (Color::Operator, "font-family: @math;", '…'),
+        '⟦' | '⟧' | '‖' => (Color::Set, "font-family: @math;", short.get()),
+        // Relations
+        '≠' | '≔' | '⩴' | '≕' | '≪' | '⋘' | '≫' | '⋙' | '≤' | '≥' => {
+            (Color::Comparison, "font-family: @math;", short.get())
+        }
+        // Markup shorthands, dashes are kept in the text font
+        '\u{2013}' | '\u{2014}' => (Color::Operator, "", short.get()),
+        '\u{00A0}' => (Color::Operator, "", ' '),
+        '\u{00AD}' => (Color::Operator, "", '-'),
+        // Arrows
+        c => (
+            Color::Comparison,
+            "font-family: @math; font-weight: bold;",
+            c,
+        ),
+    };
+    let mut buffer = [0; 4];
+    let content: &str = content.encode_utf8(&mut buffer);
+    parser.insert_result(
+        parser.expr.range(),
+        &[parser.uuid, "-", content],
+        content,
+        color,
+        format!("{}{}", parser.added_text_decoration, decoration),
+        parser.offset,
+    );
+}
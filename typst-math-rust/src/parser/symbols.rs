@@ -0,0 +1,248 @@
+//! Blocks of the symbols, math identifiers and field accesses (`alpha`, `sym.arrow.r`), and of the markers of the equations
+
+use super::functions::func_call_block;
+use super::macros::macro_ident_block;
+use super::parser::schedule;
+use super::physica::physica_ident_block;
+use super::utils::{
+    byte_range_to_utf16, cast_expr, find_symbol, get_symbol, utf16_position, InnerParser,
+};
+use crate::interface::{Options, Suggestion};
+use crate::utils::search::nearest_names;
+use crate::utils::symbols::{Category, Color, OPERATORS, SPACINGS, SYMBOLS};
+use typst_syntax::ast::{Expr, FieldAccess, MathIdent};
+use typst_syntax::{LinkedNode, SyntaxKind};
+
+/// Recursive function to convert a field access into a string (`[alpha, ., alt]` -> `'alpha.alt'`)
+pub fn field_access_recursive(access: FieldAccess) -> Option<String> {
+    // Check if the target is a math identifier or another field access
+    match access.target() {
+        Expr::FieldAccess(subaccess) => {
+            if let Some(start) = field_access_recursive(subaccess) {
+                return Some(format!("{}.{}", start, access.field().as_str()));
+            }
+        }
+        Expr::MathIdent(ident) => {
+            return Some(format!("{}.{}", ident.as_str(), access.field().as_str()));
+        }
+        Expr::Ident(ident) => {
+            return Some(format!("{}.{}", ident.as_str(), access.field().as_str()));
+        }
+        _ => {}
+    }
+    None
+}
+
+/// Insert an upright operator, like `sin` or `op("argmax")`
+pub fn insert_operator(
+    parser: &mut InnerParser,
+    range: std::ops::Range<usize>,
+    name: String,
+    offset: (usize, usize),
+) {
+    parser.insert_result(
+        range,
+        &[parser.uuid, "op-", &name],
+        &name,
+        Color::Keyword,
+        format!(
+            "{}font-family: @math; font-style: normal;",
+            parser.added_text_decoration
+        ),
+        offset,
+    );
+}
+
+/// Parse a math ident block, symply add a symbol if it is in the symbols list
+pub fn math_ident_block(parser: &mut InnerParser) {
+    let Some(ident) = cast_expr::<MathIdent>(parser.expr) else {
+        return;
+    };
+    // Names bound by the user aren't symbols, except aliases like `#let RR = $bb(R)$`
+    if parser.state.bindings.contains(ident.as_str()) {
+        if parser.options.features.aliases {
+            macro_ident_block(parser, ident.as_str());
+        }
+        return;
+    }
+    // Built-in operators like `sin` or `lim` are rendered upright
+    if parser.options.features.operators
+        && OPERATORS.contains_key(ident.as_str())
+        && get_symbol(ident.to_string(), parser.options).is_none()
+    {
+        insert_operator(
+            parser,
+            parser.expr.range(),
+            ident.to_string(),
+            parser.offset,
+        );
+        return;
+    }
+    // Vector calculus operators of physica
+    if parser.options.features.packages && physica_ident_block(parser, ident.as_str()) {
+        return;
+    }
+    // Math spacings are rendered as spaces of the same width, unless redefined by the user
+    if let Some(space) = SPACINGS
+        .get(ident.as_str())
+        .filter(|_| get_symbol(ident.to_string(), parser.options).is_none())
+    {
+        parser.insert_result_category(
+            parser.expr.range(),
+            &[parser.uuid, ident.as_str()],
+            space,
+            Category::Space,
+            parser.added_text_decoration,
+            parser.offset,
+        );
+        return;
+    }
+    if is_unknown_ident(parser, ident.as_str()) {
+        let candidates = nearest_names(ident.as_str(), known_names(parser.options), 3);
+        let range = byte_range_to_utf16(parser.source, &parser.expr.range());
+        if let (false, Some(range)) = (candidates.is_empty(), range) {
+            parser.state.suggestions.push(Suggestion {
+                name: ident.to_string(),
+                candidates,
+                position: utf16_position(parser.source, range),
+            });
+        }
+    }
+    parser.insert_result_symbol(
+        parser.expr.range(),
+        ident.to_string(),
+        &[parser.uuid, ident.as_str()],
+        parser.added_text_decoration,
+        parser.offset,
+        ("", ""),
+    );
+}
+
+/// Check if a math identifier isn't a symbol nor any other name typst knows, like the typo `alpa` \
+/// Callees and argument values aren't checked, and nothing is unknown after a wildcard import
+fn is_unknown_ident(parser: &InnerParser, name: &str) -> bool {
+    !matches!(
+        parser.expr.parent_kind(),
+        Some(SyntaxKind::FuncCall | SyntaxKind::Named)
+    ) && parser.state.bindings.is_complete()
+        && !parser.state.imports.binds(name, parser.expr.range().start)
+        && !OPERATORS.contains_key(name)
+        && !SPACINGS.contains_key(name)
+        && find_symbol(name.to_string(), parser.options).is_none()
+}
+
+/// List the names of the symbols, operators and spacings, variants excluded
+fn known_names(options: &Options) -> impl Iterator<Item = &str> {
+    SYMBOLS
+        .keys()
+        .map(|name| name.split('.').next().unwrap_or(name))
+        .chain(OPERATORS.keys().copied())
+        .chain(SPACINGS.keys().copied())
+        .chain(options.custom_symbols.keys().map(String::as_str))
+        .chain(options.symbol_table.keys().map(String::as_str))
+}
+
+/// Parse a field access block, create a string containing all fields sparated with a dot (alpha.alt), and check if it is in symbols list
+/// Also check if the symbol starts with `sym.` and remove it if needed
+pub fn field_access_block(parser: &mut InnerParser) {
+    let Some(access) = cast_expr::<FieldAccess>(parser.expr) else {
+        return;
+    };
+    if let Some(content) = field_access_recursive(access).filter(|content| {
+        // Fields of names bound by the user aren't symbols
+        !content
+            .split('.')
+            .next()
+            .is_some_and(|name| parser.state.bindings.contains(name))
+    }) {
+        // Add one to offset to remove the # of code expressions, like `#sym.alpha` in markup
+        let Some(hash_offset) = parser.hash_offset() else {
+            return;
+        };
+        parser.offset.0 += hash_offset;
+
+        // Symbols can be accessed from the sym and math modules
+        let content = content
+            .strip_prefix("sym.")
+            .or_else(|| content.strip_prefix("math."))
+            .unwrap_or(&content)
+            .to_string();
+        parser.insert_result_symbol(
+            parser.expr.range(),
+            content.clone(),
+            &[parser.uuid, &content],
+            parser.added_text_decoration,
+            parser.offset,
+            ("", ""),
+        );
+    }
+}
+
+/// Parse a code expression embedded in math with a hash \
+/// Only symbols and math functions are rendered (`#sym.alpha`, `#math.bb("R")`), other code like `#calc.pow(2, 3)` is skipped,
+/// except for the content nested inside (`#box[$x$]`)
+pub fn embedded_code_block(parser: &mut InnerParser) {
+    let callee = match parser.expr.cast::<Expr>() {
+        Some(Expr::FieldAccess(access)) => Some(access),
+        Some(Expr::FuncCall(func)) => match func.callee() {
+            Expr::FieldAccess(access) => Some(access),
+            _ => None,
+        },
+        _ => None,
+    };
+    let module = callee
+        .and_then(field_access_recursive)
+        .and_then(|content| Some(content.split_once('.')?.0.to_string()));
+    match (parser.expr.kind(), module.as_deref()) {
+        (SyntaxKind::FieldAccess, Some("sym" | "math" | "emoji")) => field_access_block(parser),
+        (SyntaxKind::FuncCall, Some("math")) => func_call_block(parser),
+        _ => nested_content_block(parser, parser.expr),
+    }
+}
+
+/// Continue over the content blocks and equations nested in code, skipping the code itself
+fn nested_content_block(parser: &mut InnerParser, expr: &LinkedNode) {
+    let mut stack: Vec<LinkedNode> = expr.children().rev().collect();
+    while let Some(child) = stack.pop() {
+        match child.kind() {
+            SyntaxKind::ContentBlock | SyntaxKind::Equation => {
+                schedule(parser, &child, "", "", (0, 0))
+            }
+            _ => stack.extend(child.children().rev()),
+        }
+    }
+}
+
+/// Simply replace a linebreak with an arrow
+pub fn linebreak_block(parser: &mut InnerParser) {
+    parser.insert_result(
+        parser.expr.range(),
+        &[parser.uuid, "linebreak"],
+        "⮰",
+        Color::Comparison,
+        format!(
+            "{}font-family: @math; font-weight: bold;",
+            parser.added_text_decoration
+        ),
+        parser.offset,
+    );
+}
+
+/// Replace an alignment point with a faint column marker if asked, it is kept as is otherwise \
+/// Alignment points in attachments don't align anything and aren't marked
+pub fn align_point_block(parser: &mut InnerParser) {
+    if !parser.options.render_align_points || parser.state.is_attachment {
+        return;
+    }
+    parser.insert_result(
+        parser.expr.range(),
+        &[parser.uuid, "align"],
+        "│",
+        Color::Operator,
+        format!(
+            "{}font-family: @mono; opacity: 0.4;",
+            parser.added_text_decoration
+        ),
+        parser.offset,
+    );
+}
@@ -0,0 +1,118 @@
+//! Blocks of the texts, escapes and strings
+
+use super::utils::{cast_expr, get_category_by_char, InnerParser};
+use crate::utils::symbols::Color;
+use typst_syntax::ast::{Escape, Str, Text};
+use typst_syntax::SyntaxKind;
+
+/// Replace a text block with a specific style \
+/// Some symbols are here instead of shorthand, the multi-character operators being shorthands already \
+/// Also, if we are in an attachment, apply a specific style
+pub fn text_block(parser: &mut InnerParser) {
+    let Some(text) = cast_expr::<Text>(parser.expr) else {
+        return;
+    };
+    if let Some((color, decoration, content)) = match text.get().as_str() {
+        "+" => Some((Color::Operator, "", "+")),
+        "=" | "<" | ">" => Some((Color::Comparison, "", text.get().as_str())),
+        "[" | "]" => Some((Color::Set, "", text.get().as_str())),
+        _ => None,
+    } {
+        parser.insert_result(
+            parser.expr.range(),
+            &[parser.uuid, "-", content],
+            content,
+            color,
+            format!("{}{}", parser.added_text_decoration, decoration),
+            parser.offset,
+        );
+        return;
+    }
+    // Unicode symbols typed directly are styled like their named version
+    let mut chars = text.get().chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if let Some(category) = get_category_by_char(c).filter(|_| !c.is_ascii()) {
+            let mut buffer = [0; 4];
+            let c: &str = c.encode_utf8(&mut buffer);
+            parser.insert_result_category(
+                parser.expr.range(),
+                &[parser.uuid, "-", c],
+                c,
+                category,
+                parser.added_text_decoration,
+                parser.offset,
+            );
+            return;
+        }
+    }
+    if parser.state.is_attachment {
+        parser.insert_result(
+            parser.expr.range(),
+            &[parser.uuid, "-text-", text.get()],
+            text.get(),
+            Color::Number,
+            parser.added_text_decoration.to_string(),
+            parser.offset,
+        );
+    }
+}
+
+/// Replace an escape sequence in math with the escaped character, hiding the backslash \
+/// Unicode escapes (`\u{1F600}`) are styled like the character typed directly
+pub fn escape_block(parser: &mut InnerParser) {
+    // Escapes in markup are kept as they are written
+    let in_math = std::iter::successors(parser.expr.parent(), |node| node.parent())
+        .find(|node| matches!(node.kind(), SyntaxKind::Math | SyntaxKind::Markup))
+        .is_some_and(|node| node.kind() == SyntaxKind::Math);
+    if !in_math {
+        return;
+    }
+    let Some(c) = cast_expr::<Escape>(parser.expr).map(|escape| escape.get()) else {
+        return;
+    };
+    let mut buffer = [0; 4];
+    let symbol: &str = c.encode_utf8(&mut buffer);
+    if let Some(category) = get_category_by_char(c).filter(|_| !c.is_ascii()) {
+        parser.insert_result_category(
+            parser.expr.range(),
+            &[parser.uuid, "-", symbol],
+            symbol,
+            category,
+            parser.added_text_decoration,
+            parser.offset,
+        );
+    } else {
+        let color = if parser.state.is_attachment {
+            Color::Number
+        } else {
+            Color::Letter
+        };
+        parser.insert_result(
+            parser.expr.range(),
+            &[parser.uuid, "-text-", symbol],
+            symbol,
+            color,
+            parser.added_text_decoration.to_string(),
+            parser.offset,
+        );
+    }
+}
+
+/// Same as text block, but for a string block (between quotes) \
+/// Apply a specific style if we are in an attachment
+pub fn str_block(parser: &mut InnerParser) {
+    let Some(text) = cast_expr::<Str>(parser.expr) else {
+        return;
+    };
+    if parser.state.is_attachment {
+        let text = text.get();
+        parser.insert_result(
+            parser.expr.range(),
+            &[parser.uuid, "-text-", &text],
+            &text,
+            Color::Number,
+            parser.added_text_decoration.to_string(),
+            parser.offset,
+        );
+    }
+}
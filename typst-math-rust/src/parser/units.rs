@@ -0,0 +1,138 @@
+//! Rendering of the unit functions of packages like unify or metro (`qty(3, "m/s")`, `unit("kg m^-2")`)
+
+use super::{
+    imports::Package,
+    parser::schedule_argument,
+    rules::{Call, DecorationRule, Rendering},
+    utils::{superscript, InnerParser},
+};
+use crate::utils::symbols::Color;
+use typst_syntax::{ast::Str, LinkedNode, SyntaxKind};
+
+/// Style of the rendered units
+const UNIT_STYLE: &str = "font-family: @math; font-style: normal;";
+
+/// Units, used in math and in markup
+pub struct Units;
+
+impl DecorationRule for Units {
+    fn render(&self, parser: &mut InnerParser, call: &Call) -> Rendering {
+        match &call.ident {
+            Some(name) if parser.options.features.packages => {
+                units_block(parser, name, &call.callee, &call.args).into()
+            }
+            _ => Rendering::Skipped,
+        }
+    }
+}
+
+/// Try to render a unit function call, return false if the function isn't handled or no unit package is imported
+pub fn units_block(
+    parser: &mut InnerParser,
+    name: &str,
+    callee: &LinkedNode,
+    args: &LinkedNode,
+) -> bool {
+    let Some(name) = [Package::Unify, Package::Metro].iter().find_map(|package| {
+        parser
+            .state
+            .imports
+            .resolve(*package, name, parser.expr.range().start)
+    }) else {
+        return false;
+    };
+    let children: Vec<LinkedNode> = args.children().collect();
+    if children.first().map(|c| c.kind()) != Some(SyntaxKind::LeftParen)
+        || children.last().map(|c| c.kind()) != Some(SyntaxKind::RightParen)
+    {
+        return false;
+    }
+    let items: Vec<&LinkedNode> = children[1..children.len() - 1]
+        .iter()
+        .filter(|c| !matches!(c.kind(), SyntaxKind::Comma | SyntaxKind::Space))
+        .collect();
+    let (value, unit) = match (name.as_str(), items.as_slice()) {
+        ("qty", [value, unit]) => (Some(*value), *unit),
+        ("unit", [unit]) => (None, *unit),
+        _ => return false,
+    };
+    let Some(unit_text) = unit.cast::<Str>().map(|s| format_unit(&s.get())) else {
+        return false;
+    };
+    // Hide the `#` of calls in markup
+    let Some(hash_offset) = parser.hash_offset() else {
+        return false;
+    };
+
+    parser.insert_void(callee.range(), (parser.offset.0 + hash_offset, 1));
+    if let Some(value) = value {
+        // Numbers can be given as strings, hide the quotes
+        match value.cast::<Str>() {
+            Some(number) => parser.insert_result(
+                value.range(),
+                &[parser.uuid, "unit-", &number.get()],
+                &number.get(),
+                Color::Number,
+                parser.added_text_decoration.to_string(),
+                (0, 0),
+            ),
+            None => schedule_argument(parser, value),
+        }
+        parser.insert_result(
+            value.range().end..unit.range().start,
+            &[parser.uuid, "unit-space"],
+            "\u{2009}",
+            Color::Number,
+            parser.added_text_decoration.to_string(),
+            (0, 0),
+        );
+    }
+    parser.insert_result(
+        unit.range().start..args.range().end,
+        &[parser.uuid, "unit-", &unit_text],
+        &unit_text,
+        Color::Number,
+        format!("{}{}", UNIT_STYLE, parser.added_text_decoration),
+        (0, parser.offset.1),
+    );
+    true
+}
+
+/// Format a unit string: units are separated by a middle dot and exponents are written as superscripts \
+/// (`kg m^-2` -> `kg·m⁻²`)
+fn format_unit(unit: &str) -> String {
+    let mut result = String::new();
+    let mut exponent = false;
+    for c in unit.trim().chars() {
+        match c {
+            '^' => exponent = true,
+            ' ' => {
+                exponent = false;
+                if !result.ends_with('·') {
+                    result.push('·');
+                }
+            }
+            c if exponent => match superscript(c) {
+                Some(c) => result.push(c),
+                None => {
+                    exponent = false;
+                    result.push(c);
+                }
+            },
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_unit;
+
+    #[test]
+    fn test_format_unit() {
+        assert_eq!(format_unit("kg m^-2"), "kg·m⁻²");
+        assert_eq!(format_unit("m/s"), "m/s");
+        assert_eq!(format_unit(" J  mol^-1 K^-1 "), "J·mol⁻¹·K⁻¹");
+    }
+}
@@ -1,28 +1,302 @@
 //! Utility functions for the parser
 
+use super::overlaps::Priority;
 use super::parser::State;
 use crate::{
-    interface::{Decoration, Options, Position},
+    interface::{Decoration, Diagnostic, Options, Position, SymbolInfo, SymbolSource},
+    unicode::plain_symbol,
     utils::{
-        styles::SYMBOLS_STYLES,
-        symbols::{get_category_by_name, Category, Color, SYMBOLS},
+        emoji::EMOJI,
+        styles::{resolve_fonts, without_layout, SYMBOLS_STYLES},
+        symbols::{
+            get_category_by_name, Category, Color, Symbol, BLACKBOLD_LETTERS, BOLD_LETTERS,
+            CAL_LETTERS, FRAK_LETTERS, ITALIC_LETTERS, MONO_LETTERS, SANS_LETTERS, SYMBOLS,
+            UPRIGHT_LETTERS,
+        },
     },
 };
-use std::{collections::HashMap, ops::Range};
+use std::{borrow::Cow, cmp::Reverse, collections::HashMap, ops::Range, sync::OnceLock};
 use typst_syntax::SyntaxNode;
-use typst_syntax::{ast::AstNode, LinkedNode, Source};
+use typst_syntax::{ast::AstNode, LinkedNode, Source, Span, SyntaxKind};
 
-/// Get symbol from it's name
+/// Get symbol from it's name, None if it isn't rendered with the options
 pub fn get_symbol(content: String, options: &Options) -> Option<(Category, String)> {
+    if !is_symbol_allowed(&content, options) {
+        return None;
+    }
+    find_symbol(content, options)
+        .filter(|(category, _)| !options.hidden_categories.contains(category))
+}
+
+/// Find a symbol from it's name, in the user defined symbols and in the symbols lists
+pub fn find_symbol(content: String, options: &Options) -> Option<(Category, String)> {
     // Check if the symbol is defined by the user
     if let Some(entry) = options.custom_symbols.get(&content) {
         return Some((get_category_by_name(&entry.category), entry.symbol.clone()));
     }
     // Check if the symbol is in the symbols list
-    else if let Some(entry) = SYMBOLS.get_entry(&content.as_str()) {
+    else if let Some(entry) = SYMBOLS.get_entry(content.as_str()) {
         return Some((entry.1.category, format!("{}", entry.1.symbol)));
     }
-    return None;
+    // Emoji from the typst emoji module
+    else if let Some(name) = content.strip_prefix("emoji.") {
+        let entry = EMOJI.get(name).or_else(|| find_variant(&EMOJI, name))?;
+        return Some((entry.category, format!("{}", entry.symbol)));
+    }
+    // Modifiers can be written in any order
+    else if let Some(entry) = find_variant(&SYMBOLS, &content) {
+        return Some((entry.category, format!("{}", entry.symbol)));
+    }
+    // Symbols added by the user, like the ones of a package
+    else if let Some(entry) = options.symbol_table.get(&content) {
+        return Some((get_category_by_name(&entry.category), entry.symbol.clone()));
+    }
+    None
+}
+
+/// List every symbol known with the options, with the name it is found by \
+/// User symbols shadowing a built-in one replace it, like when symbols are rendered
+pub fn list_symbols(options: &Options) -> Vec<SymbolInfo> {
+    let info =
+        |name: String, symbol: String, category: Category, source: SymbolSource| SymbolInfo {
+            name,
+            symbol,
+            category,
+            source,
+        };
+    let mut custom: Vec<SymbolInfo> = options
+        .custom_symbols
+        .iter()
+        .chain(
+            options
+                .symbol_table
+                .iter()
+                .filter(|(name, _)| !options.custom_symbols.contains_key(*name)),
+        )
+        .filter(|(name, _)| {
+            options.custom_symbols.contains_key(*name) || !SYMBOLS.contains_key(name.as_str())
+        })
+        .map(|(name, entry)| {
+            info(
+                name.clone(),
+                entry.symbol.clone(),
+                get_category_by_name(&entry.category),
+                SymbolSource::Custom,
+            )
+        })
+        .collect();
+    custom.sort_by(|a, b| a.name.cmp(&b.name));
+    SYMBOLS
+        .entries()
+        .filter(|(name, _)| !options.custom_symbols.contains_key(**name))
+        .map(|(name, entry)| {
+            info(
+                name.to_string(),
+                entry.symbol.to_string(),
+                entry.category,
+                SymbolSource::Builtin,
+            )
+        })
+        .chain(EMOJI.entries().map(|(name, entry)| {
+            info(
+                format!("emoji.{}", name),
+                entry.symbol.to_string(),
+                entry.category,
+                SymbolSource::Emoji,
+            )
+        }))
+        .chain(custom)
+        .collect()
+}
+
+/// Check if a symbol is long enough (`pi.alt` is as long as `pi`), isn't blacklisted and,
+/// if there is a whitelist, is whitelisted
+fn is_symbol_allowed(name: &str, options: &Options) -> bool {
+    let base = name.split('.').next().unwrap_or(name);
+    base.chars().count() >= options.min_symbol_length
+        && !options
+            .blacklisted_symbols
+            .iter()
+            .any(|pattern| glob_match(pattern, name))
+        && (options.whitelisted_symbols.is_empty()
+            || options
+                .whitelisted_symbols
+                .iter()
+                .any(|pattern| glob_match(pattern, name)))
+}
+
+/// Match a name against a pattern where `*` matches any characters (`arrow.*` matches `arrow.r.long`)
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            // Try every possible length for the part matched by the star
+            name.char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(name.len()))
+                .any(|i| glob_match(rest, &name[i..]))
+        }
+    }
+}
+
+/// Find the best variant of a symbol for the given modifiers, using the same algorithm as typst. \
+/// Every modifier must be in the variant, and the variant with the most matching modifiers and the fewest other ones wins.
+/// On equality, the first variant defined is chosen.
+fn find_variant(
+    symbols: &'static phf::OrderedMap<&'static str, Symbol>,
+    content: &str,
+) -> Option<&'static Symbol> {
+    let (name, modifiers) = content.split_once('.')?;
+    let parts = |modifiers: &'static str| modifiers.split('.').filter(|m| !m.is_empty());
+    let mut best: Option<(&Symbol, (usize, Reverse<usize>))> = None;
+    for (key, symbol) in symbols.entries() {
+        let candidate = match key.strip_prefix(name) {
+            Some("") => "",
+            Some(rest) => match rest.strip_prefix('.') {
+                Some(candidate) => candidate,
+                None => continue,
+            },
+            None => continue,
+        };
+        if !modifiers
+            .split('.')
+            .all(|m| parts(candidate).any(|c| c == m))
+        {
+            continue;
+        }
+        let matching = parts(candidate)
+            .filter(|c| modifiers.split('.').any(|m| m == *c))
+            .count();
+        let score = (matching, Reverse(parts(candidate).count()));
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((symbol, score));
+        }
+    }
+    best.map(|(symbol, _)| symbol)
+}
+
+/// Check if a character has no width and is invisible once rendered (`zws`, `wj`, soft hyphen...)
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{200E}' | '\u{200F}' | '\u{2060}'
+    )
+}
+
+/// Get the category of a unicode character typed directly, from the first symbol using it
+pub fn get_category_by_char(c: char) -> Option<Category> {
+    static CATEGORIES: OnceLock<HashMap<char, Category>> = OnceLock::new();
+    CATEGORIES
+        .get_or_init(|| {
+            let mut categories = HashMap::new();
+            for symbol in SYMBOLS.values() {
+                categories.entry(symbol.symbol).or_insert(symbol.category);
+            }
+            categories
+        })
+        .get(&c)
+        .copied()
+}
+
+/// Get the typst names of a unicode character, from the shortest one, with emojis named `emoji.name`
+pub fn names_for_char(c: char) -> &'static [String] {
+    static NAMES: OnceLock<HashMap<char, Vec<String>>> = OnceLock::new();
+    NAMES
+        .get_or_init(|| {
+            let mut names: HashMap<char, Vec<String>> = HashMap::new();
+            for (name, symbol) in SYMBOLS.entries() {
+                names
+                    .entry(symbol.symbol)
+                    .or_default()
+                    .push(name.to_string());
+            }
+            for (name, symbol) in EMOJI.entries() {
+                names
+                    .entry(symbol.symbol)
+                    .or_default()
+                    .push(format!("emoji.{}", name));
+            }
+            for list in names.values_mut() {
+                list.sort_by_key(|name| name.len());
+            }
+            names
+        })
+        .get(&c)
+        .map_or(&[], |names| names.as_slice())
+}
+
+/// Get the superscript version of a character, for digits, signs and `n`
+pub fn superscript(c: char) -> Option<char> {
+    match c {
+        '0' => Some('⁰'),
+        '1' => Some('¹'),
+        '2' => Some('²'),
+        '3' => Some('³'),
+        '4' => Some('⁴'),
+        '5' => Some('⁵'),
+        '6' => Some('⁶'),
+        '7' => Some('⁷'),
+        '8' => Some('⁸'),
+        '9' => Some('⁹'),
+        '-' => Some('⁻'),
+        '+' => Some('⁺'),
+        'n' => Some('ⁿ'),
+        _ => None,
+    }
+}
+
+/// Get the subscript version of a character, for digits, signs and the letters having one
+pub fn subscript(c: char) -> Option<char> {
+    match c {
+        '0'..='9' => char::from_u32('₀' as u32 + c.to_digit(10)?),
+        '-' => Some('₋'),
+        '+' => Some('₊'),
+        '=' => Some('₌'),
+        'a' => Some('ₐ'),
+        'e' => Some('ₑ'),
+        'h' => Some('ₕ'),
+        'i' => Some('ᵢ'),
+        'j' => Some('ⱼ'),
+        'k' => Some('ₖ'),
+        'l' => Some('ₗ'),
+        'm' => Some('ₘ'),
+        'n' => Some('ₙ'),
+        'o' => Some('ₒ'),
+        'p' => Some('ₚ'),
+        'r' => Some('ᵣ'),
+        's' => Some('ₛ'),
+        't' => Some('ₜ'),
+        'u' => Some('ᵤ'),
+        'v' => Some('ᵥ'),
+        'x' => Some('ₓ'),
+        _ => None,
+    }
+}
+
+/// Get the letters map and the css style of a letter style function (`cal`, `bb`, ...) from it's name
+pub fn get_letter_style(name: &str) -> Option<(&'static phf::Map<char, char>, &'static str)> {
+    match name {
+        "cal" => Some((&CAL_LETTERS, "font-family: @math;")),
+        "frak" => Some((&FRAK_LETTERS, "font-family: @math;")),
+        "bb" => Some((&BLACKBOLD_LETTERS, "")),
+        "bold" => Some((&BOLD_LETTERS, "font-family: @math;")),
+        "italic" => Some((&ITALIC_LETTERS, "font-family: @math;")),
+        "sans" => Some((&SANS_LETTERS, "font-family: @math;")),
+        "mono" => Some((&MONO_LETTERS, "font-family: @math;")),
+        "upright" => Some((&UPRIGHT_LETTERS, "font-family: @math; font-style: normal;")),
+        _ => None,
+    }
+}
+
+/// Replace each letter of a text with its styled version, letters without a styled version are kept as is
+pub fn apply_letter_style(map: &phf::Map<char, char>, content: &str) -> String {
+    content
+        .chars()
+        .map(|letter| *map.get(&letter).unwrap_or(&letter))
+        .collect()
 }
 
 /// Get color and text_decoration css style from a symbol category
@@ -35,29 +309,181 @@ fn get_style_from_category(category: Category) -> (Color, std::string::String) {
         color = style.0;
         text_decoration = style.1.to_string();
     }
-    return (color, text_decoration);
+    (color, text_decoration)
 }
 
-/// Cast expr to the given AST type. No checks are done, will panick if the given expression is not of the given type.
-pub fn unchecked_cast_expr<'a, T: AstNode<'a>>(expr: &'a SyntaxNode) -> T {
-    T::from_untyped(expr).unwrap()
+/// Cast expr to the given AST type, None if the expression is not of the given type
+pub fn cast_expr<'a, T: AstNode<'a>>(expr: &'a SyntaxNode) -> Option<T> {
+    T::from_untyped(expr)
+}
+
+/// Find the direct child of a node with a span, which still works when the spans of a reparsed tree aren't ordered
+pub fn find_child<'a>(node: &LinkedNode<'a>, span: Span) -> Option<LinkedNode<'a>> {
+    node.children().find(|child| child.span() == span)
+}
+
+/// Get the leaf at a byte cursor like `LinkedNode::leaf_at`, descending with a loop for deeply nested documents
+pub fn leaf_at<'a>(root: &LinkedNode<'a>, cursor: usize) -> Option<LinkedNode<'a>> {
+    let mut node = root.clone();
+    loop {
+        if node.children().len() == 0 {
+            return (cursor <= node.range().end).then_some(node);
+        }
+        let count = node.children().len();
+        node = node.children().enumerate().find_map(|(index, child)| {
+            let range = child.range();
+            ((range.start < cursor && cursor <= range.end)
+                || (range.start == cursor && index + 1 == count))
+                .then_some(child)
+        })?;
+    }
+}
+
+/// Check if a node is a line or a block comment
+pub fn is_comment(node: &LinkedNode) -> bool {
+    matches!(
+        node.kind(),
+        SyntaxKind::LineComment | SyntaxKind::BlockComment
+    )
+}
+
+/// Check if a node doesn't change the shape of its parent, a comment or an alignment point
+fn is_insignificant(node: &LinkedNode) -> bool {
+    is_comment(node) || node.kind() == SyntaxKind::MathAlignPoint
+}
+
+/// Children of a node without its comments and alignment points, the spaces left at the ends or doubled
+/// being dropped too, so that `(x /* y */)` and `(x &)` have the shape of `(x)` \
+/// Nodes without comments or alignment points keep all their children
+pub fn significant_children<'a>(node: &LinkedNode<'a>) -> Vec<LinkedNode<'a>> {
+    let children: Vec<LinkedNode> = node.children().collect();
+    if !children.iter().any(is_insignificant) {
+        return children;
+    }
+    let kept: Vec<LinkedNode> = children
+        .into_iter()
+        .filter(|c| !is_insignificant(c))
+        .collect();
+    let mut significant: Vec<LinkedNode> = vec![];
+    for (index, child) in kept.iter().enumerate() {
+        if child.kind() == SyntaxKind::Space {
+            let previous = significant.last().map(|c| c.kind());
+            let next = kept[index + 1..]
+                .iter()
+                .map(|c| c.kind())
+                .find(|kind| *kind != SyntaxKind::Space);
+            if matches!(
+                previous,
+                None | Some(SyntaxKind::LeftParen | SyntaxKind::Space)
+            ) || matches!(next, None | Some(SyntaxKind::RightParen))
+            {
+                continue;
+            }
+        }
+        significant.push(child.clone());
+    }
+    significant
+}
+
+/// Positions of the arguments in the children list of the args, \
+/// None if the args aren't simply wrapped in parens
+pub fn argument_items(children: &[LinkedNode]) -> Option<Vec<usize>> {
+    if children.first().map(|c| c.kind()) != Some(SyntaxKind::LeftParen)
+        || children.last().map(|c| c.kind()) != Some(SyntaxKind::RightParen)
+    {
+        return None;
+    }
+    Some(
+        (1..children.len() - 1)
+            .filter(|i| {
+                !matches!(children[*i].kind(), SyntaxKind::Comma | SyntaxKind::Space)
+                    && !is_comment(&children[*i])
+            })
+            .collect(),
+    )
+}
+
+/// Positions of the positional arguments in the children list of the args, \
+/// None if the args aren't simply wrapped in parens or contain named arguments
+pub fn arguments(children: &[LinkedNode]) -> Option<Vec<usize>> {
+    let items = argument_items(children)?;
+    if items
+        .iter()
+        .any(|i| children[*i].kind() == SyntaxKind::Named)
+    {
+        return None;
+    }
+    Some(items)
+}
+
+/// Get the nodes at the top level of the document, including the content of top level code blocks (`#{ ... }`)
+pub fn top_level_nodes<'a>(root: &LinkedNode<'a>) -> Vec<LinkedNode<'a>> {
+    root.children()
+        .flat_map(|child| match child.kind() {
+            SyntaxKind::CodeBlock | SyntaxKind::Code => top_level_nodes(&child),
+            _ => vec![child],
+        })
+        .collect()
 }
 
 /// The number of code units this string would use if it was encoded in
 /// UTF16. This runs in linear time.
-fn len_utf16(string: &str) -> usize {
+pub fn len_utf16(string: &str) -> usize {
     string.chars().map(char::len_utf16).sum()
 }
 
 /// Return the index range of the UTF-16 code unit at the byte index range. \
 /// Faster than calling `byte_to_utf16` over start and end.
-fn byte_range_to_utf16(source: &Source, range: &Range<usize>) -> Option<Range<usize>> {
+pub fn byte_range_to_utf16(source: &Source, range: &Range<usize>) -> Option<Range<usize>> {
     let start = source.byte_to_utf16(range.start)?;
 
     let head = source.get(range.start..range.end)?;
     let end = start + len_utf16(head);
 
-    return Some(start..end);
+    Some(start..end)
+}
+
+/// Get the line and the UTF-16 column of a UTF-16 offset, VSCode positions counting columns in UTF-16 code units
+pub fn utf16_line_column(source: &Source, utf16: usize) -> Option<(usize, usize)> {
+    let line = source.byte_to_line(source.utf16_to_byte(utf16)?)?;
+    let line_start = source.byte_to_utf16(source.line_to_byte(line)?)?;
+    Some((line, utf16 - line_start))
+}
+
+/// Get the line and the UTF-16 column of a byte offset
+pub fn byte_line_column(source: &Source, byte: usize) -> Option<(usize, usize)> {
+    utf16_line_column(source, source.byte_to_utf16(byte)?)
+}
+
+/// Build the position of a UTF-16 range, with the lines and columns of its ends
+pub fn utf16_position(source: &Source, range: Range<usize>) -> Position {
+    let (start_line, start_column) = utf16_line_column(source, range.start).unwrap_or_default();
+    let (end_line, end_column) = utf16_line_column(source, range.end).unwrap_or_default();
+    Position {
+        start: range.start,
+        end: range.end,
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    }
+}
+
+/// Get the syntax errors of a node of a source, with their span in UTF-16 offsets
+pub fn syntax_diagnostics(source: &Source, node: &SyntaxNode) -> Vec<Diagnostic> {
+    if !node.erroneous() {
+        return vec![];
+    }
+    node.errors()
+        .into_iter()
+        .filter_map(|error| {
+            let range = byte_range_to_utf16(source, &source.range(error.span)?)?;
+            Some(Diagnostic {
+                message: error.message.to_string(),
+                position: utf16_position(source, range),
+            })
+        })
+        .collect()
 }
 
 /// Store the current data of the parsing
@@ -65,7 +491,7 @@ pub struct InnerParser<'a> {
     /// Source of the document
     pub source: &'a typst_syntax::Source,
     /// Vector containing decorations to render
-    pub result: &'a mut HashMap<String, Decoration>,
+    pub result: &'a mut HashMap<u32, Decoration>,
     /// Current state of the parser
     pub state: &'a mut State,
     /// User settings
@@ -86,7 +512,7 @@ impl<'a> InnerParser<'a> {
     pub fn new(
         source: &'a typst_syntax::Source,
         expr: &'a LinkedNode<'a>,
-        result: &'a mut HashMap<String, Decoration>,
+        result: &'a mut HashMap<u32, Decoration>,
         state: &'a mut State,
         options: &'a Options,
     ) -> InnerParser<'a> {
@@ -125,85 +551,191 @@ impl<'a> InnerParser<'a> {
         &mut self,
         range: Range<usize>,
         content: String,
-        uuid: String,
+        uuid: &[&str],
         added_text_decoration: &str,
         offset: (usize, usize),
         additional_content: (&str, &str),
     ) {
         if let Some((category, symbol)) = get_symbol(content, self.options) {
-            // If we are in a space and we don't want to render them, return
-            if !self.options.render_spaces && category == Category::Space {
-                return;
-            }
+            self.insert_result_category(
+                range,
+                uuid,
+                &format!("{}{}{}", additional_content.0, symbol, additional_content.1,),
+                category,
+                added_text_decoration,
+                offset,
+            );
+        }
+    }
+    /// Helper function to insert a new symbol in the symbols hashmap, styled with its category
+    pub fn insert_result_category(
+        &mut self,
+        range: Range<usize>,
+        uuid: &[&str],
+        symbol: &str,
+        category: Category,
+        added_text_decoration: &str,
+        offset: (usize, usize),
+    ) {
+        // Invisible characters are replaced by a faint marker if asked
+        if self.options.render_invisible && !symbol.is_empty() && symbol.chars().all(is_invisible) {
             let (color, text_decoration) = get_style_from_category(category);
             self.insert_result(
                 range,
                 uuid,
-                format!(
-                    "{}{}{}",
-                    additional_content.0,
-                    symbol.to_string(),
-                    additional_content.1,
-                ),
+                "◦",
                 color,
-                format!("{text_decoration} {added_text_decoration}"),
+                format!("{text_decoration} opacity: 0.5; {added_text_decoration}"),
                 offset,
             );
+            return;
+        }
+        // If we are in a space and we don't want to render them, return
+        if !self.options.render_spaces && category == Category::Space {
+            return;
         }
+        let (color, text_decoration) = get_style_from_category(category);
+        self.insert_result(
+            range,
+            uuid,
+            symbol,
+            color,
+            format!("{text_decoration} {added_text_decoration}"),
+            offset,
+        );
     }
-    /// Helper function to insert a new symbol in the symbols hashmap
+    /// Helper function to insert a new symbol in the symbols hashmap \
+    /// The uuid is given in parts, so that it is only allocated for a new decoration
     pub fn insert_result(
         &mut self,
         range: Range<usize>,
-        uuid: String,
-        symbol: String,
+        uuid: &[&str],
+        symbol: &str,
         color: Color,
         text_decoration: String,
         offset: (usize, usize),
     ) {
+        // Nothing is rendered in the regions disabled by magic comments
+        if self.state.disabled.contains(range.start) {
+            return;
+        }
+        // Symbols of equations styled by show rules take their fonts and size, with a specific uuid to avoid mixing styles
+        let (style_prefix, text_decoration) = match &self.state.math_style {
+            Some(style) if !symbol.is_empty() => (style.prefix(), style.apply(&text_decoration)),
+            _ => ("", text_decoration),
+        };
+        let text_decoration = resolve_fonts(&text_decoration, &self.options.fonts);
+        // Attachments are approximated with unicode characters for hosts which can't apply css transforms, \
+        // the uuid doesn't start with the attachment anymore so that plain renderings don't convert them again
+        let (plain_prefix, symbol, text_decoration) = if self.options.plain_unicode {
+            (
+                "plain-",
+                Cow::Owned(plain_symbol(&uuid.concat(), symbol)),
+                without_layout(&text_decoration),
+            )
+        } else {
+            ("", Cow::Borrowed(symbol), text_decoration)
+        };
         // Convert position to UTF-16, because VSCode uses UTF-16 for positions
-        let utf16_range = byte_range_to_utf16(self.source, &range).unwrap();
-        let position = Position {
-            start: utf16_range.start - offset.0,
-            end: utf16_range.end + offset.1,
+        let Some(utf16_range) = byte_range_to_utf16(self.source, &range) else {
+            return;
+        };
+        let position = utf16_position(
+            self.source,
+            utf16_range.start.saturating_sub(offset.0)..utf16_range.end + offset.1,
+        );
+
+        // Apply the color of a surrounding `text(fill: ...)`, with a specific uuid to avoid mixing colors
+        let (fill_prefix, text_decoration) = match &self.state.fill {
+            Some(fill) if !symbol.is_empty() => (
+                ["fill-", fill.as_str(), "-"],
+                format!("{} color: {} !important;", text_decoration, fill),
+            ),
+            _ => ([""; 3], text_decoration),
         };
 
-        // Check if the symbol is blacklisted
+        // Check if the symbol is blacklisted, by its source text (`->`, `alpha`)
+        let text = self.source.get(range).unwrap_or("UNREACHABLE");
         if self
             .options
             .blacklisted_symbols
-            .contains(&self.source.get(range).unwrap_or("UNREACHABLE").to_string())
+            .iter()
+            .any(|pattern| glob_match(pattern, text))
         {
             return;
         }
 
         // If the decoration already exists, simply add a new range
-        if let Some(map) = self.result.get_mut(&uuid) {
+        let [fill_start, fill, fill_end] = fill_prefix;
+        let prefixes = [fill_start, fill, fill_end, style_prefix, plain_prefix];
+        let id = self
+            .state
+            .interner
+            .intern(prefixes.iter().chain(uuid).copied());
+        if let Some(map) = self.result.get_mut(&id) {
             map.positions.push(position);
         } else {
             // If not, create the decoration and add this range
+            let priority = Priority::of(&symbol);
             self.result.insert(
-                uuid.clone(),
+                id,
                 Decoration {
-                    uuid,
-                    symbol,
+                    id,
+                    symbol: symbol.into_owned(),
                     color,
                     text_decoration,
+                    resolved_color: self.options.colors.get(&color).cloned(),
+                    priority,
                     positions: vec![position],
                 },
             );
         }
     }
+    /// Offset hiding the `#` before a code expression (`#sym.alpha`), \
+    /// None if the expression is in markup and symbols outside math aren't rendered
+    pub fn hash_offset(&self) -> Option<usize> {
+        if self.expr.prev_sibling_kind() != Some(SyntaxKind::Hash) {
+            Some(0)
+        } else if self.expr.parent_kind() == Some(SyntaxKind::Math)
+            || self.options.render_outside_math
+        {
+            Some(1)
+        } else {
+            None
+        }
+    }
     /// Helper function to insert a new invisible symbol in the symbols hashmap to hide a span
     pub fn insert_void(&mut self, range: Range<usize>, offset: (usize, usize)) {
-        self.insert_result(
-            range,
-            "void".to_string(),
-            "".to_string(),
-            Color::Number,
-            "".to_string(),
-            offset,
-        )
+        self.insert_result(range, &["void"], "", Color::Number, "".to_string(), offset)
+    }
+    /// Hide a span like `insert_void`, except the comments among the children of a node, which stay visible \
+    /// The offset is applied to the first and the last hidden parts
+    pub fn insert_void_except_comments(
+        &mut self,
+        node: &LinkedNode,
+        range: Range<usize>,
+        offset: (usize, usize),
+    ) {
+        let mut parts = vec![];
+        let mut start = range.start;
+        for comment in node.children().filter(is_comment) {
+            let comment = comment.range();
+            if comment.start >= start && comment.end <= range.end {
+                parts.push(start..comment.start);
+                start = comment.end;
+            }
+        }
+        parts.push(start..range.end);
+        let last = parts.len() - 1;
+        for (index, part) in parts.into_iter().enumerate() {
+            let offset = (
+                if index == 0 { offset.0 } else { 0 },
+                if index == last { offset.1 } else { 0 },
+            );
+            if !part.is_empty() || offset != (0, 0) {
+                self.insert_void(part, offset);
+            }
+        }
     }
 }
 
@@ -211,7 +743,7 @@ impl<'a> InnerParser<'a> {
 mod tests {
     use typst_syntax::SyntaxNode;
 
-    use crate::{interface::Options, parser::parser::State};
+    use crate::{interface::Options, parser::parser::State, utils::symbols::Category};
 
     #[test]
     fn test_inner_parser() {
@@ -222,26 +754,13 @@ mod tests {
         let node = SyntaxNode::leaf(typst_syntax::SyntaxKind::Ident, "alpha");
         let expr = typst_syntax::LinkedNode::new(&node);
         let mut parser = super::InnerParser::new(&source, &expr, &mut result, &mut state, &options);
-        parser.insert_result_symbol(
-            0..2,
-            "alpha".to_string(),
-            "alpha".to_string(),
-            "",
-            (0, 0),
-            ("", ""),
-        );
+        parser.insert_result_symbol(0..2, "alpha".to_string(), &["alpha"], "", (0, 0), ("", ""));
 
         let mut parser = super::InnerParser::from(&mut parser, &expr, "alpha", "", (0, 0));
-        parser.insert_result_symbol(
-            0..2,
-            "alpha".to_string(),
-            "alpha".to_string(),
-            "",
-            (0, 0),
-            ("", ""),
-        );
+        parser.insert_result_symbol(0..2, "alpha".to_string(), &["alpha"], "", (0, 0), ("", ""));
         assert_eq!(parser.result.len(), 1);
-        assert_eq!(parser.result.get("alpha").unwrap().symbol, "α");
+        let id = parser.state.interner.intern(["alpha"]);
+        assert_eq!(parser.result.get(&id).unwrap().symbol, "α");
     }
 
     #[test]
@@ -253,29 +772,38 @@ mod tests {
         let node = SyntaxNode::leaf(typst_syntax::SyntaxKind::MathIdent, "zwnj");
         let expr = typst_syntax::LinkedNode::new(&node);
         let mut parser = super::InnerParser::new(&source, &expr, &mut result, &mut state, &options);
-        parser.insert_result_symbol(
-            0..4,
-            "zwnj".to_string(),
-            "zwnj".to_string(),
-            "",
-            (0, 0),
-            ("", ""),
-        );
+        parser.insert_result_symbol(0..4, "zwnj".to_string(), &["zwnj"], "", (0, 0), ("", ""));
         assert_eq!(parser.result.len(), 0);
 
         options.render_spaces = true;
         let mut parser = super::InnerParser::new(&source, &expr, &mut result, &mut state, &options);
-        parser.insert_result_symbol(
-            0..4,
-            "zwnj".to_string(),
-            "zwnj".to_string(),
-            "",
-            (0, 0),
-            ("", ""),
-        );
+        parser.insert_result_symbol(0..4, "zwnj".to_string(), &["zwnj"], "", (0, 0), ("", ""));
         assert_eq!(parser.result.len(), 1);
     }
 
+    #[test]
+    fn test_inner_parser_invisible() {
+        let source = typst_syntax::Source::detached("zws");
+        let mut result = std::collections::HashMap::new();
+        let mut state = State::default();
+        let options = Options {
+            render_invisible: true,
+            ..Options::default()
+        };
+        let node = SyntaxNode::leaf(typst_syntax::SyntaxKind::MathIdent, "zws");
+        let expr = typst_syntax::LinkedNode::new(&node);
+        let mut parser = super::InnerParser::new(&source, &expr, &mut result, &mut state, &options);
+        parser.insert_result_category(0..3, &["zws"], "\u{200B}", Category::Operator, "", (0, 0));
+        // An empty symbol is not an invisible one
+        parser.insert_result_category(0..3, &["empty"], "", Category::Operator, "", (0, 0));
+        let mut symbol = |uuid: &str| {
+            let id = parser.state.interner.intern([uuid]);
+            parser.result.get(&id).unwrap().symbol.clone()
+        };
+        assert_eq!(symbol("zws"), "◦");
+        assert_eq!(symbol("empty"), "");
+    }
+
     #[test]
     fn test_inner_parser_not_found() {
         let source = typst_syntax::Source::detached("");
@@ -288,13 +816,66 @@ mod tests {
         parser.insert_result_symbol(
             0..5,
             "doesn't exist".to_string(),
-            "doesn't exist".to_string(),
+            &["doesn't exist"],
             "",
             (0, 0),
             ("", ""),
         );
     }
 
+    #[test]
+    fn test_glob_match() {
+        assert!(super::glob_match("alpha", "alpha"));
+        assert!(!super::glob_match("alpha", "alpha.alt"));
+        assert!(super::glob_match("arrow.*", "arrow.r.long"));
+        assert!(!super::glob_match("arrow.*", "arrow"));
+        assert!(super::glob_match("*.not", "eq.not"));
+        assert!(super::glob_match("a*.*.l*", "arrow.double.long"));
+        assert!(super::glob_match("*", ""));
+    }
+
+    #[test]
+    fn test_significant_children() {
+        let kinds = |text: &str| {
+            let source = typst_syntax::Source::detached(text);
+            let root = typst_syntax::LinkedNode::new(source.root());
+            let args = find_args(&root).unwrap();
+            super::significant_children(&args)
+                .iter()
+                .map(|child| child.kind())
+                .collect::<Vec<_>>()
+        };
+        use typst_syntax::SyntaxKind::*;
+        assert_eq!(kinds("$cal(x /* y */)$"), vec![LeftParen, Text, RightParen]);
+        assert_eq!(
+            kinds("$cal( /* a */ x // b\n)$"),
+            vec![LeftParen, Text, RightParen]
+        );
+        // Spaces are kept without comments
+        assert_eq!(kinds("$cal(x )$"), vec![LeftParen, Text, Space, RightParen]);
+    }
+
+    fn find_args<'a>(node: &typst_syntax::LinkedNode<'a>) -> Option<typst_syntax::LinkedNode<'a>> {
+        if node.kind() == typst_syntax::SyntaxKind::Args {
+            return Some(node.clone());
+        }
+        node.children().find_map(|child| find_args(&child))
+    }
+
+    #[test]
+    fn test_inner_parser_whitelist() {
+        let source = typst_syntax::Source::detached("alpha");
+        let mut result = std::collections::HashMap::new();
+        let mut state = State::default();
+        let mut options = Options::default();
+        options.whitelisted_symbols.push("arrow.*".to_string());
+        let node = SyntaxNode::leaf(typst_syntax::SyntaxKind::Ident, "alpha");
+        let expr = typst_syntax::LinkedNode::new(&node);
+        let mut parser = super::InnerParser::new(&source, &expr, &mut result, &mut state, &options);
+        parser.insert_result_symbol(0..5, "alpha".to_string(), &["alpha"], "", (0, 0), ("", ""));
+        assert_eq!(parser.result.len(), 0);
+    }
+
     #[test]
     fn test_inner_parser_blacklist() {
         let source = typst_syntax::Source::detached("alpha");
@@ -305,14 +886,7 @@ mod tests {
         let node = SyntaxNode::leaf(typst_syntax::SyntaxKind::Ident, "alpha");
         let expr = typst_syntax::LinkedNode::new(&node);
         let mut parser = super::InnerParser::new(&source, &expr, &mut result, &mut state, &options);
-        parser.insert_result_symbol(
-            0..5,
-            "alpha".to_string(),
-            "alpha".to_string(),
-            "",
-            (0, 0),
-            ("", ""),
-        );
+        parser.insert_result_symbol(0..5, "alpha".to_string(), &["alpha"], "", (0, 0), ("", ""));
         assert_eq!(parser.result.len(), 0);
     }
 }
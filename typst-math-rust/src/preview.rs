@@ -0,0 +1,142 @@
+//! Previews of equations too complex to be decorated, compiled on their own with the typst compiler into SVG or PNG
+
+use std::sync::OnceLock;
+
+use comemo::Prehashed;
+use typst::{
+    diag::{FileError, FileResult},
+    eval::Tracer,
+    foundations::{Bytes, Datetime},
+    model::Document,
+    syntax::{FileId, LinkedNode, Source, SyntaxKind},
+    text::{Font, FontBook},
+    visualize::Color,
+    Library, World,
+};
+
+use crate::unicode::equation_at;
+
+/// Fonts embedded in the compiler, with their metadata
+fn fonts() -> &'static (Prehashed<FontBook>, Vec<Font>) {
+    static FONTS: OnceLock<(Prehashed<FontBook>, Vec<Font>)> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let fonts: Vec<Font> = typst_assets::fonts()
+            .flat_map(|data| Font::iter(Bytes::from_static(data)))
+            .collect();
+        (Prehashed::new(FontBook::from_fonts(&fonts)), fonts)
+    })
+}
+
+fn library() -> &'static Prehashed<Library> {
+    static LIBRARY: OnceLock<Prehashed<Library>> = OnceLock::new();
+    LIBRARY.get_or_init(|| Prehashed::new(Library::default()))
+}
+
+/// World of a single file, without packages nor other files
+struct PreviewWorld {
+    source: Source,
+}
+
+impl World for PreviewWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        library()
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &fonts().0
+    }
+
+    fn main(&self) -> Source {
+        self.source.clone()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if id == self.source.id() {
+            Ok(self.source.clone())
+        } else {
+            Err(FileError::AccessDenied)
+        }
+    }
+
+    fn file(&self, _: FileId) -> FileResult<Bytes> {
+        Err(FileError::AccessDenied)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        fonts().1.get(index).cloned()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        None
+    }
+}
+
+/// Check if a text color is a css hex color, like `#fff` or `#1e1e1ecc`
+fn is_hex_color(color: &str) -> bool {
+    color.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// Get the top-level `#let` bindings of a source before a byte offset, like `#let RR = $bb(R)$`
+fn definitions(source: &Source, before: usize) -> String {
+    let root = LinkedNode::new(source.root());
+    let mut definitions = String::new();
+    let mut hash = None;
+    for child in root.children() {
+        if child.offset() >= before {
+            break;
+        }
+        match child.kind() {
+            SyntaxKind::Hash => hash = Some(child.offset()),
+            SyntaxKind::LetBinding => {
+                if let Some(start) = hash.take() {
+                    definitions.push_str(source.get(start..child.range().end).unwrap_or_default());
+                    definitions.push('\n');
+                }
+            }
+            _ => hash = None,
+        }
+    }
+    definitions
+}
+
+/// Compile the equation around a UTF-16 offset of a document on a page fitting it, with a text color \
+/// The top-level definitions of the document before it are kept if they compile, None if there is no equation there
+fn compile(content: &str, offset: usize, fill: &str) -> Option<Document> {
+    let source = Source::detached(content);
+    let offset = source.utf16_to_byte(offset)?;
+    let equation = equation_at(&source, offset)?;
+    let fill = if is_hex_color(fill) { fill } else { "#000000" };
+    let preamble = format!(
+        "#set page(width: auto, height: auto, margin: 0.25em, fill: none)\n#set text(fill: rgb(\"{}\"))\n",
+        fill
+    );
+    let equation = source.get(equation.range())?;
+    // Definitions can depend on files or packages which aren't available
+    vec![definitions(&source, offset), String::new()]
+        .into_iter()
+        .find_map(|definitions| {
+            let world = PreviewWorld {
+                source: Source::detached(format!("{}{}{}", preamble, definitions, equation)),
+            };
+            typst::compile(&world, &mut Tracer::new()).ok()
+        })
+}
+
+/// Render the equation around a UTF-16 offset of a document into SVG, None if it doesn't compile
+pub fn equation_svg(content: &str, offset: usize, fill: &str) -> Option<String> {
+    let document = compile(content, offset, fill)?;
+    Some(typst_svg::svg(&document.pages.first()?.frame))
+}
+
+/// Render the equation around a UTF-16 offset of a document into PNG, with `scale` pixels per point
+pub fn equation_png(content: &str, offset: usize, fill: &str, scale: f32) -> Option<Vec<u8>> {
+    let document = compile(content, offset, fill)?;
+    let pixmap = typst_render::render(
+        &document.pages.first()?.frame,
+        scale,
+        Color::WHITE.with_alpha(0.0),
+    );
+    pixmap.encode_png().ok()
+}
@@ -0,0 +1,160 @@
+//! Newline-delimited JSON protocol, for hosts driving the engine through stdin and stdout (WASI runtimes, remote shells)
+//!
+//! Each request is a single line `{"id": ..., "method": ..., "params": {...}}` answered by a single line
+//! `{"id": ..., "result": ...}`, or `{"id": ..., "error": "..."}` if it failed. Offsets are UTF-16 like in the WASM API.
+//!
+//! - `parse` (`content`) returns the decorations of a document, typed by `bindings/Parsed.ts`
+//! - `query` (`content`, `offset`) returns the symbol rendered at an offset, or null
+//! - `convert` (`content`, optional `start`, `end` and `to`) returns the unicode rendering of the math of a range,
+//!   or the edits writing its unicode characters with typst names if `to` is `"typst"`
+//!
+//! Every method accepts an optional `options` object with `rendering_mode`, `features`, `hidden_categories`,
+//! `symbol_table` and `colors`, like the settings of the extension.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use typst_syntax::Source;
+
+use crate::{decorate, interface::Options, unicode, utils::interner::Interner};
+
+/// A request read from a line
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    method: Method,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "lowercase")]
+enum Method {
+    Parse {
+        content: String,
+        #[serde(default)]
+        options: RequestOptions,
+    },
+    Query {
+        content: String,
+        offset: usize,
+        #[serde(default)]
+        options: RequestOptions,
+    },
+    Convert {
+        content: String,
+        #[serde(default)]
+        start: Option<usize>,
+        #[serde(default)]
+        end: Option<usize>,
+        #[serde(default)]
+        to: Target,
+        #[serde(default)]
+        options: RequestOptions,
+    },
+}
+
+/// Output of a conversion
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Target {
+    #[default]
+    Unicode,
+    Typst,
+}
+
+/// Rendering options of a request, the default options being used for missing fields
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RequestOptions {
+    rendering_mode: Option<u8>,
+    features: HashMap<String, bool>,
+    hidden_categories: Vec<String>,
+    symbol_table: Option<Value>,
+    colors: HashMap<String, String>,
+}
+
+impl RequestOptions {
+    fn build(&self) -> Result<Options, String> {
+        let mut options = Options::default();
+        if let Some(rendering_mode) = self.rendering_mode {
+            options.set_rendering_mode(rendering_mode);
+        }
+        for (name, enabled) in &self.features {
+            options.set_feature(name, *enabled)?;
+        }
+        for name in &self.hidden_categories {
+            options.hide_category(name)?;
+        }
+        if let Some(table) = &self.symbol_table {
+            options.add_symbol_table(&table.to_string())?;
+        }
+        for (name, color) in &self.colors {
+            options.set_color(name, color)?;
+        }
+        Ok(options)
+    }
+}
+
+/// Serialize a result of the library
+fn to_value(value: impl Serialize) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|err| err.to_string())
+}
+
+/// Run a request
+fn execute(method: Method) -> Result<Value, String> {
+    match method {
+        Method::Parse { content, options } => {
+            let options = options.build()?;
+            let source = Source::detached(content);
+            to_value(decorate(
+                &source,
+                None,
+                &options,
+                &mut Interner::default(),
+                None,
+            ))
+        }
+        Method::Query {
+            content,
+            offset,
+            options,
+        } => to_value(crate::symbol_at(&content, offset, &options.build()?)),
+        Method::Convert {
+            content,
+            start,
+            end,
+            to,
+            options,
+        } => {
+            let options = options.build()?;
+            let start = start.unwrap_or(0);
+            let end = end.unwrap_or_else(|| content.encode_utf16().count());
+            let source = Source::detached(content);
+            match to {
+                Target::Unicode => to_value(unicode::to_unicode(&source, start, end, &options)),
+                Target::Typst => to_value(unicode::typstify(&source, start, end)),
+            }
+        }
+    }
+}
+
+/// Answer a line of the protocol, the answer being a single line without the line feed
+pub fn handle_request(line: &str) -> String {
+    let response = match serde_json::from_str::<Request>(line) {
+        Ok(request) => match execute(request.method) {
+            Ok(result) => json!({ "id": request.id, "result": result }),
+            Err(error) => json!({ "id": request.id, "error": error }),
+        },
+        // Still answer with the id if the request could be read that far
+        Err(error) => {
+            let id = serde_json::from_str::<Value>(line)
+                .ok()
+                .and_then(|value| value.get("id").cloned())
+                .unwrap_or(Value::Null);
+            json!({ "id": id, "error": format!("Invalid request: {}", error) })
+        }
+    };
+    response.to_string()
+}
@@ -0,0 +1,139 @@
+//! Semantic tokens of math, classifying the source of equations for themes, even when symbols aren't rendered
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    interface::{Options, SemanticToken, TokenKind},
+    parser::utils::{byte_range_to_utf16, find_symbol, get_category_by_char, utf16_position},
+    unicode::find_equations,
+    utils::{
+        depth::walk,
+        symbols::{Category, OPERATORS},
+    },
+};
+
+/// Check if a character is a greek letter, including the mathematical alphanumeric ones
+fn is_greek(c: char) -> bool {
+    matches!(c, '\u{0391}'..='\u{03A9}' | '\u{03B1}'..='\u{03C9}' | '\u{03D0}'..='\u{03F6}' | '\u{1D6A8}'..='\u{1D7C9}')
+}
+
+/// Get the class of a character from its shape, None if it depends on its category
+fn kind_of_char(c: char) -> Option<TokenKind> {
+    match c {
+        _ if is_greek(c) => Some(TokenKind::Greek),
+        '(' | ')' | '[' | ']' | '{' | '}' | '|' | '‖' | '⟨' | '⟩' | '⌈' | '⌉' | '⌊' | '⌋' | '⟦'
+        | '⟧' => Some(TokenKind::Delimiter),
+        '=' | '<' | '>' => Some(TokenKind::Relation),
+        '+' | '-' | '*' | '/' | '!' => Some(TokenKind::Operator),
+        _ => None,
+    }
+}
+
+/// Get the class of the symbols of a category
+fn kind_of_category(category: Category) -> Option<TokenKind> {
+    match category {
+        Category::Comparison => Some(TokenKind::Relation),
+        Category::Operator | Category::Keyword => Some(TokenKind::Operator),
+        Category::Number => Some(TokenKind::Number),
+        _ => None,
+    }
+}
+
+/// Get the class of a symbol written with a single character or with its name
+fn kind_of_symbol(symbol: &str, category: Option<Category>) -> Option<TokenKind> {
+    let mut chars = symbol.chars();
+    let single = match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    };
+    single
+        .and_then(kind_of_char)
+        .or_else(|| category.and_then(kind_of_category))
+}
+
+/// Get the class of a text leaf of math, like `2.5`, `+` or `α`
+fn kind_of_text(text: &str) -> Option<TokenKind> {
+    if text.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some(TokenKind::Number);
+    }
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => kind_of_symbol(text, get_category_by_char(c)),
+        _ => None,
+    }
+}
+
+/// Get the class of an identifier or a field access of math, like `alpha` or `arrow.r`
+fn kind_of_name(name: &str, options: &Options) -> Option<TokenKind> {
+    if OPERATORS.contains_key(name) {
+        return Some(TokenKind::Function);
+    }
+    let (category, symbol) = find_symbol(name.to_string(), options)?;
+    kind_of_symbol(&symbol, Some(category))
+}
+
+/// Add a token covering a node
+fn push(source: &Source, node: &LinkedNode, kind: TokenKind, tokens: &mut Vec<SemanticToken>) {
+    if let Some(range) = byte_range_to_utf16(source, &node.range()) {
+        tokens.push(SemanticToken {
+            kind,
+            position: utf16_position(source, range),
+        });
+    }
+}
+
+/// Collect the tokens of the math of an equation, skipping the code embedded with `#` \
+/// The nodes deeper than the maximum depth of the options are skipped
+fn collect(
+    source: &Source,
+    equation: &LinkedNode,
+    options: &Options,
+    tokens: &mut Vec<SemanticToken>,
+) {
+    walk(equation, options.max_depth, (), |node, _| {
+        if node.prev_sibling_kind() == Some(SyntaxKind::Hash) {
+            return None;
+        }
+        if node.parent_kind() == Some(SyntaxKind::FuncCall) && node.index() == 0 {
+            if matches!(node.kind(), SyntaxKind::MathIdent | SyntaxKind::FieldAccess) {
+                push(source, node, TokenKind::Function, tokens);
+            }
+            return None;
+        }
+        let kind = match node.kind() {
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                kind_of_name(&node.get().clone().into_text(), options)
+            }
+            SyntaxKind::Text => kind_of_text(node.text()),
+            SyntaxKind::Shorthand => node
+                .cast::<ast::Shorthand>()
+                .and_then(|shorthand| kind_of_text(&shorthand.get().to_string())),
+            SyntaxKind::LeftParen
+            | SyntaxKind::RightParen
+            | SyntaxKind::LeftBracket
+            | SyntaxKind::RightBracket
+            | SyntaxKind::LeftBrace
+            | SyntaxKind::RightBrace => Some(TokenKind::Delimiter),
+            SyntaxKind::Slash => Some(TokenKind::Operator),
+            // Markup and nested equations are found on their own
+            SyntaxKind::Hash | SyntaxKind::ContentBlock | SyntaxKind::Equation => None,
+            _ => return Some(()),
+        };
+        if let Some(kind) = kind {
+            push(source, node, kind, tokens);
+        }
+        None
+    });
+}
+
+/// List the semantic tokens of the equations of a source, sorted by position
+pub fn semantic_tokens(source: &Source, options: &Options) -> Vec<SemanticToken> {
+    let mut equations = vec![];
+    find_equations(&LinkedNode::new(source.root()), &mut equations);
+    let mut tokens = vec![];
+    for equation in equations {
+        collect(source, &equation, options, &mut tokens);
+    }
+    tokens.sort_by_key(|token| token.position.start);
+    tokens
+}
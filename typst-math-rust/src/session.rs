@@ -0,0 +1,276 @@
+//! Long-lived parsing session, keeping the source of a document to reparse only the edited parts
+
+use std::{collections::HashMap, ops::Range};
+
+use typst_syntax::{is_newline, LinkedNode, Source, SyntaxKind};
+#[cfg(not(feature = "coverage"))]
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    decorate, decorate_lines, decorate_streaming, find_definition, find_references, find_symbol_at,
+    interface::{Decoration, Delta, DocumentStatistics, Options, Position, SymbolHover},
+    parser::{parser::DocumentContext, utils::len_utf16},
+    statistics::document_statistics,
+    utils::interner::Interner,
+    Parsed,
+};
+
+/// Source of a document edited incrementally, with the range reparsed since the last decorations
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+pub struct Session {
+    source: Source,
+    reparsed: Option<Range<usize>>,
+    /// Decorations of the last delta, by id
+    decorations: HashMap<u32, Decoration>,
+    version: u32,
+    /// Ids of the uuids, kept between parses so that each uuid is sent only once
+    interner: Interner,
+    /// Context of the document collected by the last parse, kept through the edits which can't change it
+    context: Option<DocumentContext>,
+}
+
+/// Context of a document, collected if no parse kept it since the last edit changing it
+fn document_context<'a>(
+    source: &Source,
+    context: &'a mut Option<DocumentContext>,
+) -> &'a DocumentContext {
+    context.get_or_insert_with(|| DocumentContext::collect(&LinkedNode::new(source.root())))
+}
+
+/// Range of the outermost equation strictly containing a byte range, None if there is none or if it has comments, \
+/// which may be magic comments
+fn equation_around(source: &Source, range: &Range<usize>) -> Option<Range<usize>> {
+    let mut node = LinkedNode::new(source.root());
+    while node.kind() != SyntaxKind::Equation {
+        node = node.children().find(|child| {
+            let bounds = child.range();
+            bounds.start <= range.start && range.end <= bounds.end
+        })?;
+    }
+    let equation = node.range();
+    if equation.start >= range.start || range.end >= equation.end {
+        return None;
+    }
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if matches!(
+            node.kind(),
+            SyntaxKind::LineComment | SyntaxKind::BlockComment
+        ) {
+            return None;
+        }
+        stack.extend(node.children());
+    }
+    Some(equation)
+}
+
+#[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+impl Session {
+    /// Create a session from the content of a document
+    #[cfg_attr(not(feature = "coverage"), wasm_bindgen(constructor))]
+    pub fn new(content: &str) -> Session {
+        Session {
+            source: Source::detached(content.to_string()),
+            reparsed: None,
+            decorations: HashMap::new(),
+            version: 0,
+            interner: Interner::default(),
+            context: None,
+        }
+    }
+    /// Replace a range of the document, given in UTF-16 offsets like VSCode positions, with a text \
+    /// Only the part of the syntax tree around the edit is reparsed by typst, and the context of the document is kept
+    /// if the edit stays inside an equation without comments, which keeps its bounds
+    pub fn edit(&mut self, start: usize, end: usize, text: &str) {
+        let len = self.source.len_bytes();
+        let start = self.source.utf16_to_byte(start).unwrap_or(len);
+        let end = self.source.utf16_to_byte(end).unwrap_or(len).max(start);
+        let equation = self
+            .context
+            .as_ref()
+            .and_then(|_| equation_around(&self.source, &(start..end)));
+        let reparsed = self.source.edit(start..end, text);
+        // Move the range reparsed by previous edits
+        let shift = |position: usize| {
+            if position <= start {
+                position
+            } else if position >= end {
+                position - (end - start) + text.len()
+            } else {
+                start + text.len()
+            }
+        };
+        self.reparsed = Some(match self.reparsed.take() {
+            Some(previous) => {
+                shift(previous.start).min(reparsed.start)..shift(previous.end).max(reparsed.end)
+            }
+            None => reparsed,
+        });
+        // Other edits may add or remove bindings, imports, rules and magic comments, the context is collected again
+        let unchanged = equation.is_some_and(|equation| {
+            equation_around(&self.source, &(start..start + text.len()))
+                == Some(equation.start..shift(equation.end))
+        });
+        match (&mut self.context, unchanged) {
+            (Some(context), true) => context.shift(shift),
+            _ => self.context = None,
+        }
+    }
+    /// Get the UTF-16 offset of a line and a UTF-16 column, like the position of an LSP client \
+    /// Columns after the end of the line give the end of the line, before its line break
+    pub fn offset_at(&self, line: usize, column: usize) -> Option<usize> {
+        let range = self.source.line_to_range(line)?;
+        let text = self.source.get(range.clone())?;
+        let length = len_utf16(text.trim_end_matches(is_newline));
+        Some(self.source.byte_to_utf16(range.start)? + column.min(length))
+    }
+    /// Get the decorations of the entire document \
+    /// This result and the one of `parse_edits` aren't tracked, the next delta will be a reset
+    pub fn parse(&mut self, options: &Options) -> Parsed {
+        self.reparsed = None;
+        self.version = self.version.wrapping_add(1);
+        let context = document_context(&self.source, &mut self.context);
+        decorate(
+            &self.source,
+            None,
+            options,
+            &mut self.interner,
+            Some(context),
+        )
+    }
+    /// Get the decorations of the part reparsed since the last decorations, or of the entire document if nothing was edited
+    pub fn parse_edits(&mut self, options: &Options) -> Parsed {
+        let reparsed = self.reparsed.take();
+        self.version = self.version.wrapping_add(1);
+        let context = document_context(&self.source, &mut self.context);
+        let parsed = decorate(
+            &self.source,
+            reparsed.clone(),
+            options,
+            &mut self.interner,
+            Some(context),
+        );
+        // The edits of a cancelled parse are decorated by the next one
+        if parsed.cancelled {
+            self.reparsed = reparsed;
+        }
+        parsed
+    }
+    /// Call `callback` with the decorations of each top-level node of the document, from the top of the document \
+    /// Like `parse`, the result isn't tracked and the next delta will be a reset
+    #[cfg(not(feature = "coverage"))]
+    pub fn parse_streaming(&mut self, options: &Options, callback: &js_sys::Function) {
+        self.stream(options, |parsed| {
+            let _ = callback.call1(&JsValue::NULL, &JsValue::from(parsed));
+        });
+    }
+    /// Get the decorations of the lines from `start_line` to `end_line` (included), without changing the range reparsed since the last decorations
+    pub fn parse_range(&mut self, start_line: usize, end_line: usize, options: &Options) -> Parsed {
+        let context = document_context(&self.source, &mut self.context);
+        decorate_lines(
+            &self.source,
+            start_line,
+            end_line,
+            options,
+            &mut self.interner,
+            Some(context),
+        )
+    }
+    /// Get the changes of the decorations of the entire document since the result of the given version \
+    /// If the version isn't the one of the last delta, the delta is computed from an empty result and `reset` is set
+    pub fn parse_delta(&mut self, options: &Options, version: u32) -> Delta {
+        self.reparsed = None;
+        let context = document_context(&self.source, &mut self.context);
+        let parsed = decorate(
+            &self.source,
+            None,
+            options,
+            &mut self.interner,
+            Some(context),
+        );
+        let reset = version != self.version;
+        // A cancelled parse keeps the previous result as the reference of the next delta
+        if parsed.cancelled {
+            return Delta {
+                version,
+                reset: false,
+                added: vec![],
+                removed: vec![],
+                unchanged: vec![],
+                uuids: parsed.uuids,
+                uuids_start: parsed.uuids_start,
+                erroneous: parsed.erroneous,
+                diagnostics: parsed.diagnostics,
+                suggestions: parsed.suggestions,
+                lints: parsed.lints,
+                truncated: parsed.truncated,
+                cancelled: true,
+            };
+        }
+        let mut previous = std::mem::take(&mut self.decorations);
+        if reset {
+            previous.clear();
+        }
+        let mut added = vec![];
+        let mut unchanged = vec![];
+        for decoration in parsed.decorations {
+            match previous.remove(&decoration.id) {
+                Some(old) if old == decoration => unchanged.push(decoration.id),
+                _ => added.push(decoration.clone()),
+            }
+            self.decorations.insert(decoration.id, decoration);
+        }
+        self.version = self.version.wrapping_add(1);
+        Delta {
+            version: self.version,
+            reset,
+            added,
+            removed: previous.into_keys().collect(),
+            unchanged,
+            uuids: parsed.uuids,
+            uuids_start: parsed.uuids_start,
+            erroneous: parsed.erroneous,
+            diagnostics: parsed.diagnostics,
+            suggestions: parsed.suggestions,
+            lints: parsed.lints,
+            truncated: parsed.truncated,
+            cancelled: false,
+        }
+    }
+    /// Get the symbol rendered at a UTF-16 offset of the document, None if there is no decoration there
+    pub fn symbol_at(&self, offset: usize, options: &Options) -> Option<SymbolHover> {
+        find_symbol_at(&self.source, offset, options, self.context.as_ref())
+    }
+    /// Get where the identifier of math at a UTF-16 offset of the document is declared, None if it isn't bound by the user
+    pub fn definition_at(&self, offset: usize) -> Option<Position> {
+        find_definition(&self.source, offset)
+    }
+    /// Get the spans of the identifiers to rename with the identifier of math at a UTF-16 offset of the document
+    pub fn rename_locations(&self, offset: usize) -> Vec<Position> {
+        find_references(&self.source, offset)
+    }
+    /// Get the statistics of the math of the document: equations, symbols by frequency and deepest nesting
+    pub fn statistics(&self, options: &Options) -> DocumentStatistics {
+        document_statistics(&self.source, options)
+    }
+    /// Get the content of the document
+    pub fn text(&self) -> String {
+        self.source.text().to_string()
+    }
+}
+
+impl Session {
+    /// Give the decorations of each top-level node of the document to `emit`, from the top of the document
+    pub fn stream(&mut self, options: &Options, mut emit: impl FnMut(Parsed)) {
+        self.reparsed = None;
+        self.version = self.version.wrapping_add(1);
+        let context = document_context(&self.source, &mut self.context);
+        decorate_streaming(
+            &self.source,
+            options,
+            &mut self.interner,
+            Some(context),
+            &mut emit,
+        );
+    }
+}
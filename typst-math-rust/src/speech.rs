@@ -0,0 +1,444 @@
+//! Spoken readings of equations, like `x squared plus y sub i`, for screen readers
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    convert::{
+        attachment, call_arguments, fraction_parts, function_name, matrix_rows, parenthesized,
+    },
+    interface::Options,
+    parser::utils::{find_symbol, names_for_char},
+    unicode::equation_at,
+    utils::{depth::DepthLimit, symbols::OPERATORS},
+};
+
+/// Get the reading of a symbol character, None to read its typst name
+fn spoken_char(c: char) -> Option<&'static str> {
+    Some(match c {
+        '+' => "plus",
+        '-' | '−' => "minus",
+        '±' => "plus or minus",
+        '*' | '∗' | '×' | '⋅' | '·' => "times",
+        '/' | '÷' => "divided by",
+        '=' => "equals",
+        '≠' => "is not equal to",
+        '<' => "is less than",
+        '>' => "is greater than",
+        '≤' => "is less than or equal to",
+        '≥' => "is greater than or equal to",
+        '≈' => "is approximately equal to",
+        '≡' => "is equivalent to",
+        '∝' => "is proportional to",
+        '∈' => "is in",
+        '∉' => "is not in",
+        '⊂' => "is a subset of",
+        '⊆' => "is a subset of or equal to",
+        '∪' => "union",
+        '∩' => "intersection",
+        '∖' => "minus",
+        '→' => "to",
+        '⇒' => "implies",
+        '⇔' => "if and only if",
+        '↦' => "maps to",
+        '∀' => "for all",
+        '∃' => "there exists",
+        '¬' => "not",
+        '∧' => "and",
+        '∨' => "or",
+        '∞' => "infinity",
+        '∂' => "partial",
+        '∇' => "nabla",
+        '∑' => "sum",
+        '∏' => "product",
+        '∫' => "integral",
+        '∬' => "double integral",
+        '∭' => "triple integral",
+        '∮' => "contour integral",
+        '∅' => "empty set",
+        '…' | '⋯' => "dot dot dot",
+        ',' => "comma",
+        '!' => "factorial",
+        '′' => "prime",
+        'ℕ' => "N",
+        'ℤ' => "Z",
+        'ℚ' => "Q",
+        'ℝ' => "R",
+        'ℂ' => "C",
+        '(' => "open paren",
+        ')' => "close paren",
+        '[' => "open bracket",
+        ']' => "close bracket",
+        '{' => "open brace",
+        '}' => "close brace",
+        '|' => "vertical bar",
+        '‖' => "double vertical bar",
+        '⟨' => "open angle",
+        '⟩' => "close angle",
+        _ => return None,
+    })
+}
+
+/// Get the reading of a typst name, its modifiers being separated by spaces (`arrow.r.double` -> `arrow r double`)
+fn spoken_name(name: &str) -> String {
+    name.replace('.', " ")
+}
+
+/// Get the reading of a text or a rendered symbol, like `α` -> `alpha` or `≤` -> `is less than or equal to`
+fn spoken_text(text: &str) -> String {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => match spoken_char(c) {
+            Some(spoken) => spoken.to_string(),
+            None if c.is_ascii_alphanumeric() => text.to_string(),
+            None => names_for_char(c)
+                .first()
+                .map_or_else(|| text.to_string(), |name| spoken_name(name)),
+        },
+        _ => text.to_string(),
+    }
+}
+
+/// Get the reading of a power with a single token, like `squared` or `to the power n`
+fn spoken_power(script: &str) -> String {
+    match script {
+        "2" => "squared".to_string(),
+        "3" => "cubed".to_string(),
+        _ => format!("to the power {}", script),
+    }
+}
+
+/// Get the reading of the index of a root, like `cube root of`
+fn spoken_root(index: &str) -> String {
+    match index {
+        "2" => "square root of".to_string(),
+        "3" => "cube root of".to_string(),
+        _ => format!("{}-th root of", index),
+    }
+}
+
+/// Reading of a node, with the number of its tokens to know if it needs to be delimited, like in `x sub i plus 1 end sub`
+struct Spoken {
+    text: String,
+    simple: bool,
+}
+
+impl Spoken {
+    fn new(text: String, simple: bool) -> Spoken {
+        Spoken { text, simple }
+    }
+
+    /// Read the node between a start and an end, unless it is a single token
+    fn delimited(self, start: &str, end: &str) -> String {
+        if self.simple {
+            format!("{} {}", start, self.text)
+        } else {
+            format!("{} {} {}", start, self.text, end)
+        }
+    }
+}
+
+/// Reader of the math of an equation
+struct Reader<'a> {
+    options: &'a Options,
+    /// Depth of the node being read, the nodes deeper than the maximum depth being skipped
+    limit: DepthLimit,
+}
+
+impl Reader<'_> {
+    /// Read the children of a node, line breaks being read as new lines
+    fn sequence(&self, node: &LinkedNode) -> Spoken {
+        let children: Vec<LinkedNode> = node.children().collect();
+        self.join(&children)
+    }
+
+    /// Read nodes one after the other, skipping the code embedded with `#`
+    fn join(&self, children: &[LinkedNode]) -> Spoken {
+        let mut words: Vec<Spoken> = vec![];
+        let mut embedded = false;
+        for (index, child) in children.iter().enumerate() {
+            if std::mem::take(&mut embedded) {
+                continue;
+            }
+            match child.kind() {
+                SyntaxKind::Hash => embedded = true,
+                SyntaxKind::Linebreak => words.push(Spoken::new("new line".to_string(), true)),
+                // Function applications written with a letter, like `f(x)`
+                SyntaxKind::MathDelimited
+                    if child
+                        .children()
+                        .next()
+                        .is_some_and(|open| open.text() == "(")
+                        && index > 0
+                        && children[index - 1].kind() == SyntaxKind::Text
+                        && children[index - 1].text().chars().all(char::is_alphabetic) =>
+                {
+                    let argument = self.unparen(child).delimited("of", "end");
+                    words.push(Spoken::new(argument, false));
+                }
+                _ => words.extend(self.read(child)),
+            }
+        }
+        match words.len() {
+            1 => words.remove(0),
+            _ => Spoken::new(
+                words
+                    .into_iter()
+                    .map(|spoken| spoken.text)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                false,
+            ),
+        }
+    }
+
+    /// Read a node, None if it isn't read like spaces or if it is deeper than the maximum depth
+    fn read(&self, node: &LinkedNode) -> Option<Spoken> {
+        if !self.limit.enter() {
+            return None;
+        }
+        let spoken = self.read_node(node);
+        self.limit.leave();
+        spoken
+    }
+
+    fn read_node(&self, node: &LinkedNode) -> Option<Spoken> {
+        Some(match node.kind() {
+            SyntaxKind::Math => self.sequence(node),
+            SyntaxKind::Text => Spoken::new(spoken_text(node.text()), true),
+            SyntaxKind::Str => Spoken::new(node.cast::<ast::Str>()?.get().to_string(), true),
+            SyntaxKind::Escape => Spoken::new(
+                spoken_text(&node.cast::<ast::Escape>()?.get().to_string()),
+                true,
+            ),
+            SyntaxKind::Shorthand => Spoken::new(
+                spoken_text(&node.cast::<ast::Shorthand>()?.get().to_string()),
+                true,
+            ),
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                Spoken::new(self.name(&node.get().clone().into_text()), true)
+            }
+            SyntaxKind::MathDelimited => Spoken::new(self.sequence(node).text, false),
+            SyntaxKind::MathAttach => self.attach(node),
+            SyntaxKind::MathPrimes => Spoken::new(
+                match node.cast::<ast::MathPrimes>()?.count() {
+                    1 => "prime".to_string(),
+                    2 => "double prime".to_string(),
+                    3 => "triple prime".to_string(),
+                    count => format!("{} primes", count),
+                },
+                true,
+            ),
+            SyntaxKind::MathFrac => {
+                let (num, denom) = fraction_parts(node)?;
+                self.fraction(self.unparen(&num), self.unparen(&denom))
+            }
+            SyntaxKind::MathRoot => {
+                let radicand = self.unparen(&node.children().last()?);
+                let index = node
+                    .cast::<ast::MathRoot>()?
+                    .index()
+                    .map_or_else(|| "2".to_string(), |index| index.to_string());
+                Spoken::new(radicand.delimited(&spoken_root(&index), "end root"), false)
+            }
+            SyntaxKind::FuncCall => self.call(node),
+            SyntaxKind::Equation => self.sequence(
+                &node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::Math)?,
+            ),
+            _ => return None,
+        })
+    }
+
+    /// Read an identifier or a field access, like `alpha`, `arrow.r` or `sin`
+    fn name(&self, name: &str) -> String {
+        if OPERATORS.contains_key(name) {
+            return match name {
+                "lim" => "limit".to_string(),
+                "ln" => "natural log".to_string(),
+                _ => name.to_string(),
+            };
+        }
+        match find_symbol(name.to_string(), self.options) {
+            Some((_, symbol)) => {
+                let mut chars = symbol.chars();
+                match (chars.next().and_then(spoken_char), chars.next()) {
+                    (Some(spoken), None) => spoken.to_string(),
+                    _ => spoken_name(name),
+                }
+            }
+            None => spoken_name(name),
+        }
+    }
+
+    /// Read a script or an argument, the parentheses grouping it being removed like typst does (`x^(a + b)`)
+    fn unparen(&self, node: &LinkedNode) -> Spoken {
+        if let Some(children) = parenthesized(node) {
+            return self.join(&children);
+        }
+        self.read(node)
+            .unwrap_or_else(|| Spoken::new(String::new(), true))
+    }
+
+    fn fraction(&self, num: Spoken, denom: Spoken) -> Spoken {
+        if num.simple && denom.simple {
+            Spoken::new(format!("{} over {}", num.text, denom.text), false)
+        } else {
+            Spoken::new(
+                format!("fraction {} over {} end fraction", num.text, denom.text),
+                false,
+            )
+        }
+    }
+
+    /// Check if the attachments of a base are read as bounds, like `sum from i equals 1 to n`
+    fn has_bounds(&self, base: &LinkedNode) -> bool {
+        let text = match base.kind() {
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                let name = base.get().clone().into_text();
+                if OPERATORS.get(name.as_str()) == Some(&true) {
+                    return true;
+                }
+                match find_symbol(name.to_string(), self.options) {
+                    Some((_, symbol)) => symbol,
+                    None => return false,
+                }
+            }
+            SyntaxKind::Text => base.text().to_string(),
+            _ => return false,
+        };
+        text.starts_with(['∑', '∏', '∐', '⋂', '⋃', '∫', '∬', '∭', '∮'])
+    }
+
+    /// Read an attachment, like `x squared`, `y sub i` or `sum from i equals 1 to n`
+    fn attach(&self, node: &LinkedNode) -> Spoken {
+        let Some(attachment) = attachment(node) else {
+            return Spoken::new(String::new(), true);
+        };
+        let base = attachment.base;
+        let bottom = attachment.bottom.map(|script| self.unparen(&script));
+        let top = attachment.top.map(|script| self.unparen(&script));
+        let primes = attachment.primes.and_then(|primes| self.read(&primes));
+        let bounds = self.has_bounds(&base);
+        let mut words = vec![self
+            .read(&base)
+            .map(|spoken| spoken.text)
+            .unwrap_or_default()];
+        if let Some(primes) = primes {
+            words.push(primes.text);
+        }
+        if bounds {
+            if let Some(bottom) = bottom {
+                words.push(format!("from {}", bottom.text));
+            }
+            if let Some(top) = top {
+                words.push(format!("to {}", top.text));
+            }
+        } else {
+            if let Some(bottom) = bottom {
+                words.push(bottom.delimited("sub", "end sub"));
+            }
+            if let Some(top) = top {
+                words.push(if top.simple {
+                    spoken_power(&top.text)
+                } else {
+                    top.delimited("to the power", "end power")
+                });
+            }
+        }
+        Spoken::new(words.join(" "), false)
+    }
+
+    /// Read the rows of a matrix, like `row 1: 1, 2; row 2: 3, 4`
+    fn rows(&self, rows: &[Vec<LinkedNode>]) -> String {
+        rows.iter()
+            .enumerate()
+            .map(|(index, cells)| {
+                let cells: Vec<String> = cells.iter().map(|cell| self.unparen(cell).text).collect();
+                format!("row {}: {}", index + 1, cells.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Read a function call, like `frac(a, b)`, `hat(x)` or `mat(1, 2; 3, 4)`
+    fn call(&self, node: &LinkedNode) -> Spoken {
+        let name = function_name(node).unwrap_or_default();
+        let args = call_arguments(node);
+        let arg = |index: usize| {
+            args.get(index)
+                .map_or_else(|| Spoken::new(String::new(), true), |arg| self.unparen(arg))
+        };
+        let text = match name.as_str() {
+            "frac" => return self.fraction(arg(0), arg(1)),
+            "binom" => format!("{} choose {}", arg(0).text, arg(1).text),
+            "sqrt" => arg(0).delimited("square root of", "end root"),
+            "root" => arg(1).delimited(&spoken_root(&arg(0).text), "end root"),
+            "abs" => arg(0).delimited("absolute value of", "end absolute value"),
+            "norm" => arg(0).delimited("norm of", "end norm"),
+            "floor" => arg(0).delimited("floor of", "end floor"),
+            "ceil" => arg(0).delimited("ceiling of", "end ceiling"),
+            "hat" | "tilde" | "dot" | "caron" | "breve" | "acute" | "grave" => {
+                format!("{} {}", arg(0).text, name)
+            }
+            "dot.double" | "diaer" => format!("{} double dot", arg(0).text),
+            "overline" | "macron" => format!("{} bar", arg(0).text),
+            "arrow" => format!("vector {}", arg(0).text),
+            "underline" => format!("{} underlined", arg(0).text),
+            "overbrace" | "underbrace" => match args.get(1) {
+                Some(_) => format!("{}, labeled {}", arg(0).text, arg(1).text),
+                None => arg(0).text,
+            },
+            "cancel" => format!("{} cancelled", arg(0).text),
+            "bb" => format!("double-struck {}", arg(0).text),
+            "cal" => format!("script {}", arg(0).text),
+            "frak" => format!("fraktur {}", arg(0).text),
+            "bold" => format!("bold {}", arg(0).text),
+            "op" | "upright" | "italic" | "sans" | "mono" | "lr" | "mid" | "display" | "inline"
+            | "script" | "cramped" | "limits" | "scripts" => arg(0).text,
+            "vec" => {
+                let cells: Vec<String> = args.iter().map(|arg| self.unparen(arg).text).collect();
+                format!("vector {} end vector", cells.join(", "))
+            }
+            "cases" => {
+                let cases: Vec<String> = args.iter().map(|arg| self.unparen(arg).text).collect();
+                format!("cases: {} end cases", cases.join("; "))
+            }
+            "mat" => {
+                let rows = matrix_rows(&args);
+                let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+                format!(
+                    "{} by {} matrix, {}, end matrix",
+                    rows.len(),
+                    columns,
+                    self.rows(&rows)
+                )
+            }
+            // Other functions are applied to their arguments, like `sin of x`
+            _ => {
+                let arguments: Vec<String> =
+                    args.iter().map(|arg| self.unparen(arg).text).collect();
+                let simple = args.len() == 1 && self.unparen(&args[0]).simple;
+                let function = self.name(&name);
+                if simple {
+                    format!("{} of {}", function, arguments.join(", "))
+                } else {
+                    format!("{} of {} end", function, arguments.join(", "))
+                }
+            }
+        };
+        Spoken::new(text, false)
+    }
+}
+
+/// Read the equation around a UTF-16 offset of a source aloud, like `x squared plus y sub i`, None if there is no equation there
+pub fn speak(source: &Source, offset: usize, options: &Options) -> Option<String> {
+    let offset = source.utf16_to_byte(offset)?;
+    let equation = equation_at(source, offset)?;
+    let reader = Reader {
+        options,
+        limit: DepthLimit::new(options.max_depth),
+    };
+    let spoken = reader.read(&equation)?;
+    Some(spoken.text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
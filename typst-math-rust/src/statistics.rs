@@ -0,0 +1,162 @@
+//! Statistics of the math of a document: equations, symbols used and how they are written, deepest nesting
+
+use std::collections::HashMap;
+
+use typst_syntax::{ast, LinkedNode, Source, SyntaxKind};
+
+use crate::{
+    interface::{CategoryCount, DocumentStatistics, Options, SymbolCount},
+    parser::utils::{find_symbol, get_category_by_char},
+    unicode::find_equations,
+    utils::{depth::walk, symbols::Category},
+};
+
+/// Uses of a symbol, counted by spelling
+struct Uses {
+    category: Category,
+    spellings: HashMap<String, usize>,
+}
+
+/// Symbols used in the equations of a document, by rendered symbol
+#[derive(Default)]
+struct Counter {
+    symbols: HashMap<String, Uses>,
+    /// Some nodes were deeper than the maximum depth and weren't counted
+    truncated: bool,
+}
+
+impl Counter {
+    fn add(&mut self, symbol: String, category: Category, spelling: String) {
+        let uses = self.symbols.entry(symbol).or_insert_with(|| Uses {
+            category,
+            spellings: HashMap::new(),
+        });
+        *uses.spellings.entry(spelling).or_default() += 1;
+    }
+
+    /// Count a text or a shorthand rendered as a single known character, like `α` or `->`
+    fn add_char(&mut self, rendered: &str, spelling: String) {
+        let mut chars = rendered.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if let Some(category) = get_category_by_char(c) {
+                self.add(rendered.to_string(), category, spelling);
+            }
+        }
+    }
+}
+
+/// Check if a node increases the nesting of an equation
+fn is_nesting(node: &LinkedNode) -> bool {
+    matches!(
+        node.kind(),
+        SyntaxKind::MathAttach
+            | SyntaxKind::MathFrac
+            | SyntaxKind::MathRoot
+            | SyntaxKind::MathDelimited
+            | SyntaxKind::FuncCall
+    )
+}
+
+/// Count the symbols of the math of an equation, skipping the code embedded with `#` \
+/// Return the deepest nesting in the equation, the nodes deeper than the maximum depth of the options being skipped
+fn collect(equation: &LinkedNode, options: &Options, counter: &mut Counter) -> usize {
+    let mut max_nesting = 0;
+    // The value of a node is the number of nodes nesting it in the equation, itself included
+    let truncated = walk(equation, options.max_depth, 0, |node, nesting| {
+        let embedded = node.prev_sibling_kind() == Some(SyntaxKind::Hash);
+        // The callee of a function is a function, not a symbol
+        let callee = node.parent_kind() == Some(SyntaxKind::FuncCall) && node.index() == 0;
+        if embedded || callee {
+            return None;
+        }
+        match node.kind() {
+            SyntaxKind::MathIdent | SyntaxKind::FieldAccess => {
+                let name = node.get().clone().into_text().to_string();
+                if let Some((category, symbol)) = find_symbol(name.clone(), options) {
+                    counter.add(symbol, category, name);
+                }
+                None
+            }
+            SyntaxKind::Text => {
+                counter.add_char(node.text(), node.text().to_string());
+                None
+            }
+            SyntaxKind::Shorthand => {
+                if let Some(shorthand) = node.cast::<ast::Shorthand>() {
+                    counter.add_char(&shorthand.get().to_string(), node.text().to_string());
+                }
+                None
+            }
+            // Markup and nested equations are found on their own
+            SyntaxKind::ContentBlock | SyntaxKind::Equation => None,
+            _ => {
+                let nesting = nesting + usize::from(is_nesting(node));
+                max_nesting = max_nesting.max(nesting);
+                Some(nesting)
+            }
+        }
+    });
+    counter.truncated |= truncated;
+    max_nesting
+}
+
+/// Get the statistics of the equations of a source: symbols by frequency, with their spellings, and deepest nesting
+pub fn document_statistics(source: &Source, options: &Options) -> DocumentStatistics {
+    let mut equations = vec![];
+    find_equations(&LinkedNode::new(source.root()), &mut equations);
+    let mut counter = Counter::default();
+    let mut max_nesting = 0;
+    for equation in &equations {
+        max_nesting = max_nesting.max(collect(equation, options, &mut counter));
+    }
+
+    let mut symbols: Vec<SymbolCount> = counter
+        .symbols
+        .into_iter()
+        .map(|(symbol, uses)| {
+            let mut spellings: Vec<(String, usize)> = uses.spellings.into_iter().collect();
+            spellings.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            SymbolCount {
+                symbol,
+                category: uses.category,
+                count: spellings.iter().map(|(_, count)| count).sum(),
+                spellings: spellings
+                    .into_iter()
+                    .map(|(spelling, _)| spelling)
+                    .collect(),
+            }
+        })
+        .collect();
+    symbols.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.symbol.cmp(&b.symbol)));
+
+    let mut categories: Vec<CategoryCount> = vec![];
+    for symbol in &symbols {
+        match categories
+            .iter_mut()
+            .find(|count| count.category == symbol.category)
+        {
+            Some(count) => count.count += symbol.count,
+            None => categories.push(CategoryCount {
+                category: symbol.category,
+                count: symbol.count,
+            }),
+        }
+    }
+    categories.sort_by_key(|count| std::cmp::Reverse(count.count));
+
+    DocumentStatistics {
+        block_equations: equations
+            .iter()
+            .filter(|equation| {
+                equation
+                    .cast::<ast::Equation>()
+                    .is_some_and(|equation| equation.block())
+            })
+            .count(),
+        equations: equations.len(),
+        max_nesting,
+        symbols,
+        categories,
+        truncated: counter.truncated,
+    }
+}
@@ -0,0 +1,449 @@
+//! Conversion between typst math and plain unicode text, to paste equations outside of typst or import them
+
+use std::ops::Range;
+
+use typst_syntax::{ast, split_newlines, LinkedNode, Source, SyntaxKind};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    decorate, decorate_lines,
+    interface::{EquationInfo, EquationSymbol, Options, Parsed, TextEdit},
+    parser::utils::{byte_range_to_utf16, names_for_char, subscript, superscript, utf16_position},
+    utils::interner::Interner,
+};
+
+/// Attachment of a decoration, from the prefix of its uuid
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Attachment {
+    Top,
+    Bottom,
+    None,
+}
+
+/// Get the attachment of a decoration uuid, ignoring the prefixes of a surrounding `text(fill: ...)` and of show rules
+fn attachment(uuid: &str) -> Attachment {
+    let uuid = uuid
+        .strip_prefix("fill-")
+        .and_then(|rest| rest.split_once('-'))
+        .map_or(uuid, |(_, rest)| rest);
+    let uuid = uuid
+        .strip_prefix("style(")
+        .and_then(|rest| rest.split_once(")-"))
+        .map_or(uuid, |(_, rest)| rest);
+    if uuid.starts_with("top-") {
+        Attachment::Top
+    } else if uuid.starts_with("bottom-") || uuid.starts_with("under-") {
+        Attachment::Bottom
+    } else {
+        Attachment::None
+    }
+}
+
+/// Write an attachment with unicode superscripts or subscripts, or with `^` and `_` if a character has none
+fn attach(symbol: &str, marker: char, convert: fn(char) -> Option<char>) -> String {
+    match symbol.chars().map(convert).collect::<Option<String>>() {
+        Some(text) => text,
+        // A letter with combining accents or an emoji sequence is a single grapheme and needs no parentheses
+        None if symbol.graphemes(true).nth(1).is_some() => format!("{}({})", marker, symbol),
+        None => format!("{}{}", marker, symbol),
+    }
+}
+
+/// Collect the byte offsets of the equation delimiters in a range, with an explicit stack for deeply nested documents
+fn find_dollars(node: &LinkedNode, range: &Range<usize>, dollars: &mut Vec<usize>) {
+    let mut stack: Vec<LinkedNode> = node.children().rev().collect();
+    while let Some(child) = stack.pop() {
+        let child_range = child.range();
+        if child_range.end <= range.start || child_range.start >= range.end {
+            continue;
+        }
+        if child.kind() == SyntaxKind::Dollar {
+            dollars.push(child_range.start);
+        } else {
+            stack.extend(child.children().rev());
+        }
+    }
+}
+
+/// Get the plain text of a decoration, attachments being written with superscripts and subscripts instead of css
+pub fn plain_symbol(uuid: &str, symbol: &str) -> String {
+    match attachment(uuid) {
+        Attachment::Top => attach(symbol, '^', superscript),
+        Attachment::Bottom => attach(symbol, '_', subscript),
+        Attachment::None => symbol.to_string(),
+    }
+}
+
+/// Write consecutive symbols of an attachment with the whitespace between them, with unicode scripts if they all have one, \
+/// or in parentheses after `^` or `_` like `e^(i π)`
+fn plain_run(attachment: Attachment, symbols: &[(&str, &str)]) -> String {
+    let text: String = symbols
+        .iter()
+        .map(|(gap, symbol)| format!("{gap}{symbol}"))
+        .collect();
+    let (marker, convert): (char, fn(char) -> Option<char>) = match attachment {
+        Attachment::Top => ('^', superscript),
+        Attachment::Bottom => ('_', subscript),
+        Attachment::None => return text,
+    };
+    if symbols.len() == 1 {
+        return attach(&text, marker, convert);
+    }
+    let scripts: Option<String> = text
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() {
+                Some(c)
+            } else {
+                convert(c)
+            }
+        })
+        .collect();
+    scripts.unwrap_or_else(|| format!("{}({})", marker, text))
+}
+
+/// Get the byte ranges of the decorations and their unicode text, sorted by start and from the longest \
+/// Symbols of an attachment only separated by whitespace are written together, like the ones of `x^(2n+1)`
+fn replacements(source: &Source, parsed: &Parsed) -> Vec<(usize, usize, String)> {
+    let mut symbols = vec![];
+    for decoration in &parsed.decorations {
+        let uuid = parsed.uuid(decoration.id).unwrap_or_default();
+        let attachment = attachment(&uuid);
+        for position in &decoration.positions {
+            let (Some(from), Some(to)) = (
+                source.utf16_to_byte(position.start),
+                source.utf16_to_byte(position.end),
+            ) else {
+                continue;
+            };
+            symbols.push((from, to, attachment, decoration.symbol.as_str()));
+        }
+    }
+    symbols.sort_by_key(|(from, to, _, _)| (*from, std::cmp::Reverse(*to)));
+
+    let text = source.text();
+    let mut replacements = vec![];
+    let mut index = 0;
+    while index < symbols.len() {
+        let (from, mut to, attachment, symbol) = symbols[index];
+        let mut run = vec![("", symbol)];
+        index += 1;
+        while let Some(&(next_from, next_to, next_attachment, next_symbol)) = symbols.get(index) {
+            let Some(gap) = text.get(to..next_from) else {
+                break;
+            };
+            if attachment == Attachment::None
+                || next_attachment != attachment
+                || !gap.trim().is_empty()
+            {
+                break;
+            }
+            run.push((gap, next_symbol));
+            to = next_to;
+            index += 1;
+        }
+        replacements.push((from, to, plain_run(attachment, &run)));
+    }
+    replacements
+}
+
+/// Write the unicode text of a byte range, the first replacement winning when they overlap
+fn write_unicode(
+    source: &Source,
+    range: Range<usize>,
+    replacements: &[(usize, usize, String)],
+) -> String {
+    let mut dollars = vec![];
+    let root = LinkedNode::new(source.root());
+    find_dollars(&root, &range, &mut dollars);
+
+    let text = source.text();
+    let mut result = String::new();
+    let mut cursor = range.start;
+    let copy = |result: &mut String, from: usize, to: usize| {
+        for (offset, c) in text[from..to].char_indices() {
+            if !dollars.contains(&(from + offset)) {
+                result.push(c);
+            }
+        }
+    };
+    for (from, to, symbol) in replacements {
+        if *from < cursor || *to > range.end {
+            continue;
+        }
+        copy(&mut result, cursor, *from);
+        result.push_str(symbol);
+        cursor = *to;
+    }
+    copy(&mut result, cursor, range.end);
+    result.trim().to_string()
+}
+
+/// Convert the typst math between two UTF-16 offsets into unicode text, \
+/// symbols being replaced by their character and attachments by superscripts and subscripts when possible
+pub fn to_unicode(source: &Source, start: usize, end: usize, options: &Options) -> String {
+    let len = source.len_bytes();
+    let start = source.utf16_to_byte(start).unwrap_or(len);
+    let end = source.utf16_to_byte(end).unwrap_or(len).max(start);
+    let (Some(start_line), Some(end_line)) = (source.byte_to_line(start), source.byte_to_line(end))
+    else {
+        return String::new();
+    };
+    let parsed = decorate_lines(
+        source,
+        start_line,
+        end_line,
+        options,
+        &mut Interner::default(),
+        None,
+    );
+    write_unicode(source, start..end, &replacements(source, &parsed))
+}
+
+/// Collect the equations of a node in the order of the document, including the ones nested in other equations \
+/// The tree is walked with an explicit stack, so that deeply nested documents don't overflow the stack
+pub fn find_equations<'a>(node: &LinkedNode<'a>, equations: &mut Vec<LinkedNode<'a>>) {
+    let mut stack: Vec<LinkedNode> = node.children().rev().collect();
+    while let Some(child) = stack.pop() {
+        if child.kind() == SyntaxKind::Equation {
+            equations.push(child.clone());
+        }
+        stack.extend(child.children().rev());
+    }
+}
+
+/// Find the outermost equation around a byte offset of a source, its end included
+pub fn equation_at(source: &Source, offset: usize) -> Option<LinkedNode<'_>> {
+    let mut equations = vec![];
+    find_equations(&LinkedNode::new(source.root()), &mut equations);
+    equations
+        .into_iter()
+        .find(|equation| equation.range().contains(&offset) || equation.range().end == offset)
+}
+
+/// List the equations of a source in the order of the document, with their unicode rendering on a single line
+pub fn equations(source: &Source, options: &Options) -> Vec<EquationInfo> {
+    let parsed = decorate(source, None, options, &mut Interner::default(), None);
+    let replacements = replacements(source, &parsed);
+    let mut equations = vec![];
+    find_equations(&LinkedNode::new(source.root()), &mut equations);
+    equations
+        .into_iter()
+        .filter_map(|node| {
+            let range = node.range();
+            let position = byte_range_to_utf16(source, &range)?;
+            let unicode = write_unicode(source, range.clone(), &replacements);
+            Some(EquationInfo {
+                position: utf16_position(source, position),
+                block: node.cast::<ast::Equation>().is_some_and(|eq| eq.block()),
+                source: source.get(range)?.to_string(),
+                unicode: unicode.split_whitespace().collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// Get the label attached to an equation, like `<eq:euler>` after `$ e^(i pi) = -1 $`
+fn equation_label<'a>(node: &LinkedNode<'a>) -> Option<LinkedNode<'a>> {
+    let parent = node.parent()?;
+    parent
+        .children()
+        .skip(node.index() + 1)
+        .find(|sibling| sibling.kind() != SyntaxKind::Space)
+        .filter(|sibling| sibling.kind() == SyntaxKind::Label)
+}
+
+/// List the equations of a source which have a label, with the unicode rendering of the equation as preview
+pub fn equation_symbols(source: &Source, options: &Options) -> Vec<EquationSymbol> {
+    let mut equations = vec![];
+    find_equations(&LinkedNode::new(source.root()), &mut equations);
+    let labeled: Vec<_> = equations
+        .into_iter()
+        .filter_map(|node| Some((equation_label(&node)?, node)))
+        .collect();
+    if labeled.is_empty() {
+        return vec![];
+    }
+    let parsed = decorate(source, None, options, &mut Interner::default(), None);
+    let replacements = replacements(source, &parsed);
+    labeled
+        .into_iter()
+        .filter_map(|(label, node)| {
+            let position = byte_range_to_utf16(source, &(node.offset()..label.range().end))?;
+            let selection = byte_range_to_utf16(source, &label.range())?;
+            let preview = write_unicode(source, node.range(), &replacements);
+            Some(EquationSymbol {
+                name: label.cast::<ast::Label>()?.get().to_string(),
+                position: utf16_position(source, position),
+                selection: utf16_position(source, selection),
+                preview: preview.split_whitespace().collect::<Vec<_>>().join(" "),
+                block: node.cast::<ast::Equation>().is_some_and(|eq| eq.block()),
+            })
+        })
+        .collect()
+}
+
+/// Write a fenced code block, its fence being longer than the backticks of the text
+fn code_block(text: &str, language: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}{}\n{}\n{}\n", fence, language, text, fence)
+}
+
+/// Write a Markdown document listing the equations of a source in their order, each with its label, its typst source
+/// and its unicode rendering on a single line
+pub fn to_markdown(source: &Source, options: &Options) -> String {
+    let mut equations = vec![];
+    find_equations(&LinkedNode::new(source.root()), &mut equations);
+    if equations.is_empty() {
+        return String::new();
+    }
+    let parsed = decorate(source, None, options, &mut Interner::default(), None);
+    let replacements = replacements(source, &parsed);
+    let mut markdown = String::from("# Equations\n");
+    for (index, node) in equations.iter().enumerate() {
+        let range = node.range();
+        let block = node.cast::<ast::Equation>().is_some_and(|eq| eq.block());
+        let line = source.byte_to_line(range.start).unwrap_or(0) + 1;
+        markdown.push_str(&format!("\n## Equation {}", index + 1));
+        if let Some(label) = equation_label(node) {
+            markdown.push_str(&format!(" `{}`", label.text()));
+        }
+        markdown.push_str(&format!(
+            "\n\n{}, line {}\n\n",
+            if block { "Block" } else { "Inline" },
+            line
+        ));
+        // The export has `\n` line endings whatever the ones of the document
+        let typst = split_newlines(&source.text()[range.clone()]).join("\n");
+        markdown.push_str(&code_block(&typst, "typst"));
+        let unicode = write_unicode(source, range, &replacements);
+        let unicode = unicode.split_whitespace().collect::<Vec<_>>().join(" ");
+        markdown.push('\n');
+        markdown.push_str(&code_block(&unicode, "text"));
+    }
+    markdown
+}
+
+/// Get the canonical name of a literal unicode character, None for ASCII characters and emojis
+fn canonical_name(c: char) -> Option<&'static str> {
+    if c.is_ascii() {
+        return None;
+    }
+    names_for_char(c)
+        .iter()
+        .find(|name| !name.starts_with("emoji."))
+        .map(|name| name.as_str())
+}
+
+/// Collect the text leaves crossing a byte range, with whether they are in math, in the order of the document \
+/// The tree is walked with an explicit stack, so that deeply nested documents don't overflow the stack
+fn find_text_leaves<'a>(
+    node: &LinkedNode<'a>,
+    range: &Range<usize>,
+    leaves: &mut Vec<(LinkedNode<'a>, bool)>,
+) {
+    let mut stack: Vec<LinkedNode> = node.children().rev().collect();
+    while let Some(child) = stack.pop() {
+        let child_range = child.range();
+        if child_range.end <= range.start || child_range.start >= range.end {
+            continue;
+        }
+        // Text of raw blocks and other leaves outside markup and math are kept
+        match (child.kind(), child.parent_kind()) {
+            (SyntaxKind::Text | SyntaxKind::MathIdent, Some(SyntaxKind::Math)) => {
+                leaves.push((child, true))
+            }
+            (SyntaxKind::Text, Some(SyntaxKind::Markup)) => leaves.push((child, false)),
+            (SyntaxKind::Text | SyntaxKind::MathIdent, _) => {}
+            _ => stack.extend(child.children().rev()),
+        }
+    }
+}
+
+/// Get the edits replacing the literal unicode math characters between two UTF-16 offsets by their typst names, \
+/// `alpha` in equations and `#sym.alpha` in markup, spaced so that they don't merge with their neighbours
+pub fn typstify(source: &Source, start: usize, end: usize) -> Vec<TextEdit> {
+    let len = source.len_bytes();
+    let start = source.utf16_to_byte(start).unwrap_or(len);
+    let end = source.utf16_to_byte(end).unwrap_or(len).max(start);
+    let mut leaves = vec![];
+    let root = LinkedNode::new(source.root());
+    find_text_leaves(&root, &(start..end), &mut leaves);
+
+    let text = source.text();
+    let mut edits = vec![];
+    for (leaf, in_math) in leaves {
+        let offset = leaf.offset();
+        for (index, c) in leaf.text().char_indices() {
+            let from = offset + index;
+            let to = from + c.len_utf8();
+            if from < start || to > end {
+                continue;
+            }
+            let Some(name) = canonical_name(c) else {
+                continue;
+            };
+            let previous = text[..from].chars().next_back();
+            let next = text[to..].chars().next();
+            let replacement = if in_math {
+                let before = if previous.is_some_and(char::is_alphanumeric) {
+                    " "
+                } else {
+                    ""
+                };
+                let after =
+                    if next.is_some_and(|n| n.is_alphanumeric() && canonical_name(n).is_none()) {
+                        " "
+                    } else {
+                        ""
+                    };
+                format!("{}{}{}", before, name, after)
+            } else if next.is_some_and(|n| n.is_alphanumeric() || matches!(n, '.' | '(' | '[')) {
+                format!("#(sym.{})", name)
+            } else {
+                format!("#sym.{}", name)
+            };
+            let (Some(utf16_from), Some(utf16_to)) =
+                (source.byte_to_utf16(from), source.byte_to_utf16(to))
+            else {
+                continue;
+            };
+            edits.push(TextEdit {
+                position: utf16_position(source, utf16_from..utf16_to),
+                text: replacement,
+            });
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_dollars;
+    use typst_syntax::{LinkedNode, Source};
+
+    #[test]
+    fn test_find_dollars_deep() {
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let source = Source::detached(format!("${}alpha{}$", "(".repeat(1000), ")".repeat(1000)));
+        let (source, dollars) = std::thread::Builder::new()
+            .stack_size(128 * 1024)
+            .spawn(move || {
+                let root = LinkedNode::new(source.root());
+                let mut dollars = vec![];
+                find_dollars(&root, &(0..source.text().len()), &mut dollars);
+                drop(root);
+                // The tree is parsed and dropped on the stack of the test, only the walk runs on the small one
+                (source, dollars)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(dollars, vec![0, source.text().len() - 1]);
+    }
+}
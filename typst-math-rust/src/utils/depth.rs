@@ -0,0 +1,120 @@
+//! Depth limited walks of the math of equations, shared by the converters so that deeply nested equations
+//! stay within the WASM stack, the nodes deeper than the maximum depth of the options being skipped
+
+use std::cell::Cell;
+
+use typst_syntax::LinkedNode;
+
+/// Walk the descendants of a node in the order of the document, with an explicit stack \
+/// `visit` gets each node with the value of its parent, and returns the value of its children or None to skip them \
+/// Return true if some nodes were deeper than the maximum depth and were skipped
+pub fn walk<'a, T: Clone>(
+    node: &LinkedNode<'a>,
+    max_depth: usize,
+    value: T,
+    mut visit: impl FnMut(&LinkedNode<'a>, &T) -> Option<T>,
+) -> bool {
+    let mut truncated = false;
+    let mut stack: Vec<(LinkedNode, usize, T)> = node
+        .children()
+        .rev()
+        .map(|child| (child, 1, value.clone()))
+        .collect();
+    while let Some((node, depth, value)) = stack.pop() {
+        if depth > max_depth {
+            truncated = true;
+            continue;
+        }
+        if let Some(value) = visit(&node, &value) {
+            stack.extend(
+                node.children()
+                    .rev()
+                    .map(|child| (child, depth + 1, value.clone())),
+            );
+        }
+    }
+    truncated
+}
+
+/// Depth of the node being converted by a recursive converter, whose recursion stops at the maximum depth
+pub struct DepthLimit {
+    max_depth: usize,
+    depth: Cell<usize>,
+    /// Some nodes were deeper than the maximum depth and were skipped
+    truncated: Cell<bool>,
+}
+
+impl DepthLimit {
+    pub fn new(max_depth: usize) -> DepthLimit {
+        DepthLimit {
+            max_depth,
+            depth: Cell::new(0),
+            truncated: Cell::new(false),
+        }
+    }
+    /// Enter a node one level deeper, false if it is deeper than the maximum depth and must be skipped \
+    /// A node entered is left once converted
+    pub fn enter(&self) -> bool {
+        let depth = self.depth.get();
+        if depth >= self.max_depth {
+            self.truncated.set(true);
+            return false;
+        }
+        self.depth.set(depth + 1);
+        true
+    }
+    /// Leave the node entered last
+    pub fn leave(&self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+    /// Check if some nodes were skipped since the last check
+    pub fn take_truncated(&self) -> bool {
+        self.truncated.take()
+    }
+    /// Check if the children of the node entered last are deeper than the maximum depth
+    pub fn is_reached(&self) -> bool {
+        self.depth.get() >= self.max_depth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk, DepthLimit};
+    use typst_syntax::{LinkedNode, Source, SyntaxKind};
+
+    #[test]
+    fn test_truncation() {
+        let source = Source::detached(format!("${}alpha{}$", "(".repeat(1000), ")".repeat(1000)));
+        let root = LinkedNode::new(source.root());
+        // Only the parentheses within the maximum depth are walked, the value of a node being its depth
+        let mut delimited = vec![];
+        let truncated = walk(&root, 6, 0, |node, parent| {
+            if node.kind() == SyntaxKind::MathDelimited {
+                delimited.push(parent + 1);
+            }
+            Some(parent + 1)
+        });
+        assert!(truncated);
+        assert_eq!(delimited, vec![3, 5]);
+        // Deeply nested equations are walked without growing the stack
+        let mut found = false;
+        let truncated = walk(&root, usize::MAX, (), |node, _| {
+            found |= node.kind() == SyntaxKind::MathIdent;
+            Some(())
+        });
+        assert!(!truncated);
+        assert!(found);
+
+        // Recursive converters enter the nodes until the maximum depth, and leave them once converted
+        let limit = DepthLimit::new(2);
+        assert!(limit.enter());
+        assert!(!limit.is_reached());
+        assert!(limit.enter());
+        assert!(limit.is_reached());
+        assert!(!limit.take_truncated());
+        assert!(!limit.enter());
+        assert!(limit.take_truncated());
+        limit.leave();
+        assert!(!limit.is_reached());
+    }
+}
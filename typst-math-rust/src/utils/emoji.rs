@@ -0,0 +1,1156 @@
+//! List of the emoji of the typst `emoji` module.
+//! Generated from https://github.com/typst/typst/blob/main/crates/typst/src/symbols/emoji.rs
+
+use crate::utils::symbols::{Category, Symbol};
+use phf::phf_ordered_map;
+use typst_math_macros::symbols;
+
+/// The list of emoji, accessed with `emoji.name`.
+pub static EMOJI: phf::OrderedMap<&str, Symbol> = symbols! {
+    abacus: '🧮'; Default,
+    abc: '🔤'; Default,
+    abcd: '🔡'; Default,
+    ABCD: '🔠'; Default,
+    accordion: '🪗'; Default,
+    aesculapius: '⚕'; Default,
+    airplane: ['✈', landing: '🛬', small: '🛩', takeoff: '🛫']; Default,
+    alembic: '⚗'; Default,
+    alien: ['👽', monster: '👾']; Default,
+    ambulance: '🚑'; Default,
+    amphora: '🏺'; Default,
+    anchor: '⚓'; Default,
+    anger: '💢'; Default,
+    ant: '🐜'; Default,
+    apple: [green: '🍏', red: '🍎']; Default,
+    arm: [mech: '🦾', muscle: '💪', selfie: '🤳']; Default,
+    arrow: [
+        r.filled: '➡',
+        r.hook: '↪',
+        r.soon: '🔜',
+        l.filled: '⬅',
+        l.hook: '↩',
+        l.back: '🔙',
+        l.end: '🔚',
+        t.filled: '⬆',
+        t.curve: '⤴',
+        t.top: '🔝',
+        b.filled: '⬇',
+        b.curve: '⤵',
+        l.r: '↔',
+        l.r.on: '🔛',
+        t.b: '↕',
+        bl: '↙',
+        br: '↘',
+        tl: '↖',
+        tr: '↗',
+    ]; Default,
+    arrows: [cycle: '🔄']; Default,
+    ast: ['*', box: '✳']; Default,
+    atm: '🏧'; Default,
+    atom: '⚛'; Default,
+    aubergine: '🍆'; Default,
+    avocado: '🥑'; Default,
+    axe: '🪓'; Default,
+    baby: ['👶', angel: '👼', box: '🚼']; Default,
+    babybottle: '🍼'; Default,
+    backpack: '🎒'; Default,
+    bacon: '🥓'; Default,
+    badger: '🦡'; Default,
+    badminton: '🏸'; Default,
+    bagel: '🥯'; Default,
+    baggageclaim: '🛄'; Default,
+    baguette: '🥖'; Default,
+    balloon: '🎈'; Default,
+    ballot: [check: '☑']; Default,
+    ballotbox: '🗳'; Default,
+    banana: '🍌'; Default,
+    banjo: '🪕'; Default,
+    bank: '🏦'; Default,
+    barberpole: '💈'; Default,
+    baseball: '⚾'; Default,
+    basecap: '🧢'; Default,
+    basket: '🧺'; Default,
+    basketball: ['⛹', ball: '🏀']; Default,
+    bat: '🦇'; Default,
+    bathtub: ['🛀', foam: '🛁']; Default,
+    battery: ['🔋', low: '🪫']; Default,
+    beach: [palm: '🏝', umbrella: '🏖']; Default,
+    beads: '📿'; Default,
+    beans: '🫘'; Default,
+    bear: '🐻'; Default,
+    beaver: '🦫'; Default,
+    bed: ['🛏', person: '🛌']; Default,
+    bee: '🐝'; Default,
+    beer: ['🍺', clink: '🍻']; Default,
+    beetle: ['🪲', lady: '🐞']; Default,
+    bell: ['🔔', ding: '🛎', not: '🔕']; Default,
+    bento: '🍱'; Default,
+    bicyclist: ['🚴', mountain: '🚵']; Default,
+    bike: ['🚲', not: '🚳']; Default,
+    bikini: '👙'; Default,
+    billiards: '🎱'; Default,
+    bin: '🗑'; Default,
+    biohazard: '☣'; Default,
+    bird: '🐦'; Default,
+    bison: '🦬'; Default,
+    blood: '🩸'; Default,
+    blouse: '👚'; Default,
+    blowfish: '🐡'; Default,
+    blueberries: '🫐'; Default,
+    boar: '🐗'; Default,
+    boat: [sail: '⛵', row: '🚣', motor: '🛥', speed: '🚤', canoe: '🛶']; Default,
+    bolt: '🔩'; Default,
+    bomb: '💣'; Default,
+    bone: '🦴'; Default,
+    book: [red: '📕', blue: '📘', green: '📗', orange: '📙', spiral: '📒', open: '📖']; Default,
+    bookmark: '🔖'; Default,
+    books: '📚'; Default,
+    boomerang: '🪃'; Default,
+    bordercontrol: '🛂'; Default,
+    bouquet: '💐'; Default,
+    bow: '🏹'; Default,
+    bowl: [spoon: '🥣', steam: '🍜']; Default,
+    bowling: '🎳'; Default,
+    boxing: '🥊'; Default,
+    boy: '👦'; Default,
+    brain: '🧠'; Default,
+    bread: '🍞'; Default,
+    brick: '🧱'; Default,
+    bride: '👰'; Default,
+    bridge: [fog: '🌁', night: '🌉']; Default,
+    briefcase: '💼'; Default,
+    briefs: '🩲'; Default,
+    brightness: [high: '🔆', low: '🔅']; Default,
+    broccoli: '🥦'; Default,
+    broom: '🧹'; Default,
+    brush: '🖌'; Default,
+    bubble: [speech.r: '💬', speech.l: '🗨', thought: '💭', anger.r: '🗯']; Default,
+    bubbles: '🫧'; Default,
+    bubbletea: '🧋'; Default,
+    bucket: '🪣'; Default,
+    buffalo: [water: '🐃']; Default,
+    bug: '🐛'; Default,
+    builder: '👷'; Default,
+    burger: '🍔'; Default,
+    burrito: '🌯'; Default,
+    bus: ['🚌', front: '🚍', small: '🚐', stop: '🚏', trolley: '🚎']; Default,
+    butter: '🧈'; Default,
+    butterfly: '🦋'; Default,
+    button: ['🔲', alt: '🔳', radio: '🔘']; Default,
+    cabinet: [file: '🗄']; Default,
+    cablecar: ['🚠', small: '🚡']; Default,
+    cactus: '🌵'; Default,
+    cake: ['🎂', fish: '🍥', moon: '🥮', slice: '🍰']; Default,
+    calendar: ['📅', spiral: '🗓', tearoff: '📆']; Default,
+    camel: ['🐫', dromedar: '🐪']; Default,
+    camera: ['📷', flash: '📸', movie: '🎥', movie.box: '🎦', video: '📹']; Default,
+    camping: '🏕'; Default,
+    can: '🥫'; Default,
+    candle: '🕯'; Default,
+    candy: '🍬'; Default,
+    cane: '🦯'; Default,
+    car: [
+        '🚗',
+        front: '🚘',
+        pickup: '🛻',
+        police: '🚓',
+        police.front: '🚔',
+        racing: '🏎',
+        rickshaw: '🛺',
+        suv: '🚙',
+    ]; Default,
+    card: [credit: '💳', id: '🪪']; Default,
+    cardindex: '📇'; Default,
+    carrot: '🥕'; Default,
+    cart: '🛒'; Default,
+    cassette: '📼'; Default,
+    castle: [eu: '🏰', jp: '🏯']; Default,
+    cat: [
+        '🐈',
+        face: '🐱',
+        face.angry: '😾',
+        face.cry: '😿',
+        face.heart: '😻',
+        face.joy: '😹',
+        face.kiss: '😽',
+        face.laugh: '😸',
+        face.shock: '🙀',
+        face.smile: '😺',
+        face.smirk: '😼',
+    ]; Default,
+    chain: '🔗'; Default,
+    chains: '⛓'; Default,
+    chair: '🪑'; Default,
+    champagne: '🍾'; Default,
+    chart: [bar: '📊', up: '📈', down: '📉', yen.up: '💹']; Default,
+    checkmark: [heavy: '✔', box: '✅']; Default,
+    cheese: '🧀'; Default,
+    cherries: '🍒'; Default,
+    chess: '♟'; Default,
+    chestnut: '🌰'; Default,
+    chicken: ['🐔', baby: '🐥', baby.egg: '🐣', baby.head: '🐤', leg: '🍗', male: '🐓']; Default,
+    child: '🧒'; Default,
+    chipmunk: '🐿'; Default,
+    chocolate: '🍫'; Default,
+    chopsticks: '🥢'; Default,
+    church: ['⛪', love: '💒']; Default,
+    cigarette: ['🚬', not: '🚭']; Default,
+    circle: [
+        black: '⚫',
+        blue: '🔵',
+        brown: '🟤',
+        green: '🟢',
+        orange: '🟠',
+        purple: '🟣',
+        white: '⚪',
+        red: '🔴',
+        yellow: '🟡',
+        stroked: '⭕',
+    ]; Default,
+    circus: '🎪'; Default,
+    city: ['🏙', dusk: '🌆', night: '🌃', sunset: '🌇']; Default,
+    clamp: '🗜'; Default,
+    clapperboard: '🎬'; Default,
+    climbing: '🧗'; Default,
+    clip: '📎'; Default,
+    clipboard: '📋'; Default,
+    clips: '🖇'; Default,
+    clock: [
+        one: '🕐',
+        one.thirty: '🕜',
+        two: '🕑',
+        two.thirty: '🕝',
+        three: '🕒',
+        three.thirty: '🕞',
+        four: '🕓',
+        four.thirty: '🕟',
+        five: '🕔',
+        five.thirty: '🕠',
+        six: '🕕',
+        six.thirty: '🕡',
+        seven: '🕖',
+        seven.thirty: '🕢',
+        eight: '🕗',
+        eight.thirty: '🕣',
+        nine: '🕘',
+        nine.thirty: '🕤',
+        ten: '🕙',
+        ten.thirty: '🕥',
+        eleven: '🕚',
+        eleven.thirty: '🕦',
+        twelve: '🕛',
+        twelve.thirty: '🕧',
+        alarm: '⏰',
+        old: '🕰',
+        timer: '⏲',
+    ]; Default,
+    cloud: [
+        '☁',
+        dust: '💨',
+        rain: '🌧',
+        snow: '🌨',
+        storm: '⛈',
+        sun: '⛅',
+        sun.hidden: '🌥',
+        sun.rain: '🌦',
+        thunder: '🌩',
+    ]; Default,
+    coat: ['🧥', lab: '🥼']; Default,
+    cockroach: '🪳'; Default,
+    cocktail: [martini: '🍸', tropical: '🍹']; Default,
+    coconut: '🥥'; Default,
+    coffee: '☕'; Default,
+    coffin: '⚰'; Default,
+    coin: '🪙'; Default,
+    comet: '☄'; Default,
+    compass: '🧭'; Default,
+    computer: '🖥'; Default,
+    computermouse: '🖱'; Default,
+    confetti: '🎊'; Default,
+    construction: '🚧'; Default,
+    controller: '🎮'; Default,
+    cookie: ['🍪', fortune: '🥠']; Default,
+    cooking: '🍳'; Default,
+    cool: '🆒'; Default,
+    copyright: '©'; Default,
+    coral: '🪸'; Default,
+    corn: '🌽'; Default,
+    couch: '🛋'; Default,
+    couple: '💑'; Default,
+    cow: ['🐄', face: '🐮']; Default,
+    crab: '🦀'; Default,
+    crane: '🏗'; Default,
+    crayon: '🖍'; Default,
+    cricket: '🦗'; Default,
+    cricketbat: '🏏'; Default,
+    crocodile: '🐊'; Default,
+    croissant: '🥐'; Default,
+    crossmark: ['❌', box: '❎']; Default,
+    crown: '👑'; Default,
+    crutch: '🩼'; Default,
+    crystal: '🔮'; Default,
+    cucumber: '🥒'; Default,
+    cup: [straw: '🥤']; Default,
+    cupcake: '🧁'; Default,
+    curling: '🥌'; Default,
+    curry: '🍛'; Default,
+    custard: '🍮'; Default,
+    customs: '🛃'; Default,
+    cutlery: '🍴'; Default,
+    cyclone: '🌀'; Default,
+    dancing: [man: '🕺', woman: '💃', women.bunny: '👯']; Default,
+    darts: '🎯'; Default,
+    dash: [wave.double: '〰']; Default,
+    deer: '🦌'; Default,
+    desert: '🏜'; Default,
+    detective: '🕵'; Default,
+    diamond: [blue: '🔷', blue.small: '🔹', orange: '🔶', orange.small: '🔸', dot: '💠']; Default,
+    die: '🎲'; Default,
+    dino: [pod: '🦕', rex: '🦖']; Default,
+    disc: [cd: '💿', dvd: '📀', mini: '💽']; Default,
+    discoball: '🪩'; Default,
+    diving: '🤿'; Default,
+    dodo: '🦤'; Default,
+    dog: ['🐕', face: '🐶', guide: '🦮', poodle: '🐩']; Default,
+    dollar: '💲'; Default,
+    dolphin: '🐬'; Default,
+    donut: '🍩'; Default,
+    door: '🚪'; Default,
+    dove: [peace: '🕊']; Default,
+    dragon: ['🐉', face: '🐲']; Default,
+    dress: ['👗', kimono: '👘', sari: '🥻']; Default,
+    drop: '💧'; Default,
+    drops: '💦'; Default,
+    drum: ['🥁', big: '🪘']; Default,
+    duck: '🦆'; Default,
+    dumpling: '🥟'; Default,
+    eagle: '🦅'; Default,
+    ear: ['👂', aid: '🦻']; Default,
+    egg: '🥚'; Default,
+    eighteen: [not: '🔞']; Default,
+    elephant: '🐘'; Default,
+    elevator: '🛗'; Default,
+    elf: '🧝'; Default,
+    email: '📧'; Default,
+    excl: ['❗', white: '❕', double: '‼', quest: '⁉']; Default,
+    explosion: '💥'; Default,
+    extinguisher: '🧯'; Default,
+    eye: '👁'; Default,
+    eyes: '👀'; Default,
+    face: [
+        grin: '😀',
+        angry: '😠',
+        angry.red: '😡',
+        anguish: '😧',
+        astonish: '😲',
+        bandage: '🤕',
+        beam: '😁',
+        blank: '😶',
+        clown: '🤡',
+        cold: '🥶',
+        concern: '😦',
+        cool: '😎',
+        cover: '🤭',
+        cowboy: '🤠',
+        cry: '😭',
+        devil.smile: '😈',
+        devil.frown: '👿',
+        diagonal: '🫤',
+        disguise: '🥸',
+        dizzy: '😵',
+        dotted: '🫥',
+        down: '😞',
+        down.sweat: '😓',
+        drool: '🤤',
+        explode: '🤯',
+        eyeroll: '🙄',
+        friendly: '☺',
+        fear: '😨',
+        fear.sweat: '😰',
+        fever: '🤒',
+        flush: '😳',
+        frown: '☹',
+        frown.slight: '🙁',
+        frust: '😣',
+        goofy: '🤪',
+        halo: '😇',
+        happy: '😊',
+        heart: '😍',
+        hearts: '🥰',
+        heat: '🥵',
+        hug: '🤗',
+        inv: '🙃',
+        joy: '😂',
+        kiss: '😗',
+        kiss.smile: '😙',
+        kiss.heart: '😘',
+        kiss.blush: '😚',
+        lick: '😋',
+        lie: '🤥',
+        mask: '😷',
+        meh: '😒',
+        melt: '🫠',
+        money: '🤑',
+        monocle: '🧐',
+        nausea: '🤢',
+        nerd: '🤓',
+        neutral: '😐',
+        open: '😃',
+        party: '🥳',
+        peek: '🫣',
+        plead: '🥺',
+        relief: '😌',
+        rofl: '🤣',
+        sad: '😔',
+        salute: '🫡',
+        shock: '😱',
+        shush: '🤫',
+        skeptic: '🤨',
+        sleep: '😴',
+        sleepy: '😪',
+        smile: '😄',
+        smile.slight: '🙂',
+        smile.sweat: '😅',
+        smile.tear: '🥲',
+        smirk: '😏',
+        sneeze: '🤧',
+        speak.not: '🫢',
+        squint: '😆',
+        stars: '🤩',
+        straight: '😑',
+        suffer: '😖',
+        surprise: '😯',
+        symbols: '🤬',
+        tear: '😢',
+        tear.relief: '😥',
+        tear.withheld: '🥹',
+        teeth: '😬',
+        think: '🤔',
+        tired: '😫',
+        tongue: '😛',
+        tongue.squint: '😝',
+        tongue.wink: '😜',
+        triumph: '😤',
+        unhappy: '😕',
+        vomit: '🤮',
+        weary: '😩',
+        wink: '😉',
+        woozy: '🥴',
+        worry: '😟',
+        wow: '😮',
+        yawn: '🥱',
+        zip: '🤐',
+    ]; Default,
+    factory: '🏭'; Default,
+    fairy: '🧚'; Default,
+    faith: [
+        christ: '✝',
+        dharma: '☸',
+        islam: '☪',
+        judaism: '✡',
+        menorah: '🕎',
+        om: '🕉',
+        orthodox: '☦',
+        peace: '☮',
+        star.dot: '🔯',
+        worship: '🛐',
+        yinyang: '☯',
+    ]; Default,
+    falafel: '🧆'; Default,
+    family: '👪'; Default,
+    fax: '📠'; Default,
+    feather: '🪶'; Default,
+    feeding: [breast: '🤱']; Default,
+    fencing: '🤺'; Default,
+    ferriswheel: '🎡'; Default,
+    filebox: '🗃'; Default,
+    filedividers: '🗂'; Default,
+    film: '🎞'; Default,
+    finger: [r: '👉', l: '👈', t: '👆', t.alt: '☝', b: '👇', front: '🫵', m: '🖕']; Default,
+    fingers: [cross: '🤞', pinch: '🤌', snap: '🫰']; Default,
+    fire: '🔥'; Default,
+    firecracker: '🧨'; Default,
+    fireengine: '🚒'; Default,
+    fireworks: '🎆'; Default,
+    fish: ['🐟', tropical: '🐠']; Default,
+    fishing: '🎣'; Default,
+    fist: [front: '👊', r: '🤜', l: '🤛', raised: '✊']; Default,
+    flag: [black: '🏴', white: '🏳', goal: '🏁', golf: '⛳', red: '🚩']; Default,
+    flags: [jp.crossed: '🎌']; Default,
+    flamingo: '🦩'; Default,
+    flashlight: '🔦'; Default,
+    flatbread: '🫓'; Default,
+    fleur: '⚜'; Default,
+    floppy: '💾'; Default,
+    flower: [
+        hibiscus: '🌺',
+        lotus: '🪷',
+        pink: '🌸',
+        rose: '🌹',
+        sun: '🌻',
+        tulip: '🌷',
+        white: '💮',
+        wilted: '🥀',
+        yellow: '🌼',
+    ]; Default,
+    fly: '🪰'; Default,
+    fog: '🌫'; Default,
+    folder: ['📁', open: '📂']; Default,
+    fondue: '🫕'; Default,
+    foot: '🦶'; Default,
+    football: ['⚽', am: '🏈']; Default,
+    forex: '💱'; Default,
+    fountain: '⛲'; Default,
+    fox: '🦊'; Default,
+    free: '🆓'; Default,
+    fries: '🍟'; Default,
+    frisbee: '🥏'; Default,
+    frog: [face: '🐸']; Default,
+    fuelpump: '⛽'; Default,
+    garlic: '🧄'; Default,
+    gear: '⚙'; Default,
+    gem: '💎'; Default,
+    genie: '🧞'; Default,
+    ghost: '👻'; Default,
+    giraffe: '🦒'; Default,
+    girl: '👧'; Default,
+    glass: [clink: '🥂', milk: '🥛', pour: '🫗', tumbler: '🥃']; Default,
+    glasses: ['👓', sun: '🕶']; Default,
+    globe: [am: '🌎', as.au: '🌏', eu.af: '🌍', meridian: '🌐']; Default,
+    gloves: '🧤'; Default,
+    goal: '🥅'; Default,
+    goat: '🐐'; Default,
+    goggles: '🥽'; Default,
+    golfing: '🏌'; Default,
+    gorilla: '🦍'; Default,
+    grapes: '🍇'; Default,
+    guard: [man: '💂']; Default,
+    guitar: '🎸'; Default,
+    gymnastics: '🤸'; Default,
+    haircut: '💇'; Default,
+    hammer: ['🔨', pick: '⚒', wrench: '🛠']; Default,
+    hamsa: '🪬'; Default,
+    hamster: [face: '🐹']; Default,
+    hand: [
+        raised: '✋',
+        raised.alt: '🤚',
+        r: '🫱',
+        l: '🫲',
+        t: '🫴',
+        b: '🫳',
+        ok: '👌',
+        call: '🤙',
+        love: '🤟',
+        part: '🖖',
+        peace: '✌',
+        pinch: '🤏',
+        rock: '🤘',
+        splay: '🖐',
+        wave: '👋',
+        write: '✍',
+    ]; Default,
+    handbag: '👜'; Default,
+    handball: '🤾'; Default,
+    handholding: [man.man: '👬', woman.man: '👫', woman.woman: '👭']; Default,
+    hands: [
+        folded: '🙏',
+        palms: '🤲',
+        clap: '👏',
+        heart: '🫶',
+        open: '👐',
+        raised: '🙌',
+        shake: '🤝',
+    ]; Default,
+    hash: '#'; Default,
+    hat: [ribbon: '👒', top: '🎩']; Default,
+    headphone: '🎧'; Default,
+    heart: [
+        '❤',
+        arrow: '💘',
+        beat: '💓',
+        black: '🖤',
+        blue: '💙',
+        box: '💟',
+        broken: '💔',
+        brown: '🤎',
+        double: '💕',
+        excl: '❣',
+        green: '💚',
+        grow: '💗',
+        orange: '🧡',
+        purple: '💜',
+        real: '🫀',
+        revolve: '💞',
+        ribbon: '💝',
+        spark: '💖',
+        white: '🤍',
+        yellow: '💛',
+    ]; Default,
+    hedgehog: '🦔'; Default,
+    helicopter: '🚁'; Default,
+    helix: '🧬'; Default,
+    helmet: [cross: '⛑', military: '🪖']; Default,
+    hippo: '🦛'; Default,
+    hockey: '🏑'; Default,
+    hole: '🕳'; Default,
+    honey: '🍯'; Default,
+    hongbao: '🧧'; Default,
+    hook: '🪝'; Default,
+    horn: [postal: '📯']; Default,
+    horse: ['🐎', carousel: '🎠', face: '🐴', race: '🏇']; Default,
+    hospital: '🏥'; Default,
+    hotdog: '🌭'; Default,
+    hotel: ['🏨', love: '🏩']; Default,
+    hotspring: '♨'; Default,
+    hourglass: ['⌛', flow: '⏳']; Default,
+    house: ['🏠', derelict: '🏚', garden: '🏡', multiple: '🏘']; Default,
+    hundred: '💯'; Default,
+    hut: '🛖'; Default,
+    ice: '🧊'; Default,
+    icecream: ['🍨', shaved: '🍧', soft: '🍦']; Default,
+    icehockey: '🏒'; Default,
+    id: '🆔'; Default,
+    info: 'ℹ'; Default,
+    izakaya: '🏮'; Default,
+    jar: '🫙'; Default,
+    jeans: '👖'; Default,
+    jigsaw: '🧩'; Default,
+    joystick: '🕹'; Default,
+    juggling: '🤹'; Default,
+    juice: '🧃'; Default,
+    kaaba: '🕋'; Default,
+    kadomatsu: '🎍'; Default,
+    kangaroo: '🦘'; Default,
+    gachi: '🈷'; Default,
+    go: '🈴'; Default,
+    hi: '㊙'; Default,
+    ka: '🉑'; Default,
+    kachi: '🈹'; Default,
+    kara: '🈳'; Default,
+    kon: '🈲'; Default,
+    muryo: '🈚'; Default,
+    shin: '🈸'; Default,
+    shuku: '㊗'; Default,
+    toku: '🉐'; Default,
+    yo: '🈺'; Default,
+    yubi: '🈯'; Default,
+    yuryo: '🈶'; Default,
+    koko: '🈁'; Default,
+    sa: '🈂'; Default,
+    kebab: '🥙'; Default,
+    key: ['🔑', old: '🗝']; Default,
+    keyboard: '⌨'; Default,
+    kiss: '💏'; Default,
+    kissmark: '💋'; Default,
+    kite: '🪁'; Default,
+    kiwi: '🥝'; Default,
+    knife: ['🔪', dagger: '🗡']; Default,
+    knot: '🪢'; Default,
+    koala: '🐨'; Default,
+    koinobori: '🎏'; Default,
+    label: '🏷'; Default,
+    lacrosse: '🥍'; Default,
+    ladder: '🪜'; Default,
+    lamp: [diya: '🪔']; Default,
+    laptop: '💻'; Default,
+    a: '🅰'; Default,
+    ab: '🆎'; Default,
+    b: '🅱'; Default,
+    cl: '🆑'; Default,
+    o: '🅾'; Default,
+    leaf: [
+        clover.three: '☘',
+        clover.four: '🍀',
+        fall: '🍂',
+        herb: '🌿',
+        maple: '🍁',
+        wind: '🍃',
+    ]; Default,
+    leftluggage: '🛅'; Default,
+    leg: ['🦵', mech: '🦿']; Default,
+    lemon: '🍋'; Default,
+    leopard: '🐆'; Default,
+    letter: [love: '💌']; Default,
+    liberty: '🗽'; Default,
+    lightbulb: '💡'; Default,
+    lightning: '⚡'; Default,
+    lion: '🦁'; Default,
+    lipstick: '💄'; Default,
+    litter: ['🚮', not: '🚯']; Default,
+    lizard: '🦎'; Default,
+    llama: '🦙'; Default,
+    lobster: '🦞'; Default,
+    lock: ['🔒', key: '🔐', open: '🔓', pen: '🔏']; Default,
+    lollipop: '🍭'; Default,
+    lotion: '🧴'; Default,
+    luggage: '🧳'; Default,
+    lungs: '🫁'; Default,
+    mage: '🧙'; Default,
+    magnet: '🧲'; Default,
+    magnify: [r: '🔎', l: '🔍']; Default,
+    mahjong: [dragon.red: '🀄']; Default,
+    mail: ['✉', arrow: '📩']; Default,
+    mailbox: [closed.empty: '📪', closed.full: '📫', open.empty: '📭', open.full: '📬']; Default,
+    mammoth: '🦣'; Default,
+    man: [
+        '👨',
+        box: '🚹',
+        crown: '🤴',
+        guapimao: '👲',
+        levitate: '🕴',
+        old: '👴',
+        pregnant: '🫃',
+        turban: '👳',
+        tuxedo: '🤵',
+    ]; Default,
+    mango: '🥭'; Default,
+    map: [world: '🗺', jp: '🗾']; Default,
+    martialarts: '🥋'; Default,
+    masks: '🎭'; Default,
+    mate: '🧉'; Default,
+    matryoshka: '🪆'; Default,
+    meat: ['🥩', bone: '🍖']; Default,
+    medal: [first: '🥇', second: '🥈', third: '🥉', sports: '🏅', military: '🎖']; Default,
+    megaphone: ['📢', simple: '📣']; Default,
+    melon: '🍈'; Default,
+    merperson: '🧜'; Default,
+    metro: 'Ⓜ'; Default,
+    microbe: '🦠'; Default,
+    microphone: ['🎤', studio: '🎙']; Default,
+    microscope: '🔬'; Default,
+    milkyway: '🌌'; Default,
+    mirror: '🪞'; Default,
+    mixer: '🎛'; Default,
+    money: [bag: '💰', dollar: '💵', euro: '💶', pound: '💷', yen: '💴', wings: '💸']; Default,
+    monkey: ['🐒', face: '🐵', hear.not: '🙉', see.not: '🙈', speak.not: '🙊']; Default,
+    moon: [
+        crescent: '🌙',
+        full: '🌕',
+        full.face: '🌝',
+        new: '🌑',
+        new.face: '🌚',
+        wane.one: '🌖',
+        wane.two: '🌗',
+        wane.three.face: '🌜',
+        wane.three: '🌘',
+        wax.one: '🌒',
+        wax.two: '🌓',
+        wax.two.face: '🌛',
+        wax.three: '🌔',
+    ]; Default,
+    mortarboard: '🎓'; Default,
+    mosque: '🕌'; Default,
+    mosquito: '🦟'; Default,
+    motorcycle: '🏍'; Default,
+    motorway: '🛣'; Default,
+    mountain: ['⛰', fuji: '🗻', snow: '🏔', sunrise: '🌄']; Default,
+    mouse: ['🐁', face: '🐭']; Default,
+    mousetrap: '🪤'; Default,
+    mouth: ['👄', bite: '🫦']; Default,
+    moyai: '🗿'; Default,
+    museum: '🏛'; Default,
+    mushroom: '🍄'; Default,
+    musicalscore: '🎼'; Default,
+    nails: [polish: '💅']; Default,
+    namebadge: '📛'; Default,
+    nazar: '🧿'; Default,
+    necktie: '👔'; Default,
+    needle: '🪡'; Default,
+    nest: [empty: '🪹', eggs: '🪺']; Default,
+    new: '🆕'; Default,
+    newspaper: ['📰', rolled: '🗞']; Default,
+    ng: '🆖'; Default,
+    ningyo: '🎎'; Default,
+    ninja: '🥷'; Default,
+    noentry: '⛔'; Default,
+    nose: '👃'; Default,
+    notebook: ['📓', deco: '📔']; Default,
+    notepad: '🗒'; Default,
+    notes: ['🎵', triple: '🎶']; Default,
+    numbers: '🔢'; Default,
+    octopus: '🐙'; Default,
+    office: '🏢'; Default,
+    oil: '🛢'; Default,
+    ok: '🆗'; Default,
+    olive: '🫒'; Default,
+    oni: '👹'; Default,
+    onion: '🧅'; Default,
+    orangutan: '🦧'; Default,
+    otter: '🦦'; Default,
+    owl: '🦉'; Default,
+    ox: '🐂'; Default,
+    oyster: '🦪'; Default,
+    package: '📦'; Default,
+    paella: '🥘'; Default,
+    page: ['📄', curl: '📃', pencil: '📝']; Default,
+    pager: '📟'; Default,
+    pages: [tabs: '📑']; Default,
+    painting: '🖼'; Default,
+    palette: '🎨'; Default,
+    pancakes: '🥞'; Default,
+    panda: '🐼'; Default,
+    parachute: '🪂'; Default,
+    park: '🏞'; Default,
+    parking: '🅿'; Default,
+    parrot: '🦜'; Default,
+    partalteration: '〽'; Default,
+    party: '🎉'; Default,
+    peach: '🍑'; Default,
+    peacock: '🦚'; Default,
+    peanuts: '🥜'; Default,
+    pear: '🍐'; Default,
+    pedestrian: ['🚶', not: '🚷']; Default,
+    pen: [ball: '🖊', fountain: '🖋']; Default,
+    pencil: '✏'; Default,
+    penguin: '🐧'; Default,
+    pepper: ['🫑', hot: '🌶']; Default,
+    person: [
+        '🧑',
+        angry: '🙎',
+        beard: '🧔',
+        blonde: '👱',
+        bow: '🙇',
+        crown: '🫅',
+        deaf: '🧏',
+        facepalm: '🤦',
+        frown: '🙍',
+        hijab: '🧕',
+        kneel: '🧎',
+        lotus: '🧘',
+        massage: '💆',
+        no: '🙅',
+        ok: '🙆',
+        old: '🧓',
+        pregnant: '🫄',
+        raise: '🙋',
+        sassy: '💁',
+        shrug: '🤷',
+        stand: '🧍',
+        steam: '🧖',
+    ]; Default,
+    petri: '🧫'; Default,
+    phone: [
+        '📱',
+        arrow: '📲',
+        classic: '☎',
+        not: '📵',
+        off: '📴',
+        receiver: '📞',
+        signal: '📶',
+        vibrate: '📳',
+    ]; Default,
+    piano: '🎹'; Default,
+    pick: '⛏'; Default,
+    pie: '🥧'; Default,
+    pig: ['🐖', face: '🐷', nose: '🐽']; Default,
+    pill: '💊'; Default,
+    pin: ['📌', round: '📍']; Default,
+    pinata: '🪅'; Default,
+    pineapple: '🍍'; Default,
+    pingpong: '🏓'; Default,
+    pistol: '🔫'; Default,
+    pizza: '🍕'; Default,
+    placard: '🪧'; Default,
+    planet: '🪐'; Default,
+    plant: '🪴'; Default,
+    plaster: '🩹'; Default,
+    plate: [cutlery: '🍽']; Default,
+    playback: [
+        down: '⏬',
+        eject: '⏏',
+        forward: '⏩',
+        pause: '⏸',
+        record: '⏺',
+        repeat: '🔁',
+        repeat.once: '🔂',
+        repeat.v: '🔃',
+        restart: '⏮',
+        rewind: '⏪',
+        shuffle: '🔀',
+        skip: '⏭',
+        stop: '⏹',
+        toggle: '⏯',
+        up: '⏫',
+    ]; Default,
+    playingcard: [flower: '🎴', joker: '🃏']; Default,
+    plunger: '🪠'; Default,
+    policeofficer: '👮'; Default,
+    poo: '💩'; Default,
+    popcorn: '🍿'; Default,
+    post: [eu: '🏤', jp: '🏣']; Default,
+    postbox: '📮'; Default,
+    potato: ['🥔', sweet: '🍠']; Default,
+    pouch: '👝'; Default,
+    powerplug: '🔌'; Default,
+    present: '🎁'; Default,
+    pretzel: '🥨'; Default,
+    printer: '🖨'; Default,
+    prints: [foot: '👣', paw: '🐾']; Default,
+    prohibited: '🚫'; Default,
+    projector: '📽'; Default,
+    pumpkin: [lantern: '🎃']; Default,
+    purse: '👛'; Default,
+    quest: ['❓', white: '❔']; Default,
+    rabbit: ['🐇', face: '🐰']; Default,
+    raccoon: '🦝'; Default,
+    radio: '📻'; Default,
+    radioactive: '☢'; Default,
+    railway: '🛤'; Default,
+    rainbow: '🌈'; Default,
+    ram: '🐏'; Default,
+    rat: '🐀'; Default,
+    razor: '🪒'; Default,
+    receipt: '🧾'; Default,
+    recycling: '♻'; Default,
+    reg: '®'; Default,
+    restroom: '🚻'; Default,
+    rhino: '🦏'; Default,
+    ribbon: ['🎀', remind: '🎗']; Default,
+    rice: ['🍚', cracker: '🍘', ear: '🌾', onigiri: '🍙']; Default,
+    ring: '💍'; Default,
+    ringbuoy: '🛟'; Default,
+    robot: '🤖'; Default,
+    rock: '🪨'; Default,
+    rocket: '🚀'; Default,
+    rollercoaster: '🎢'; Default,
+    rosette: '🏵'; Default,
+    rugby: '🏉'; Default,
+    ruler: ['📏', triangle: '📐']; Default,
+    running: '🏃'; Default,
+    safetypin: '🧷'; Default,
+    safetyvest: '🦺'; Default,
+    sake: '🍶'; Default,
+    salad: '🥗'; Default,
+    salt: '🧂'; Default,
+    sandwich: '🥪'; Default,
+    santa: [man: '🎅', woman: '🤶']; Default,
+    satdish: '📡'; Default,
+    satellite: '🛰'; Default,
+    saw: '🪚'; Default,
+    saxophone: '🎷'; Default,
+    scales: '⚖'; Default,
+    scarf: '🧣'; Default,
+    school: '🏫'; Default,
+    scissors: '✂'; Default,
+    scooter: ['🛴', motor: '🛵']; Default,
+    scorpion: '🦂'; Default,
+    screwdriver: '🪛'; Default,
+    scroll: '📜'; Default,
+    seal: '🦭'; Default,
+    seat: '💺'; Default,
+    seedling: '🌱'; Default,
+    shark: '🦈'; Default,
+    sheep: '🐑'; Default,
+    shell: [spiral: '🐚']; Default,
+    shield: '🛡'; Default,
+    ship: ['🚢', cruise: '🛳', ferry: '⛴']; Default,
+    shirt: [sports: '🎽', t: '👕']; Default,
+    shoe: [
+        '👞',
+        ballet: '🩰',
+        flat: '🥿',
+        heel: '👠',
+        hike: '🥾',
+        ice: '⛸',
+        roller: '🛼',
+        sandal.heel: '👡',
+        ski: '🎿',
+        sneaker: '👟',
+        tall: '👢',
+        thong: '🩴',
+    ]; Default,
+    shopping: '🛍'; Default,
+    shorts: '🩳'; Default,
+    shoshinsha: '🔰'; Default,
+    shower: '🚿'; Default,
+    shrimp: ['🦐', fried: '🍤']; Default,
+    shrine: '⛩'; Default,
+    sign: [crossing: '🚸', stop: '🛑']; Default,
+    silhouette: ['👤', double: '👥', hug: '🫂', speak: '🗣']; Default,
+    siren: '🚨'; Default,
+    skateboard: '🛹'; Default,
+    skewer: [dango: '🍡', oden: '🍢']; Default,
+    skiing: '⛷'; Default,
+    skull: ['💀', bones: '☠']; Default,
+    skunk: '🦨'; Default,
+    sled: '🛷'; Default,
+    slide: '🛝'; Default,
+    slider: '🎚'; Default,
+    sloth: '🦥'; Default,
+    slots: '🎰'; Default,
+    snail: '🐌'; Default,
+    snake: '🐍'; Default,
+    snowboarding: '🏂'; Default,
+    snowflake: '❄'; Default,
+    snowman: ['⛄', snow: '☃']; Default,
+    soap: '🧼'; Default,
+    socks: '🧦'; Default,
+    softball: '🥎'; Default,
+    sos: '🆘'; Default,
+    soup: '🍲'; Default,
+    spaghetti: '🍝'; Default,
+    sparkle: [box: '❇']; Default,
+    sparkler: '🎇'; Default,
+    sparkles: '✨'; Default,
+    speaker: ['🔈', not: '🔇', wave: '🔉', waves: '🔊']; Default,
+    spider: '🕷'; Default,
+    spiderweb: '🕸'; Default,
+    spinach: '🥬'; Default,
+    sponge: '🧽'; Default,
+    spoon: '🥄'; Default,
+    square: [
+        black: '⬛',
+        black.tiny: '▪',
+        black.small: '◾',
+        black.medium: '◼',
+        white: '⬜',
+        white.tiny: '▫',
+        white.small: '◽',
+        white.medium: '◻',
+        blue: '🟦',
+        brown: '🟫',
+        green: '🟩',
+        orange: '🟧',
+        purple: '🟪',
+        red: '🟥',
+        yellow: '🟨',
+    ]; Default,
+    squid: '🦑'; Default,
+    stadium: '🏟'; Default,
+    star: ['⭐', arc: '💫', box: '✴', glow: '🌟', shoot: '🌠']; Default,
+    stethoscope: '🩺'; Default,
+    store: [big: '🏬', small: '🏪']; Default,
+    strawberry: '🍓'; Default,
+    suit: [club: '♣', diamond: '♦', heart: '♥', spade: '♠']; Default,
+    sun: ['☀', cloud: '🌤', face: '🌞']; Default,
+    sunrise: '🌅'; Default,
+    superhero: '🦸'; Default,
+    supervillain: '🦹'; Default,
+    surfing: '🏄'; Default,
+    sushi: '🍣'; Default,
+    swan: '🦢'; Default,
+    swimming: '🏊'; Default,
+    swimsuit: '🩱'; Default,
+    swords: '⚔'; Default,
+    symbols: '🔣'; Default,
+    synagogue: '🕍'; Default,
+    syringe: '💉'; Default,
+    taco: '🌮'; Default,
+    takeout: '🥡'; Default,
+    tamale: '🫔'; Default,
+    tanabata: '🎋'; Default,
+    tangerine: '🍊'; Default,
+    tap: ['🚰', not: '🚱']; Default,
+    taxi: ['🚕', front: '🚖']; Default,
+    teacup: '🍵'; Default,
+    teapot: '🫖'; Default,
+    teddy: '🧸'; Default,
+    telescope: '🔭'; Default,
+    temple: '🛕'; Default,
+    ten: '🔟'; Default,
+    tengu: '👺'; Default,
+    tennis: '🎾'; Default,
+    tent: '⛺'; Default,
+    testtube: '🧪'; Default,
+    thermometer: '🌡'; Default,
+    thread: '🧵'; Default,
+    thumb: [up: '👍', down: '👎']; Default,
+    ticket: [event: '🎟', travel: '🎫']; Default,
+    tiger: ['🐅', face: '🐯']; Default,
+    tm: '™'; Default,
+    toilet: '🚽'; Default,
+    toiletpaper: '🧻'; Default,
+    tomato: '🍅'; Default,
+    tombstone: '🪦'; Default,
+    tongue: '👅'; Default,
+    toolbox: '🧰'; Default,
+    tooth: '🦷'; Default,
+    toothbrush: '🪥'; Default,
+    tornado: '🌪'; Default,
+    tower: [tokyo: '🗼']; Default,
+    trackball: '🖲'; Default,
+    tractor: '🚜'; Default,
+    trafficlight: [v: '🚦', h: '🚥']; Default,
+    train: [
+        '🚆',
+        car: '🚃',
+        light: '🚈',
+        metro: '🚇',
+        mono: '🚝',
+        mountain: '🚞',
+        speed: '🚄',
+        speed.bullet: '🚅',
+        steam: '🚂',
+        stop: '🚉',
+        suspend: '🚟',
+        tram: '🚊',
+        tram.car: '🚋',
+    ]; Default,
+    transgender: '⚧'; Default,
+    tray: [inbox: '📥', mail: '📨', outbox: '📤']; Default,
+    tree: [deciduous: '🌳', evergreen: '🌲', palm: '🌴', xmas: '🎄']; Default,
+    triangle: [r: '▶', l: '◀', t: '🔼', b: '🔽', t.red: '🔺', b.red: '🔻']; Default,
+    trident: '🔱'; Default,
+    troll: '🧌'; Default,
+    trophy: '🏆'; Default,
+    truck: ['🚚', trailer: '🚛']; Default,
+    trumpet: '🎺'; Default,
+    tsukimi: '🎑'; Default,
+    turkey: '🦃'; Default,
+    turtle: '🐢'; Default,
+    tv: '📺'; Default,
+    ufo: '🛸'; Default,
+    umbrella: [open: '☂', closed: '🌂', rain: '☔', sun: '⛱']; Default,
+    unicorn: '🦄'; Default,
+    unknown: '🦳'; Default,
+    up: '🆙'; Default,
+    urn: '⚱'; Default,
+    vampire: '🧛'; Default,
+    violin: '🎻'; Default,
+    volcano: '🌋'; Default,
+    volleyball: '🏐'; Default,
+    vs: '🆚'; Default,
+    waffle: '🧇'; Default,
+    wand: '🪄'; Default,
+    warning: '⚠'; Default,
+    watch: ['⌚', stop: '⏱']; Default,
+    watermelon: '🍉'; Default,
+    waterpolo: '🤽'; Default,
+    wave: '🌊'; Default,
+    wc: '🚾'; Default,
+    weightlifting: '🏋'; Default,
+    whale: ['🐋', spout: '🐳']; Default,
+    wheel: '🛞'; Default,
+    wheelchair: ['🦽', box: '♿', motor: '🦼']; Default,
+    wind: '🌬'; Default,
+    windchime: '🎐'; Default,
+    window: '🪟'; Default,
+    wine: '🍷'; Default,
+    wolf: '🐺'; Default,
+    woman: ['👩', box: '🚺', crown: '👸', old: '👵', pregnant: '🤰']; Default,
+    wood: '🪵'; Default,
+    worm: '🪱'; Default,
+    wrench: '🔧'; Default,
+    wrestling: '🤼'; Default,
+    xray: '🩻'; Default,
+    yarn: '🧶'; Default,
+    yoyo: '🪀'; Default,
+    zebra: '🦓'; Default,
+    zodiac: [
+        aquarius: '♒',
+        aries: '♈',
+        cancer: '♋',
+        capri: '♑',
+        gemini: '♊',
+        leo: '♌',
+        libra: '♎',
+        ophi: '⛎',
+        pisces: '♓',
+        sagit: '♐',
+        scorpio: '♏',
+        taurus: '♉',
+        virgo: '♍',
+    ]; Default,
+    zombie: '🧟'; Default,
+    zzz: '💤'; Default,
+};
@@ -0,0 +1,61 @@
+//! Interning of decoration uuids into numeric ids
+use std::collections::HashMap;
+
+/// Table of the uuids met so far, each uuid having the index of its first appearance as id
+#[derive(Clone, Default, Debug)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    uuids: Vec<String>,
+    /// Buffer joining the parts of the uuid being interned, to look it up without allocating
+    key: String,
+}
+
+impl Interner {
+    /// Get the id of a uuid given in parts (`["top-", "alpha"]`), adding it to the table if it is new \
+    /// The uuid is only allocated when it is added
+    pub fn intern<'a>(&mut self, parts: impl IntoIterator<Item = &'a str>) -> u32 {
+        self.key.clear();
+        self.key.extend(parts);
+        if let Some(id) = self.ids.get(self.key.as_str()) {
+            return *id;
+        }
+        let id = self.uuids.len() as u32;
+        self.uuids.push(self.key.clone());
+        self.ids.insert(self.key.clone(), id);
+        id
+    }
+    /// Id of the next new uuid, which is also the number of uuids in the table
+    pub fn next_id(&self) -> u32 {
+        self.uuids.len() as u32
+    }
+    /// Get the uuid of an id, to merge the tables of the parallel traversal
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn uuid(&self, id: u32) -> Option<&str> {
+        self.uuids.get(id as usize).map(|uuid| uuid.as_str())
+    }
+    /// Uuids added to the table since it had `start` uuids
+    pub fn uuids_since(&self, start: u32) -> Vec<String> {
+        self.uuids
+            .get(start as usize..)
+            .map_or(vec![], |uuids| uuids.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Interner;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = Interner::default();
+        assert_eq!(interner.intern(["alpha"]), 0);
+        assert_eq!(interner.intern(["top-", "beta"]), 1);
+        assert_eq!(interner.intern(["alpha"]), 0);
+        assert_eq!(interner.intern(["top-beta"]), 1);
+        assert_eq!(interner.intern(["", "al", "pha"]), 0);
+        let start = interner.next_id();
+        assert_eq!(interner.intern(["gamma"]), 2);
+        assert_eq!(interner.uuids_since(start), vec!["gamma"]);
+        assert_eq!(interner.uuids_since(0), vec!["alpha", "top-beta", "gamma"]);
+    }
+}
@@ -1,3 +1,7 @@
+pub mod depth;
+pub mod emoji;
 pub mod hook;
+pub mod interner;
+pub mod search;
 pub mod styles;
 pub mod symbols;
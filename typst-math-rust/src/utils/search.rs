@@ -0,0 +1,149 @@
+//! Fuzzy search of symbols by name, for symbol pickers
+use crate::interface::SymbolInfo;
+
+/// Cost of a query term matching a part of a name, None if it doesn't match \
+/// Exact parts cost nothing, then prefixes, then abbreviations, then parts at a small edit distance
+fn term_cost(term: &str, part: &str) -> Option<usize> {
+    if term == part {
+        return Some(0);
+    }
+    if part.starts_with(term) {
+        return Some(1);
+    }
+    if is_abbreviation(term, part) {
+        return Some(2);
+    }
+    let distance = edit_distance(term, part);
+    let allowed = (term.chars().count() / 3).max(1);
+    (distance <= allowed).then_some(3 + distance)
+}
+
+/// Check if a term abbreviates a part, starting with the same letter and keeping the order of its letters (`rgt` for `right`)
+fn is_abbreviation(term: &str, part: &str) -> bool {
+    let mut letters = part.chars();
+    term.chars().next() == part.chars().next()
+        && term.chars().all(|c| letters.any(|letter| letter == c))
+}
+
+/// Word spelled out by a one-letter part of a name, like `r` in `arrow.r`
+fn spelled_out(part: &str) -> Option<&'static str> {
+    match part {
+        "r" => Some("right"),
+        "l" => Some("left"),
+        "t" => Some("top"),
+        "b" => Some("bottom"),
+        _ => None,
+    }
+}
+
+/// Levenshtein distance between two strings, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Cost of a name for a query, None if a term of the query doesn't match any part of the name \
+/// One-letter parts also match the word they stand for, so that `arrow.r` is found with `right`
+fn name_cost(terms: &[&str], name: &str) -> Option<usize> {
+    let parts: Vec<&str> = name.split('.').collect();
+    terms.iter().try_fold(0, |cost, term| {
+        let best = parts
+            .iter()
+            .flat_map(|part| std::iter::once(*part).chain(spelled_out(part)))
+            .filter_map(|part| term_cost(term, part))
+            .min()?;
+        Some(cost + best)
+    })
+}
+
+/// Rank the symbols matching a query, whose terms are separated by spaces or dots \
+/// The best matches come first, then the shortest names
+pub fn search(symbols: Vec<SymbolInfo>, query: &str, limit: usize) -> Vec<SymbolInfo> {
+    let query = query.to_lowercase();
+    let terms: Vec<&str> = query
+        .split(|c: char| c.is_whitespace() || c == '.')
+        .filter(|term| !term.is_empty())
+        .collect();
+    if terms.is_empty() {
+        return vec![];
+    }
+    let mut matches: Vec<(usize, SymbolInfo)> = symbols
+        .into_iter()
+        .filter_map(|symbol| Some((name_cost(&terms, &symbol.name.to_lowercase())?, symbol)))
+        .collect();
+    matches
+        .sort_by_key(|(cost, symbol)| (*cost, symbol.name.split('.').count(), symbol.name.len()));
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, symbol)| symbol)
+        .collect()
+}
+
+/// Find the known names closest to an unknown one, for typos like `alpa` \
+/// Names it starts come first, then the names it abbreviates, then the names at a small edit distance, the shortest first
+pub fn nearest_names<'a>(
+    name: &str,
+    known: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let mut matches: Vec<(usize, &str)> = known
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .filter_map(|candidate| Some((term_cost(name, candidate)?, candidate)))
+        .collect();
+    matches.sort_by_key(|(cost, candidate)| (*cost, candidate.len(), *candidate));
+    matches.dedup_by_key(|(_, candidate)| *candidate);
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, is_abbreviation, name_cost, nearest_names};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("arow", "arrow"), 1);
+        assert_eq!(edit_distance("alpha", "alpha"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_is_abbreviation() {
+        assert!(is_abbreviation("rgt", "right"));
+        assert!(is_abbreviation("arow", "arrow"));
+        assert!(!is_abbreviation("gt", "right"));
+        assert!(!is_abbreviation("rtg", "right"));
+    }
+
+    #[test]
+    fn test_name_cost() {
+        assert_eq!(name_cost(&["arow", "rgt"], "arrow.r"), Some(4));
+        assert_eq!(name_cost(&["arrow", "right"], "arrow.r"), Some(0));
+        assert_eq!(name_cost(&["arrow", "rgt"], "arrow.l"), None);
+    }
+
+    #[test]
+    fn test_nearest_names() {
+        let known = ["alpha", "aleph", "alef", "beta", "phi"];
+        assert_eq!(nearest_names("alpa", known, 3), vec!["alpha"]);
+        assert_eq!(nearest_names("alep", known, 3), vec!["aleph", "alef"]);
+        assert_eq!(nearest_names("bet", known, 3), vec!["beta"]);
+        assert!(nearest_names("xyz", known, 3).is_empty());
+    }
+}
@@ -1,33 +1,106 @@
 //! Styles for symbols rendering
 use super::symbols::Color;
+use crate::interface::Fonts;
+use phf::phf_map;
 
 /// Styles for symbols rendering, ordered by category
-pub const SYMBOLS_STYLES: [(Color, &str); 9] = [
+pub const SYMBOLS_STYLES: [(Color, &str); 10] = [
     // KEYWORDS
-    (
-        Color::Keyword,
-        "font-family: \"NewComputerModernMath\"; font-weight: bold;",
-    ),
+    (Color::Keyword, "font-family: @math; font-weight: bold;"),
     // COMPARISON
-    (
-        Color::Comparison,
-        "font-family: \"NewComputerModernMath\"; font-weight: bold;",
-    ),
+    (Color::Comparison, "font-family: @math; font-weight: bold;"),
     // OPERATORS
-    (Color::Operator, "font-family: \"Fira Math\";"),
+    (Color::Operator, "font-family: @symbols;"),
     // NUMBERS
-    (Color::Number, "font-family: \"NewComputerModernMath\";"),
+    (Color::Number, "font-family: @math;"),
     // GREEK LETTERS
-    (Color::Letter, "font-family: \"JuliaMono\";"),
+    (Color::Letter, "font-family: @mono;"),
     // BIG GREEK LETTERS
-    (Color::Letter, "font-family: \"NewComputerModernMath\";"),
+    (Color::Letter, "font-family: @math;"),
     // SETS
-    (Color::Set, "font-family: \"Fira Math\";"),
+    (Color::Set, "font-family: @symbols;"),
     // SPACES
     (
         Color::Number,
         "box-shadow: 0px 0px 0px 1px rgba(128, 128, 128, 0.5); background-color: #80808080",
     ),
+    // DIFFERENTIALS
+    (
+        Color::Differential,
+        "font-family: @math; font-style: normal;",
+    ),
     // DEFAULT
-    (Color::Number, "font-family: \"NewComputerModernMath\";"),
+    (Color::Number, "font-family: @math;"),
 ];
+
+/// Replace the font placeholders of a css style (`@math`, `@mono`, `@symbols`) with the fonts set by the user
+pub fn resolve_fonts(css: &str, fonts: &Fonts) -> String {
+    if !css.contains('@') {
+        return css.to_string();
+    }
+    let family = |font: &str| {
+        std::iter::once(font)
+            .chain(fonts.fallback.iter().map(String::as_str))
+            .map(|font| format!("\"{}\"", font))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    css.replace("@math", &family(&fonts.math))
+        .replace("@mono", &family(&fonts.mono))
+        .replace("@symbols", &family(&fonts.symbols))
+}
+
+/// Css properties moving or resizing the symbols, which some hosts can't apply
+const LAYOUT_PROPERTIES: [&str; 4] = ["transform", "position", "display", "font-size"];
+
+/// Remove the declarations of a css style which move or resize the symbols, keeping their fonts and colors
+pub fn without_layout(css: &str) -> String {
+    css.split(';')
+        .map(str::trim)
+        .filter(|declaration| {
+            let property = declaration.split(':').next().unwrap_or_default().trim();
+            !declaration.is_empty() && !LAYOUT_PROPERTIES.contains(&property)
+        })
+        .map(|declaration| format!("{};", declaration))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Check if a color set by the user is a css color in `#RRGGBB` or `rgb(r, g, b)` format
+pub fn is_css_color(color: &str) -> bool {
+    if let Some(hex) = color.strip_prefix('#') {
+        matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    } else if let Some(values) = color
+        .strip_prefix("rgba(")
+        .or_else(|| color.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        values
+            .split(',')
+            .all(|value| value.trim().parse::<f32>().is_ok())
+    } else {
+        false
+    }
+}
+
+/// Typst predefined colors, with their css value
+pub static NAMED_COLORS: phf::Map<&str, &str> = phf_map! {
+    "black" => "#000000",
+    "gray" => "#aaaaaa",
+    "silver" => "#dddddd",
+    "white" => "#ffffff",
+    "navy" => "#001f3f",
+    "blue" => "#0074d9",
+    "aqua" => "#7fdbff",
+    "teal" => "#39cccc",
+    "eastern" => "#239dad",
+    "purple" => "#b10dc9",
+    "fuchsia" => "#f012be",
+    "maroon" => "#85144b",
+    "red" => "#ff4136",
+    "orange" => "#ff851b",
+    "yellow" => "#ffdc00",
+    "olive" => "#3d9970",
+    "green" => "#2ecc40",
+    "lime" => "#01ff70",
+};
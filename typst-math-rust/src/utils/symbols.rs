@@ -2,7 +2,8 @@
 //! Stealed from https://github.com/typst/typst/blob/main/crates/typst/src/symbols/symbol.rs
 //! and edited to be used in the frontend.
 
-use phf::phf_map;
+use phf::{phf_map, phf_ordered_map};
+use serde::Serialize;
 use std::fmt::Debug;
 use typst_math_macros::symbols;
 use wasm_bindgen::prelude::*;
@@ -17,7 +18,7 @@ pub struct Symbol {
 
 /// Represents a symbol category, used for styling.
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
 pub enum Category {
     Keyword,
@@ -28,11 +29,12 @@ pub enum Category {
     BigLetter,
     Set,
     Space,
+    Differential,
     Default,
 }
 
-pub fn get_category_by_name(name: &String) -> Category {
-    return match name.to_lowercase().as_str() {
+pub fn get_category_by_name(name: &str) -> Category {
+    match name.to_lowercase().as_str() {
         "keyword" => Category::Keyword,
         "comparison" => Category::Comparison,
         "operator" => Category::Operator,
@@ -41,13 +43,15 @@ pub fn get_category_by_name(name: &String) -> Category {
         "bigletter" => Category::BigLetter,
         "set" => Category::Set,
         "space" => Category::Space,
+        "differential" => Category::Differential,
         _ => Category::Default,
-    };
+    }
 }
 
 /// Represents a symbol color, passed to the frontend for styling.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 #[cfg_attr(not(feature = "coverage"), wasm_bindgen)]
+#[cfg_attr(test, derive(ts_rs::TS), ts(export))]
 pub enum Color {
     Keyword,
     Comparison,
@@ -55,10 +59,25 @@ pub enum Color {
     Letter,
     Set,
     Number,
+    Differential,
+}
+
+/// Get a color from its name in the settings, `group` being the color of sets
+pub fn get_color_by_name(name: &str) -> Option<Color> {
+    match name.to_lowercase().as_str() {
+        "keyword" => Some(Color::Keyword),
+        "comparison" => Some(Color::Comparison),
+        "operator" => Some(Color::Operator),
+        "letter" => Some(Color::Letter),
+        "group" | "set" => Some(Color::Set),
+        "number" => Some(Color::Number),
+        "differential" => Some(Color::Differential),
+        _ => None,
+    }
 }
 
 /// The list of general symbols.
-pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
+pub static SYMBOLS: phf::OrderedMap<&str, Symbol> = symbols! {
     // Control.
     wj: '\u{2060}'; Space,
     zwj: '\u{200D}'; Space,
@@ -88,7 +107,7 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
     paren: [l: '(', r: ')', t: '⏜', b: '⏝']; Set,
     brace: [l: '{', r: '}', t: '⏞', b: '⏟']; Set,
     bracket: [l: '[', l.double: '⟦', r: ']', r.double: '⟧', t: '⎴', b: '⎵']; Set,
-    shell: [l: '〔', r: '〕', t: '⏠', b: '⏡']; Set,
+    turtle: [l: '〔', r: '〕', t: '⏠', b: '⏡']; Set,
     bar: [v: '|', v.double: '‖', v.triple: '⦀', v.broken: '¦', v.circle: '⦶', h: '―']; Operator,
     fence: [l: '⧘', l.double: '⧚', r: '⧙', r.double: '⧛', dotted: '⦙']; Comparison,
     angle: [
@@ -162,7 +181,7 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
     section: '§'; Default,
     semi: [';', rev: '⁏']; Default,
     slash: ['/', double: '⫽', triple: '⫻', big: '\u{29f8}']; Comparison,
-    dots: [h.c: '⋯', h: '…', v: '⋮', down: '⋱', up: '⋰']; Comparison,
+    dots: [h.c: '⋯', h: '…', v: '⋮', down: '⋱', up: '⋰']; Operator,
     tilde: [
         op: '∼',
         basic: '~',
@@ -329,7 +348,7 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
         approx: '⪅',
         double: '≪',
         eq: '≤',
-        eq.slant: '⩽' ,
+        eq.slant: '⩽',
         eq.gt: '⋚',
         eq.not: '≰',
         equiv: '≦',
@@ -378,22 +397,13 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
     ]; Comparison,
     equiv: ['≡', not: '≢']; Comparison,
     prop: '∝'; Default,
-    original: '⊶'; Default,
-    image: '⊷'; Default,
 
     // Set theory.
     emptyset: ['∅', rev: '⦰']; Set,
     nothing: ['∅', rev: '⦰']; Set,
     without: '∖'; Set,
     complement: '∁'; Keyword,
-    in: [
-        '∈',
-        not: '∉',
-        rev: '∋',
-        rev.not: '∌',
-        rev.small: '∍',
-        small: '∊',
-    ]; Keyword,
+    in: ['∈', not: '∉', rev: '∋', rev.not: '∌', rev.small: '∍', small: '∊']; Keyword,
     subset: [
         '⊂',
         dot: '⪽',
@@ -445,23 +455,12 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
         sq.big: '⨅',
         sq.double: '⩎',
     ]; Keyword,
-    inter: [
-        '∩',
-        and: '⩄',
-        big: '⋂',
-        dot: '⩀',
-        double: '⋒',
-        sq: '⊓',
-        sq.big: '⨅',
-        sq.double: '⩎',
-    ]; Keyword,
 
     // Calculus.
     infinity: '∞'; Default,
     oo: '∞'; Default,
-    dif: 'd'; Default, // Added manually, not from official typst repo
-    diff: '∂'; Default, // Deprecation planned
-    partial: '∂'; Default,
+    diff: '∂'; Differential,
+    partial: '∂'; Differential,
     gradient: '∇'; Default,
     nabla: '∇'; Default,
     sum: ['∑', integral: '⨋']; BigLetter,
@@ -547,6 +546,10 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
     servicemark: '℠'; Default,
     maltese: '✠'; Default,
     suit: [
+        club: '♣',
+        diamond: '♦',
+        heart: '♥',
+        spade: '♠',
         club.filled: '♣',
         club.stroked: '♧',
         diamond.filled: '♦',
@@ -571,40 +574,35 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
         dotted: '◌',
         nested: '⊚',
     ]; Default,
-    ellipse: [
-        stroked.h: '⬭',
-        stroked.v: '⬯',
-        filled.h: '⬬',
-        filled.v: '⬮',
-    ]; Default,
+    ellipse: [stroked.h: '⬭', stroked.v: '⬯', filled.h: '⬬', filled.v: '⬮']; Default,
     triangle: [
-        stroked.t: '△',
-        stroked.b: '▽',
         stroked.r: '▷',
         stroked.l: '◁',
+        stroked.t: '△',
+        stroked.b: '▽',
         stroked.bl: '◺',
         stroked.br: '◿',
         stroked.tl: '◸',
         stroked.tr: '◹',
-        stroked.small.t: '▵',
-        stroked.small.b: '▿',
         stroked.small.r: '▹',
+        stroked.small.b: '▿',
         stroked.small.l: '◃',
+        stroked.small.t: '▵',
         stroked.rounded: '🛆',
         stroked.nested: '⟁',
         stroked.dot: '◬',
-        filled.t: '▲',
-        filled.b: '▼',
         filled.r: '▶',
         filled.l: '◀',
+        filled.t: '▲',
+        filled.b: '▼',
         filled.bl: '◣',
         filled.br: '◢',
         filled.tl: '◤',
         filled.tr: '◥',
-        filled.small.t: '▴',
-        filled.small.b: '▾',
         filled.small.r: '▸',
+        filled.small.b: '▾',
         filled.small.l: '◂',
+        filled.small.t: '▴',
     ]; Default,
     square: [
         stroked: '□',
@@ -620,12 +618,7 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
         filled.medium: '◼',
         filled.big: '⬛',
     ]; Default,
-    rect: [
-        stroked.h: '▭',
-        stroked.v: '▯',
-        filled.h: '▬',
-        filled.v: '▮',
-    ]; Default,
+    rect: [stroked.h: '▭', stroked.v: '▯', filled.h: '▬', filled.v: '▮']; Default,
     penta: [stroked: '⬠', filled: '⬟']; Default,
     hexa: [stroked: '⬡', filled: '⬢']; Default,
     diamond: [
@@ -645,10 +638,6 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
         filled.small: '⬪',
         filled.medium: '⬧',
     ]; Default,
-    parallelogram: [
-        stroked: '▱',
-        filled: '▰',
-    ]; Default,
     star: [op: '⋆', stroked: '☆', filled: '★']; Operator,
 
     // Arrows, harpoons, and tacks.
@@ -783,10 +772,7 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
         rrr: '⇶',
         lll: '⬱',
     ]; Comparison,
-    arrowhead: [
-        t: '⌃',
-        b: '⌄',
-    ]; Default,
+    arrowhead: [t: '⌃', b: '⌄']; Default,
     harpoon: [
         rt: '⇀',
         rt.bar: '⥛',
@@ -959,10 +945,28 @@ pub const SYMBOLS: phf::Map<&str, Symbol> = symbols! {
     Re: 'ℜ'; Default,
     Im: 'ℑ'; Default,
     dotless: [i: '𝚤', j: '𝚥']; Letter,
+
+    // Not defined in the typst sym module.
+    shell: [l: '〔', r: '〕', t: '⏠', b: '⏡']; Set,
+    original: '⊶'; Default,
+    image: '⊷'; Default,
+    inter: [
+        '∩',
+        and: '⩄',
+        big: '⋂',
+        dot: '⩀',
+        double: '⋒',
+        sq: '⊓',
+        sq.big: '⨅',
+        sq.double: '⩎',
+    ]; Keyword,
+    dif: 'd'; Differential,
+    parallelogram: [stroked: '▱', filled: '▰']; Default,
 };
 
-/// The list of caligraphic letters.
-pub const CAL_LETTERS: phf::Map<char, char> = phf_map! {
+/// The list of caligraphic letters. \
+/// Unicode has no script digits, so they are left untouched by the substitution loop.
+pub static CAL_LETTERS: phf::Map<char, char> = phf_map! {
     'A' => '𝒜',
     'B' => 'ℬ',
     'C' => '𝒞',
@@ -1018,7 +1022,7 @@ pub const CAL_LETTERS: phf::Map<char, char> = phf_map! {
 };
 
 /// The list of fraktur letters.
-pub const FRAK_LETTERS: phf::Map<char, char> = phf_map! {
+pub static FRAK_LETTERS: phf::Map<char, char> = phf_map! {
     'A' => '𝔄',
     'B' => '𝔅',
     'C' => 'ℭ',
@@ -1074,7 +1078,7 @@ pub const FRAK_LETTERS: phf::Map<char, char> = phf_map! {
 };
 
 /// The list of blackbold letters.
-pub const BLACKBOLD_LETTERS: phf::Map<char, char> = phf_map! {
+pub static BLACKBOLD_LETTERS: phf::Map<char, char> = phf_map! {
     'A' => '𝔸',
     'B' => '𝔹',
     'C' => 'ℂ',
@@ -1137,39 +1141,1872 @@ pub const BLACKBOLD_LETTERS: phf::Map<char, char> = phf_map! {
     '7' => '𝟟',
     '8' => '𝟠',
     '9' => '𝟡',
+    'γ' => 'ℽ',
+    'Γ' => 'ℾ',
+    'π' => 'ℼ',
+    'Π' => 'ℿ',
+    '∑' => '⅀',
+};
+
+/// The list of bold letters.
+pub static BOLD_LETTERS: phf::Map<char, char> = phf_map! {
+    'A' => '𝐀',
+    'B' => '𝐁',
+    'C' => '𝐂',
+    'D' => '𝐃',
+    'E' => '𝐄',
+    'F' => '𝐅',
+    'G' => '𝐆',
+    'H' => '𝐇',
+    'I' => '𝐈',
+    'J' => '𝐉',
+    'K' => '𝐊',
+    'L' => '𝐋',
+    'M' => '𝐌',
+    'N' => '𝐍',
+    'O' => '𝐎',
+    'P' => '𝐏',
+    'Q' => '𝐐',
+    'R' => '𝐑',
+    'S' => '𝐒',
+    'T' => '𝐓',
+    'U' => '𝐔',
+    'V' => '𝐕',
+    'W' => '𝐖',
+    'X' => '𝐗',
+    'Y' => '𝐘',
+    'Z' => '𝐙',
+    'a' => '𝐚',
+    'b' => '𝐛',
+    'c' => '𝐜',
+    'd' => '𝐝',
+    'e' => '𝐞',
+    'f' => '𝐟',
+    'g' => '𝐠',
+    'h' => '𝐡',
+    'i' => '𝐢',
+    'j' => '𝐣',
+    'k' => '𝐤',
+    'l' => '𝐥',
+    'm' => '𝐦',
+    'n' => '𝐧',
+    'o' => '𝐨',
+    'p' => '𝐩',
+    'q' => '𝐪',
+    'r' => '𝐫',
+    's' => '𝐬',
+    't' => '𝐭',
+    'u' => '𝐮',
+    'v' => '𝐯',
+    'w' => '𝐰',
+    'x' => '𝐱',
+    'y' => '𝐲',
+    'z' => '𝐳',
+    '0' => '𝟎',
+    '1' => '𝟏',
+    '2' => '𝟐',
+    '3' => '𝟑',
+    '4' => '𝟒',
+    '5' => '𝟓',
+    '6' => '𝟔',
+    '7' => '𝟕',
+    '8' => '𝟖',
+    '9' => '𝟗',
+    'Α' => '𝚨',
+    'Β' => '𝚩',
+    'Γ' => '𝚪',
+    'Δ' => '𝚫',
+    'Ε' => '𝚬',
+    'Ζ' => '𝚭',
+    'Η' => '𝚮',
+    'Θ' => '𝚯',
+    'Ι' => '𝚰',
+    'Κ' => '𝚱',
+    'Λ' => '𝚲',
+    'Μ' => '𝚳',
+    'Ν' => '𝚴',
+    'Ξ' => '𝚵',
+    'Ο' => '𝚶',
+    'Π' => '𝚷',
+    'Ρ' => '𝚸',
+    'Σ' => '𝚺',
+    'Τ' => '𝚻',
+    'Υ' => '𝚼',
+    'Φ' => '𝚽',
+    'Χ' => '𝚾',
+    'Ψ' => '𝚿',
+    'Ω' => '𝛀',
+    'ϴ' => '𝚹',
+    '∇' => '𝛁',
+    'α' => '𝛂',
+    'β' => '𝛃',
+    'γ' => '𝛄',
+    'δ' => '𝛅',
+    'ε' => '𝛆',
+    'ζ' => '𝛇',
+    'η' => '𝛈',
+    'θ' => '𝛉',
+    'ι' => '𝛊',
+    'κ' => '𝛋',
+    'λ' => '𝛌',
+    'μ' => '𝛍',
+    'ν' => '𝛎',
+    'ξ' => '𝛏',
+    'ο' => '𝛐',
+    'π' => '𝛑',
+    'ρ' => '𝛒',
+    'ς' => '𝛓',
+    'σ' => '𝛔',
+    'τ' => '𝛕',
+    'υ' => '𝛖',
+    'φ' => '𝛗',
+    'χ' => '𝛘',
+    'ψ' => '𝛙',
+    'ω' => '𝛚',
+    '∂' => '𝛛',
+    'ϵ' => '𝛜',
+    'ϑ' => '𝛝',
+    'ϰ' => '𝛞',
+    'ϕ' => '𝛟',
+    'ϱ' => '𝛠',
+    'ϖ' => '𝛡',
+    'Ϝ' => '𝟊',
+    'ϝ' => '𝟋',
+    // Bold caligraphic
+    '𝒜' => '𝓐',
+    'ℬ' => '𝓑',
+    '𝒞' => '𝓒',
+    '𝒟' => '𝓓',
+    'ℰ' => '𝓔',
+    'ℱ' => '𝓕',
+    '𝒢' => '𝓖',
+    'ℋ' => '𝓗',
+    'ℐ' => '𝓘',
+    '𝒥' => '𝓙',
+    '𝒦' => '𝓚',
+    'ℒ' => '𝓛',
+    'ℳ' => '𝓜',
+    '𝒩' => '𝓝',
+    '𝒪' => '𝓞',
+    '𝒫' => '𝓟',
+    '𝒬' => '𝓠',
+    'ℛ' => '𝓡',
+    '𝒮' => '𝓢',
+    '𝒯' => '𝓣',
+    '𝒰' => '𝓤',
+    '𝒱' => '𝓥',
+    '𝒲' => '𝓦',
+    '𝒳' => '𝓧',
+    '𝒴' => '𝓨',
+    '𝒵' => '𝓩',
+    '𝒶' => '𝓪',
+    '𝒷' => '𝓫',
+    '𝒸' => '𝓬',
+    '𝒹' => '𝓭',
+    'ℯ' => '𝓮',
+    '𝒻' => '𝓯',
+    'ℊ' => '𝓰',
+    '𝒽' => '𝓱',
+    '𝒾' => '𝓲',
+    '𝒿' => '𝓳',
+    '𝓀' => '𝓴',
+    '𝓁' => '𝓵',
+    '𝓂' => '𝓶',
+    '𝓃' => '𝓷',
+    'ℴ' => '𝓸',
+    '𝓅' => '𝓹',
+    '𝓆' => '𝓺',
+    '𝓇' => '𝓻',
+    '𝓈' => '𝓼',
+    '𝓉' => '𝓽',
+    '𝓊' => '𝓾',
+    '𝓋' => '𝓿',
+    '𝓌' => '𝔀',
+    '𝓍' => '𝔁',
+    '𝓎' => '𝔂',
+    '𝓏' => '𝔃',
+    // Bold fraktur
+    '𝔄' => '𝕬',
+    '𝔅' => '𝕭',
+    'ℭ' => '𝕮',
+    '𝔇' => '𝕯',
+    '𝔈' => '𝕰',
+    '𝔉' => '𝕱',
+    '𝔊' => '𝕲',
+    'ℌ' => '𝕳',
+    'ℑ' => '𝕴',
+    '𝔍' => '𝕵',
+    '𝔎' => '𝕶',
+    '𝔏' => '𝕷',
+    '𝔐' => '𝕸',
+    '𝔑' => '𝕹',
+    '𝔒' => '𝕺',
+    '𝔓' => '𝕻',
+    '𝔔' => '𝕼',
+    'ℜ' => '𝕽',
+    '𝔖' => '𝕾',
+    '𝔗' => '𝕿',
+    '𝔘' => '𝖀',
+    '𝔙' => '𝖁',
+    '𝔚' => '𝖂',
+    '𝔛' => '𝖃',
+    '𝔜' => '𝖄',
+    'ℨ' => '𝖅',
+    '𝔞' => '𝖆',
+    '𝔟' => '𝖇',
+    '𝔠' => '𝖈',
+    '𝔡' => '𝖉',
+    '𝔢' => '𝖊',
+    '𝔣' => '𝖋',
+    '𝔤' => '𝖌',
+    '𝔥' => '𝖍',
+    '𝔦' => '𝖎',
+    '𝔧' => '𝖏',
+    '𝔨' => '𝖐',
+    '𝔩' => '𝖑',
+    '𝔪' => '𝖒',
+    '𝔫' => '𝖓',
+    '𝔬' => '𝖔',
+    '𝔭' => '𝖕',
+    '𝔮' => '𝖖',
+    '𝔯' => '𝖗',
+    '𝔰' => '𝖘',
+    '𝔱' => '𝖙',
+    '𝔲' => '𝖚',
+    '𝔳' => '𝖛',
+    '𝔴' => '𝖜',
+    '𝔵' => '𝖝',
+    '𝔶' => '𝖞',
+    '𝔷' => '𝖟',
+    // Bold italic
+    '𝐴' => '𝑨',
+    '𝐵' => '𝑩',
+    '𝐶' => '𝑪',
+    '𝐷' => '𝑫',
+    '𝐸' => '𝑬',
+    '𝐹' => '𝑭',
+    '𝐺' => '𝑮',
+    '𝐻' => '𝑯',
+    '𝐼' => '𝑰',
+    '𝐽' => '𝑱',
+    '𝐾' => '𝑲',
+    '𝐿' => '𝑳',
+    '𝑀' => '𝑴',
+    '𝑁' => '𝑵',
+    '𝑂' => '𝑶',
+    '𝑃' => '𝑷',
+    '𝑄' => '𝑸',
+    '𝑅' => '𝑹',
+    '𝑆' => '𝑺',
+    '𝑇' => '𝑻',
+    '𝑈' => '𝑼',
+    '𝑉' => '𝑽',
+    '𝑊' => '𝑾',
+    '𝑋' => '𝑿',
+    '𝑌' => '𝒀',
+    '𝑍' => '𝒁',
+    '𝑎' => '𝒂',
+    '𝑏' => '𝒃',
+    '𝑐' => '𝒄',
+    '𝑑' => '𝒅',
+    '𝑒' => '𝒆',
+    '𝑓' => '𝒇',
+    '𝑔' => '𝒈',
+    'ℎ' => '𝒉',
+    '𝑖' => '𝒊',
+    '𝑗' => '𝒋',
+    '𝑘' => '𝒌',
+    '𝑙' => '𝒍',
+    '𝑚' => '𝒎',
+    '𝑛' => '𝒏',
+    '𝑜' => '𝒐',
+    '𝑝' => '𝒑',
+    '𝑞' => '𝒒',
+    '𝑟' => '𝒓',
+    '𝑠' => '𝒔',
+    '𝑡' => '𝒕',
+    '𝑢' => '𝒖',
+    '𝑣' => '𝒗',
+    '𝑤' => '𝒘',
+    '𝑥' => '𝒙',
+    '𝑦' => '𝒚',
+    '𝑧' => '𝒛',
+    '𝛢' => '𝜜',
+    '𝛣' => '𝜝',
+    '𝛤' => '𝜞',
+    '𝛥' => '𝜟',
+    '𝛦' => '𝜠',
+    '𝛧' => '𝜡',
+    '𝛨' => '𝜢',
+    '𝛩' => '𝜣',
+    '𝛪' => '𝜤',
+    '𝛫' => '𝜥',
+    '𝛬' => '𝜦',
+    '𝛭' => '𝜧',
+    '𝛮' => '𝜨',
+    '𝛯' => '𝜩',
+    '𝛰' => '𝜪',
+    '𝛱' => '𝜫',
+    '𝛲' => '𝜬',
+    '𝛴' => '𝜮',
+    '𝛵' => '𝜯',
+    '𝛶' => '𝜰',
+    '𝛷' => '𝜱',
+    '𝛸' => '𝜲',
+    '𝛹' => '𝜳',
+    '𝛺' => '𝜴',
+    '𝛳' => '𝜭',
+    '𝛻' => '𝜵',
+    '𝛼' => '𝜶',
+    '𝛽' => '𝜷',
+    '𝛾' => '𝜸',
+    '𝛿' => '𝜹',
+    '𝜀' => '𝜺',
+    '𝜁' => '𝜻',
+    '𝜂' => '𝜼',
+    '𝜃' => '𝜽',
+    '𝜄' => '𝜾',
+    '𝜅' => '𝜿',
+    '𝜆' => '𝝀',
+    '𝜇' => '𝝁',
+    '𝜈' => '𝝂',
+    '𝜉' => '𝝃',
+    '𝜊' => '𝝄',
+    '𝜋' => '𝝅',
+    '𝜌' => '𝝆',
+    '𝜍' => '𝝇',
+    '𝜎' => '𝝈',
+    '𝜏' => '𝝉',
+    '𝜐' => '𝝊',
+    '𝜑' => '𝝋',
+    '𝜒' => '𝝌',
+    '𝜓' => '𝝍',
+    '𝜔' => '𝝎',
+    '𝜕' => '𝝏',
+    '𝜖' => '𝝐',
+    '𝜗' => '𝝑',
+    '𝜘' => '𝝒',
+    '𝜙' => '𝝓',
+    '𝜚' => '𝝔',
+    '𝜛' => '𝝕',
+    // Bold sans-serif
+    '𝖠' => '𝗔',
+    '𝖡' => '𝗕',
+    '𝖢' => '𝗖',
+    '𝖣' => '𝗗',
+    '𝖤' => '𝗘',
+    '𝖥' => '𝗙',
+    '𝖦' => '𝗚',
+    '𝖧' => '𝗛',
+    '𝖨' => '𝗜',
+    '𝖩' => '𝗝',
+    '𝖪' => '𝗞',
+    '𝖫' => '𝗟',
+    '𝖬' => '𝗠',
+    '𝖭' => '𝗡',
+    '𝖮' => '𝗢',
+    '𝖯' => '𝗣',
+    '𝖰' => '𝗤',
+    '𝖱' => '𝗥',
+    '𝖲' => '𝗦',
+    '𝖳' => '𝗧',
+    '𝖴' => '𝗨',
+    '𝖵' => '𝗩',
+    '𝖶' => '𝗪',
+    '𝖷' => '𝗫',
+    '𝖸' => '𝗬',
+    '𝖹' => '𝗭',
+    '𝖺' => '𝗮',
+    '𝖻' => '𝗯',
+    '𝖼' => '𝗰',
+    '𝖽' => '𝗱',
+    '𝖾' => '𝗲',
+    '𝖿' => '𝗳',
+    '𝗀' => '𝗴',
+    '𝗁' => '𝗵',
+    '𝗂' => '𝗶',
+    '𝗃' => '𝗷',
+    '𝗄' => '𝗸',
+    '𝗅' => '𝗹',
+    '𝗆' => '𝗺',
+    '𝗇' => '𝗻',
+    '𝗈' => '𝗼',
+    '𝗉' => '𝗽',
+    '𝗊' => '𝗾',
+    '𝗋' => '𝗿',
+    '𝗌' => '𝘀',
+    '𝗍' => '𝘁',
+    '𝗎' => '𝘂',
+    '𝗏' => '𝘃',
+    '𝗐' => '𝘄',
+    '𝗑' => '𝘅',
+    '𝗒' => '𝘆',
+    '𝗓' => '𝘇',
+    '𝟢' => '𝟬',
+    '𝟣' => '𝟭',
+    '𝟤' => '𝟮',
+    '𝟥' => '𝟯',
+    '𝟦' => '𝟰',
+    '𝟧' => '𝟱',
+    '𝟨' => '𝟲',
+    '𝟩' => '𝟳',
+    '𝟪' => '𝟴',
+    '𝟫' => '𝟵',
+    // Bold italic sans-serif
+    '𝘈' => '𝘼',
+    '𝘉' => '𝘽',
+    '𝘊' => '𝘾',
+    '𝘋' => '𝘿',
+    '𝘌' => '𝙀',
+    '𝘍' => '𝙁',
+    '𝘎' => '𝙂',
+    '𝘏' => '𝙃',
+    '𝘐' => '𝙄',
+    '𝘑' => '𝙅',
+    '𝘒' => '𝙆',
+    '𝘓' => '𝙇',
+    '𝘔' => '𝙈',
+    '𝘕' => '𝙉',
+    '𝘖' => '𝙊',
+    '𝘗' => '𝙋',
+    '𝘘' => '𝙌',
+    '𝘙' => '𝙍',
+    '𝘚' => '𝙎',
+    '𝘛' => '𝙏',
+    '𝘜' => '𝙐',
+    '𝘝' => '𝙑',
+    '𝘞' => '𝙒',
+    '𝘟' => '𝙓',
+    '𝘠' => '𝙔',
+    '𝘡' => '𝙕',
+    '𝘢' => '𝙖',
+    '𝘣' => '𝙗',
+    '𝘤' => '𝙘',
+    '𝘥' => '𝙙',
+    '𝘦' => '𝙚',
+    '𝘧' => '𝙛',
+    '𝘨' => '𝙜',
+    '𝘩' => '𝙝',
+    '𝘪' => '𝙞',
+    '𝘫' => '𝙟',
+    '𝘬' => '𝙠',
+    '𝘭' => '𝙡',
+    '𝘮' => '𝙢',
+    '𝘯' => '𝙣',
+    '𝘰' => '𝙤',
+    '𝘱' => '𝙥',
+    '𝘲' => '𝙦',
+    '𝘳' => '𝙧',
+    '𝘴' => '𝙨',
+    '𝘵' => '𝙩',
+    '𝘶' => '𝙪',
+    '𝘷' => '𝙫',
+    '𝘸' => '𝙬',
+    '𝘹' => '𝙭',
+    '𝘺' => '𝙮',
+    '𝘻' => '𝙯',
+};
+
+/// The list of italic letters.
+pub static ITALIC_LETTERS: phf::Map<char, char> = phf_map! {
+    'A' => '𝐴',
+    'B' => '𝐵',
+    'C' => '𝐶',
+    'D' => '𝐷',
+    'E' => '𝐸',
+    'F' => '𝐹',
+    'G' => '𝐺',
+    'H' => '𝐻',
+    'I' => '𝐼',
+    'J' => '𝐽',
+    'K' => '𝐾',
+    'L' => '𝐿',
+    'M' => '𝑀',
+    'N' => '𝑁',
+    'O' => '𝑂',
+    'P' => '𝑃',
+    'Q' => '𝑄',
+    'R' => '𝑅',
+    'S' => '𝑆',
+    'T' => '𝑇',
+    'U' => '𝑈',
+    'V' => '𝑉',
+    'W' => '𝑊',
+    'X' => '𝑋',
+    'Y' => '𝑌',
+    'Z' => '𝑍',
+    'a' => '𝑎',
+    'b' => '𝑏',
+    'c' => '𝑐',
+    'd' => '𝑑',
+    'e' => '𝑒',
+    'f' => '𝑓',
+    'g' => '𝑔',
+    'h' => 'ℎ',
+    'i' => '𝑖',
+    'j' => '𝑗',
+    'k' => '𝑘',
+    'l' => '𝑙',
+    'm' => '𝑚',
+    'n' => '𝑛',
+    'o' => '𝑜',
+    'p' => '𝑝',
+    'q' => '𝑞',
+    'r' => '𝑟',
+    's' => '𝑠',
+    't' => '𝑡',
+    'u' => '𝑢',
+    'v' => '𝑣',
+    'w' => '𝑤',
+    'x' => '𝑥',
+    'y' => '𝑦',
+    'z' => '𝑧',
+    'Α' => '𝛢',
+    'Β' => '𝛣',
+    'Γ' => '𝛤',
+    'Δ' => '𝛥',
+    'Ε' => '𝛦',
+    'Ζ' => '𝛧',
+    'Η' => '𝛨',
+    'Θ' => '𝛩',
+    'Ι' => '𝛪',
+    'Κ' => '𝛫',
+    'Λ' => '𝛬',
+    'Μ' => '𝛭',
+    'Ν' => '𝛮',
+    'Ξ' => '𝛯',
+    'Ο' => '𝛰',
+    'Π' => '𝛱',
+    'Ρ' => '𝛲',
+    'Σ' => '𝛴',
+    'Τ' => '𝛵',
+    'Υ' => '𝛶',
+    'Φ' => '𝛷',
+    'Χ' => '𝛸',
+    'Ψ' => '𝛹',
+    'Ω' => '𝛺',
+    'ϴ' => '𝛳',
+    '∇' => '𝛻',
+    'α' => '𝛼',
+    'β' => '𝛽',
+    'γ' => '𝛾',
+    'δ' => '𝛿',
+    'ε' => '𝜀',
+    'ζ' => '𝜁',
+    'η' => '𝜂',
+    'θ' => '𝜃',
+    'ι' => '𝜄',
+    'κ' => '𝜅',
+    'λ' => '𝜆',
+    'μ' => '𝜇',
+    'ν' => '𝜈',
+    'ξ' => '𝜉',
+    'ο' => '𝜊',
+    'π' => '𝜋',
+    'ρ' => '𝜌',
+    'ς' => '𝜍',
+    'σ' => '𝜎',
+    'τ' => '𝜏',
+    'υ' => '𝜐',
+    'φ' => '𝜑',
+    'χ' => '𝜒',
+    'ψ' => '𝜓',
+    'ω' => '𝜔',
+    '∂' => '𝜕',
+    'ϵ' => '𝜖',
+    'ϑ' => '𝜗',
+    'ϰ' => '𝜘',
+    'ϕ' => '𝜙',
+    'ϱ' => '𝜚',
+    'ϖ' => '𝜛',
+    // Bold italic
+    '𝐀' => '𝑨',
+    '𝐁' => '𝑩',
+    '𝐂' => '𝑪',
+    '𝐃' => '𝑫',
+    '𝐄' => '𝑬',
+    '𝐅' => '𝑭',
+    '𝐆' => '𝑮',
+    '𝐇' => '𝑯',
+    '𝐈' => '𝑰',
+    '𝐉' => '𝑱',
+    '𝐊' => '𝑲',
+    '𝐋' => '𝑳',
+    '𝐌' => '𝑴',
+    '𝐍' => '𝑵',
+    '𝐎' => '𝑶',
+    '𝐏' => '𝑷',
+    '𝐐' => '𝑸',
+    '𝐑' => '𝑹',
+    '𝐒' => '𝑺',
+    '𝐓' => '𝑻',
+    '𝐔' => '𝑼',
+    '𝐕' => '𝑽',
+    '𝐖' => '𝑾',
+    '𝐗' => '𝑿',
+    '𝐘' => '𝒀',
+    '𝐙' => '𝒁',
+    '𝐚' => '𝒂',
+    '𝐛' => '𝒃',
+    '𝐜' => '𝒄',
+    '𝐝' => '𝒅',
+    '𝐞' => '𝒆',
+    '𝐟' => '𝒇',
+    '𝐠' => '𝒈',
+    '𝐡' => '𝒉',
+    '𝐢' => '𝒊',
+    '𝐣' => '𝒋',
+    '𝐤' => '𝒌',
+    '𝐥' => '𝒍',
+    '𝐦' => '𝒎',
+    '𝐧' => '𝒏',
+    '𝐨' => '𝒐',
+    '𝐩' => '𝒑',
+    '𝐪' => '𝒒',
+    '𝐫' => '𝒓',
+    '𝐬' => '𝒔',
+    '𝐭' => '𝒕',
+    '𝐮' => '𝒖',
+    '𝐯' => '𝒗',
+    '𝐰' => '𝒘',
+    '𝐱' => '𝒙',
+    '𝐲' => '𝒚',
+    '𝐳' => '𝒛',
+    '𝚨' => '𝜜',
+    '𝚩' => '𝜝',
+    '𝚪' => '𝜞',
+    '𝚫' => '𝜟',
+    '𝚬' => '𝜠',
+    '𝚭' => '𝜡',
+    '𝚮' => '𝜢',
+    '𝚯' => '𝜣',
+    '𝚰' => '𝜤',
+    '𝚱' => '𝜥',
+    '𝚲' => '𝜦',
+    '𝚳' => '𝜧',
+    '𝚴' => '𝜨',
+    '𝚵' => '𝜩',
+    '𝚶' => '𝜪',
+    '𝚷' => '𝜫',
+    '𝚸' => '𝜬',
+    '𝚺' => '𝜮',
+    '𝚻' => '𝜯',
+    '𝚼' => '𝜰',
+    '𝚽' => '𝜱',
+    '𝚾' => '𝜲',
+    '𝚿' => '𝜳',
+    '𝛀' => '𝜴',
+    '𝚹' => '𝜭',
+    '𝛁' => '𝜵',
+    '𝛂' => '𝜶',
+    '𝛃' => '𝜷',
+    '𝛄' => '𝜸',
+    '𝛅' => '𝜹',
+    '𝛆' => '𝜺',
+    '𝛇' => '𝜻',
+    '𝛈' => '𝜼',
+    '𝛉' => '𝜽',
+    '𝛊' => '𝜾',
+    '𝛋' => '𝜿',
+    '𝛌' => '𝝀',
+    '𝛍' => '𝝁',
+    '𝛎' => '𝝂',
+    '𝛏' => '𝝃',
+    '𝛐' => '𝝄',
+    '𝛑' => '𝝅',
+    '𝛒' => '𝝆',
+    '𝛓' => '𝝇',
+    '𝛔' => '𝝈',
+    '𝛕' => '𝝉',
+    '𝛖' => '𝝊',
+    '𝛗' => '𝝋',
+    '𝛘' => '𝝌',
+    '𝛙' => '𝝍',
+    '𝛚' => '𝝎',
+    '𝛛' => '𝝏',
+    '𝛜' => '𝝐',
+    '𝛝' => '𝝑',
+    '𝛞' => '𝝒',
+    '𝛟' => '𝝓',
+    '𝛠' => '𝝔',
+    '𝛡' => '𝝕',
+    // Italic sans-serif
+    '𝖠' => '𝘈',
+    '𝖡' => '𝘉',
+    '𝖢' => '𝘊',
+    '𝖣' => '𝘋',
+    '𝖤' => '𝘌',
+    '𝖥' => '𝘍',
+    '𝖦' => '𝘎',
+    '𝖧' => '𝘏',
+    '𝖨' => '𝘐',
+    '𝖩' => '𝘑',
+    '𝖪' => '𝘒',
+    '𝖫' => '𝘓',
+    '𝖬' => '𝘔',
+    '𝖭' => '𝘕',
+    '𝖮' => '𝘖',
+    '𝖯' => '𝘗',
+    '𝖰' => '𝘘',
+    '𝖱' => '𝘙',
+    '𝖲' => '𝘚',
+    '𝖳' => '𝘛',
+    '𝖴' => '𝘜',
+    '𝖵' => '𝘝',
+    '𝖶' => '𝘞',
+    '𝖷' => '𝘟',
+    '𝖸' => '𝘠',
+    '𝖹' => '𝘡',
+    '𝖺' => '𝘢',
+    '𝖻' => '𝘣',
+    '𝖼' => '𝘤',
+    '𝖽' => '𝘥',
+    '𝖾' => '𝘦',
+    '𝖿' => '𝘧',
+    '𝗀' => '𝘨',
+    '𝗁' => '𝘩',
+    '𝗂' => '𝘪',
+    '𝗃' => '𝘫',
+    '𝗄' => '𝘬',
+    '𝗅' => '𝘭',
+    '𝗆' => '𝘮',
+    '𝗇' => '𝘯',
+    '𝗈' => '𝘰',
+    '𝗉' => '𝘱',
+    '𝗊' => '𝘲',
+    '𝗋' => '𝘳',
+    '𝗌' => '𝘴',
+    '𝗍' => '𝘵',
+    '𝗎' => '𝘶',
+    '𝗏' => '𝘷',
+    '𝗐' => '𝘸',
+    '𝗑' => '𝘹',
+    '𝗒' => '𝘺',
+    '𝗓' => '𝘻',
+    // Bold italic sans-serif
+    '𝗔' => '𝘼',
+    '𝗕' => '𝘽',
+    '𝗖' => '𝘾',
+    '𝗗' => '𝘿',
+    '𝗘' => '𝙀',
+    '𝗙' => '𝙁',
+    '𝗚' => '𝙂',
+    '𝗛' => '𝙃',
+    '𝗜' => '𝙄',
+    '𝗝' => '𝙅',
+    '𝗞' => '𝙆',
+    '𝗟' => '𝙇',
+    '𝗠' => '𝙈',
+    '𝗡' => '𝙉',
+    '𝗢' => '𝙊',
+    '𝗣' => '𝙋',
+    '𝗤' => '𝙌',
+    '𝗥' => '𝙍',
+    '𝗦' => '𝙎',
+    '𝗧' => '𝙏',
+    '𝗨' => '𝙐',
+    '𝗩' => '𝙑',
+    '𝗪' => '𝙒',
+    '𝗫' => '𝙓',
+    '𝗬' => '𝙔',
+    '𝗭' => '𝙕',
+    '𝗮' => '𝙖',
+    '𝗯' => '𝙗',
+    '𝗰' => '𝙘',
+    '𝗱' => '𝙙',
+    '𝗲' => '𝙚',
+    '𝗳' => '𝙛',
+    '𝗴' => '𝙜',
+    '𝗵' => '𝙝',
+    '𝗶' => '𝙞',
+    '𝗷' => '𝙟',
+    '𝗸' => '𝙠',
+    '𝗹' => '𝙡',
+    '𝗺' => '𝙢',
+    '𝗻' => '𝙣',
+    '𝗼' => '𝙤',
+    '𝗽' => '𝙥',
+    '𝗾' => '𝙦',
+    '𝗿' => '𝙧',
+    '𝘀' => '𝙨',
+    '𝘁' => '𝙩',
+    '𝘂' => '𝙪',
+    '𝘃' => '𝙫',
+    '𝘄' => '𝙬',
+    '𝘅' => '𝙭',
+    '𝘆' => '𝙮',
+    '𝘇' => '𝙯',
+    '𝝖' => '𝞐',
+    '𝝗' => '𝞑',
+    '𝝘' => '𝞒',
+    '𝝙' => '𝞓',
+    '𝝚' => '𝞔',
+    '𝝛' => '𝞕',
+    '𝝜' => '𝞖',
+    '𝝝' => '𝞗',
+    '𝝞' => '𝞘',
+    '𝝟' => '𝞙',
+    '𝝠' => '𝞚',
+    '𝝡' => '𝞛',
+    '𝝢' => '𝞜',
+    '𝝣' => '𝞝',
+    '𝝤' => '𝞞',
+    '𝝥' => '𝞟',
+    '𝝦' => '𝞠',
+    '𝝨' => '𝞢',
+    '𝝩' => '𝞣',
+    '𝝪' => '𝞤',
+    '𝝫' => '𝞥',
+    '𝝬' => '𝞦',
+    '𝝭' => '𝞧',
+    '𝝮' => '𝞨',
+    '𝝧' => '𝞡',
+    '𝝯' => '𝞩',
+    '𝝰' => '𝞪',
+    '𝝱' => '𝞫',
+    '𝝲' => '𝞬',
+    '𝝳' => '𝞭',
+    '𝝴' => '𝞮',
+    '𝝵' => '𝞯',
+    '𝝶' => '𝞰',
+    '𝝷' => '𝞱',
+    '𝝸' => '𝞲',
+    '𝝹' => '𝞳',
+    '𝝺' => '𝞴',
+    '𝝻' => '𝞵',
+    '𝝼' => '𝞶',
+    '𝝽' => '𝞷',
+    '𝝾' => '𝞸',
+    '𝝿' => '𝞹',
+    '𝞀' => '𝞺',
+    '𝞁' => '𝞻',
+    '𝞂' => '𝞼',
+    '𝞃' => '𝞽',
+    '𝞄' => '𝞾',
+    '𝞅' => '𝞿',
+    '𝞆' => '𝟀',
+    '𝞇' => '𝟁',
+    '𝞈' => '𝟂',
+    '𝞉' => '𝟃',
+    '𝞊' => '𝟄',
+    '𝞋' => '𝟅',
+    '𝞌' => '𝟆',
+    '𝞍' => '𝟇',
+    '𝞎' => '𝟈',
+    '𝞏' => '𝟉',
+};
+
+/// The list of upright letters, mapping italic letters back to their upright form.
+pub static UPRIGHT_LETTERS: phf::Map<char, char> = phf_map! {
+    '𝐴' => 'A',
+    '𝐵' => 'B',
+    '𝐶' => 'C',
+    '𝐷' => 'D',
+    '𝐸' => 'E',
+    '𝐹' => 'F',
+    '𝐺' => 'G',
+    '𝐻' => 'H',
+    '𝐼' => 'I',
+    '𝐽' => 'J',
+    '𝐾' => 'K',
+    '𝐿' => 'L',
+    '𝑀' => 'M',
+    '𝑁' => 'N',
+    '𝑂' => 'O',
+    '𝑃' => 'P',
+    '𝑄' => 'Q',
+    '𝑅' => 'R',
+    '𝑆' => 'S',
+    '𝑇' => 'T',
+    '𝑈' => 'U',
+    '𝑉' => 'V',
+    '𝑊' => 'W',
+    '𝑋' => 'X',
+    '𝑌' => 'Y',
+    '𝑍' => 'Z',
+    '𝑎' => 'a',
+    '𝑏' => 'b',
+    '𝑐' => 'c',
+    '𝑑' => 'd',
+    '𝑒' => 'e',
+    '𝑓' => 'f',
+    '𝑔' => 'g',
+    'ℎ' => 'h',
+    '𝑖' => 'i',
+    '𝑗' => 'j',
+    '𝑘' => 'k',
+    '𝑙' => 'l',
+    '𝑚' => 'm',
+    '𝑛' => 'n',
+    '𝑜' => 'o',
+    '𝑝' => 'p',
+    '𝑞' => 'q',
+    '𝑟' => 'r',
+    '𝑠' => 's',
+    '𝑡' => 't',
+    '𝑢' => 'u',
+    '𝑣' => 'v',
+    '𝑤' => 'w',
+    '𝑥' => 'x',
+    '𝑦' => 'y',
+    '𝑧' => 'z',
+    '𝛢' => 'Α',
+    '𝛣' => 'Β',
+    '𝛤' => 'Γ',
+    '𝛥' => 'Δ',
+    '𝛦' => 'Ε',
+    '𝛧' => 'Ζ',
+    '𝛨' => 'Η',
+    '𝛩' => 'Θ',
+    '𝛪' => 'Ι',
+    '𝛫' => 'Κ',
+    '𝛬' => 'Λ',
+    '𝛭' => 'Μ',
+    '𝛮' => 'Ν',
+    '𝛯' => 'Ξ',
+    '𝛰' => 'Ο',
+    '𝛱' => 'Π',
+    '𝛲' => 'Ρ',
+    '𝛴' => 'Σ',
+    '𝛵' => 'Τ',
+    '𝛶' => 'Υ',
+    '𝛷' => 'Φ',
+    '𝛸' => 'Χ',
+    '𝛹' => 'Ψ',
+    '𝛺' => 'Ω',
+    '𝛳' => 'ϴ',
+    '𝛻' => '∇',
+    '𝛼' => 'α',
+    '𝛽' => 'β',
+    '𝛾' => 'γ',
+    '𝛿' => 'δ',
+    '𝜀' => 'ε',
+    '𝜁' => 'ζ',
+    '𝜂' => 'η',
+    '𝜃' => 'θ',
+    '𝜄' => 'ι',
+    '𝜅' => 'κ',
+    '𝜆' => 'λ',
+    '𝜇' => 'μ',
+    '𝜈' => 'ν',
+    '𝜉' => 'ξ',
+    '𝜊' => 'ο',
+    '𝜋' => 'π',
+    '𝜌' => 'ρ',
+    '𝜍' => 'ς',
+    '𝜎' => 'σ',
+    '𝜏' => 'τ',
+    '𝜐' => 'υ',
+    '𝜑' => 'φ',
+    '𝜒' => 'χ',
+    '𝜓' => 'ψ',
+    '𝜔' => 'ω',
+    '𝜕' => '∂',
+    '𝜖' => 'ϵ',
+    '𝜗' => 'ϑ',
+    '𝜘' => 'ϰ',
+    '𝜙' => 'ϕ',
+    '𝜚' => 'ϱ',
+    '𝜛' => 'ϖ',
+    // Bold italic
+    '𝑨' => '𝐀',
+    '𝑩' => '𝐁',
+    '𝑪' => '𝐂',
+    '𝑫' => '𝐃',
+    '𝑬' => '𝐄',
+    '𝑭' => '𝐅',
+    '𝑮' => '𝐆',
+    '𝑯' => '𝐇',
+    '𝑰' => '𝐈',
+    '𝑱' => '𝐉',
+    '𝑲' => '𝐊',
+    '𝑳' => '𝐋',
+    '𝑴' => '𝐌',
+    '𝑵' => '𝐍',
+    '𝑶' => '𝐎',
+    '𝑷' => '𝐏',
+    '𝑸' => '𝐐',
+    '𝑹' => '𝐑',
+    '𝑺' => '𝐒',
+    '𝑻' => '𝐓',
+    '𝑼' => '𝐔',
+    '𝑽' => '𝐕',
+    '𝑾' => '𝐖',
+    '𝑿' => '𝐗',
+    '𝒀' => '𝐘',
+    '𝒁' => '𝐙',
+    '𝒂' => '𝐚',
+    '𝒃' => '𝐛',
+    '𝒄' => '𝐜',
+    '𝒅' => '𝐝',
+    '𝒆' => '𝐞',
+    '𝒇' => '𝐟',
+    '𝒈' => '𝐠',
+    '𝒉' => '𝐡',
+    '𝒊' => '𝐢',
+    '𝒋' => '𝐣',
+    '𝒌' => '𝐤',
+    '𝒍' => '𝐥',
+    '𝒎' => '𝐦',
+    '𝒏' => '𝐧',
+    '𝒐' => '𝐨',
+    '𝒑' => '𝐩',
+    '𝒒' => '𝐪',
+    '𝒓' => '𝐫',
+    '𝒔' => '𝐬',
+    '𝒕' => '𝐭',
+    '𝒖' => '𝐮',
+    '𝒗' => '𝐯',
+    '𝒘' => '𝐰',
+    '𝒙' => '𝐱',
+    '𝒚' => '𝐲',
+    '𝒛' => '𝐳',
+    '𝜜' => '𝚨',
+    '𝜝' => '𝚩',
+    '𝜞' => '𝚪',
+    '𝜟' => '𝚫',
+    '𝜠' => '𝚬',
+    '𝜡' => '𝚭',
+    '𝜢' => '𝚮',
+    '𝜣' => '𝚯',
+    '𝜤' => '𝚰',
+    '𝜥' => '𝚱',
+    '𝜦' => '𝚲',
+    '𝜧' => '𝚳',
+    '𝜨' => '𝚴',
+    '𝜩' => '𝚵',
+    '𝜪' => '𝚶',
+    '𝜫' => '𝚷',
+    '𝜬' => '𝚸',
+    '𝜮' => '𝚺',
+    '𝜯' => '𝚻',
+    '𝜰' => '𝚼',
+    '𝜱' => '𝚽',
+    '𝜲' => '𝚾',
+    '𝜳' => '𝚿',
+    '𝜴' => '𝛀',
+    '𝜭' => '𝚹',
+    '𝜵' => '𝛁',
+    '𝜶' => '𝛂',
+    '𝜷' => '𝛃',
+    '𝜸' => '𝛄',
+    '𝜹' => '𝛅',
+    '𝜺' => '𝛆',
+    '𝜻' => '𝛇',
+    '𝜼' => '𝛈',
+    '𝜽' => '𝛉',
+    '𝜾' => '𝛊',
+    '𝜿' => '𝛋',
+    '𝝀' => '𝛌',
+    '𝝁' => '𝛍',
+    '𝝂' => '𝛎',
+    '𝝃' => '𝛏',
+    '𝝄' => '𝛐',
+    '𝝅' => '𝛑',
+    '𝝆' => '𝛒',
+    '𝝇' => '𝛓',
+    '𝝈' => '𝛔',
+    '𝝉' => '𝛕',
+    '𝝊' => '𝛖',
+    '𝝋' => '𝛗',
+    '𝝌' => '𝛘',
+    '𝝍' => '𝛙',
+    '𝝎' => '𝛚',
+    '𝝏' => '𝛛',
+    '𝝐' => '𝛜',
+    '𝝑' => '𝛝',
+    '𝝒' => '𝛞',
+    '𝝓' => '𝛟',
+    '𝝔' => '𝛠',
+    '𝝕' => '𝛡',
+    // Italic sans-serif
+    '𝘈' => '𝖠',
+    '𝘉' => '𝖡',
+    '𝘊' => '𝖢',
+    '𝘋' => '𝖣',
+    '𝘌' => '𝖤',
+    '𝘍' => '𝖥',
+    '𝘎' => '𝖦',
+    '𝘏' => '𝖧',
+    '𝘐' => '𝖨',
+    '𝘑' => '𝖩',
+    '𝘒' => '𝖪',
+    '𝘓' => '𝖫',
+    '𝘔' => '𝖬',
+    '𝘕' => '𝖭',
+    '𝘖' => '𝖮',
+    '𝘗' => '𝖯',
+    '𝘘' => '𝖰',
+    '𝘙' => '𝖱',
+    '𝘚' => '𝖲',
+    '𝘛' => '𝖳',
+    '𝘜' => '𝖴',
+    '𝘝' => '𝖵',
+    '𝘞' => '𝖶',
+    '𝘟' => '𝖷',
+    '𝘠' => '𝖸',
+    '𝘡' => '𝖹',
+    '𝘢' => '𝖺',
+    '𝘣' => '𝖻',
+    '𝘤' => '𝖼',
+    '𝘥' => '𝖽',
+    '𝘦' => '𝖾',
+    '𝘧' => '𝖿',
+    '𝘨' => '𝗀',
+    '𝘩' => '𝗁',
+    '𝘪' => '𝗂',
+    '𝘫' => '𝗃',
+    '𝘬' => '𝗄',
+    '𝘭' => '𝗅',
+    '𝘮' => '𝗆',
+    '𝘯' => '𝗇',
+    '𝘰' => '𝗈',
+    '𝘱' => '𝗉',
+    '𝘲' => '𝗊',
+    '𝘳' => '𝗋',
+    '𝘴' => '𝗌',
+    '𝘵' => '𝗍',
+    '𝘶' => '𝗎',
+    '𝘷' => '𝗏',
+    '𝘸' => '𝗐',
+    '𝘹' => '𝗑',
+    '𝘺' => '𝗒',
+    '𝘻' => '𝗓',
+    // Bold italic sans-serif
+    '𝘼' => '𝗔',
+    '𝘽' => '𝗕',
+    '𝘾' => '𝗖',
+    '𝘿' => '𝗗',
+    '𝙀' => '𝗘',
+    '𝙁' => '𝗙',
+    '𝙂' => '𝗚',
+    '𝙃' => '𝗛',
+    '𝙄' => '𝗜',
+    '𝙅' => '𝗝',
+    '𝙆' => '𝗞',
+    '𝙇' => '𝗟',
+    '𝙈' => '𝗠',
+    '𝙉' => '𝗡',
+    '𝙊' => '𝗢',
+    '𝙋' => '𝗣',
+    '𝙌' => '𝗤',
+    '𝙍' => '𝗥',
+    '𝙎' => '𝗦',
+    '𝙏' => '𝗧',
+    '𝙐' => '𝗨',
+    '𝙑' => '𝗩',
+    '𝙒' => '𝗪',
+    '𝙓' => '𝗫',
+    '𝙔' => '𝗬',
+    '𝙕' => '𝗭',
+    '𝙖' => '𝗮',
+    '𝙗' => '𝗯',
+    '𝙘' => '𝗰',
+    '𝙙' => '𝗱',
+    '𝙚' => '𝗲',
+    '𝙛' => '𝗳',
+    '𝙜' => '𝗴',
+    '𝙝' => '𝗵',
+    '𝙞' => '𝗶',
+    '𝙟' => '𝗷',
+    '𝙠' => '𝗸',
+    '𝙡' => '𝗹',
+    '𝙢' => '𝗺',
+    '𝙣' => '𝗻',
+    '𝙤' => '𝗼',
+    '𝙥' => '𝗽',
+    '𝙦' => '𝗾',
+    '𝙧' => '𝗿',
+    '𝙨' => '𝘀',
+    '𝙩' => '𝘁',
+    '𝙪' => '𝘂',
+    '𝙫' => '𝘃',
+    '𝙬' => '𝘄',
+    '𝙭' => '𝘅',
+    '𝙮' => '𝘆',
+    '𝙯' => '𝘇',
+    '𝞐' => '𝝖',
+    '𝞑' => '𝝗',
+    '𝞒' => '𝝘',
+    '𝞓' => '𝝙',
+    '𝞔' => '𝝚',
+    '𝞕' => '𝝛',
+    '𝞖' => '𝝜',
+    '𝞗' => '𝝝',
+    '𝞘' => '𝝞',
+    '𝞙' => '𝝟',
+    '𝞚' => '𝝠',
+    '𝞛' => '𝝡',
+    '𝞜' => '𝝢',
+    '𝞝' => '𝝣',
+    '𝞞' => '𝝤',
+    '𝞟' => '𝝥',
+    '𝞠' => '𝝦',
+    '𝞢' => '𝝨',
+    '𝞣' => '𝝩',
+    '𝞤' => '𝝪',
+    '𝞥' => '𝝫',
+    '𝞦' => '𝝬',
+    '𝞧' => '𝝭',
+    '𝞨' => '𝝮',
+    '𝞡' => '𝝧',
+    '𝞩' => '𝝯',
+    '𝞪' => '𝝰',
+    '𝞫' => '𝝱',
+    '𝞬' => '𝝲',
+    '𝞭' => '𝝳',
+    '𝞮' => '𝝴',
+    '𝞯' => '𝝵',
+    '𝞰' => '𝝶',
+    '𝞱' => '𝝷',
+    '𝞲' => '𝝸',
+    '𝞳' => '𝝹',
+    '𝞴' => '𝝺',
+    '𝞵' => '𝝻',
+    '𝞶' => '𝝼',
+    '𝞷' => '𝝽',
+    '𝞸' => '𝝾',
+    '𝞹' => '𝝿',
+    '𝞺' => '𝞀',
+    '𝞻' => '𝞁',
+    '𝞼' => '𝞂',
+    '𝞽' => '𝞃',
+    '𝞾' => '𝞄',
+    '𝞿' => '𝞅',
+    '𝟀' => '𝞆',
+    '𝟁' => '𝞇',
+    '𝟂' => '𝞈',
+    '𝟃' => '𝞉',
+    '𝟄' => '𝞊',
+    '𝟅' => '𝞋',
+    '𝟆' => '𝞌',
+    '𝟇' => '𝞍',
+    '𝟈' => '𝞎',
+    '𝟉' => '𝞏',
+};
+
+/// The list of sans-serif letters.
+pub static SANS_LETTERS: phf::Map<char, char> = phf_map! {
+    'A' => '𝖠',
+    'B' => '𝖡',
+    'C' => '𝖢',
+    'D' => '𝖣',
+    'E' => '𝖤',
+    'F' => '𝖥',
+    'G' => '𝖦',
+    'H' => '𝖧',
+    'I' => '𝖨',
+    'J' => '𝖩',
+    'K' => '𝖪',
+    'L' => '𝖫',
+    'M' => '𝖬',
+    'N' => '𝖭',
+    'O' => '𝖮',
+    'P' => '𝖯',
+    'Q' => '𝖰',
+    'R' => '𝖱',
+    'S' => '𝖲',
+    'T' => '𝖳',
+    'U' => '𝖴',
+    'V' => '𝖵',
+    'W' => '𝖶',
+    'X' => '𝖷',
+    'Y' => '𝖸',
+    'Z' => '𝖹',
+    'a' => '𝖺',
+    'b' => '𝖻',
+    'c' => '𝖼',
+    'd' => '𝖽',
+    'e' => '𝖾',
+    'f' => '𝖿',
+    'g' => '𝗀',
+    'h' => '𝗁',
+    'i' => '𝗂',
+    'j' => '𝗃',
+    'k' => '𝗄',
+    'l' => '𝗅',
+    'm' => '𝗆',
+    'n' => '𝗇',
+    'o' => '𝗈',
+    'p' => '𝗉',
+    'q' => '𝗊',
+    'r' => '𝗋',
+    's' => '𝗌',
+    't' => '𝗍',
+    'u' => '𝗎',
+    'v' => '𝗏',
+    'w' => '𝗐',
+    'x' => '𝗑',
+    'y' => '𝗒',
+    'z' => '𝗓',
+    '0' => '𝟢',
+    '1' => '𝟣',
+    '2' => '𝟤',
+    '3' => '𝟥',
+    '4' => '𝟦',
+    '5' => '𝟧',
+    '6' => '𝟨',
+    '7' => '𝟩',
+    '8' => '𝟪',
+    '9' => '𝟫',
+    // Bold sans-serif
+    '𝐀' => '𝗔',
+    '𝐁' => '𝗕',
+    '𝐂' => '𝗖',
+    '𝐃' => '𝗗',
+    '𝐄' => '𝗘',
+    '𝐅' => '𝗙',
+    '𝐆' => '𝗚',
+    '𝐇' => '𝗛',
+    '𝐈' => '𝗜',
+    '𝐉' => '𝗝',
+    '𝐊' => '𝗞',
+    '𝐋' => '𝗟',
+    '𝐌' => '𝗠',
+    '𝐍' => '𝗡',
+    '𝐎' => '𝗢',
+    '𝐏' => '𝗣',
+    '𝐐' => '𝗤',
+    '𝐑' => '𝗥',
+    '𝐒' => '𝗦',
+    '𝐓' => '𝗧',
+    '𝐔' => '𝗨',
+    '𝐕' => '𝗩',
+    '𝐖' => '𝗪',
+    '𝐗' => '𝗫',
+    '𝐘' => '𝗬',
+    '𝐙' => '𝗭',
+    '𝐚' => '𝗮',
+    '𝐛' => '𝗯',
+    '𝐜' => '𝗰',
+    '𝐝' => '𝗱',
+    '𝐞' => '𝗲',
+    '𝐟' => '𝗳',
+    '𝐠' => '𝗴',
+    '𝐡' => '𝗵',
+    '𝐢' => '𝗶',
+    '𝐣' => '𝗷',
+    '𝐤' => '𝗸',
+    '𝐥' => '𝗹',
+    '𝐦' => '𝗺',
+    '𝐧' => '𝗻',
+    '𝐨' => '𝗼',
+    '𝐩' => '𝗽',
+    '𝐪' => '𝗾',
+    '𝐫' => '𝗿',
+    '𝐬' => '𝘀',
+    '𝐭' => '𝘁',
+    '𝐮' => '𝘂',
+    '𝐯' => '𝘃',
+    '𝐰' => '𝘄',
+    '𝐱' => '𝘅',
+    '𝐲' => '𝘆',
+    '𝐳' => '𝘇',
+    '𝟎' => '𝟬',
+    '𝟏' => '𝟭',
+    '𝟐' => '𝟮',
+    '𝟑' => '𝟯',
+    '𝟒' => '𝟰',
+    '𝟓' => '𝟱',
+    '𝟔' => '𝟲',
+    '𝟕' => '𝟳',
+    '𝟖' => '𝟴',
+    '𝟗' => '𝟵',
+    '𝚨' => '𝝖',
+    '𝚩' => '𝝗',
+    '𝚪' => '𝝘',
+    '𝚫' => '𝝙',
+    '𝚬' => '𝝚',
+    '𝚭' => '𝝛',
+    '𝚮' => '𝝜',
+    '𝚯' => '𝝝',
+    '𝚰' => '𝝞',
+    '𝚱' => '𝝟',
+    '𝚲' => '𝝠',
+    '𝚳' => '𝝡',
+    '𝚴' => '𝝢',
+    '𝚵' => '𝝣',
+    '𝚶' => '𝝤',
+    '𝚷' => '𝝥',
+    '𝚸' => '𝝦',
+    '𝚺' => '𝝨',
+    '𝚻' => '𝝩',
+    '𝚼' => '𝝪',
+    '𝚽' => '𝝫',
+    '𝚾' => '𝝬',
+    '𝚿' => '𝝭',
+    '𝛀' => '𝝮',
+    '𝚹' => '𝝧',
+    '𝛁' => '𝝯',
+    '𝛂' => '𝝰',
+    '𝛃' => '𝝱',
+    '𝛄' => '𝝲',
+    '𝛅' => '𝝳',
+    '𝛆' => '𝝴',
+    '𝛇' => '𝝵',
+    '𝛈' => '𝝶',
+    '𝛉' => '𝝷',
+    '𝛊' => '𝝸',
+    '𝛋' => '𝝹',
+    '𝛌' => '𝝺',
+    '𝛍' => '𝝻',
+    '𝛎' => '𝝼',
+    '𝛏' => '𝝽',
+    '𝛐' => '𝝾',
+    '𝛑' => '𝝿',
+    '𝛒' => '𝞀',
+    '𝛓' => '𝞁',
+    '𝛔' => '𝞂',
+    '𝛕' => '𝞃',
+    '𝛖' => '𝞄',
+    '𝛗' => '𝞅',
+    '𝛘' => '𝞆',
+    '𝛙' => '𝞇',
+    '𝛚' => '𝞈',
+    '𝛛' => '𝞉',
+    '𝛜' => '𝞊',
+    '𝛝' => '𝞋',
+    '𝛞' => '𝞌',
+    '𝛟' => '𝞍',
+    '𝛠' => '𝞎',
+    '𝛡' => '𝞏',
+    // Italic sans-serif
+    '𝐴' => '𝘈',
+    '𝐵' => '𝘉',
+    '𝐶' => '𝘊',
+    '𝐷' => '𝘋',
+    '𝐸' => '𝘌',
+    '𝐹' => '𝘍',
+    '𝐺' => '𝘎',
+    '𝐻' => '𝘏',
+    '𝐼' => '𝘐',
+    '𝐽' => '𝘑',
+    '𝐾' => '𝘒',
+    '𝐿' => '𝘓',
+    '𝑀' => '𝘔',
+    '𝑁' => '𝘕',
+    '𝑂' => '𝘖',
+    '𝑃' => '𝘗',
+    '𝑄' => '𝘘',
+    '𝑅' => '𝘙',
+    '𝑆' => '𝘚',
+    '𝑇' => '𝘛',
+    '𝑈' => '𝘜',
+    '𝑉' => '𝘝',
+    '𝑊' => '𝘞',
+    '𝑋' => '𝘟',
+    '𝑌' => '𝘠',
+    '𝑍' => '𝘡',
+    '𝑎' => '𝘢',
+    '𝑏' => '𝘣',
+    '𝑐' => '𝘤',
+    '𝑑' => '𝘥',
+    '𝑒' => '𝘦',
+    '𝑓' => '𝘧',
+    '𝑔' => '𝘨',
+    'ℎ' => '𝘩',
+    '𝑖' => '𝘪',
+    '𝑗' => '𝘫',
+    '𝑘' => '𝘬',
+    '𝑙' => '𝘭',
+    '𝑚' => '𝘮',
+    '𝑛' => '𝘯',
+    '𝑜' => '𝘰',
+    '𝑝' => '𝘱',
+    '𝑞' => '𝘲',
+    '𝑟' => '𝘳',
+    '𝑠' => '𝘴',
+    '𝑡' => '𝘵',
+    '𝑢' => '𝘶',
+    '𝑣' => '𝘷',
+    '𝑤' => '𝘸',
+    '𝑥' => '𝘹',
+    '𝑦' => '𝘺',
+    '𝑧' => '𝘻',
+    // Bold italic sans-serif
+    '𝑨' => '𝘼',
+    '𝑩' => '𝘽',
+    '𝑪' => '𝘾',
+    '𝑫' => '𝘿',
+    '𝑬' => '𝙀',
+    '𝑭' => '𝙁',
+    '𝑮' => '𝙂',
+    '𝑯' => '𝙃',
+    '𝑰' => '𝙄',
+    '𝑱' => '𝙅',
+    '𝑲' => '𝙆',
+    '𝑳' => '𝙇',
+    '𝑴' => '𝙈',
+    '𝑵' => '𝙉',
+    '𝑶' => '𝙊',
+    '𝑷' => '𝙋',
+    '𝑸' => '𝙌',
+    '𝑹' => '𝙍',
+    '𝑺' => '𝙎',
+    '𝑻' => '𝙏',
+    '𝑼' => '𝙐',
+    '𝑽' => '𝙑',
+    '𝑾' => '𝙒',
+    '𝑿' => '𝙓',
+    '𝒀' => '𝙔',
+    '𝒁' => '𝙕',
+    '𝒂' => '𝙖',
+    '𝒃' => '𝙗',
+    '𝒄' => '𝙘',
+    '𝒅' => '𝙙',
+    '𝒆' => '𝙚',
+    '𝒇' => '𝙛',
+    '𝒈' => '𝙜',
+    '𝒉' => '𝙝',
+    '𝒊' => '𝙞',
+    '𝒋' => '𝙟',
+    '𝒌' => '𝙠',
+    '𝒍' => '𝙡',
+    '𝒎' => '𝙢',
+    '𝒏' => '𝙣',
+    '𝒐' => '𝙤',
+    '𝒑' => '𝙥',
+    '𝒒' => '𝙦',
+    '𝒓' => '𝙧',
+    '𝒔' => '𝙨',
+    '𝒕' => '𝙩',
+    '𝒖' => '𝙪',
+    '𝒗' => '𝙫',
+    '𝒘' => '𝙬',
+    '𝒙' => '𝙭',
+    '𝒚' => '𝙮',
+    '𝒛' => '𝙯',
+    '𝜜' => '𝞐',
+    '𝜝' => '𝞑',
+    '𝜞' => '𝞒',
+    '𝜟' => '𝞓',
+    '𝜠' => '𝞔',
+    '𝜡' => '𝞕',
+    '𝜢' => '𝞖',
+    '𝜣' => '𝞗',
+    '𝜤' => '𝞘',
+    '𝜥' => '𝞙',
+    '𝜦' => '𝞚',
+    '𝜧' => '𝞛',
+    '𝜨' => '𝞜',
+    '𝜩' => '𝞝',
+    '𝜪' => '𝞞',
+    '𝜫' => '𝞟',
+    '𝜬' => '𝞠',
+    '𝜮' => '𝞢',
+    '𝜯' => '𝞣',
+    '𝜰' => '𝞤',
+    '𝜱' => '𝞥',
+    '𝜲' => '𝞦',
+    '𝜳' => '𝞧',
+    '𝜴' => '𝞨',
+    '𝜭' => '𝞡',
+    '𝜵' => '𝞩',
+    '𝜶' => '𝞪',
+    '𝜷' => '𝞫',
+    '𝜸' => '𝞬',
+    '𝜹' => '𝞭',
+    '𝜺' => '𝞮',
+    '𝜻' => '𝞯',
+    '𝜼' => '𝞰',
+    '𝜽' => '𝞱',
+    '𝜾' => '𝞲',
+    '𝜿' => '𝞳',
+    '𝝀' => '𝞴',
+    '𝝁' => '𝞵',
+    '𝝂' => '𝞶',
+    '𝝃' => '𝞷',
+    '𝝄' => '𝞸',
+    '𝝅' => '𝞹',
+    '𝝆' => '𝞺',
+    '𝝇' => '𝞻',
+    '𝝈' => '𝞼',
+    '𝝉' => '𝞽',
+    '𝝊' => '𝞾',
+    '𝝋' => '𝞿',
+    '𝝌' => '𝟀',
+    '𝝍' => '𝟁',
+    '𝝎' => '𝟂',
+    '𝝏' => '𝟃',
+    '𝝐' => '𝟄',
+    '𝝑' => '𝟅',
+    '𝝒' => '𝟆',
+    '𝝓' => '𝟇',
+    '𝝔' => '𝟈',
+    '𝝕' => '𝟉',
+};
+
+/// The list of monospace letters.
+pub static MONO_LETTERS: phf::Map<char, char> = phf_map! {
+    'A' => '𝙰',
+    'B' => '𝙱',
+    'C' => '𝙲',
+    'D' => '𝙳',
+    'E' => '𝙴',
+    'F' => '𝙵',
+    'G' => '𝙶',
+    'H' => '𝙷',
+    'I' => '𝙸',
+    'J' => '𝙹',
+    'K' => '𝙺',
+    'L' => '𝙻',
+    'M' => '𝙼',
+    'N' => '𝙽',
+    'O' => '𝙾',
+    'P' => '𝙿',
+    'Q' => '𝚀',
+    'R' => '𝚁',
+    'S' => '𝚂',
+    'T' => '𝚃',
+    'U' => '𝚄',
+    'V' => '𝚅',
+    'W' => '𝚆',
+    'X' => '𝚇',
+    'Y' => '𝚈',
+    'Z' => '𝚉',
+    'a' => '𝚊',
+    'b' => '𝚋',
+    'c' => '𝚌',
+    'd' => '𝚍',
+    'e' => '𝚎',
+    'f' => '𝚏',
+    'g' => '𝚐',
+    'h' => '𝚑',
+    'i' => '𝚒',
+    'j' => '𝚓',
+    'k' => '𝚔',
+    'l' => '𝚕',
+    'm' => '𝚖',
+    'n' => '𝚗',
+    'o' => '𝚘',
+    'p' => '𝚙',
+    'q' => '𝚚',
+    'r' => '𝚛',
+    's' => '𝚜',
+    't' => '𝚝',
+    'u' => '𝚞',
+    'v' => '𝚟',
+    'w' => '𝚠',
+    'x' => '𝚡',
+    'y' => '𝚢',
+    'z' => '𝚣',
+    '0' => '𝟶',
+    '1' => '𝟷',
+    '2' => '𝟸',
+    '3' => '𝟹',
+    '4' => '𝟺',
+    '5' => '𝟻',
+    '6' => '𝟼',
+    '7' => '𝟽',
+    '8' => '𝟾',
+    '9' => '𝟿',
+};
+
+/// The math spacings (`thin`, `quad`, ...), with unicode spaces approaching their width. \
+/// Typst widths are 1/6, 2/9, 5/18, 1 and 2 em.
+pub static SPACINGS: phf::Map<&str, &str> = phf_map! {
+    "thin" => "\u{2009}",
+    "med" => "\u{205F}",
+    "thick" => "\u{2004}",
+    "quad" => "\u{2003}",
+    "wide" => "\u{2003}\u{2003}",
+};
+
+/// The vector calculus operators of the physica package.
+pub static PHYSICA_OPERATORS: phf::Map<&str, &str> = phf_map! {
+    "grad" => "∇",
+    "div" => "∇·",
+    "curl" => "∇×",
+    "laplacian" => "∇²",
+};
+
+/// LaTeX commands used in mitex, with the name of the typst symbol they correspond to. \
+/// Commands with the same name in typst (`alpha`, `sum`...) aren't listed.
+pub static LATEX_COMMANDS: phf::Map<&str, &str> = phf_map! {
+    // Arrows
+    "to" => "arrow.r",
+    "rightarrow" => "arrow.r",
+    "leftarrow" => "arrow.l",
+    "gets" => "arrow.l",
+    "uparrow" => "arrow.t",
+    "downarrow" => "arrow.b",
+    "leftrightarrow" => "arrow.l.r",
+    "Rightarrow" => "arrow.r.double",
+    "Leftarrow" => "arrow.l.double",
+    "Leftrightarrow" => "arrow.l.r.double",
+    "longrightarrow" => "arrow.r.long",
+    "longleftarrow" => "arrow.l.long",
+    "implies" => "arrow.r.double.long",
+    "iff" => "arrow.l.r.double.long",
+    "mapsto" => "arrow.r.bar",
+    // Relations
+    "leq" => "lt.eq",
+    "le" => "lt.eq",
+    "geq" => "gt.eq",
+    "ge" => "gt.eq",
+    "neq" => "eq.not",
+    "ne" => "eq.not",
+    "ll" => "lt.double",
+    "gg" => "gt.double",
+    "sim" => "tilde.op",
+    "simeq" => "tilde.eq",
+    "cong" => "tilde.equiv",
+    "propto" => "prop",
+    // Operators
+    "pm" => "plus.minus",
+    "mp" => "minus.plus",
+    "cdot" => "dot.op",
+    "ast" => "ast.op",
+    "circ" => "compose",
+    "oplus" => "plus.circle",
+    "otimes" => "times.circle",
+    "star" => "star.op",
+    // Calculus
+    "infty" => "infinity",
+    "int" => "integral",
+    "iint" => "integral.double",
+    "iiint" => "integral.triple",
+    "oint" => "integral.cont",
+    "prod" => "product",
+    "coprod" => "product.co",
+    // Sets and logic
+    "notin" => "in.not",
+    "ni" => "in.rev",
+    "subseteq" => "subset.eq",
+    "supseteq" => "supset.eq",
+    "cup" => "union",
+    "cap" => "sect",
+    "bigcup" => "union.big",
+    "bigcap" => "sect.big",
+    "setminus" => "without",
+    "varnothing" => "emptyset",
+    "nexists" => "exists.not",
+    "neg" => "not",
+    "lnot" => "not",
+    "land" => "and",
+    "wedge" => "and",
+    "lor" => "or",
+    "vee" => "or",
+    // Dots and delimiters
+    "ldots" => "dots.h",
+    "cdots" => "dots.h.c",
+    "vdots" => "dots.v",
+    "ddots" => "dots.down",
+    "langle" => "angle.l",
+    "rangle" => "angle.r",
+    // Letters, LaTeX and typst swap the variants of epsilon and phi
+    "hbar" => "planck.reduce",
+    "aleph" => "alef",
+    "epsilon" => "epsilon.alt",
+    "varepsilon" => "epsilon",
+    "phi" => "phi.alt",
+    "varphi" => "phi",
+    "vartheta" => "theta.alt",
+    "varrho" => "rho.alt",
+    "varsigma" => "sigma.alt",
+    "varpi" => "pi.alt",
+    "varkappa" => "kappa.alt",
+};
+
+/// LaTeX letter style commands, with the corresponding typst function.
+pub static LATEX_LETTER_STYLES: phf::Map<&str, &str> = phf_map! {
+    "mathbb" => "bb",
+    "mathcal" => "cal",
+    "mathfrak" => "frak",
+    "mathbf" => "bold",
+    "mathit" => "italic",
+    "mathsf" => "sans",
+    "mathtt" => "mono",
+    "mathrm" => "upright",
+};
+
+/// The list of built-in math operators, rendered upright. \
+/// The value indicates if the operator displays its bottom attachment as a limit (under the operator).
+pub static OPERATORS: phf::Map<&str, bool> = phf_map! {
+    "arccos" => false,
+    "arcsin" => false,
+    "arctan" => false,
+    "arg" => false,
+    "cos" => false,
+    "cosh" => false,
+    "cot" => false,
+    "coth" => false,
+    "csc" => false,
+    "csch" => false,
+    "ctg" => false,
+    "deg" => false,
+    "det" => true,
+    "dim" => false,
+    "exp" => false,
+    "gcd" => true,
+    "lcm" => true,
+    "hom" => false,
+    "id" => false,
+    "im" => false,
+    "inf" => true,
+    "ker" => false,
+    "lg" => false,
+    "lim" => true,
+    "liminf" => true,
+    "limsup" => true,
+    "ln" => false,
+    "log" => false,
+    "max" => true,
+    "min" => true,
+    "mod" => false,
+    "Pr" => true,
+    "sec" => false,
+    "sech" => false,
+    "sin" => false,
+    "sinc" => false,
+    "sinh" => false,
+    "sup" => true,
+    "tan" => false,
+    "tanh" => false,
+    "tg" => false,
+    "tr" => false,
 };
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::symbols::{get_category_by_name, Category};
+    use crate::utils::symbols::{
+        get_category_by_name, get_color_by_name, Category, Color, FRAK_LETTERS,
+    };
 
     #[test]
     fn test_get_category_by_name() {
-        assert_eq!(
-            get_category_by_name(&"leTter".to_string()),
-            Category::Letter
-        );
-        assert_eq!(
-            get_category_by_name(&"coMparison".to_string()),
-            Category::Comparison
-        );
-        assert_eq!(
-            get_category_by_name(&"keyword".to_string()),
-            Category::Keyword
-        );
-        assert_eq!(get_category_by_name(&"Set".to_string()), Category::Set);
-        assert_eq!(
-            get_category_by_name(&"bigletter".to_string()),
-            Category::BigLetter
-        );
-        assert_eq!(
-            get_category_by_name(&"Number".to_string()),
-            Category::Number
-        );
-        assert_eq!(get_category_by_name(&"space".to_string()), Category::Space);
-        assert_eq!(
-            get_category_by_name(&"doesn't exists".to_string()),
-            Category::Default
-        );
+        assert_eq!(get_category_by_name("leTter"), Category::Letter);
+        assert_eq!(get_category_by_name("coMparison"), Category::Comparison);
+        assert_eq!(get_category_by_name("keyword"), Category::Keyword);
+        assert_eq!(get_category_by_name("Set"), Category::Set);
+        assert_eq!(get_category_by_name("bigletter"), Category::BigLetter);
+        assert_eq!(get_category_by_name("Number"), Category::Number);
+        assert_eq!(get_category_by_name("space"), Category::Space);
+        assert_eq!(get_category_by_name("differential"), Category::Differential);
+        assert_eq!(get_category_by_name("doesn't exists"), Category::Default);
+    }
+
+    #[test]
+    fn test_get_color_by_name() {
+        assert_eq!(get_color_by_name("Keyword"), Some(Color::Keyword));
+        assert_eq!(get_color_by_name("group"), Some(Color::Set));
+        assert_eq!(get_color_by_name("set"), Some(Color::Set));
+        assert_eq!(get_color_by_name("differential"), Some(Color::Differential));
+        assert_eq!(get_color_by_name("bigletter"), None);
+    }
+
+    #[test]
+    fn test_frak_letters() {
+        // These uppercase letters live in the letterlike symbols block
+        for (letter, frak) in [('C', 'ℭ'), ('H', 'ℌ'), ('I', 'ℑ'), ('R', 'ℜ'), ('Z', 'ℨ')]
+        {
+            assert_eq!(FRAK_LETTERS.get(&letter), Some(&frak));
+        }
+        assert_eq!(FRAK_LETTERS.get(&'A'), Some(&'𝔄'));
+        // Full lowercase range
+        for (i, letter) in ('a'..='z').enumerate() {
+            let expected = char::from_u32(0x1D51E + i as u32).unwrap();
+            assert_eq!(FRAK_LETTERS.get(&letter), Some(&expected));
+        }
     }
 }
@@ -1,13 +1,23 @@
 #[cfg(test)]
 mod tests {
-    use typst_math_rust::parse_document;
+    use typst_math_rust::{
+        braille_equation, definition_at, document_statistics, equation_symbols, export_markdown,
+        folding_ranges, handle_request, inlay_hints, input_candidates, list_equations,
+        list_symbols, names_for_char, parse_document, parse_document_extmarks, parse_document_json,
+        parse_document_overlays, parse_document_with_options, parse_range, quick_fixes,
+        rename_locations, render_to_latex, render_to_layout, render_to_mathml, render_to_unicode,
+        search_symbols, semantic_tokens, speak_equation, stream_document, symbol_at, typstify,
+        Batch, CancellationToken, CustomSymbol, Delta, Error, Features, Options, Parsed, Scope,
+        Session, Severity, SymbolSource, TokenKind,
+    };
 
     #[test]
     fn basic_symbol() {
-        let parsed = parse_document("$alpha$", -1, -1, 3, true, true, false, vec![], vec![]);
+        let parsed =
+            parse_document("$alpha$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
         assert_eq!(parsed.decorations.len(), 1);
         assert_eq!(parsed.decorations[0].symbol, "α");
-        assert_eq!(parsed.decorations[0].uuid, "alpha");
+        assert_eq!(parsed.uuid(parsed.decorations[0].id).unwrap(), "alpha");
     }
 
     #[test]
@@ -22,10 +32,11 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 1);
         assert_eq!(parsed.decorations[0].symbol, "α");
-        assert_eq!(parsed.decorations[0].uuid, "alpha");
+        assert_eq!(parsed.uuid(parsed.decorations[0].id).unwrap(), "alpha");
     }
 
     #[test]
@@ -40,15 +51,21 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 2);
         assert_eq!(parsed.decorations[0].symbol, "α");
         assert_eq!(parsed.decorations[1].symbol, "α");
-        let parsed = parse_document("$x^alpha$", -1, -1, 3, true, true, false, vec![], vec![]);
+        let parsed =
+            parse_document("$x^alpha$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
         assert_eq!(parsed.decorations[0].positions[0].start, 2);
-        assert_eq!(parsed.decorations[0].uuid, "top-alpha");
-        let parsed = parse_document("$x_alpha$", -1, -1, 3, true, true, false, vec![], vec![]);
-        assert_eq!(parsed.decorations[0].uuid, "bottom-alpha");
+        assert_eq!(parsed.uuid(parsed.decorations[0].id).unwrap(), "top-alpha");
+        let parsed =
+            parse_document("$x_alpha$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(
+            parsed.uuid(parsed.decorations[0].id).unwrap(),
+            "bottom-alpha"
+        );
 
         let parsed = parse_document(
             "$x_alpha_alpha^alpha^alpha$",
@@ -60,8 +77,10 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
-        assert_eq!(parsed.decorations.len(), 3);
+        )
+        .unwrap();
+        // Each nesting level has its own style
+        assert_eq!(parsed.decorations.len(), 4);
         let parsed = parse_document(
             "$x_alpha_alpha^alpha^alpha$",
             -1,
@@ -72,7 +91,8 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 1);
     }
 
@@ -88,7 +108,8 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 2);
         let parsed = parse_document(
             "\n\nnothing on this line\n$zeta^2$\n#sym.arrow\n$(alpha)$",
@@ -100,12 +121,14 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 0);
     }
     #[test]
     fn test_functions() {
-        let parsed = parse_document("$arrow(x)$", -1, -1, 3, true, true, false, vec![], vec![]);
+        let parsed =
+            parse_document("$arrow(x)$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
         assert_eq!(parsed.decorations.len(), 2);
 
         // Check that not too many decorations are added
@@ -119,7 +142,8 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 6);
         let parsed = parse_document(
             "$bb(\"hello\") cal(\"world!\") frak(!)$",
@@ -131,7 +155,8 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 3);
         let parsed = parse_document(
             "$dot(x) dot.double(x) tilde(x) norm(x) sqrt(2) sqrt(2^2)$",
@@ -143,17 +168,14 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 9);
     }
     #[test]
-    fn test_field_access() {
-        let parsed = parse_document("$beta.alt$", -1, -1, 3, true, true, false, vec![], vec![]);
-        assert_eq!(parsed.decorations.len(), 1);
-        assert_eq!(parsed.decorations[0].symbol, "ϐ");
-        assert_eq!(parsed.decorations[0].uuid, "beta.alt");
+    fn test_bold() {
         let parsed = parse_document(
-            "$triangle.filled.b$",
+            "$bold(A) bold(alpha)$",
             -1,
             -1,
             3,
@@ -162,19 +184,54 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
-        assert_eq!(parsed.decorations.len(), 1);
-        assert_eq!(parsed.decorations[0].symbol, "▼");
-        assert_eq!(parsed.decorations[0].uuid, "triangle.filled.b");
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 2);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["𝐀", "𝛂"]);
     }
     #[test]
-    fn test_text() {
-        let parsed = parse_document("$x^a x_a$", -1, -1, 3, true, true, false, vec![], vec![]);
+    fn test_bold_letters() {
+        let parsed = parse_document(
+            "$bold(Gamma) bold(\"x1\") e^bold(v)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["𝐯", "𝐱𝟏", "𝚪"]);
+    }
+    #[test]
+    fn test_italic_upright() {
+        let parsed = parse_document(
+            "$italic(x) upright(d)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 2);
-        assert_eq!(parsed.decorations[0].symbol, "a");
-
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["d", "𝑥"]);
+    }
+    #[test]
+    fn test_sans_mono() {
         let parsed = parse_document(
-            "$x^\"text\" x_\"text\"$",
+            "$sans(\"A1\") mono(x)$",
             -1,
             -1,
             3,
@@ -183,14 +240,17 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 2);
-        assert_eq!(parsed.decorations[0].symbol, "text");
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["𝖠𝟣", "𝚡"]);
     }
     #[test]
-    fn test_linebreak() {
+    fn test_cal_lowercase() {
         let parsed = parse_document(
-            "$x$ \\ \\ \\ x",
+            "$cal(l) cal(\"abc1\")$",
             -1,
             -1,
             3,
@@ -199,14 +259,17 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
-        assert_eq!(parsed.decorations.len(), 1);
-        assert_eq!(parsed.decorations[0].symbol, "⮰");
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 2);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["𝒶𝒷𝒸1", "𝓁"]);
     }
     #[test]
-    fn test_math_block() {
+    fn test_blackbold() {
         let parsed = parse_document(
-            "$x^(5+3-2)=6$",
+            "$bb(1) bb(k) bb(1 k) bb(pi)$",
             -1,
             -1,
             3,
@@ -215,12 +278,89 @@ mod tests {
             false,
             vec![],
             vec![],
-        );
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 4);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["ℼ", "𝕜", "𝟙", "𝟙 𝕜"]);
+    }
+    #[test]
+    fn test_letter_style_identifiers() {
+        let parsed = parse_document(
+            "$cal(XY) frak(A B) bb(NN)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 3);
-        let parsed = parse_document("$x^(alpha)$", -1, -1, 3, true, true, false, vec![], vec![]);
-        assert_eq!(parsed.decorations.len(), 2);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["ℕ", "𝒳𝒴", "𝔄 𝔅"]);
+    }
+    #[test]
+    fn test_nested_letter_styles() {
         let parsed = parse_document(
-            "$x^(\"alpha\") x^(-\"alpha\") x^(-alpha)$",
+            "$bold(cal(A)) italic(bold(x)) bold(frak(g))$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 3);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["𝒙", "𝓐", "𝖌"]);
+    }
+    #[test]
+    fn test_text_fill() {
+        let parsed = parse_document(
+            "#text(fill: red)[$alpha$] $text(fill: rgb(\"#00ff00\"), alpha) text(#luma(0), beta)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 3);
+        let mut uuids: Vec<String> = parsed
+            .decorations
+            .iter()
+            .map(|d| parsed.uuid(d.id).unwrap())
+            .collect();
+        uuids.sort();
+        assert_eq!(
+            uuids,
+            vec![
+                "fill-#00ff00-alpha",
+                "fill-#ff4136-alpha",
+                "fill-rgb(0, 0, 0)-beta"
+            ]
+        );
+        assert!(parsed
+            .decorations
+            .iter()
+            .all(|d| d.text_decoration.contains("color:")));
+    }
+    #[test]
+    fn test_operators() {
+        let parsed = parse_document(
+            "$sin x + op(\"argmax\")$",
             -1,
             -1,
             3,
@@ -229,13 +369,250 @@ mod tests {
             false,
             vec![],
             vec![],
+        )
+        .unwrap();
+        let mut operators: Vec<(String, usize, usize)> = parsed
+            .decorations
+            .iter()
+            .filter(|d| parsed.uuid(d.id).unwrap().starts_with("op-"))
+            .map(|d| (d.symbol.clone(), d.positions[0].start, d.positions[0].end))
+            .collect();
+        operators.sort();
+        assert_eq!(
+            operators,
+            vec![("argmax".to_string(), 9, 21), ("sin".to_string(), 1, 4)]
         );
+
+        let parsed =
+            parse_document("$lim_x f$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert!(parsed
+            .decorations
+            .iter()
+            .any(|d| parsed.uuid(d.id).unwrap().starts_with("under-")));
+        let parsed =
+            parse_document("$lim_x f$", -1, -1, 1, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations.len(), 0);
+    }
+    #[test]
+    fn test_multi_char_operators() {
+        let parsed = parse_document(
+            "$a <= b >= c != d := e -> f$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 5);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["→", "≔", "≠", "≤", "≥"]);
+    }
+    #[test]
+    fn test_shorthands() {
+        let parsed = parse_document(
+            "$a |-> b <==> c ~> d ::= e ... f [| g |] ||h||$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 8);
+        let ellipsis = parsed.decorations.iter().find(|d| d.symbol == "…").unwrap();
+        assert_eq!(format!("{:?}", ellipsis.color), "Operator");
+        let norm = parsed.decorations.iter().find(|d| d.symbol == "‖").unwrap();
+        assert_eq!(format!("{:?}", norm.color), "Set");
+        assert_eq!(norm.positions.len(), 2);
+        let arrow = parsed.decorations.iter().find(|d| d.symbol == "↦").unwrap();
+        assert!(arrow.text_decoration.contains("bold"));
+        let relation = parsed.decorations.iter().find(|d| d.symbol == "⩴").unwrap();
+        assert!(!relation.text_decoration.contains("bold"));
+    }
+    #[test]
+    fn test_unicode_symbols() {
+        let parsed = parse_document(
+            "$α → x ∑ alpha$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 4);
+        let alpha = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "-α")
+            .unwrap();
+        let named = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "alpha")
+            .unwrap();
+        assert_eq!(alpha.color, named.color);
+        assert_eq!(alpha.text_decoration, named.text_decoration);
+        let arrow = parsed.decorations.iter().find(|d| d.symbol == "→").unwrap();
+        assert_eq!(format!("{:?}", arrow.color), "Comparison");
     }
     #[test]
-    fn test_shortands() {
+    fn test_spacings() {
         let parsed = parse_document(
-            "$=> + - * |--> [ |]$",
+            "$a thin b quad c wide d space.hair e$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 4);
+        let wide = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "wide")
+            .unwrap();
+        assert_eq!(wide.symbol, "\u{2003}\u{2003}");
+        let parsed = parse_document(
+            "$a thin b quad c$",
+            -1,
+            -1,
+            3,
+            true,
+            false,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 0);
+    }
+    #[test]
+    fn test_invisible_characters() {
+        let source = "$a zws b wj c hyph.soft d$";
+        let parsed = parse_document_with_options(source, -1, -1, &Options::default()).unwrap();
+        assert_eq!(parsed.decorations.len(), 0);
+        let options = Options {
+            render_invisible: true,
+            ..Default::default()
+        };
+        let parsed = parse_document_with_options(source, -1, -1, &options).unwrap();
+        assert_eq!(parsed.decorations.len(), 3);
+        assert!(parsed
+            .decorations
+            .iter()
+            .all(|d| d.symbol == "◦" && d.text_decoration.contains("opacity")));
+    }
+    #[test]
+    fn test_dots() {
+        let parsed = parse_document(
+            "$mat(1, dots.c, 2; dots.v, dots.down, dots.up; dots.h, dots, 3) times 2$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let mut symbols: Vec<String> = parsed
+            .decorations
+            .iter()
+            .filter(|d| parsed.uuid(d.id).unwrap().contains("dots"))
+            .map(|d| d.symbol.clone())
+            .collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["…", "⋮", "⋯", "⋯", "⋰", "⋱"]);
+        // Dots have the color of the other operators
+        let times = parsed.decorations.iter().find(|d| d.symbol == "×").unwrap();
+        assert!(parsed
+            .decorations
+            .iter()
+            .filter(|d| parsed.uuid(d.id).unwrap().contains("dots"))
+            .all(|d| d.color == times.color));
+    }
+    #[test]
+    fn test_differentials() {
+        let parsed = parse_document(
+            "$(dif y)/(dif x) dif^2 y (partial f)/(diff x)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let dif = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "dif")
+            .unwrap();
+        assert_eq!(dif.symbol, "d");
+        assert!(dif.text_decoration.contains("font-style: normal"));
+        let positions: Vec<(usize, usize)> =
+            dif.positions.iter().map(|p| (p.start, p.end)).collect();
+        assert_eq!(positions, vec![(2, 5), (10, 13), (17, 20)]);
+        let partial = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "partial")
+            .unwrap();
+        let diff = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "diff")
+            .unwrap();
+        assert_eq!(partial.color, diff.color);
+        assert_eq!(format!("{:?}", partial.color), "Differential");
+    }
+    #[test]
+    fn test_physica_derivatives() {
+        let parsed = parse_document(
+            "#import \"@preview/physica:0.9.3\": *\n$dv(f, x) pdv(f,x,2)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let mut symbols: Vec<String> = parsed
+            .decorations
+            .iter()
+            .map(|d| d.symbol.clone())
+            .collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["", "/d", "/∂", "d", "²", "∂²"]);
+        let numerator = parsed.decorations.iter().find(|d| d.symbol == "d").unwrap();
+        // Hide `dv(`
+        assert_eq!(numerator.positions[0].start, 37);
+        assert_eq!(numerator.positions[0].end, 40);
+        // Unknown orders and named arguments are left untouched
+        let parsed = parse_document(
+            "#import \"@preview/physica:0.9.3\": *\n$dv(f, x, k+1) pdv(f, x, s: slash)$",
             -1,
             -1,
             3,
@@ -244,7 +621,2710 @@ mod tests {
             false,
             vec![],
             vec![],
+        )
+        .unwrap();
+        assert!(parsed
+            .decorations
+            .iter()
+            .all(|d| !parsed.uuid(d.id).unwrap().contains("func")));
+    }
+    #[test]
+    fn test_arguments_attachment() {
+        // The arguments of package functions and macros are styled like the attachment they are in
+        let cases = [
+            (
+                "#import \"@preview/physica:0.9.3\": *\n$e^dv(phi, tau)$",
+                vec!["top-phi", "top-tau"],
+            ),
+            (
+                "#import \"@preview/physica:0.9.3\": *\n$e^braket(phi, psi)$",
+                vec!["top-phi", "top-psi"],
+            ),
+            (
+                "#import \"@preview/physica:0.9.3\": *\n$e^curl(phi)$",
+                vec!["top-phi"],
+            ),
+            (
+                "#import \"@preview/unify:0.5.0\": *\n$e^qty(3, \"m/s\")$",
+                vec!["top--text-3"],
+            ),
+            (
+                "#let avg(x) = $angle.l x angle.r$\n$e^avg(phi)$",
+                vec!["top-phi"],
+            ),
+        ];
+        for (content, uuids) in cases {
+            let parsed = parse_document_with_options(content, -1, -1, &Options::default()).unwrap();
+            for uuid in uuids {
+                let decoration = parsed
+                    .decorations
+                    .iter()
+                    .find(|d| parsed.uuid(d.id).unwrap() == uuid)
+                    .unwrap_or_else(|| panic!("{} isn't decorated in {}", uuid, content));
+                assert!(decoration.text_decoration.contains("font-size: 0.8em"));
+            }
+        }
+    }
+    #[test]
+    fn test_physica_braket() {
+        let parsed = parse_document(
+            "#import \"@preview/physica:0.9.3\": *\n$bra(psi) ket(phi) braket(a, b) expval(A)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let count = |symbol: &str| {
+            parsed
+                .decorations
+                .iter()
+                .find(|d| d.symbol == symbol)
+                .map_or(0, |d| d.positions.len())
+        };
+        assert_eq!(count("⟨"), 3);
+        assert_eq!(count("|"), 3);
+        assert_eq!(count("⟩"), 3);
+        let open = parsed.decorations.iter().find(|d| d.symbol == "⟨").unwrap();
+        assert_eq!(format!("{:?}", open.color), "Operator");
+        // Hide `bra(`
+        assert_eq!((open.positions[0].start, open.positions[0].end), (37, 41));
+    }
+    #[test]
+    fn test_physica_operators() {
+        let parsed = parse_document(
+            "#import \"@preview/physica:0.9.3\": *\n$grad f + curl(F) laplacian phi a div b$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let symbol = |uuid: &str| {
+            parsed
+                .decorations
+                .iter()
+                .find(|d| parsed.uuid(d.id).unwrap() == uuid)
+                .map(|d| d.symbol.clone())
+        };
+        assert_eq!(symbol("grad"), Some("∇".to_string()));
+        assert_eq!(symbol("func-∇×"), Some("∇×".to_string()));
+        assert_eq!(symbol("laplacian"), Some("∇²".to_string()));
+        // `div` replaces the standard symbol once physica is imported
+        assert_eq!(symbol("div"), Some("∇·".to_string()));
+        // Physica functions aren't rendered without the import
+        let parsed = parse_document(
+            "$grad f + dv(f, x) a div b$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert!(parsed.decorations.iter().all(|d| d.symbol != "∇"));
+        assert!(parsed
+            .decorations
+            .iter()
+            .all(|d| !parsed.uuid(d.id).unwrap().contains("func")));
+        assert!(parsed.decorations.iter().any(|d| d.symbol == "÷"));
+        // Renamed imports are followed
+        let parsed = parse_document(
+            "#import \"@preview/physica:0.9.3\": dv as der\n$der(f, x) dv(f, x)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let numerator = parsed.decorations.iter().find(|d| d.symbol == "d").unwrap();
+        assert_eq!(numerator.positions.len(), 1);
+        // Names are only bound after the import
+        let parsed = parse_document(
+            "$dv(f, x) grad f$\n#import \"@preview/physica:0.9.3\": *\n$dv(f, x) grad f$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let starts = |symbol: &str| {
+            let d = parsed
+                .decorations
+                .iter()
+                .find(|d| d.symbol == symbol)
+                .unwrap();
+            d.positions.iter().map(|p| p.start).collect::<Vec<_>>()
+        };
+        assert_eq!(starts("d"), vec![55]);
+        assert_eq!(starts("∇"), vec![64]);
+    }
+    #[test]
+    fn test_units() {
+        let parsed = parse_document(
+            "#import \"@preview/unify:0.5.0\": *\n#unit(\"kg m^-2\") $qty(3, \"m/s\")$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let unit = parsed
+            .decorations
+            .iter()
+            .find(|d| d.symbol == "kg·m⁻²")
+            .unwrap();
+        assert!(unit.text_decoration.contains("font-style: normal"));
+        // Hide `#unit("` with the quotes, up to the closing paren
+        let hidden = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "void")
+            .unwrap();
+        assert_eq!(
+            (hidden.positions[0].start, hidden.positions[0].end),
+            (34, 40)
         );
+        assert_eq!((unit.positions[0].start, unit.positions[0].end), (40, 50));
+        assert!(parsed.decorations.iter().any(|d| d.symbol == "m/s"));
+        // Markup calls are kept when symbols outside math aren't rendered
+        let parsed = parse_document(
+            "#import \"@preview/unify:0.5.0\": *\n#unit(\"kg m^-2\")",
+            -1,
+            -1,
+            3,
+            false,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 0);
+        // Units aren't rendered without a unit package
+        let parsed = parse_document(
+            "#unit(\"kg m^-2\")",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 0);
+    }
+    #[test]
+    fn test_mitex() {
+        let source = "#import \"@preview/mitex:0.2.4\": *\n#mitex(`\\alpha + \\mathbb{R} \\to \\dot{x} \\leq`)";
+        let parsed = parse_document_with_options(source, -1, -1, &Options::default()).unwrap();
+        let find = |symbol: &str| {
+            let d = parsed
+                .decorations
+                .iter()
+                .find(|d| d.symbol == symbol)
+                .unwrap();
+            (d.positions[0].start, d.positions[0].end)
+        };
+        assert_eq!(find("α"), (42, 48));
+        assert_eq!(find("ℝ"), (51, 61));
+        assert_eq!(find("→"), (62, 65));
+        assert_eq!(find("≤"), (74, 78));
+        // Accents aren't rendered as symbols
+        assert_eq!(parsed.decorations.len(), 4);
+        let options = Options {
+            render_latex: false,
+            ..Default::default()
+        };
+        let parsed = parse_document_with_options(source, -1, -1, &options).unwrap();
+        assert!(parsed.decorations.is_empty());
+        // LaTeX isn't rendered without mitex
+        let parsed =
+            parse_document_with_options("#mi(`\\alpha`)", -1, -1, &Options::default()).unwrap();
+        assert!(parsed.decorations.is_empty());
+        // Calls in markup are kept when symbols outside math aren't rendered
+        let options = Options {
+            render_outside_math: false,
+            ..Default::default()
+        };
+        let parsed = parse_document_with_options(source, -1, -1, &options).unwrap();
+        assert!(parsed.decorations.is_empty());
+    }
+    #[test]
+    fn test_let_macros() {
+        let parsed = parse_document(
+            "#let RR = $bb(R)$\n#let avg(x) = $angle.l x angle.r$\n$RR avg(a + b)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let find = |uuid: &str| {
+            let d = parsed
+                .decorations
+                .iter()
+                .find(|d| parsed.uuid(d.id).unwrap() == uuid)
+                .unwrap();
+            (d.symbol.clone(), d.positions[0].start, d.positions[0].end)
+        };
+        assert_eq!(find("let-RR-ℝ"), ("ℝ".to_string(), 53, 55));
+        // Hide `avg(` and `)`
+        assert_eq!(find("let-avg-⟨"), ("⟨".to_string(), 56, 60));
+        assert_eq!(find("let-avg-⟩"), ("⟩".to_string(), 65, 66));
+        assert!(parsed.decorations.iter().any(|d| d.symbol == "+"));
+        // Macros with parameters inside styles or used twice aren't expanded
+        let parsed = parse_document(
+            "#let vb(x) = $bold(x)$\n#let sq(x) = $x x$\n$vb(a) sq(b)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert!(parsed
+            .decorations
+            .iter()
+            .all(|d| !parsed.uuid(d.id).unwrap().starts_with("let-")));
+    }
+    #[test]
+    fn test_shadowed_bindings() {
+        let parsed = parse_document(
+            "#let alpha = 5\n#import \"util.typ\": hat\n#let f(beta) = $beta$\n$alpha alpha.alt beta hat(x)$ #[#let beta = 1; $beta$]",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        ).unwrap();
+        let symbols: Vec<&str> = parsed
+            .decorations
+            .iter()
+            .map(|d| d.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["β"]);
+        // Only the `beta` outside the closure and the content block is rendered
+        assert_eq!(parsed.decorations[0].positions.len(), 1);
+        assert_eq!(parsed.decorations[0].positions[0].start, 78);
+    }
+    #[test]
+    fn test_embedded_code() {
+        let parsed = parse_document(
+            "$#calc.pow(2, 3) #text.size #sym.alpha #math.beta #box[$gamma$] emoji.abacus$",
+            -1,
+            -1,
+            3,
+            false,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        let find = |symbol: &str| {
+            let d = parsed
+                .decorations
+                .iter()
+                .find(|d| d.symbol == symbol)
+                .unwrap();
+            (d.positions[0].start, d.positions[0].end)
+        };
+        assert_eq!(parsed.decorations.len(), 4);
+        // The hash is hidden even if symbols outside math aren't rendered
+        assert_eq!(find("α"), (28, 38));
+        assert_eq!(find("β"), (39, 49));
+        assert_eq!(find("γ"), (56, 61));
+        assert_eq!(find("🧮"), (64, 76));
+    }
+    #[test]
+    fn test_math_module_calls() {
+        let source = "#math.bb(\"R\") #math.attach($x$, t: $2$) $math.cal(A) attach(y, b: i)$";
+        let parsed = parse_document(source, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        let find = |symbol: &str| {
+            let d = parsed
+                .decorations
+                .iter()
+                .find(|d| d.symbol == symbol)
+                .unwrap();
+            (
+                parsed.uuid(d.id).unwrap(),
+                d.positions[0].start,
+                d.positions[0].end,
+            )
+        };
+        // Hide `#math.bb(` and `)`
+        assert_eq!(find("ℝ"), ("-ℝ".to_string(), 0, 13));
+        assert_eq!(find("2"), ("top--text-2".to_string(), 36, 37));
+        assert_eq!(find("𝒜"), ("-𝒜".to_string(), 41, 52));
+        assert_eq!(find("i").0, "bottom--text-i");
+        // `#math.attach($`, `$, t: $` and `$)` are hidden
+        let void = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "void")
+            .unwrap();
+        assert!(void.positions.iter().any(|p| (p.start, p.end) == (14, 28)));
+        assert!(void.positions.iter().any(|p| (p.start, p.end) == (29, 36)));
+        assert!(void.positions.iter().any(|p| (p.start, p.end) == (37, 39)));
+        // Calls in markup are kept when symbols outside math aren't rendered
+        let parsed = parse_document(source, -1, -1, 3, false, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations.len(), 3);
+    }
+    #[test]
+    fn test_symbol_table() {
+        let mut options = Options::default();
+        options
+            .add_symbol_table(
+                "{\"contra\": {\"char\": \"↯\", \"category\": \"operator\"}, \"alpha\": {\"symbol\": \"a\"}}",
+            )
+            .unwrap();
+        let parsed = parse_document_with_options("$contra alpha$", -1, -1, &options).unwrap();
+        let mut symbols: Vec<(String, String)> = parsed
+            .decorations
+            .iter()
+            .map(|d| (d.symbol.clone(), format!("{:?}", d.color)))
+            .collect();
+        symbols.sort();
+        // Built-in symbols take precedence
+        assert_eq!(
+            symbols,
+            vec![
+                ("α".to_string(), "Letter".to_string()),
+                ("↯".to_string(), "Operator".to_string())
+            ]
+        );
+        // Custom symbols replace the built-in ones
+        options.add_custom_symbol(CustomSymbol {
+            name: "alpha".to_string(),
+            symbol: "a".to_string(),
+            category: "letter".to_string(),
+        });
+        let parsed = parse_document_with_options("$alpha$", -1, -1, &options).unwrap();
+        assert_eq!(parsed.decorations[0].symbol, "a");
+        assert!(options.add_symbol_table("{\"qed\": \"∎\"}").is_err());
+    }
+    #[test]
+    fn test_function_rules() {
+        let mut options = Options::default();
+        options
+            .add_function_rules(
+                "{\"vecb\": {\"symbol\": \"→\", \"style\": \"font-size: 0.8em;\"}, \"my.op\": {\"char\": \"∮\", \"args\": \"any\"}, \"arrow\": {\"symbol\": \"⇀\"}}",
+            )
+            .unwrap();
+        let parsed = parse_document_with_options(
+            "#let vecb(x) = x\n$vecb(x) + my.op(a, b) + arrow(v) + vecb(x + y)$",
+            -1,
+            -1,
+            &options,
+        )
+        .unwrap();
+        let mut symbols: Vec<(String, String, usize, usize)> = parsed
+            .decorations
+            .iter()
+            .filter(|d| parsed.uuid(d.id).unwrap().contains("rule"))
+            .flat_map(|d| {
+                d.positions
+                    .iter()
+                    .map(move |p| (d.symbol.clone(), d.text_decoration.clone(), p.start, p.end))
+            })
+            .collect();
+        symbols.sort();
+        // Rules apply to functions bound by the user and take precedence over built-in accents, \
+        // but only if the arguments match
+        assert_eq!(
+            symbols,
+            vec![
+                ("→".to_string(), "font-size: 0.8em;".to_string(), 18, 23),
+                ("⇀".to_string(), "".to_string(), 42, 48),
+                ("∮".to_string(), "".to_string(), 28, 34),
+            ]
+        );
+        assert!(parsed
+            .decorations
+            .iter()
+            .any(|d| parsed.uuid(d.id).unwrap() == "-func-rule-vecb"));
+        assert!(options
+            .add_function_rules("{\"vecb\": {\"symbol\": \"→\", \"args\": \"none\"}}")
+            .is_err());
+    }
+    #[test]
+    fn test_colors() {
+        let mut options = Options::default();
+        options.set_color("operator", "#FD971F").unwrap();
+        options.set_color("group", "rgb(102, 217, 239)").unwrap();
+        assert!(options.set_color("bigletter", "#000000").is_err());
+        assert!(options.set_color("letter", "red").is_err());
+        assert!(options.set_color("letter", "#12345").is_err());
+        let parsed = parse_document_with_options("$alpha + NN$", -1, -1, &options).unwrap();
+        let mut colors: Vec<(String, Option<String>)> = parsed
+            .decorations
+            .iter()
+            .map(|d| (d.symbol.clone(), d.resolved_color.clone()))
+            .collect();
+        colors.sort();
+        assert_eq!(
+            colors,
+            vec![
+                ("+".to_string(), Some("#FD971F".to_string())),
+                ("α".to_string(), None),
+                ("ℕ".to_string(), Some("rgb(102, 217, 239)".to_string()))
+            ]
+        );
+    }
+    #[test]
+    fn test_symbol_lists() {
+        let options = Options {
+            blacklisted_symbols: vec!["arrow.*".to_string(), "*.not".to_string()],
+            ..Default::default()
+        };
+        let parsed = parse_document_with_options(
+            "$arrow.r.long alpha eq.not -> #sym.arrow.l$",
+            -1,
+            -1,
+            &options,
+        )
+        .unwrap();
+        let mut symbols: Vec<String> = parsed
+            .decorations
+            .iter()
+            .map(|d| d.symbol.clone())
+            .collect();
+        symbols.sort();
+        // Shorthands are only blacklisted by their source text
+        assert_eq!(symbols, vec!["α".to_string(), "→".to_string()]);
+        let options = Options {
+            whitelisted_symbols: vec!["alpha".to_string(), "arrow.*".to_string()],
+            ..Default::default()
+        };
+        let parsed = parse_document_with_options("$alpha beta arrow.r$", -1, -1, &options).unwrap();
+        let mut symbols: Vec<String> = parsed
+            .decorations
+            .iter()
+            .map(|d| d.symbol.clone())
+            .collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["α".to_string(), "→".to_string()]);
+    }
+    #[test]
+    fn test_features() {
+        let mut options = Options::default();
+        options.set_feature("letterStyles", false).unwrap();
+        options.set_feature("absNorm", false).unwrap();
+        assert!(options.set_feature("fractions", true).is_err());
+        let parsed =
+            parse_document_with_options("$bb(R) abs(x) hat(x)$", -1, -1, &options).unwrap();
+        let mut symbols: Vec<String> = parsed
+            .decorations
+            .iter()
+            .filter(|d| !d.symbol.is_empty())
+            .map(|d| d.symbol.clone())
+            .collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["^".to_string()]);
+        // Rendering modes are mapped to features
+        options.set_rendering_mode(2);
+        assert!(options.features.letter_styles && options.features.attachments);
+        assert!(!options.features.accents && !options.features.packages);
+        assert_eq!(
+            Features::from_rendering_mode(1),
+            Features {
+                attachments: false,
+                letter_styles: false,
+                operators: false,
+                aliases: false,
+                macros: false,
+                packages: false,
+                accents: false,
+                abs_norm: false,
+                sqrt: false,
+                function_rules: false,
+                function_names: false,
+            }
+        );
+    }
+    #[test]
+    fn test_attachment_style() {
+        let mut options = Options::default();
+        options.attachment_style.scale = 0.75;
+        options.attachment_style.top_offset = -35.5;
+        options.attachment_style.limits_offset = 50.0;
+        let parsed = parse_document_with_options("$x^2 lim_i$", -1, -1, &options).unwrap();
+        let mut styles: Vec<(String, String)> = parsed
+            .decorations
+            .iter()
+            .filter(|d| {
+                parsed.uuid(d.id).unwrap().starts_with("top-")
+                    || parsed.uuid(d.id).unwrap().starts_with("under-")
+            })
+            .map(|d| (d.symbol.clone(), d.text_decoration.clone()))
+            .collect();
+        styles.sort();
+        assert_eq!(
+            styles,
+            vec![
+                (
+                    "2".to_string(),
+                    "font-size: 0.75em; transform: translateY(-35.5%); display: inline-block;"
+                        .to_string()
+                ),
+                (
+                    "i".to_string(),
+                    "font-size: 0.7em; transform: translateY(50%); display: inline-block;"
+                        .to_string()
+                )
+            ]
+        );
+    }
+    #[test]
+    fn test_fonts() {
+        let mut options = Options::default();
+        options.fonts.math = "STIX Two Math".to_string();
+        options.fonts.fallback = vec!["Cambria Math".to_string()];
+        let parsed = parse_document_with_options("$cal(A) times alpha$", -1, -1, &options).unwrap();
+        let mut styles: Vec<(String, String)> = parsed
+            .decorations
+            .iter()
+            .map(|d| (d.symbol.clone(), d.text_decoration.clone()))
+            .collect();
+        styles.sort();
+        assert_eq!(
+            styles,
+            vec![
+                (
+                    "×".to_string(),
+                    "font-family: \"Fira Math\", \"Cambria Math\"; ".to_string()
+                ),
+                (
+                    "α".to_string(),
+                    "font-family: \"JuliaMono\", \"Cambria Math\"; ".to_string()
+                ),
+                (
+                    "𝒜".to_string(),
+                    "font-family: \"STIX Two Math\", \"Cambria Math\";".to_string()
+                )
+            ]
+        );
+    }
+    #[test]
+    fn test_scope() {
+        let symbols = |scope: Scope, start: i32, end: i32| {
+            let options = Options {
+                scope,
+                ..Default::default()
+            };
+            let parsed =
+                parse_document_with_options("$alpha$\n$ beta $\n$\ngamma\n$", start, end, &options)
+                    .unwrap();
+            let mut symbols: Vec<String> = parsed
+                .decorations
+                .iter()
+                .map(|d| d.symbol.clone())
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        assert_eq!(symbols(Scope::All, -1, -1), vec!["α", "β", "γ"]);
+        assert_eq!(symbols(Scope::Block, -1, -1), vec!["β", "γ"]);
+        assert_eq!(symbols(Scope::Inline, -1, -1), vec!["α"]);
+        // Incremental parsing starts inside equations
+        assert_eq!(symbols(Scope::Inline, 3, 3), Vec::<String>::new());
+        assert!(symbols(Scope::Block, 3, 3).contains(&"γ".to_string()));
+    }
+    #[test]
+    fn test_magic_comments() {
+        let parsed = parse_document(
+            "$alpha$ // typst-math: off\n$beta$\n// typst-math: on\n$gamma$ $delta$ // typst-math: ignore",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        ).unwrap();
+        let mut symbols: Vec<String> = parsed
+            .decorations
+            .iter()
+            .map(|d| d.symbol.clone())
+            .collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["α", "γ"]);
+    }
+    #[test]
+    fn test_symbol_filters() {
+        let symbols = |options: &Options| {
+            let parsed =
+                parse_document_with_options("$pi.alt + alpha + Omega in NN$", -1, -1, options)
+                    .unwrap();
+            let mut symbols: Vec<String> = parsed
+                .decorations
+                .iter()
+                .map(|d| d.symbol.clone())
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        let options = Options {
+            min_symbol_length: 3,
+            ..Default::default()
+        };
+        assert_eq!(symbols(&options), vec!["+", "Ω", "α"]);
+        let mut options = Options::default();
+        options.hide_category("letter").unwrap();
+        options.hide_category("Set").unwrap();
+        assert!(options.hide_category("greek").is_err());
+        assert_eq!(symbols(&options), vec!["+", "∈"]);
+    }
+    #[test]
+    fn test_session() {
+        let options = Options::default();
+        let symbols = |parsed: Parsed| {
+            let mut symbols: Vec<String> = parsed
+                .decorations
+                .iter()
+                .map(|d| d.symbol.clone())
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        // Typst only reparses the content block around the edit, top-level equations would reparse the whole markup
+        let mut session = Session::new("$alpha$\n\n#[$𝔸 beta$]");
+        assert_eq!(symbols(session.parse(&options)), vec!["α", "β", "𝔸"]);
+        // Offsets are in UTF-16, `𝔸` is two code units long
+        session.edit(19, 19, " + gamma");
+        session.edit(15, 15, "delta ");
+        assert_eq!(session.text(), "$alpha$\n\n#[$𝔸 delta beta + gamma$]");
+        let parsed = session.parse_edits(&options);
+        assert_eq!(parsed.edit_start_line, 2);
+        assert_eq!(symbols(parsed), vec!["+", "β", "γ", "δ", "𝔸"]);
+        // Nothing was edited since the last decorations
+        assert_eq!(
+            symbols(session.parse_edits(&options)),
+            vec!["+", "α", "β", "γ", "δ", "𝔸"]
+        );
+    }
+    #[test]
+    fn test_session_context() {
+        let options = Options::default();
+        let summary = |parsed: Parsed| {
+            let mut decorations: Vec<(String, String, Vec<usize>)> = parsed
+                .decorations
+                .into_iter()
+                .map(|d| {
+                    let starts = d.positions.iter().map(|p| p.start).collect();
+                    (d.symbol, d.text_decoration, starts)
+                })
+                .collect();
+            decorations.sort();
+            decorations
+        };
+        let fresh = |session: &Session| {
+            summary(parse_document_with_options(&session.text(), -1, -1, &options).unwrap())
+        };
+        let mut session = Session::new(
+            "$x + y$\n#show math.equation: set text(size: 1.5em)\n$alpha$ // typst-math: off\n$beta$",
+        );
+        let parsed = summary(session.parse(&options));
+        // The show rule only styles the equations after it, and the one after the magic comment isn't rendered
+        let css = |symbol: &str| {
+            parsed
+                .iter()
+                .find(|(s, _, _)| s == symbol)
+                .map(|d| d.1.clone())
+        };
+        assert!(css("α").unwrap().contains("1.5em"));
+        assert!(!css("+").unwrap().contains("1.5em"));
+        assert_eq!(css("β"), None);
+        // Edits inside an equation keep the context, moving its regions and rules
+        let at = |session: &Session, text: &str| session.text().find(text).unwrap();
+        session.edit(1, 6, "x");
+        assert_eq!(summary(session.parse(&options)), fresh(&session));
+        let alpha = at(&session, "alpha");
+        session.edit(alpha, alpha + 5, "gamma + delta");
+        assert_eq!(summary(session.parse(&options)), fresh(&session));
+        // Others collect it again, like removing the magic comment or closing the equation early
+        let comment = at(&session, " //");
+        session.edit(comment, comment + 18, "");
+        let parsed = summary(session.parse(&options));
+        assert!(parsed.iter().any(|(symbol, _, _)| symbol == "β"));
+        assert_eq!(parsed, fresh(&session));
+        let plus = at(&session, "+");
+        session.edit(plus, plus, "$");
+        assert_eq!(summary(session.parse(&options)), fresh(&session));
+    }
+    #[test]
+    fn test_session_delta() {
+        let options = Options::default();
+        let added = |delta: &Delta| {
+            let mut symbols: Vec<String> = delta.added.iter().map(|d| d.symbol.clone()).collect();
+            symbols.sort();
+            symbols
+        };
+        let mut session = Session::new("$alpha beta$");
+        let delta = session.parse_delta(&options, 0);
+        assert!(!delta.reset);
+        assert_eq!(added(&delta), vec!["α", "β"]);
+        assert!(delta.removed.is_empty() && delta.unchanged.is_empty());
+        assert_eq!((delta.uuids_start, delta.uuids.len()), (0, 2));
+        let id = |uuid: &str| delta.uuids.iter().position(|u| u == uuid).unwrap() as u32;
+        let (alpha, beta) = (id("alpha"), id("beta"));
+
+        session.edit(7, 11, "gamma");
+        let delta = session.parse_delta(&options, delta.version);
+        assert_eq!(added(&delta), vec!["γ"]);
+        assert_eq!(delta.removed, vec![beta]);
+        assert_eq!(delta.unchanged, vec![alpha]);
+        // Only the new uuid is sent
+        assert_eq!(
+            (delta.uuids_start, delta.uuids.clone()),
+            (2, vec!["gamma".to_string()])
+        );
+
+        // Positions are compared, moving a symbol changes its decoration
+        session.edit(0, 0, " ");
+        let delta = session.parse_delta(&options, delta.version);
+        assert_eq!(added(&delta), vec!["α", "γ"]);
+        assert!(delta.removed.is_empty() && delta.unchanged.is_empty());
+
+        // An unknown version resets the decorations
+        let delta = session.parse_delta(&options, delta.version + 10);
+        assert!(delta.reset);
+        assert_eq!(added(&delta), vec!["α", "γ"]);
+        assert!(delta.removed.is_empty());
+    }
+    #[test]
+    fn test_parse_range() {
+        let options = Options::default();
+        let symbols = |parsed: Parsed| {
+            let mut symbols: Vec<String> = parsed
+                .decorations
+                .iter()
+                .map(|d| d.symbol.clone())
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        let content = "#let x = $alpha$\n$beta$\n$ gamma\n delta $\n$epsilon$";
+        // The equation crossing the last line is parsed completely
+        let parsed = parse_range(content, 1, 2, &options);
+        assert_eq!((parsed.edit_start_line, parsed.edit_end_line), (1, 3));
+        assert_eq!(symbols(parsed), vec!["β", "γ", "δ"]);
+        // Bindings outside the lines still shadow symbols
+        assert!(parse_range("#let alpha = 5\n$alpha beta$", 1, 1, &options)
+            .decorations
+            .iter()
+            .all(|d| d.symbol != "α"));
+        assert!(parse_range(content, 10, 12, &options)
+            .decorations
+            .is_empty());
+        let mut session = Session::new(content);
+        assert_eq!(symbols(session.parse_range(4, 4, &options)), vec!["ε"]);
+    }
+    #[test]
+    fn test_max_depth() {
+        let nested = |depth: usize| format!("${}alpha{}$", "(".repeat(depth), ")".repeat(depth));
+        let mut options = Options::default();
+        let parsed = parse_document_with_options(&nested(10), -1, -1, &options).unwrap();
+        assert!(!parsed.truncated);
+        assert!(parsed.decorations.iter().any(|d| d.symbol == "α"));
+
+        // Deeper nodes are skipped
+        options.max_depth = 10;
+        let parsed = parse_document_with_options(&nested(10), -1, -1, &options).unwrap();
+        assert!(parsed.truncated);
+        assert!(parsed.decorations.iter().all(|d| d.symbol != "α"));
+
+        // Deeply nested documents don't overflow the stack
+        let parsed =
+            parse_document_with_options(&nested(1000), -1, -1, &Options::default()).unwrap();
+        assert!(parsed.truncated);
+
+        // Without a limit, nested blocks are pushed on the same stack as the other nodes, even on a small one
+        let parsed = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(|| {
+                let deep = format!("${}alpha{}$", "x^(".repeat(300), ")".repeat(300));
+                let options = Options {
+                    max_depth: usize::MAX,
+                    ..Default::default()
+                };
+                parse_document_with_options(&deep, -1, -1, &options).unwrap()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(!parsed.truncated);
+        assert!(parsed.decorations.iter().any(|d| d.symbol == "α"));
+    }
+    #[test]
+    fn test_many_equations() {
+        let content: String = (0..100).map(|i| format!("$alpha_{i} beta$\n")).collect();
+        let parsed = parse_document_with_options(&content, -1, -1, &Options::default()).unwrap();
+        let alpha = parsed
+            .decorations
+            .iter()
+            .find(|d| parsed.uuid(d.id).unwrap() == "alpha")
+            .unwrap();
+        assert_eq!(alpha.positions.len(), 100);
+        // Positions of the top-level nodes are merged in the order of the document
+        assert!(alpha
+            .positions
+            .windows(2)
+            .all(|pair| pair[0].start < pair[1].start));
+        // Ids follow the order in which uuids are met, like when the equations are traversed together
+        let wrapped = format!("#[{content}]");
+        let sequential =
+            parse_document_with_options(&wrapped, -1, -1, &Options::default()).unwrap();
+        assert_eq!(parsed.uuids, sequential.uuids);
+    }
+    #[test]
+    fn test_stream_document() {
+        let mut batches = vec![];
+        stream_document(
+            "$alpha$\n\nsome text\n\n$beta alpha$",
+            &Options::default(),
+            |parsed| batches.push(parsed),
+        );
+        // Nodes without decorations don't produce batches
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].edit_start_line, 0);
+        assert_eq!(batches[0].uuids, vec!["alpha"]);
+        assert_eq!(batches[1].edit_start_line, 4);
+        assert_eq!(batches[1].decorations.len(), 2);
+        // Only the new uuid is sent, `alpha` keeps its id
+        assert_eq!(
+            (batches[1].uuids_start, batches[1].uuids.clone()),
+            (1, vec!["beta".to_string()])
+        );
+        assert!(batches[1].decorations.iter().any(|d| d.id == 0));
+
+        let mut session = Session::new("$alpha$ $gamma$");
+        let mut symbols = vec![];
+        session.stream(&Options::default(), |parsed| {
+            symbols.extend(parsed.decorations.into_iter().map(|d| d.symbol))
+        });
+        assert_eq!(symbols, vec!["α", "γ"]);
+    }
+    #[test]
+    fn test_batch() {
+        let mut batch = Batch::new();
+        let mut options = Options::default();
+        batch.add("file:///a.typ".to_string(), "$alpha$".to_string(), &options);
+        options.blacklisted_symbols = vec!["beta".to_string()];
+        batch.add(
+            "file:///b.typ".to_string(),
+            "$beta gamma$".to_string(),
+            &options,
+        );
+        assert_eq!(batch.len(), 2);
+        let results = batch.parse();
+        assert_eq!(results[0].uri, "file:///a.typ");
+        assert_eq!(results[0].parsed.decorations[0].symbol, "α");
+        // Each document keeps its own options
+        assert_eq!(results[1].uri, "file:///b.typ");
+        assert_eq!(results[1].parsed.decorations.len(), 1);
+        assert_eq!(results[1].parsed.decorations[0].symbol, "γ");
+        assert_eq!(results[1].parsed.uuids, vec!["gamma"]);
+    }
+    #[test]
+    fn test_cancellation() {
+        let token = CancellationToken::new();
+        let mut options = Options::default();
+        options.set_cancellation_token(&token);
+        token.cancel();
+        let parsed = parse_document_with_options("$alpha$", -1, -1, &options).unwrap();
+        assert!(parsed.cancelled);
+        assert!(parsed.decorations.is_empty());
+
+        // Cancelled between two top-level nodes
+        token.reset();
+        let mut batches = 0;
+        stream_document("$alpha$\n\n$beta$\n\n$gamma$", &options, |parsed| {
+            batches += 1;
+            assert!(!parsed.cancelled);
+            token.cancel();
+        });
+        assert_eq!(batches, 1);
+
+        // The edits of a cancelled parse are kept for the next one
+        token.reset();
+        let mut session = Session::new("$alpha$\n\n#[$beta$]");
+        session.parse(&options);
+        session.edit(12, 16, "gamma");
+        token.cancel();
+        assert!(session.parse_edits(&options).cancelled);
+        token.reset();
+        let parsed = session.parse_edits(&options);
+        assert!(!parsed.cancelled);
+        assert_eq!(parsed.decorations[0].symbol, "γ");
+    }
+    #[test]
+    fn test_list_symbols() {
+        let mut options = Options::default();
+        options.add_custom_symbol(CustomSymbol {
+            name: "alpha".to_string(),
+            symbol: "A".to_string(),
+            category: "letter".to_string(),
+        });
+        options
+            .add_symbol_table(r#"{"mho": {"char": "℧", "category": "letter"}}"#)
+            .unwrap();
+        let symbols = list_symbols(&options);
+        let find = |name: &str| {
+            symbols
+                .iter()
+                .filter(|s| s.name == name)
+                .collect::<Vec<_>>()
+        };
+        let beta = find("beta");
+        assert_eq!(beta.len(), 1);
+        assert_eq!(beta[0].symbol, "β");
+        assert_eq!(beta[0].source, SymbolSource::Builtin);
+        let alpha = find("alpha");
+        assert_eq!(alpha.len(), 1);
+        assert_eq!(alpha[0].symbol, "A");
+        assert_eq!(alpha[0].source, SymbolSource::Custom);
+        assert_eq!(find("mho")[0].source, SymbolSource::Custom);
+        assert_eq!(find("emoji.cat")[0].source, SymbolSource::Emoji);
+    }
+
+    #[test]
+    fn test_names_for_char() {
+        assert_eq!(names_for_char("α"), vec!["alpha"]);
+        let names = names_for_char("≤");
+        assert_eq!(names[0], "lt.eq");
+        assert_eq!(names_for_char("🐈"), vec!["emoji.cat"]);
+        assert!(names_for_char("αβ").is_empty());
+        assert!(names_for_char("x").is_empty());
+    }
+
+    #[test]
+    fn test_symbol_at() {
+        let options = Options::default();
+        let source = "text 𝔸\n$x arrow.r alpha$";
+        let hover = symbol_at(source, 15, &options).unwrap();
+        assert_eq!(hover.name, "arrow.r");
+        assert_eq!(hover.symbol, "→");
+        assert_eq!((hover.position.start, hover.position.end), (11, 18));
+        let hover = symbol_at(source, 20, &options).unwrap();
+        assert_eq!(hover.name, "alpha");
+        assert_eq!(hover.symbol, "α");
+        assert!(symbol_at(source, 10, &options).is_none());
+        assert!(symbol_at(source, 2, &options).is_none());
+        let session = Session::new(source);
+        assert_eq!(session.symbol_at(20, &options).unwrap().name, "alpha");
+    }
+
+    #[test]
+    fn test_search_symbols() {
+        let options = Options::default();
+        let names = |query: &str| {
+            search_symbols(query, &options, 5)
+                .into_iter()
+                .map(|symbol| symbol.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("alpha")[0], "alpha");
+        assert_eq!(names("arow r")[0], "arrow.r");
+        assert_eq!(names("arow rgt")[0], "arrow.r");
+        assert_eq!(names("arrow.r.long")[0], "arrow.r.long");
+        assert!(names("lt eq").contains(&"lt.eq".to_string()));
+        assert_eq!(names("alph")[0], "alpha");
+        assert_eq!(names("arrow").len(), 5);
+        assert!(names("").is_empty());
+        assert!(names("zzzzzz").is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let parsed =
+            parse_document_with_options("$alpha$ #let", -1, -1, &Options::default()).unwrap();
+        assert!(parsed.erroneous);
+        assert_eq!(parsed.diagnostics.len(), 1);
+        assert_eq!(parsed.diagnostics[0].position.start, 12);
+        assert!(parsed.diagnostics[0].message.contains("expected"));
+        assert_eq!(parsed.decorations.len(), 1);
+        let parsed = parse_document_with_options("$alpha$", -1, -1, &Options::default()).unwrap();
+        assert!(parsed.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let parse = |content: &str| {
+            parse_document_with_options(content, -1, -1, &Options::default())
+                .unwrap()
+                .suggestions
+        };
+        let suggestions = parse("$alpa + beta + sinn(x) + xyzzy$");
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].name, "alpa");
+        assert_eq!(suggestions[0].candidates[0], "alpha");
+        assert_eq!(suggestions[0].position.start, 1);
+        assert_eq!(suggestions[0].position.end, 5);
+        // Suggestions of several top-level nodes are all kept
+        assert_eq!(parse("$alpa$ and $gamme$").len(), 2);
+        // Bound names, operators, callees and argument values are known
+        assert!(parse("#let alpa = 1\n$alpa + sinh + foo(x) + text(fill: blu, x)$").is_empty());
+        // Names of wildcard imports aren't known
+        assert!(parse("#import \"defs.typ\": *\n$alpa$").is_empty());
+    }
+
+    #[test]
+    fn test_parallel_traversal() {
+        let document = |lines: usize| {
+            (0..lines)
+                .map(|i| format!("Line {} with $x_{}^2 + alpha_(n+1)$ and $beta$.\n", i, i))
+                .collect::<String>()
+        };
+        let parse = |content: &str, threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let mut decorations = pool
+                .install(|| parse_document_with_options(content, -1, -1, &Options::default()))
+                .unwrap()
+                .decorations;
+            decorations.sort_by_key(|decoration| decoration.id);
+            decorations
+        };
+        // Chunks traversed in parallel give the same decorations as a single thread
+        let content = document(1000);
+        assert_eq!(parse(&content, 4), parse(&content, 1));
+        // The time grows linearly with the number of top-level nodes, a quadratic traversal taking 16 times longer
+        let time = |lines: usize| {
+            let content = document(lines);
+            let start = std::time::Instant::now();
+            parse(&content, 4);
+            start.elapsed()
+        };
+        let small = time(2000);
+        let large = time(8000);
+        assert!(
+            large < small * 10,
+            "2000 lines took {:?} and 8000 lines {:?}",
+            small,
+            large
+        );
+    }
+
+    #[test]
+    fn test_lints() {
+        let lints = |content: &str| {
+            parse_document_with_options(content, -1, -1, &Options::default())
+                .unwrap()
+                .lints
+                .into_iter()
+                .map(|lint| (lint.code, lint.position.start, lint.position.end))
+                .collect::<Vec<_>>()
+        };
+        let lint = |code: &str, start: usize, end: usize| (code.to_string(), start, end);
+        assert_eq!(
+            lints("$lr(a + b) + lr(angle.l x angle.r) + lr(|x|)$"),
+            vec![lint("lr-delimiters", 4, 9)]
+        );
+        assert_eq!(
+            lints("$[x + y) + (z + w$"),
+            vec![lint("unclosed-delimiter", 11, 12)]
+        );
+        assert_eq!(
+            lints("$x) + x^(2)$"),
+            vec![lint("unopened-delimiter", 2, 3)]
+        );
+        assert_eq!(
+            lints("$x^() + x_\"\" + x^2$"),
+            vec![
+                lint("empty-attachment", 2, 5),
+                lint("empty-attachment", 9, 12)
+            ]
+        );
+        assert_eq!(
+            lints("$integral_0^1 dif x + integral dif x f(x) + integral f dif x$"),
+            vec![lint("missing-integrand", 14, 19)]
+        );
+        assert_eq!(
+            lints("$a * b dot c$ $a * b$"),
+            vec![lint("mixed-multiplication", 3, 4)]
+        );
+        let parsed = parse_document_with_options("$x^()$", -1, -1, &Options::default()).unwrap();
+        assert_eq!(parsed.lints[0].severity, Severity::Warning);
+        assert_eq!(parsed.lints[0].message, "Empty superscript");
+    }
+
+    #[test]
+    fn test_line_column_positions() {
+        let parsed =
+            parse_document_with_options("𝔸 $alpha$\n$𝔸 beta$", -1, -1, &Options::default())
+                .unwrap();
+        let position = |uuid: &str| {
+            let decoration = parsed
+                .decorations
+                .iter()
+                .find(|d| parsed.uuid(d.id).unwrap() == uuid)
+                .unwrap();
+            decoration.positions[0].clone()
+        };
+        let alpha = position("alpha");
+        assert_eq!((alpha.start, alpha.end), (4, 9));
+        assert_eq!((alpha.start_line, alpha.start_column), (0, 4));
+        assert_eq!((alpha.end_line, alpha.end_column), (0, 9));
+        let beta = position("beta");
+        assert_eq!((beta.start, beta.end), (15, 19));
+        assert_eq!((beta.start_line, beta.start_column), (1, 4));
+        assert_eq!((beta.end_line, beta.end_column), (1, 8));
+    }
+
+    #[test]
+    fn test_render_to_unicode() {
+        let options = Options::default();
+        let unicode = |source: &str| render_to_unicode(source, 0, source.len(), &options);
+        assert_eq!(unicode("$x^2 + a_n <= alpha dot beta$"), "x² + aₙ ≤ α ⋅ β");
+        assert_eq!(unicode("$x^(k+1) arrow.r NN$"), "x^(k+1) → ℕ");
+        assert_eq!(unicode("Let $alpha in RR$."), "Let α ∈ ℝ.");
+        let source = "$alpha$ and $beta^2$";
+        assert_eq!(render_to_unicode(source, 12, 20, &options), "β²");
+    }
+
+    #[test]
+    fn test_typstify() {
+        let apply = |source: &str| {
+            let utf16: Vec<u16> = source.encode_utf16().collect();
+            let mut edits = typstify(source, 0, utf16.len());
+            edits.sort_by_key(|edit| std::cmp::Reverse(edit.position.start));
+            let mut utf16 = utf16;
+            for edit in edits {
+                let text: Vec<u16> = edit.text.encode_utf16().collect();
+                utf16.splice(edit.position.start..edit.position.end, text);
+            }
+            String::from_utf16(&utf16).unwrap()
+        };
+        assert_eq!(apply("$α ≤ ∑ x$"), "$alpha lt.eq sum x$");
+        assert_eq!(apply("$xα αβ 2$"), "$x alpha alpha beta 2$");
+        assert_eq!(apply("Let α be 2α."), "Let #sym.alpha be 2#(sym.alpha).");
+        assert_eq!(apply("α2 #\"α\" `α`"), "#(sym.alpha)2 #\"α\" `α`");
+        assert_eq!(apply("$x + y = 1$ 🐈"), "$x + y = 1$ 🐈");
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let deep = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(move || apply(&format!("${}α{}$", "(".repeat(1000), ")".repeat(1000))))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(
+            deep,
+            format!("${}alpha{}$", "(".repeat(1000), ")".repeat(1000))
+        );
+    }
+
+    #[test]
+    fn test_list_equations() {
+        let source = "Let $alpha in RR$ be\n$ sum_(i=0)^n\n  x_i $";
+        let equations = list_equations(source, &Options::default());
+        assert_eq!(equations.len(), 2);
+        assert!(!equations[0].block);
+        assert_eq!(equations[0].source, "$alpha in RR$");
+        assert_eq!(equations[0].unicode, "α ∈ ℝ");
+        assert_eq!(
+            (equations[0].position.start, equations[0].position.end),
+            (4, 17)
+        );
+        assert!(equations[1].block);
+        assert_eq!(equations[1].position.start_line, 1);
+        assert_eq!(equations[1].position.end_line, 2);
+        assert!(equations[1].unicode.starts_with('∑'));
+        assert!(!equations[1].unicode.contains('\n'));
+        // Nested equations are listed in the order of the document
+        let equations = list_equations("$a #box[$b$] c$ $d$", &Options::default());
+        let sources: Vec<&str> = equations.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(sources, vec!["$a #box[$b$] c$", "$b$", "$d$"]);
+    }
+
+    #[test]
+    fn test_json_result() {
+        let json = parse_document_json("$alpha$", &Options::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["decorations"][0]["symbol"], "α");
+        assert_eq!(value["decorations"][0]["color"], "Letter");
+        assert_eq!(value["decorations"][0]["positions"][0]["start"], 1);
+        assert_eq!(value["uuids"][0], "alpha");
+        assert_eq!(value["erroneous"], false);
+        assert!(value["decorations"][0]["resolved_color"].is_null());
+    }
+
+    #[test]
+    fn test_extmarks() {
+        let extmarks = parse_document_extmarks("é $alpha^2$\n$x <= y$", &Options::default());
+        let marks: Vec<_> = extmarks
+            .iter()
+            .map(|m| {
+                (
+                    m.line,
+                    m.start_col,
+                    m.end_col,
+                    m.conceal.as_str(),
+                    m.hl_group.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            marks,
+            vec![
+                (0, 4, 9, "α", "TypstMathLetter"),
+                (0, 9, 11, "²", "TypstMathNumber"),
+                (1, 3, 5, "≤", "TypstMathComparison"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlays() {
+        let overlays = parse_document_overlays("é 𝔸 $alpha^2$", &Options::default());
+        let overlays: Vec<_> = overlays
+            .iter()
+            .map(|o| (o.start, o.end, o.display.as_str(), o.face.as_str()))
+            .collect();
+        assert_eq!(
+            overlays,
+            vec![
+                (6, 11, "α", "typst-math-letter"),
+                (11, 13, "²", "typst-math-number"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_handle_request() {
+        let answer =
+            |line: &str| serde_json::from_str::<serde_json::Value>(&handle_request(line)).unwrap();
+        let parsed = answer(r#"{"id": 1, "method": "parse", "params": {"content": "$alpha$"}}"#);
+        assert_eq!(parsed["id"], 1);
+        assert_eq!(parsed["result"]["decorations"][0]["symbol"], "α");
+        let hover = answer(
+            r#"{"id": 2, "method": "query", "params": {"content": "$a in B$", "offset": 3}}"#,
+        );
+        assert_eq!(hover["result"]["name"], "in");
+        let unicode =
+            answer(r#"{"id": 3, "method": "convert", "params": {"content": "$x^2 <= alpha$"}}"#);
+        assert_eq!(unicode["result"], "x² ≤ α");
+        let edits = answer(
+            r#"{"id": 4, "method": "convert", "params": {"content": "$α$", "to": "typst"}}"#,
+        );
+        assert_eq!(edits["result"][0]["text"], "alpha");
+        let error = answer(
+            r#"{"id": 5, "method": "parse", "params": {"content": "", "options": {"features": {"unknown": true}}}}"#,
+        );
+        assert_eq!(error["id"], 5);
+        assert!(error["error"].is_string());
+        assert_eq!(answer("not json")["id"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let mut options = Options::default();
+        options.set_rendering_mode(0);
+        let content = "$sin(x) + alpha <= 2.5 / (b) arrow.r #f(1) vec(x)$ 12";
+        let tokens: Vec<(TokenKind, &str)> = semantic_tokens(content, &options)
+            .into_iter()
+            .map(|token| {
+                (
+                    token.kind,
+                    &content[token.position.start..token.position.end],
+                )
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (TokenKind::Function, "sin"),
+                (TokenKind::Delimiter, "("),
+                (TokenKind::Delimiter, ")"),
+                (TokenKind::Operator, "+"),
+                (TokenKind::Greek, "alpha"),
+                (TokenKind::Relation, "<="),
+                (TokenKind::Number, "2.5"),
+                (TokenKind::Operator, "/"),
+                (TokenKind::Delimiter, "("),
+                (TokenKind::Delimiter, ")"),
+                (TokenKind::Relation, "arrow.r"),
+                (TokenKind::Function, "vec"),
+                (TokenKind::Delimiter, "("),
+                (TokenKind::Delimiter, ")"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let content =
+            "$ x = 1\n  + 2\n$\n$ mat(\n  1, 2;\n  3, 4\n) + cases(a, b) $\n$x\n+ y$ $ y $";
+        let ranges: Vec<(usize, usize)> = folding_ranges(content)
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line))
+            .collect();
+        // The inline equation and the single-line calls are not folded
+        assert_eq!(ranges, vec![(0, 1), (3, 6), (3, 5)]);
+    }
+
+    #[test]
+    fn test_inlay_hints() {
+        let content = r#"$mat(delim: "[", 1; 2) vec(delim: #none, x) op("lim", limits: #true) lr(size: #150%, [x]) f(delim: "[")$"#;
+        let hints: Vec<(usize, String)> = inlay_hints(content)
+            .into_iter()
+            .map(|hint| (hint.position.start, hint.label))
+            .collect();
+        // Arguments of unknown functions are not described
+        assert_eq!(
+            hints,
+            vec![
+                (15, "[ … ]".to_string()),
+                (39, "no delimiters".to_string()),
+                (67, "attachments as limits".to_string()),
+                (83, "150% of the content".to_string()),
+            ]
+        );
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let deep = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(|| {
+                let deep = format!(
+                    r#"${}mat(delim: "[", 1){}$"#,
+                    "(".repeat(1000),
+                    ")".repeat(1000)
+                );
+                inlay_hints(&deep)
+                    .into_iter()
+                    .map(|hint| (hint.position.start, hint.label))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(deep, vec![(1015, "[ … ]".to_string())]);
+    }
+
+    #[test]
+    fn test_equation_symbols() {
+        let content = "$ x^2 $ <eq:a>\n$ y $\n<eq:b>\n\n$ z $ text <c> $alpha$<eq:d>";
+        let symbols: Vec<(String, String, String, bool)> =
+            equation_symbols(content, &Options::default())
+                .into_iter()
+                .map(|symbol| {
+                    let position = &symbol.position;
+                    let selection = &symbol.selection;
+                    (
+                        symbol.name,
+                        content[position.start..position.end].to_string(),
+                        content[selection.start..selection.end].to_string(),
+                        symbol.block,
+                    )
+                })
+                .collect();
+        // Labels separated by other content are not attached to the equation
+        assert_eq!(
+            symbols,
+            vec![
+                (
+                    "eq:a".to_string(),
+                    "$ x^2 $ <eq:a>".to_string(),
+                    "<eq:a>".to_string(),
+                    true
+                ),
+                (
+                    "eq:b".to_string(),
+                    "$ y $\n<eq:b>".to_string(),
+                    "<eq:b>".to_string(),
+                    true
+                ),
+                (
+                    "eq:d".to_string(),
+                    "$alpha$<eq:d>".to_string(),
+                    "<eq:d>".to_string(),
+                    false
+                ),
+            ]
+        );
+        assert_eq!(
+            equation_symbols(content, &Options::default())[0].preview,
+            "x²"
+        );
+    }
+
+    #[test]
+    fn test_definition_at() {
+        let content = "#let RR = $bb(R)$\n#let f(xy) = $xy^2 + RR$\n#{\n  let RR = 1\n  $RR$\n}\n$RR + alpha + #f(x)$";
+        let definition = |offset: usize| {
+            definition_at(content, offset)
+                .map(|position| (position.start, &content[position.start..position.end]))
+        };
+        // The parameter and the top-level binding in the closure body
+        assert_eq!(definition(32), Some((25, "xy")));
+        assert_eq!(definition(39), Some((5, "RR")));
+        // The binding of the block shadows the top-level one
+        assert_eq!(definition(62), Some((52, "RR")));
+        // Outside the block, at the start and the end of the identifier
+        assert_eq!(definition(69), Some((5, "RR")));
+        assert_eq!(definition(71), Some((5, "RR")));
+        assert_eq!(definition(74), None);
+        // Code embedded in the equation, the parameter being bound in the closure only
+        assert_eq!(definition(83), Some((23, "f")));
+        assert_eq!(definition(85), None);
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let deep = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(|| {
+                let deep = format!("#let xy = 1\n${}xy{}$", "(".repeat(1000), ")".repeat(1000));
+                definition_at(&deep, 1013).map(|position| position.start)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(deep, Some(5));
+    }
+
+    #[test]
+    fn test_rename_locations() {
+        let content = "#let RR = $bb(R)$\n#let f(xy) = $xy^2 + RR$\n#{\n  let RR = 1\n  $RR$\n}\n$RR + alpha + #f(x) + RR.x + #RR$";
+        let locations = |offset: usize| -> Vec<usize> {
+            rename_locations(content, offset)
+                .into_iter()
+                .map(|position| position.start)
+                .collect()
+        };
+        // The top-level binding, skipping the one of the block which shadows it
+        assert_eq!(locations(69), vec![5, 39, 69, 90, 98]);
+        assert_eq!(locations(5), Vec::<usize>::new());
+        assert_eq!(locations(62), vec![52, 62]);
+        assert_eq!(locations(32), vec![25, 32]);
+        assert_eq!(locations(74), Vec::<usize>::new());
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let deep = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(|| {
+                let deep = format!("#let xy = 1\n${}xy{}$", "(".repeat(1000), ")".repeat(1000));
+                rename_locations(&deep, 1013)
+                    .into_iter()
+                    .map(|position| position.start)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(deep, vec![5, 1013]);
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        let fixes = quick_fixes("$e^-x + x^2y + x^-2a + x_1y + e^- x + x^2 y$");
+        let edits: Vec<(usize, usize, &str)> = fixes
+            .iter()
+            .map(|fix| {
+                let position = &fix.edit.position;
+                (position.start, position.end, fix.edit.text.as_str())
+            })
+            .collect();
+        assert_eq!(
+            edits,
+            vec![(3, 5, "(-x)"), (10, 12, "(2y)"), (17, 20, "(-2a)")]
+        );
+        assert_eq!(fixes[0].title, "Wrap `-x` in parentheses");
+        assert!(quick_fixes("$e^(-x) + x^10 + x^2$").is_empty());
+        // Deeply nested documents are walked without growing the stack, even on a small one
+        let deep = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(|| {
+                let deep = format!("${}e^-x{}$", "(".repeat(1000), ")".repeat(1000));
+                quick_fixes(&deep)
+                    .into_iter()
+                    .map(|fix| (fix.edit.position.start, fix.edit.position.end))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(deep, vec![(1003, 1005)]);
+    }
+
+    #[test]
+    fn test_latex_fixes() {
+        let fix = |content: &str| {
+            quick_fixes(content)
+                .into_iter()
+                .map(|fix| {
+                    (
+                        fix.edit.position.start,
+                        fix.edit.position.end,
+                        fix.edit.text,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            fix("$\\alpha + \\le$"),
+            vec![(1, 7, "alpha".to_string()), (10, 13, "lt.eq".to_string())]
+        );
+        assert_eq!(
+            fix("$\\frac{\\alpha}{x^{2}} \\sqrt[3]{y}$"),
+            vec![
+                (1, 21, "frac(alpha, x^(2))".to_string()),
+                (22, 33, "root(3, y)".to_string())
+            ]
+        );
+        assert_eq!(
+            fix("$\\alpha\\beta \\mathbb{R} \\left( x \\right)$")[0].2,
+            "alpha "
+        );
+        let fixes = quick_fixes("$\\mathbb{R} \\text{if} \\vec{v}$");
+        assert_eq!(fixes[0].title, "Replace `\\mathbb{R}` with `bb(R)`");
+        assert_eq!(fixes[1].edit.text, "\"if\"");
+        assert_eq!(fixes[2].edit.text, "arrow(v)");
+        // Unknown commands, escapes and commands in strings aren't fixed
+        assert!(quick_fixes("$\\foo \\{ \"\\alpha\" \\u{03b1}$").is_empty());
+    }
+
+    #[test]
+    fn test_input_candidates() {
+        let options = Options::default();
+        let names = |line: &str, column: usize| {
+            input_candidates(line, column, &options, 5)
+                .into_iter()
+                .map(|candidate| (candidate.name, candidate.start, candidate.end))
+                .collect::<Vec<_>>()
+        };
+        let le = names("$x \\le", 6);
+        assert_eq!(le[0], ("lt.eq".to_string(), 3, 6));
+        assert_eq!(le.iter().filter(|(name, _, _)| name == "lt.eq").count(), 1);
+        assert_eq!(names("$\\alpha", 7)[0].0, "alpha");
+        assert_eq!(names("$x + RR", 7)[0], ("RR".to_string(), 5, 7));
+        let integrals = names("$int", 4);
+        assert_eq!(integrals[0].0, "inter");
+        assert!(integrals.iter().any(|(name, _, _)| name == "integral"));
+        assert!(integrals.iter().all(|(name, _, _)| name.starts_with("int")));
+        // Only the characters before the column are typed, in UTF-16
+        assert_eq!(names("𝔸 arrow.r.long", 9)[0], ("arrow.r".to_string(), 3, 9));
+        assert!(names("$x + ", 5).is_empty());
+        assert!(names("$x + 2", 6).is_empty());
+    }
+
+    #[test]
+    fn test_document_statistics() {
+        let statistics = document_statistics(
+            "$phi.alt + ϕ + alpha -> x$ and $ frac(1, sqrt(x^2)) $ #box[$alpha + #sym.alpha$]",
+            &Options::default(),
+        );
+        assert_eq!(statistics.equations, 3);
+        assert_eq!(statistics.block_equations, 1);
+        assert_eq!(statistics.max_nesting, 3);
+        let count = |symbol: &str| {
+            statistics
+                .symbols
+                .iter()
+                .find(|count| count.symbol == symbol)
+                .map(|count| (count.count, count.spellings.clone()))
+        };
+        assert_eq!(
+            count("ϕ"),
+            Some((2, vec!["phi.alt".to_string(), "ϕ".to_string()]))
+        );
+        // Symbols embedded with `#` aren't math
+        assert_eq!(count("α"), Some((2, vec!["alpha".to_string()])));
+        assert_eq!(count("→"), Some((1, vec!["->".to_string()])));
+        assert_eq!(count("x"), None);
+        let total: usize = statistics.symbols.iter().map(|count| count.count).sum();
+        assert_eq!(
+            statistics
+                .categories
+                .iter()
+                .map(|count| count.count)
+                .sum::<usize>(),
+            total
+        );
+        assert!(statistics
+            .symbols
+            .windows(2)
+            .all(|pair| pair[0].count >= pair[1].count));
+        assert!(!statistics.truncated);
+    }
+
+    #[test]
+    fn test_mathml() {
+        let mathml = |content: &str| {
+            let len = content.encode_utf16().count();
+            render_to_mathml(content, 0, len, &Options::default())
+        };
+        assert_eq!(
+            mathml("$alpha^2 + x'_i$"),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"inline\"><mrow><msup><mi>α</mi><mn>2</mn></msup><mo>+</mo><msubsup><mi>x</mi><mi>i</mi><mo>′</mo></msubsup></mrow></math>"
+        );
+        assert!(mathml("$ sum_(i=0)^n (a + b)/2 $").contains(
+            "<munderover><mo>∑</mo><mrow><mi>i</mi><mo>=</mo><mn>0</mn></mrow><mi>n</mi></munderover><mfrac><mrow><mi>a</mi><mo>+</mo><mi>b</mi></mrow><mn>2</mn></mfrac>"
+        ));
+        // Limits are only written above and below in display equations
+        assert!(mathml("$sum_i x$").contains("<msub><mo>∑</mo><mi>i</mi></msub>"));
+        assert!(mathml("$sqrt(x) + root(3, y) + hat(a) + bb(R)$").contains(
+            "<msqrt><mi>x</mi></msqrt><mo>+</mo><mroot><mi>y</mi><mn>3</mn></mroot><mo>+</mo><mover accent=\"true\"><mi>a</mi><mo>^</mo></mover><mo>+</mo><mi>ℝ</mi>"
+        ));
+        assert!(mathml("$mat(1, 2; 3, 4)$").contains(
+            "<mtable><mtr><mtd><mn>1</mn></mtd><mtd><mn>2</mn></mtd></mtr><mtr><mtd><mn>3</mn></mtd><mtd><mn>4</mn></mtd></mtr></mtable>"
+        ));
+        assert!(mathml("$ x &= 1 \\ y &< 2 $").contains(
+            "<mtable><mtr><mtd columnalign=\"right\"><mi>x</mi></mtd><mtd columnalign=\"left\"><mrow><mo>=</mo><mn>1</mn></mrow></mtd></mtr>"
+        ));
+        assert!(mathml("$sin(x) + \"if\" < 1$").contains(
+            "<mrow><mi>sin</mi><mo>(</mo><mi>x</mi><mo>)</mo></mrow><mo>+</mo><mtext>if</mtext><mo>&lt;</mo>"
+        ));
+        // Nodes deeper than the maximum depth are replaced by an error
+        let shallow = Options {
+            max_depth: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_to_mathml("$(x + 1)$", 0, 9, &shallow),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"inline\"><mrow><mrow><mi>(</mi><merror><mtext>…</mtext></merror><mi>)</mi></mrow></mrow></math>"
+        );
+        // Only the equations of the range are converted
+        assert_eq!(mathml("a $x$ b $y$").lines().count(), 2);
+        assert_eq!(
+            render_to_mathml("a $x$ b $y$", 9, 9, &Options::default()),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"inline\"><mi>y</mi></math>"
+        );
+    }
+
+    #[cfg(feature = "preview")]
+    #[test]
+    fn test_equation_preview() {
+        use typst_math_rust::{equation_png, equation_svg};
+
+        let content = "#let RR = $bb(R)$\nSome $x in RR$ and $ sum_(i=0)^n i $ #let y = 2";
+        let svg = equation_svg(content, 25, "#fff").unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("#ffffff"));
+        assert!(equation_png(content, 40, "#000", 2.0)
+            .unwrap()
+            .starts_with(b"\x89PNG"));
+        // Outside of equations
+        assert_eq!(equation_svg(content, 20, "#fff"), None);
+        // Definitions which don't compile are left out
+        let svg = equation_svg("#let f = read(\"file\")\n$f(x)$", 25, "red").unwrap();
+        assert!(svg.contains("#000000"));
+    }
+
+    #[test]
+    fn test_speak_equation() {
+        let speak = |content: &str| speak_equation(content, 1, &Options::default()).unwrap();
+        assert_eq!(speak("$x^2 + y_i$"), "x squared plus y sub i");
+        assert_eq!(
+            speak("$x_(i+1)^n <= f(x)$"),
+            "x sub i plus 1 end sub to the power n is less than or equal to f of x"
+        );
+        assert_eq!(
+            speak("$ sum_(i=1)^n (a+b)/2 $"),
+            "sum from i equals 1 to n fraction a plus b over 2 end fraction"
+        );
+        assert_eq!(
+            speak("$sqrt(x) + root(3, y) + hat(alpha) + a/b$"),
+            "square root of x plus cube root of y plus alpha hat plus a over b"
+        );
+        assert_eq!(
+            speak("$mat(1, 2; 3, 4) -> sin(x)$"),
+            "2 by 2 matrix, row 1: 1, 2; row 2: 3, 4, end matrix to sin of x"
+        );
+        assert_eq!(speak_equation("a $x$", 0, &Options::default()), None);
+    }
+
+    #[test]
+    fn test_braille_equation() {
+        let braille = |content: &str| braille_equation(content, 1, &Options::default()).unwrap();
+        assert_eq!(braille("$x^2 + y_i$"), "⠭⠘⠆⠐⠬⠽⠰⠊");
+        // Numbers after a space start with the numeric indicator, numeric subscripts follow their letter
+        assert_eq!(braille("$x_1 = 2.5$"), "⠭⠂⠀⠨⠅⠀⠼⠆⠨⠢");
+        assert_eq!(braille("$alpha + Delta$"), "⠨⠁⠬⠠⠨⠙");
+        assert_eq!(braille("$(a + 1)/b$"), "⠹⠁⠬⠂⠌⠃⠼");
+        assert_eq!(braille("$frac(1, 1/x)$"), "⠠⠹⠂⠠⠌⠹⠂⠌⠭⠼⠠⠼");
+        assert_eq!(braille("$sqrt(x) + root(3, y)$"), "⠜⠭⠻⠬⠣⠒⠜⠽⠻");
+        assert_eq!(braille("$sum_(i=1)^n i$"), "⠐⠠⠨⠎⠩⠊⠀⠨⠅⠀⠼⠂⠣⠝⠻⠊");
+        assert_eq!(braille("$x^(a_1) + 1$"), "⠭⠘⠁⠘⠰⠂⠐⠬⠂");
+        assert_eq!(braille_equation("a $x$", 0, &Options::default()), None);
+    }
+
+    #[test]
+    fn test_latex() {
+        let latex = |content: &str| {
+            let len = content.encode_utf16().count();
+            render_to_latex(content, 0, len, &Options::default())
+        };
+        assert_eq!(latex("$alpha^2 + x'_i <= y$"), "$\\alpha^2 + x'_i \\le y$");
+        assert_eq!(
+            latex("$ sum_(i=0)^n (a + b)/2 -> oo $"),
+            "\\[ \\sum_{i=0}^n \\frac{a + b}{2} \\to \\infty \\]"
+        );
+        assert_eq!(
+            latex("$sqrt(x) + root(3, y) + hat(a) + RR + cal(A)$"),
+            "$\\sqrt{x} + \\sqrt[3]{y} + \\hat{a} + \\mathbb{R} + \\mathcal{A}$"
+        );
+        // Typst and LaTeX swap the variants of epsilon
+        assert_eq!(latex("$epsilon epsilon.alt$"), "$\\varepsilon \\epsilon$");
+        assert_eq!(
+            latex("$mat(delim: \"[\", 1, 2; 3, 4)$"),
+            "$\\begin{bmatrix} 1 & 2 \\\\ 3 & 4 \\end{bmatrix}$"
+        );
+        assert_eq!(
+            latex("$ x &= 1 \\ y &< 2 $"),
+            "\\[ \\begin{aligned} x &= 1 \\\\ y &< 2 \\end{aligned} \\]"
+        );
+        assert_eq!(
+            latex("$sin(x) + ctg x + \"a_b\" + abs(x)$"),
+            "$\\sin(x) + \\operatorname{ctg} x + \\text{a\\_b} + \\left| x \\right|$"
+        );
+        assert_eq!(
+            latex("$norm(x) + floor(y)$"),
+            "$\\left\\| x \\right\\| + \\left\\lfloor y \\right\\rfloor$"
+        );
+        // Commands are separated from the letters following them
+        assert_eq!(latex("$alpha#h(1em)x$"), "$\\alpha x$");
+        assert_eq!(
+            render_to_latex("a $x$ b $y$", 9, 9, &Options::default()),
+            "$y$"
+        );
+    }
+
+    #[test]
+    fn test_plain_unicode() {
+        let options = Options {
+            plain_unicode: true,
+            ..Default::default()
+        };
+        let symbols = |source: &str| {
+            let parsed = parse_document_with_options(source, -1, -1, &options).unwrap();
+            let mut symbols: Vec<String> = parsed
+                .decorations
+                .iter()
+                .filter(|d| !d.symbol.is_empty())
+                .map(|d| d.symbol.clone())
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        // Attachments without a unicode version keep their marker
+        assert_eq!(
+            symbols("$x^2 + y_i + z_12 + x^alpha$"),
+            vec!["+", "^α", "²", "ᵢ", "₁₂"]
+        );
+        assert_eq!(
+            symbols("$hat(x) + arrow(alpha)$"),
+            vec!["+", "x\u{302}", "α\u{20D7}"]
+        );
+        assert_eq!(symbols("$sqrt(x)$"), vec!["√"]);
+        // No css moves the symbols
+        let parsed =
+            parse_document_with_options("$x^2 + sqrt(x) + hat(y)$", -1, -1, &options).unwrap();
+        assert!(parsed
+            .decorations
+            .iter()
+            .all(|d| !d.text_decoration.contains("transform")
+                && !d.text_decoration.contains("position")));
+        // Symbols which are already approximated aren't converted again
+        assert_eq!(render_to_unicode("$x^2 + y_i$", 0, 11, &options), "x² + yᵢ");
+    }
+
+    #[test]
+    fn test_layout() {
+        let layout = |content: &str| {
+            let len = content.encode_utf16().count();
+            render_to_layout(content, 0, len, &Options::default())
+        };
+        assert_eq!(layout("$x^2 + y_i$"), "x² + yᵢ");
+        assert_eq!(
+            layout("$ sum_(i=0)^n (a + b)/2 $"),
+            [" n   a + b", " ∑  ───────", "i=0    2"].join("\n")
+        );
+        assert_eq!(
+            layout("$mat(1, 2; 3, 4) + sqrt(x)$"),
+            ["          _", "⎛1  2⎞ + √x", "⎝3  4⎠"].join("\n")
+        );
+        assert_eq!(
+            layout("$ x &= 1 \\ y + z &= 3 $"),
+            ["    x = 1", "y + z = 3"].join("\n")
+        );
+        assert_eq!(
+            layout("$f(x) = cases(1 & \"if\" x > 0, 0 & \"else\")$"),
+            ["f(x) = ⎧1  if x > 0", "       ⎩0  else"].join("\n")
+        );
+        // Scripts without unicode superscripts are raised above the baseline
+        assert_eq!(
+            layout("$e^(i pi) + hat(x)$"),
+            [" i π", "e    + x\u{302}"].join("\n")
+        );
+        assert_eq!(
+            layout("a $x$ b $y/2$"),
+            ["x", "", " y", "───", " 2"].join("\n")
+        );
+        // Empty cases and matrices have no lines
+        assert_eq!(layout("$x + cases() + y$"), "x + { + y");
+        assert_eq!(layout("$mat() / 2$"), [" ()", "────", " 2"].join("\n"));
+        // Nodes deeper than the maximum depth are skipped, the equation ending with `…`
+        let shallow = Options {
+            max_depth: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_to_layout("$x + (y + 1)$", 0, 13, &shallow),
+            "x + () …"
+        );
+    }
+
+    #[test]
+    fn test_export_markdown() {
+        let options = Options::default();
+        assert_eq!(export_markdown("No math here", &options), "");
+        let markdown = export_markdown(
+            "Euler $ e^(i pi) = -1 $ <eq:euler>\nand $x_1 + alpha$.",
+            &options,
+        );
+        assert_eq!(
+            markdown,
+            [
+                "# Equations",
+                "",
+                "## Equation 1 `<eq:euler>`",
+                "",
+                "Block, line 1",
+                "",
+                "```typst",
+                "$ e^(i pi) = -1 $",
+                "```",
+                "",
+                "```text",
+                "e^(i π) = -1",
+                "```",
+                "",
+                "## Equation 2",
+                "",
+                "Inline, line 2",
+                "",
+                "```typst",
+                "$x_1 + alpha$",
+                "```",
+                "",
+                "```text",
+                "x₁ + α",
+                "```",
+                "",
+            ]
+            .join("\n")
+        );
+        // Fences are longer than the backticks of the equation
+        let markdown = export_markdown("$ \"```\" $", &options);
+        assert!(markdown.contains("````typst\n$ \"```\" $\n````\n"));
+    }
+
+    #[test]
+    fn test_mixed_scripts() {
+        let options = Options::default();
+        // Emojis and astral symbols are two UTF-16 code units long, CJK characters one and three bytes
+        let content = "😀 中文 $alpha + 𝔸$ 👍🏽\n数学 $e^(i pi) arrow.r 𝒳$ 😀";
+        let text = |start: usize, end: usize| {
+            let units: Vec<u16> = content.encode_utf16().collect();
+            String::from_utf16(&units[start..end]).unwrap()
+        };
+        let parsed = parse_document(content, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        let mut positions: Vec<_> = parsed
+            .decorations
+            .iter()
+            .flat_map(|d| {
+                d.positions
+                    .iter()
+                    .map(move |p| (p.clone(), d.symbol.clone()))
+            })
+            .collect();
+        positions.sort_by_key(|(p, _)| p.start);
+        let found: Vec<_> = positions
+            .iter()
+            .map(|(p, symbol)| {
+                (
+                    text(p.start, p.end),
+                    symbol.as_str(),
+                    p.start_line,
+                    p.start_column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("alpha".to_string(), "α", 0, 7),
+                ("+".to_string(), "+", 0, 13),
+                ("𝔸".to_string(), "𝔸", 0, 15),
+                ("^(".to_string(), "", 1, 5),
+                ("i".to_string(), "i", 1, 7),
+                ("pi".to_string(), "π", 1, 9),
+                (")".to_string(), "", 1, 11),
+                ("arrow.r".to_string(), "→", 1, 13),
+            ]
+        );
+        let hover = symbol_at(content, 40, &options).unwrap();
+        assert_eq!(
+            (hover.name.as_str(), hover.symbol.as_str()),
+            ("arrow.r", "→")
+        );
+        let equations: Vec<_> = list_equations(content, &options)
+            .iter()
+            .map(|e| {
+                (
+                    text(e.position.start, e.position.end),
+                    e.position.end_column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            equations,
+            vec![
+                ("$alpha + 𝔸$".to_string(), 18),
+                ("$e^(i pi) arrow.r 𝒳$".to_string(), 24),
+            ]
+        );
+        let edits: Vec<_> = typstify(content, 0, content.encode_utf16().count())
+            .iter()
+            .map(|e| (text(e.position.start, e.position.end), e.text.clone()))
+            .collect();
+        assert_eq!(edits, vec![("𝔸".to_string(), "AA".to_string())]);
+        // Scripts made of a single grapheme need no parentheses
+        assert_eq!(
+            render_to_unicode("$x_👍🏽 + y_(ab)$", 0, 16, &options),
+            "x_👍🏽 + y_(ab)"
+        );
+        // Wide characters take two cells in layouts
+        assert_eq!(
+            render_to_layout("$(中文)/2$", 0, 9, &options),
+            [" 中文", "──────", "  2"].join("\n")
+        );
+    }
+
+    #[test]
+    fn test_session_offsets() {
+        let mut session = Session::new("😀 $alpha$\r\n中 $beta$");
+        assert_eq!(session.offset_at(0, 4), Some(4));
+        // Columns after the end of a line stop before its line break
+        assert_eq!(session.offset_at(0, 50), Some(10));
+        assert_eq!(session.offset_at(1, 0), Some(12));
+        assert_eq!(session.offset_at(1, 50), Some(20));
+        assert_eq!(session.offset_at(2, 0), None);
+        let options = Options::default();
+        session.parse(&options);
+        let offset = session.offset_at(1, 1).unwrap();
+        session.edit(offset, offset, "𝔸");
+        assert_eq!(session.text(), "😀 $alpha$\r\n中𝔸 $beta$");
+        let parsed = session.parse_edits(&options);
+        let beta = parsed.decorations.iter().find(|d| d.symbol == "β").unwrap();
+        let position = &beta.positions[0];
+        assert_eq!(
+            (position.start, position.start_line, position.start_column),
+            (17, 1, 5)
+        );
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let options = Options::default();
+        let lines = [
+            "a $alpha$",
+            "$ x &= 1 \\",
+            "  y &= beta $",
+            "$gamma$ // typst-math: ignore",
+        ];
+        let located = |content: &str| {
+            let parsed =
+                parse_document(content, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+            let mut located: Vec<_> = parsed
+                .decorations
+                .iter()
+                .flat_map(|d| {
+                    d.positions.iter().map(move |p| {
+                        (
+                            d.symbol.clone(),
+                            p.start_line,
+                            p.start_column,
+                            p.end_line,
+                            p.end_column,
+                        )
+                    })
+                })
+                .collect();
+            located.sort_by_key(|(_, line, column, _, _)| (*line, *column));
+            located
+        };
+        let unix = lines.join("\n");
+        let expected = located(&unix);
+        assert_eq!(expected.len(), 5);
+        assert_eq!(expected[4], ("β".to_string(), 2, 7, 2, 11));
+        for eol in ["\r\n", "\r"] {
+            let content = lines.join(eol);
+            // Decorations are on the same lines and columns whatever the line endings
+            assert_eq!(located(&content), expected);
+            let edited = |content: &str| {
+                let parsed =
+                    parse_document(content, 2, 2, 3, true, true, false, vec![], vec![]).unwrap();
+                (
+                    parsed.edit_start_line,
+                    parsed.edit_start_column,
+                    parsed.edit_end_line,
+                    parsed.edit_end_column,
+                )
+            };
+            assert_eq!(edited(&content), edited(&unix));
+            let extmarks = parse_document_extmarks(&content, &options);
+            let marks: Vec<_> = extmarks
+                .iter()
+                .map(|m| (m.line, m.start_col, m.end_col))
+                .collect();
+            let unix_marks: Vec<_> = parse_document_extmarks(&unix, &options)
+                .iter()
+                .map(|m| (m.line, m.start_col, m.end_col))
+                .collect();
+            assert_eq!(marks, unix_marks);
+            assert_eq!(
+                export_markdown(&content, &options),
+                export_markdown(&unix, &options)
+            );
+            let len = content.encode_utf16().count();
+            assert_eq!(
+                render_to_layout(&content, 0, len, &options),
+                render_to_layout(&unix, 0, len, &options)
+            );
+        }
+        // Offsets count both characters of `\r\n`
+        let mut session = Session::new(&lines.join("\r\n"));
+        assert_eq!(session.offset_at(1, 0), Some(11));
+        assert_eq!(session.offset_at(0, 20), Some(9));
+        session.parse(&options);
+        session.edit(11, 11, "z + ");
+        let parsed = session.parse_edits(&options);
+        let plus = parsed.decorations.iter().find(|d| d.symbol == "+").unwrap();
+        let position = &plus.positions[0];
+        assert_eq!(
+            (position.start, position.start_line, position.start_column),
+            (13, 1, 2)
+        );
+    }
+
+    #[test]
+    fn test_comments_in_equations() {
+        let cases = [
+            ("$bb(R /* c */)$", "ℝ", "/* c */"),
+            ("$cal(A // x\n)$", "𝒜", "// x"),
+            ("$x^(2 /* a */)$", "2", "/* a */"),
+            ("$sqrt(x /* y */)$", "√", "/* y */"),
+            ("$hat(x /* z */)$", "^", "/* z */"),
+            ("$op(\"lim\" /* l */)$", "lim", "/* l */"),
+        ];
+        for (source, symbol, comment) in cases {
+            let parsed =
+                parse_document(source, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+            assert!(
+                parsed.decorations.iter().any(|d| d.symbol == symbol),
+                "{} isn't rendered in {}",
+                symbol,
+                source
+            );
+            // The comment stays visible
+            let start = source.find(comment).unwrap();
+            let end = start + comment.len();
+            for decoration in &parsed.decorations {
+                for position in &decoration.positions {
+                    assert!(
+                        position.end <= start || position.start >= end,
+                        "{:?} covers the comment in {}",
+                        decoration.symbol,
+                        source
+                    );
+                }
+            }
+        }
+        let options = Options {
+            plain_unicode: true,
+            ..Options::default()
+        };
+        let source = "$hat(x /* z */)$";
+        let parsed = parse_document_with_options(source, -1, -1, &options).unwrap();
+        assert!(parsed.decorations.iter().any(|d| d.symbol == "x\u{302}"));
+        assert_eq!(
+            render_to_unicode("$bb(R /* c */) + x$", 0, 19, &Options::default()),
+            "ℝ/* c */ + x"
+        );
+    }
+
+    #[test]
+    fn test_escapes() {
+        let source = r"a \$ $\$ + x_\_ + \u{1F600} + a^(\u{3B1}) + bb(\u{52})$";
+        let parsed = parse_document(source, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        let located = |symbol: &str| {
+            let decoration = parsed.decorations.iter().find(|d| d.symbol == symbol)?;
+            let position = decoration.positions.first()?;
+            Some((position.start, position.end))
+        };
+        // Escapes in markup are kept, the backslash is hidden in math
+        assert_eq!(located("$"), Some((6, 8)));
+        assert_eq!(located("_"), Some((12, 15)));
+        assert_eq!(located("😀"), Some((18, 27)));
+        assert_eq!(located("α"), Some((33, 40)));
+        assert_eq!(located("ℝ"), Some((44, 54)));
+        assert_eq!(
+            render_to_unicode(source, 0, source.len(), &Options::default()),
+            r"a \$ $ + x__ + 😀 + a^α + ℝ"
+        );
+    }
+
+    #[test]
+    fn test_nested_attachments() {
+        let styles = |source: &str| {
+            let parsed =
+                parse_document(source, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+            let mut styles: Vec<_> = parsed
+                .decorations
+                .iter()
+                .filter(|d| !d.symbol.is_empty())
+                .map(|d| {
+                    (
+                        d.positions[0].start,
+                        parsed.uuid(d.id).unwrap(),
+                        d.text_decoration.clone(),
+                    )
+                })
+                .collect();
+            styles.sort();
+            styles
+        };
+        let css = |scale: &str, offset: &str| {
+            format!(
+                "font-size: {}em; transform: translateY({}%); display: inline-block;",
+                scale, offset
+            )
+        };
+        assert_eq!(
+            styles("$a^b^c$"),
+            vec![
+                (2, "top--text-b".to_string(), css("0.8", "-30")),
+                (4, "top-2--text-c".to_string(), css("0.64", "-67.5")),
+            ]
+        );
+        assert_eq!(
+            styles("$x_i^2_j$"),
+            vec![
+                (2, "bottom--text-i".to_string(), css("0.8", "20")),
+                (4, "top--text-2".to_string(), css("0.8", "-30")),
+                (6, "bottom-2--text-j".to_string(), css("0.64", "-17.5")),
+            ]
+        );
+        // Parentheses around a simple attachment are hidden like around a value
+        assert_eq!(
+            styles("$a^(b^c)$"),
+            vec![
+                (4, "top--text-b".to_string(), css("0.8", "-30")),
+                (5, "top-2--text-c".to_string(), css("0.64", "-67.5")),
+            ]
+        );
+        let options = Options::default();
+        assert_eq!(
+            render_to_unicode("$x_i^2_j + y_(i_j)$", 0, 19, &options),
+            "xᵢ²ⱼ + yᵢⱼ"
+        );
+    }
+
+    #[test]
+    fn test_alignment_points() {
+        let source = "$ x_(i &) &= bb(A &) \\ &= alpha $";
+        let symbols = |options: &Options| {
+            let parsed = parse_document_with_options(source, -1, -1, options).unwrap();
+            let mut symbols: Vec<_> = parsed
+                .decorations
+                .iter()
+                .flat_map(|d| d.positions.iter().map(move |p| (p.start, d.symbol.clone())))
+                .collect();
+            symbols.sort();
+            symbols
+        };
+        // Alignment points don't prevent the attachment and the letter style from being rendered
+        let options = Options::default();
+        let plain = symbols(&options);
+        assert!(plain.contains(&(5, "i".to_string())));
+        assert!(plain.contains(&(13, "𝔸".to_string())));
+        assert!(!plain.iter().any(|(_, symbol)| symbol == "│"));
+        // Only the alignment points of the equation are marked
+        let options = Options {
+            render_align_points: true,
+            ..Options::default()
+        };
+        let marked: Vec<_> = symbols(&options)
+            .into_iter()
+            .filter(|(_, symbol)| symbol == "│")
+            .collect();
+        assert_eq!(marked, vec![(10, "│".to_string()), (23, "│".to_string())]);
+    }
+
+    #[test]
+    fn test_overlapping_decorations() {
+        // The span hidden before the attachment and the symbol both start on the `#`
+        let source = "$attach(x, t: #sym.beta) + hat(x)^2$";
+        let parsed = parse_document(source, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        let mut positions: Vec<_> = parsed
+            .decorations
+            .iter()
+            .flat_map(|d| {
+                d.positions
+                    .iter()
+                    .map(move |p| (p.start, p.end, d.symbol.clone()))
+            })
+            .collect();
+        positions.sort();
+        for pair in positions.windows(2) {
+            assert!(
+                pair[0].1 <= pair[1].0,
+                "{:?} overlaps {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+        assert!(positions.contains(&(9, 14, "".to_string())));
+        assert!(positions.contains(&(14, 23, "β".to_string())));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let options = Options::default();
+        assert_eq!(
+            parse_document_with_options("$alpha$\n$beta$\n$gamma$", 2, 1, &options).err(),
+            Some(Error::InvalidLines { start: 2, end: 1 })
+        );
+        // Lines outside the document are clamped to it
+        assert!(parse_document_with_options("$alpha$", 0, 5, &options).is_ok());
+        // The last node of an unclosed equation followed by a comment ends after the text
+        let parsed = parse_document_with_options("$bα(:*\n//", -1, -1, &options).unwrap();
+        assert_eq!(parsed.decorations.len(), 1);
+        let mut session = Session::new("tsrootxsqrtlim{\\u{1F600}t:\"norm(&l\n");
+        session.edit(29, 24, "x^");
+        session.parse_edits(&options);
+        session.edit(24, 29, "😀$");
+        session.parse_delta(&options, 1);
+    }
+
+    #[test]
+    fn test_attachment_groups() {
+        let uuids = |source: &str, plain_unicode: bool| {
+            let options = Options {
+                plain_unicode,
+                ..Default::default()
+            };
+            let parsed = parse_document_with_options(source, -1, -1, &options).unwrap();
+            let mut uuids: Vec<_> = parsed
+                .decorations
+                .iter()
+                .flat_map(|d| {
+                    let uuid = parsed.uuid(d.id).unwrap();
+                    d.positions.iter().map(move |p| (p.start, uuid.clone()))
+                })
+                .collect();
+            uuids.sort();
+            uuids.into_iter().map(|(_, uuid)| uuid).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            uuids("$x^(2n+1)$", false),
+            vec![
+                "void",
+                "top--text-2",
+                "top--text-n",
+                "top--+",
+                "top--text-1",
+                "void"
+            ]
+        );
+        assert_eq!(
+            uuids("$e^(i pi)$", false),
+            vec!["void", "top--text-i", "top-pi", "void"]
+        );
+        // Groups with nested attachments or too many tokens keep their parentheses
+        assert_eq!(
+            uuids("$y^(2 alpha_1)$", false),
+            vec!["alpha", "bottom--text-1"]
+        );
+        assert!(uuids("$w^(a b c d e f g)$", false).is_empty());
+        // Without css, groups are only styled if all their symbols have a unicode script
+        assert_eq!(uuids("$x^(2n+1)$", true).len(), 6);
+        assert_eq!(uuids("$e^(i pi)$", true), vec!["plain-pi"]);
+        let content = "$e^(i pi) x^(2n+1)$";
+        assert_eq!(
+            render_to_unicode(content, 0, 19, &Options::default()),
+            "e^(i π) x²ⁿ⁺¹"
+        );
+    }
+
+    #[test]
+    fn test_content_block_arguments() {
+        let options = Options::default();
+        let found = |content: &str| {
+            let parsed = parse_document_with_options(content, -1, -1, &options).unwrap();
+            let mut found: Vec<_> = parsed
+                .decorations
+                .iter()
+                .flat_map(|d| {
+                    d.positions
+                        .iter()
+                        .map(move |p| (p.start, p.end, d.symbol.clone()))
+                })
+                .collect();
+            found.sort();
+            found
+        };
+        let symbol = |start: usize, end: usize, symbol: &str| (start, end, symbol.to_string());
+        // The `#[$` and `$]` around the content are hidden like parentheses
+        assert_eq!(
+            found("$bb(#[$x$]) hat(#[$x$])$"),
+            vec![
+                symbol(1, 11, "𝕩"),
+                symbol(12, 16, "^"),
+                symbol(16, 19, ""),
+                symbol(20, 22, ""),
+                symbol(22, 23, ""),
+            ]
+        );
+        assert_eq!(
+            found("$cases(#[$x > alpha$], y)$"),
+            vec![
+                symbol(7, 10, ""),
+                symbol(12, 13, ">"),
+                symbol(14, 19, "α"),
+                symbol(19, 21, ""),
+            ]
+        );
+        // Trailing content blocks of calls from code
+        assert_eq!(
+            found("#math.bb[R] #math.cal[$A$]"),
+            vec![symbol(0, 11, "ℝ"), symbol(12, 26, "𝒜")]
+        );
+        // Content blocks with several elements are only traversed
+        assert_eq!(found("$bb(#[$x$ and $alpha$])$"), vec![symbol(15, 20, "α")]);
+        // Delimiters are only drawn around parentheses
+        assert_eq!(
+            found("#math.abs[$alpha$]"),
+            vec![symbol(9, 11, ""), symbol(11, 16, "α"), symbol(16, 18, "")]
+        );
+        assert_eq!(
+            found("$abs()$"),
+            vec![symbol(1, 4, ""), symbol(4, 5, "|"), symbol(5, 6, "|")]
+        );
+    }
+
+    #[test]
+    fn test_show_rule_styles() {
+        let options = Options::default();
+        let styles = |content: &str| {
+            let parsed = parse_document_with_options(content, -1, -1, &options).unwrap();
+            let mut styles: Vec<_> = parsed
+                .decorations
+                .iter()
+                .map(|d| (d.positions[0].start_column, d.text_decoration.clone()))
+                .collect();
+            styles.sort();
+            styles.into_iter().map(|(_, css)| css).collect::<Vec<_>>()
+        };
+        // Fonts of the show rules come before the math font of the options, and the sizes are scaled
+        assert_eq!(
+            styles("#show math.equation: set text(font: \"Fira Math\", size: 1.5em)\n$x^2 + oo$"),
+            vec![
+                "font-size: 1.2em; transform: translateY(-30%); display: inline-block;",
+                "font-size: 1.5em;",
+                "font-family: \"Fira Math\", \"NewComputerModernMath\"; font-size: 1.5em;",
+            ]
+        );
+        // Absolute sizes are relative to the text size, and rules in a block stop at its end
+        assert_eq!(
+            styles("#set text(size: 10pt)\n#[#show math.equation: set text(size: 15pt); $+$] $+$"),
+            vec!["font-size: 1.5em;", ""]
+        );
+        // Equations before the rule aren't styled
+        assert_eq!(
+            styles("$+$ #show math.equation: set text(size: 2em)"),
+            vec![""]
+        );
+    }
+    #[test]
+    fn test_parenthesized_attachments() {
+        let uuids = |content: &str| {
+            let parsed = parse_document_with_options(content, -1, -1, &Options::default()).unwrap();
+            let mut uuids: Vec<(usize, String)> = parsed
+                .decorations
+                .iter()
+                .flat_map(|d| {
+                    let uuid = parsed.uuid(d.id).unwrap().to_string();
+                    d.positions.iter().map(move |p| (p.start, uuid.clone()))
+                })
+                .collect();
+            uuids.sort();
+            uuids.into_iter().map(|(_, uuid)| uuid).collect::<Vec<_>>()
+        };
+        // A text or a known symbol, with or without a sign, is styled and its parentheses hidden
+        assert_eq!(uuids("$x^(2)$"), vec!["void", "top--text-2", "void"]);
+        assert_eq!(uuids("$x^(alpha)$"), vec!["void", "top-alpha", "void"]);
+        assert_eq!(
+            uuids("$x^(-2)$"),
+            vec!["void", "top---", "top--text-2", "void"]
+        );
+        assert_eq!(
+            uuids("$x_(-alpha)$"),
+            vec!["void", "bottom---", "bottom-alpha", "void"]
+        );
+        // Unknown identifiers are kept as they are
+        assert!(uuids("$x^(foo)$").is_empty());
+    }
+    #[test]
+    fn test_cli() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+        let run = |args: &[&str], input: &str| {
+            let mut child = Command::new(env!("CARGO_BIN_EXE_typst-math"))
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .unwrap();
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(input.as_bytes())
+                .unwrap();
+            child.wait_with_output().unwrap()
+        };
+        let output = run(&["render", "-"], "Let $x^2 <= alpha$.");
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "Let x² ≤ α.\n");
+        let output = run(
+            &["serve"],
+            "{\"id\": 1, \"method\": \"convert\", \"params\": {\"content\": \"$beta$\"}}\n",
+        );
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "{\"id\":1,\"result\":\"β\"}\n"
+        );
+        let output = run(&["unknown"], "");
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("Usage: typst-math"));
+    }
+    #[test]
+    fn test_field_access() {
+        let parsed =
+            parse_document("$beta.alt$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations.len(), 1);
+        assert_eq!(parsed.decorations[0].symbol, "ϐ");
+        assert_eq!(parsed.uuid(parsed.decorations[0].id).unwrap(), "beta.alt");
+        let parsed = parse_document(
+            "$triangle.filled.b$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 1);
+        assert_eq!(parsed.decorations[0].symbol, "▼");
+        assert_eq!(
+            parsed.uuid(parsed.decorations[0].id).unwrap(),
+            "triangle.filled.b"
+        );
+    }
+    #[test]
+    fn test_modifier_order() {
+        for source in ["$arrow.r.long$", "$arrow.long.r$"] {
+            let parsed =
+                parse_document(source, -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+            assert_eq!(parsed.decorations.len(), 1);
+            assert_eq!(parsed.decorations[0].symbol, "⟶");
+        }
+        let parsed = parse_document(
+            "$triangle.b.filled.small$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations[0].symbol, "▾");
+        // Incomplete modifiers resolve to the closest variant, like in typst
+        let parsed =
+            parse_document("$arrow.long$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations[0].symbol, "⟶");
+        let parsed =
+            parse_document("$arrow.foo$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations.len(), 0);
+    }
+    #[test]
+    fn test_emoji() {
+        let parsed = parse_document(
+            "#emoji.face.grin $emoji.face.angry.red emoji.face.red.angry$ #emoji.abacus",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 4);
+        let mut symbols: Vec<String> = parsed.decorations.into_iter().map(|d| d.symbol).collect();
+        symbols.sort();
+        assert_eq!(symbols, vec!["😀", "😡", "😡", "🧮"]);
+        let parsed = parse_document(
+            "#emoji.abacus",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.uuid(parsed.decorations[0].id).unwrap(),
+            "emoji.abacus"
+        );
+        assert_eq!(parsed.decorations[0].positions[0].start, 0);
+    }
+    #[test]
+    fn test_text() {
+        let parsed =
+            parse_document("$x^a x_a$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations.len(), 2);
+        assert_eq!(parsed.decorations[0].symbol, "a");
+
+        let parsed = parse_document(
+            "$x^\"text\" x_\"text\"$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 2);
+        assert_eq!(parsed.decorations[0].symbol, "text");
+    }
+    #[test]
+    fn test_linebreak() {
+        let parsed = parse_document(
+            "$x$ \\ \\ \\ x",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 1);
+        assert_eq!(parsed.decorations[0].symbol, "⮰");
+    }
+    #[test]
+    fn test_math_block() {
+        let parsed = parse_document(
+            "$x^(5+3-2)=6$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        // The parentheses are hidden and the five tokens styled as the superscript
+        assert_eq!(parsed.decorations.len(), 7);
+        let parsed =
+            parse_document("$x^(alpha)$", -1, -1, 3, true, true, false, vec![], vec![]).unwrap();
+        assert_eq!(parsed.decorations.len(), 2);
+        let parsed = parse_document(
+            "$x^(\"alpha\") x^(-\"alpha\") x^(-alpha)$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(parsed.decorations.len(), 4);
+    }
+    #[test]
+    fn test_shortands() {
+        let parsed = parse_document(
+            "$=> + - * |--> [ |]$",
+            -1,
+            -1,
+            3,
+            true,
+            true,
+            false,
+            vec![],
+            vec![],
+        )
+        .unwrap();
         assert_eq!(parsed.decorations.len(), 7);
     }
 }